@@ -0,0 +1,147 @@
+// Standard Uses
+use std::fmt::Write;
+use std::path::Path;
+
+// Crate Uses
+use crate::output::{self, Format, VersionReport};
+use crate::{EXIT_BREAKING, EXIT_FAILURE, EXIT_SUCCESS};
+
+// External Uses
+use comline_core::package::build::{self as package_build, BuildResult, CheckResult};
+use comline_core::schema::ir::lint::{LintLevel, Rule};
+use eyre::Result;
+use serde_derive::Serialize;
+
+
+pub fn build(format: Format, path: &Path) -> Result<u8> {
+    let result = package_build::build(path)?;
+
+    let report = VersionReport::from(&result);
+    output::emit(format, &report, || {
+        let mut out = String::new();
+        match result.version_change() {
+            Some(change) if result.version_changed() => {
+                writeln!(out, "Built version {}", change).unwrap()
+            }
+            _ if result.is_initial_build() => {
+                writeln!(out, "Built initial version {}", result.current_version).unwrap()
+            }
+            _ => writeln!(out, "Version {} is up to date", result.current_version).unwrap(),
+        }
+        if !result.is_initial_build() {
+            out.push_str(&output::describe_changes(result.schema_changes.as_ref()));
+        }
+        out.push_str(&output::describe_generated(&report.generated));
+        out
+    });
+
+    Ok(breaking_code(&result))
+}
+
+#[derive(Serialize)]
+pub(crate) struct CheckReport {
+    pub valid: bool,
+    problems: Vec<ProblemReport>,
+    lints: Vec<LintReport>,
+}
+
+#[derive(Serialize)]
+struct ProblemReport {
+    schema: String,
+    context: String,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct LintReport {
+    schema: String,
+    rule: Rule,
+    level: LintLevel,
+    message: String,
+    line: usize,
+    column: usize,
+}
+
+impl From<&CheckResult> for CheckReport {
+    fn from(result: &CheckResult) -> Self {
+        Self {
+            valid: result.is_valid(),
+            problems: result.problems.iter()
+                .flat_map(|schema| schema.errors.iter().map(|e| ProblemReport {
+                    schema: schema.namespace.clone(),
+                    context: e.context.clone(),
+                    message: e.message.clone(),
+                }))
+                .collect(),
+            lints: result.lints.iter()
+                .flat_map(|schema| schema.lints.iter().map(|lint| {
+                    let (line, column) = schema.line_column(lint);
+                    LintReport {
+                        schema: schema.namespace.clone(),
+                        rule: lint.rule,
+                        level: lint.level,
+                        message: lint.message.clone(),
+                        line, column,
+                    }
+                }))
+                .collect(),
+        }
+    }
+}
+
+impl CheckReport {
+    /// Problems and lints, one after the other
+    pub fn describe(&self) -> String {
+        let mut out = String::new();
+        for problem in &self.problems {
+            writeln!(
+                out, "error: {}\n  --> {} ({})", problem.message, problem.schema, problem.context
+            ).unwrap();
+        }
+        for lint in &self.lints {
+            let severity = if lint.level == LintLevel::Deny { "error" } else { "warning" };
+            writeln!(
+                out, "{}[{}]: {}\n  --> {}:{}:{}",
+                severity, lint.rule.name(), lint.message, lint.schema, lint.line, lint.column
+            ).unwrap();
+        }
+        if !self.valid {
+            let denied = self.lints.iter().filter(|l| l.level == LintLevel::Deny).count();
+            writeln!(out, "Found {} problem(s)", self.problems.len() + denied).unwrap();
+        }
+        out
+    }
+}
+
+pub fn check(format: Format, path: &Path) -> Result<u8> {
+    let report = CheckReport::from(&package_build::check(path)?);
+
+    output::emit(format, &report, || {
+        let mut out = report.describe();
+        if report.valid { out.push_str("Package is valid\n") }
+        out
+    });
+
+    Ok(if report.valid { EXIT_SUCCESS } else { EXIT_FAILURE })
+}
+
+pub fn diff(format: Format, path: &Path) -> Result<u8> {
+    let result = package_build::diff(path)?;
+
+    output::emit(format, &VersionReport::from(&result), || {
+        let mut out = String::new();
+        if result.version_changed() {
+            writeln!(out, "Next version would be {}", result.version_change().unwrap()).unwrap();
+        } else {
+            writeln!(out, "Version {} is up to date", result.current_version).unwrap();
+        }
+        out.push_str(&output::describe_changes(result.schema_changes.as_ref()));
+        out
+    });
+
+    Ok(breaking_code(&result))
+}
+
+fn breaking_code(result: &BuildResult) -> u8 {
+    if result.is_breaking() { EXIT_BREAKING } else { EXIT_SUCCESS }
+}
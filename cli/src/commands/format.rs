@@ -0,0 +1,42 @@
+// Standard Uses
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+// Crate Uses
+use crate::output::{self, Format};
+use crate::{EXIT_FAILURE, EXIT_SUCCESS};
+
+// External Uses
+use comline_core::package::format::format_package;
+use eyre::Result;
+use serde_derive::Serialize;
+
+
+#[derive(Serialize)]
+struct FormatReport {
+    check: bool,
+    unformatted: Vec<PathBuf>,
+}
+
+pub fn format(format: Format, path: &Path, check: bool) -> Result<u8> {
+    let report = FormatReport { check, unformatted: format_package(path, check)? };
+
+    output::emit(format, &report, || {
+        let mut out = String::new();
+        for file in &report.unformatted {
+            match check {
+                true => writeln!(out, "Not formatted: {}", file.display()).unwrap(),
+                false => writeln!(out, "Formatted {}", file.display()).unwrap(),
+            }
+        }
+        if report.unformatted.is_empty() {
+            out.push_str("All files are formatted\n");
+        }
+        out
+    });
+
+    match check && !report.unformatted.is_empty() {
+        true => Ok(EXIT_FAILURE),
+        false => Ok(EXIT_SUCCESS),
+    }
+}
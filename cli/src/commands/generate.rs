@@ -0,0 +1,62 @@
+// Standard Uses
+use std::path::{Path, PathBuf};
+
+// Crate Uses
+use crate::output::{self, Format, GeneratedReport};
+use crate::EXIT_SUCCESS;
+
+// External Uses
+use comline_core::codelib_gen::find_generator;
+use comline_core::package::build::{
+    self as package_build, generate_code_for_context, GeneratedCode,
+};
+use eyre::{eyre, Result};
+use semver::VersionReq;
+use serde_derive::Serialize;
+
+
+#[derive(Serialize)]
+struct GenerateReport {
+    generated: Vec<GeneratedReport>,
+}
+
+pub fn generate(
+    format: Format, path: &Path, language: Option<String>, output_path: Option<PathBuf>
+) -> Result<u8> {
+    let context = package_build::compile(path)?;
+
+    let Some(language) = language else {
+        let generated = package_build::generate_code_for_targets(&context, path)?;
+
+        let report = GenerateReport {
+            generated: generated.iter().map(GeneratedReport::from).collect(),
+        };
+        output::emit(format, &report, || match report.generated.is_empty() {
+            true => "No code generation targets are configured\n".to_owned(),
+            false => output::describe_generated(&report.generated),
+        });
+        return Ok(EXIT_SUCCESS)
+    };
+
+    // The version, if any, is a requirement on the version of the generator
+    let (name, version) = language.split_once('#').unwrap_or((&language, ""));
+    let requirement = match version {
+        "" => VersionReq::STAR,
+        version => VersionReq::parse(version).map_err(|e| eyre!(
+            "The generator version of '{}' is not a version requirement: {}", language, e
+        ))?,
+    };
+    let generator = find_generator(name, &requirement)?;
+    generator.check_features(&package_build::compiled_schemas(&context)?)?;
+
+    let target = output_path.unwrap_or_else(|| path.join("generated").join(name));
+    let files = generate_code_for_context(&context, &generator.generate, &Default::default(), &target)?;
+
+    let generated = GeneratedCode {
+        language: name.to_owned(), version: version.to_owned(), path: target, files,
+    };
+    let report = GenerateReport { generated: vec![GeneratedReport::from(&generated)] };
+    output::emit(format, &report, || output::describe_generated(&report.generated));
+
+    Ok(EXIT_SUCCESS)
+}
@@ -0,0 +1,85 @@
+// Standard Uses
+use std::path::Path;
+
+// Crate Uses
+use crate::output::{self, Format};
+use crate::EXIT_SUCCESS;
+
+// External Uses
+use comline_core::package::config::idl::constants::CONGREGATION_EXTENSION;
+use comline_core::package::config::is_package_path;
+use comline_core::schema::idl::constants::SCHEMA_EXTENSION;
+use eyre::{bail, Result};
+use serde_derive::Serialize;
+
+
+#[derive(Serialize)]
+struct InitReport {
+    name: String,
+    config: String,
+    schema: String,
+}
+
+pub fn init(format: Format, path: &Path, name: Option<String>) -> Result<u8> {
+    if is_package_path(path) {
+        bail!("A package already exists at '{}'", path.display())
+    }
+
+    let name = match name {
+        Some(name) => name,
+        None => default_name(path)?,
+    };
+
+    if !is_identifier(&name) {
+        bail!("Package name '{}' must be a valid identifier", name)
+    }
+
+    let config_path = path.join(format!("config.{}", CONGREGATION_EXTENSION));
+    let schema_path = path.join("src").join(format!("{}.{}", name, SCHEMA_EXTENSION));
+
+    std::fs::create_dir_all(path.join("src"))?;
+    std::fs::write(&config_path, config_template(&name))?;
+    if !schema_path.exists() {
+        std::fs::write(&schema_path, schema_template(&name))?;
+    }
+
+    let report = InitReport {
+        name,
+        config: config_path.display().to_string(),
+        schema: schema_path.display().to_string(),
+    };
+
+    output::emit(format, &report, || {
+        format!("Created package '{}' at '{}'\n", report.name, path.display())
+    });
+
+    Ok(EXIT_SUCCESS)
+}
+
+fn default_name(path: &Path) -> Result<String> {
+    let absolute = std::path::absolute(path)?;
+    let Some(dir_name) = absolute.file_name().and_then(|n| n.to_str()) else {
+        bail!("Could not infer a package name from '{}', use --name", path.display())
+    };
+
+    Ok(dir_name.replace(['-', '.', ' '], "_"))
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn config_template(name: &str) -> String {
+    format!("congregation {}\nspecification_version = 1\n", name)
+}
+
+fn schema_template(name: &str) -> String {
+    format!(
+        "// {} Schema\n\n\
+        /// An example structure, replace it with your own\n\
+        struct Greeting {{\n    message: str\n}}\n",
+        name
+    )
+}
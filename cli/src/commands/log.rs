@@ -0,0 +1,51 @@
+// Standard Uses
+use std::fmt::Write;
+use std::path::Path;
+
+// Crate Uses
+use crate::output::{self, Format};
+use crate::EXIT_SUCCESS;
+
+// External Uses
+use comline_core::package::build::cas::history;
+use eyre::Result;
+use serde_derive::Serialize;
+
+
+#[derive(Serialize)]
+struct CommitReport {
+    hash: String,
+    version: String,
+    author: String,
+    timestamp: i64,
+    message: String,
+}
+
+pub fn log(format: Format, path: &Path) -> Result<u8> {
+    let report = history::log(path)?
+        .into_iter()
+        .map(|(hash, commit)| CommitReport {
+            hash: hash.to_hex(),
+            version: commit.version,
+            author: commit.author,
+            timestamp: commit.timestamp,
+            message: commit.message,
+        })
+        .collect::<Vec<_>>();
+
+    output::emit(format, &report, || {
+        let mut out = String::new();
+        if report.is_empty() {
+            out.push_str("No frozen versions yet\n");
+        }
+        for commit in &report {
+            writeln!(
+                out, "{} {} ({}, {}) {}",
+                &commit.hash[..12], commit.version, commit.author, commit.timestamp, commit.message
+            ).unwrap();
+        }
+        out
+    });
+
+    Ok(EXIT_SUCCESS)
+}
@@ -0,0 +1,17 @@
+// Relative Modules
+mod init;
+mod build;
+mod log;
+mod generate;
+mod publish;
+mod format;
+mod watch;
+
+// Re-exports
+pub use init::init;
+pub use build::{build, check, diff};
+pub use log::log;
+pub use generate::generate;
+pub use publish::publish;
+pub use format::format;
+pub use watch::watch;
@@ -0,0 +1,49 @@
+// Standard Uses
+use std::fmt::Write;
+use std::path::Path;
+
+// Crate Uses
+use crate::output::{self, Format};
+use crate::EXIT_SUCCESS;
+
+// External Uses
+use comline_core::package::publish as package_publish;
+use eyre::Result;
+use serde_derive::Serialize;
+
+
+#[derive(Serialize)]
+struct PublicationReport {
+    registry: String,
+    version: String,
+    commit: String,
+    location: String,
+}
+
+pub fn publish(format: Format, path: &Path, registry: Option<&str>) -> Result<u8> {
+    let report = package_publish::publish(path, registry)?
+        .into_iter()
+        .map(|publication| PublicationReport {
+            registry: publication.registry,
+            version: publication.version,
+            commit: publication.commit.to_hex(),
+            location: publication.location.display().to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    output::emit(format, &report, || {
+        let mut out = String::new();
+        if report.is_empty() {
+            out.push_str("No registries to publish into\n");
+        }
+        for publication in &report {
+            writeln!(
+                out, "Published {} into '{}' at '{}'",
+                publication.version, publication.registry, publication.location
+            ).unwrap();
+        }
+        out
+    });
+
+    Ok(EXIT_SUCCESS)
+}
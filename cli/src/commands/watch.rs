@@ -0,0 +1,100 @@
+// Standard Uses
+use std::fmt::Write;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+// Crate Uses
+use crate::commands::build::CheckReport;
+use crate::output::{self, Format, GeneratedReport, VersionReport};
+use crate::EXIT_SUCCESS;
+
+// External Uses
+use comline_core::package::watch::{self, Watcher, DEFAULT_POLL_INTERVAL};
+use eyre::Result;
+use serde_derive::Serialize;
+
+
+#[derive(Serialize)]
+struct RebuildReport<'a> {
+    /// Paths that changed, relative to the package
+    changed: Vec<String>,
+    check: CheckReport,
+    version: Option<VersionReport<'a>>,
+    generated: Vec<GeneratedReport>,
+}
+
+/// Rebuilds the package whenever its configuration or schemas change, until
+/// `quit` is entered. Entering `build` freezes the current state into the CAS
+pub fn watch(format: Format, path: &Path, generate: bool, debounce: Duration) -> Result<u8> {
+    let mut watcher = Watcher::new(path, debounce);
+    rebuild(format, path, &[], generate);
+
+    // Commands are read on their own thread, so that files are polled meanwhile
+    let (sender, commands) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines().map_while(Result::ok) {
+            if sender.send(line).is_err() { break }
+        }
+    });
+
+    loop {
+        // Without a standard input the watch goes on until interrupted
+        if let Ok(command) = commands.try_recv() {
+            match command.trim() {
+                "build" | "b" => {
+                    if let Err(e) = super::build(format, path) { output::error(format, &e) }
+                }
+                "quit" | "q" => return Ok(EXIT_SUCCESS),
+                "" => {}
+                other => eprintln!("Unknown command '{}', expected `build` or `quit`", other),
+            }
+        }
+
+        if let Some(changed) = watcher.poll() {
+            rebuild(format, path, &changed, generate);
+        }
+        std::thread::sleep(DEFAULT_POLL_INTERVAL);
+    }
+}
+
+fn rebuild(format: Format, path: &Path, changed: &[PathBuf], generate: bool) {
+    let rebuild = match watch::rebuild(path, generate) {
+        Ok(rebuild) => rebuild,
+        Err(e) => return output::error(format, &e),
+    };
+
+    let report = RebuildReport {
+        changed: changed.iter()
+            .map(|p| p.strip_prefix(path).unwrap_or(p).display().to_string())
+            .collect(),
+        check: CheckReport::from(&rebuild.check),
+        version: rebuild.preview.as_ref().map(VersionReport::from),
+        generated: rebuild.generated.iter().map(GeneratedReport::from).collect(),
+    };
+
+    output::emit(format, &report, || {
+        let mut out = String::new();
+        if !report.changed.is_empty() {
+            writeln!(out, "Changed: {}", report.changed.join(", ")).unwrap();
+        }
+        out.push_str(&report.check.describe());
+
+        match &rebuild.preview {
+            Some(result) if result.version_changed() => {
+                let change = result.version_change().unwrap();
+                writeln!(out, "Next version would be {}", change).unwrap();
+                out.push_str(&output::describe_changes(result.schema_changes.as_ref()));
+            }
+            Some(result) => {
+                writeln!(out, "Version {} is up to date", result.current_version).unwrap();
+            }
+            None if report.check.valid => out.push_str("No frozen versions yet\n"),
+            None => {}
+        }
+        out.push_str(&output::describe_generated(&report.generated));
+        out.push_str("Watching for changes, enter `build` to freeze this version or `quit` to stop\n");
+        out
+    });
+}
@@ -0,0 +1,139 @@
+// Relative Modules
+mod commands;
+mod output;
+
+// Standard Uses
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+// Crate Uses
+use crate::output::Format;
+
+// External Uses
+use clap::{Parser, Subcommand};
+
+
+/// Exit code when the command succeeded
+pub const EXIT_SUCCESS: u8 = 0;
+/// Exit code when the command failed, or found problems in the package
+pub const EXIT_FAILURE: u8 = 1;
+/// Exit code when the command succeeded but breaking changes were detected
+pub const EXIT_BREAKING: u8 = 3;
+
+
+#[derive(Parser)]
+#[command(name = "comline", version, about = "Comline schema compiler and package manager")]
+struct Cli {
+    /// Output format
+    #[arg(long, global = true, value_enum, default_value_t = Format::Human)]
+    format: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Scaffold a new package with a `config.idp` and a `src/` directory
+    Init {
+        /// Directory of the new package
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Package name, defaults to the directory name
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// Compile the package and freeze a new version into its CAS.
+    /// Exits with code 3 if breaking changes were frozen
+    Build {
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+    /// Compile and validate the package without freezing anything
+    Check {
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+    /// Show changes against the latest frozen version.
+    /// Exits with code 3 if there are breaking changes
+    Diff {
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+    /// List frozen versions, newest first
+    Log {
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+    /// Generate code for the configured targets, or for a single language
+    Generate {
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Language to generate, as `name` or `name#requirement`, where the
+        /// requirement is on the version of the generator, e.g. `rust#^0.2`
+        #[arg(long)]
+        language: Option<String>,
+        /// Output directory when generating a single language
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Rewrite the package configuration and schemas in their canonical layout
+    Fmt {
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Only report unformatted files, exiting with code 1 if there are any
+        #[arg(long)]
+        check: bool,
+    },
+    /// Rebuild, validate and regenerate code whenever the configuration or a
+    /// schema changes, without freezing anything until `build` is entered
+    Watch {
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Do not generate code for the configured targets
+        #[arg(long)]
+        no_generate: bool,
+        /// Milliseconds files have to stay unchanged before rebuilding
+        #[arg(long, default_value_t = 300)]
+        debounce: u64,
+    },
+    /// Publish the latest frozen version into the configured registries
+    Publish {
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Only publish into the registry with this name
+        #[arg(long)]
+        registry: Option<String>,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Init { path, name } => commands::init(cli.format, &path, name),
+        Command::Build { path } => commands::build(cli.format, &path),
+        Command::Check { path } => commands::check(cli.format, &path),
+        Command::Diff { path } => commands::diff(cli.format, &path),
+        Command::Log { path } => commands::log(cli.format, &path),
+        Command::Generate { path, language, output } => {
+            commands::generate(cli.format, &path, language, output)
+        }
+        Command::Fmt { path, check } => commands::format(cli.format, &path, check),
+        Command::Watch { path, no_generate, debounce } => {
+            commands::watch(cli.format, &path, !no_generate, Duration::from_millis(debounce))
+        }
+        Command::Publish { path, registry } => {
+            commands::publish(cli.format, &path, registry.as_deref())
+        }
+    };
+
+    match result {
+        Ok(code) => ExitCode::from(code),
+        Err(e) => {
+            output::error(cli.format, &e);
+            ExitCode::from(EXIT_FAILURE)
+        }
+    }
+}
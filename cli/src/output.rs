@@ -0,0 +1,128 @@
+// Standard Uses
+use std::fmt::Write;
+
+// Crate Uses
+
+// External Uses
+use clap::ValueEnum;
+use comline_core::package::build::{BuildResult, GeneratedCode};
+use comline_core::schema::ir::diff::SchemaChanges;
+use serde_derive::Serialize;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Readable text
+    Human,
+    /// A single JSON document on stdout
+    Json,
+}
+
+/// Print a command report, either as JSON or through its human rendering
+pub fn emit<T: serde::Serialize>(format: Format, report: &T, human: impl FnOnce() -> String) {
+    match format {
+        Format::Human => print!("{}", human()),
+        Format::Json => println!(
+            "{}", serde_json::to_string_pretty(report).expect("Reports are always serializable")
+        ),
+    }
+}
+
+pub fn error(format: Format, error: &eyre::Report) {
+    match format {
+        Format::Human => eprintln!("error: {:#}", error),
+        Format::Json => println!("{}", serde_json::json!({ "error": format!("{:#}", error) })),
+    }
+}
+
+/// Serializable view of a build or a diff
+#[derive(Serialize)]
+pub struct VersionReport<'a> {
+    pub previous_version: Option<&'a str>,
+    pub current_version: &'a str,
+    pub version_bump: String,
+    pub breaking: bool,
+    pub changes: Option<&'a SchemaChanges>,
+    pub generated: Vec<GeneratedReport>,
+}
+
+impl<'a> From<&'a BuildResult> for VersionReport<'a> {
+    fn from(result: &'a BuildResult) -> Self {
+        Self {
+            previous_version: result.previous_version.as_deref(),
+            current_version: &result.current_version,
+            version_bump: format!("{:?}", result.version_bump),
+            breaking: result.is_breaking(),
+            changes: result.schema_changes.as_ref(),
+            generated: result.generated.iter().map(GeneratedReport::from).collect(),
+        }
+    }
+}
+
+/// Serializable view of the code generated for a target
+#[derive(Serialize)]
+pub struct GeneratedReport {
+    pub language: String,
+    pub version: String,
+    pub path: String,
+    pub files: Vec<String>,
+}
+
+impl From<&GeneratedCode> for GeneratedReport {
+    fn from(generated: &GeneratedCode) -> Self {
+        Self {
+            language: generated.language.clone(),
+            version: generated.version.clone(),
+            path: generated.path.display().to_string(),
+            files: generated.files.iter().map(|f| f.display().to_string()).collect(),
+        }
+    }
+}
+
+pub fn describe_generated(generated: &[GeneratedReport]) -> String {
+    let mut out = String::new();
+
+    for target in generated {
+        let language = match target.version.as_str() {
+            "" => target.language.clone(),
+            version => format!("{} {}", target.language, version),
+        };
+        writeln!(
+            out, "Generated {} code into '{}' ({} file(s))", language, target.path, target.files.len()
+        ).unwrap();
+    }
+
+    out
+}
+
+pub fn describe_changes(changes: Option<&SchemaChanges>) -> String {
+    let mut out = String::new();
+
+    let Some(changes) = changes.filter(|c| !c.is_empty()) else {
+        out.push_str("No schema changes\n");
+        return out
+    };
+
+    if !changes.breaking_changes.is_empty() {
+        writeln!(out, "Breaking changes ({}):", changes.breaking_changes.len()).unwrap();
+        for change in &changes.breaking_changes {
+            writeln!(out, "  - {}", change).unwrap();
+        }
+    }
+
+    if !changes.new_features.is_empty() {
+        writeln!(out, "New features ({}):", changes.new_features.len()).unwrap();
+        for feature in &changes.new_features {
+            writeln!(out, "  + {}", feature).unwrap();
+        }
+    }
+
+    if !changes.modifications.is_empty() {
+        writeln!(out, "Modifications ({}):", changes.modifications.len()).unwrap();
+        for modification in &changes.modifications {
+            writeln!(out, "  ~ {}", modification).unwrap();
+        }
+    }
+
+    out
+}
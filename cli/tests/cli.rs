@@ -0,0 +1,161 @@
+// Standard Uses
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+// External Uses
+use tempfile::TempDir;
+
+
+fn comline(args: &[&str], cwd: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_comline"))
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .expect("Failed to run comline")
+}
+
+#[test]
+fn init_build_and_diff() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+
+    let output = comline(&["init", "pkg", "--name", "pkg"], root);
+    assert!(output.status.success());
+    assert!(root.join("pkg/config.idp").exists());
+    assert!(root.join("pkg/src/pkg.ids").exists());
+
+    // A second init over an existing package is refused
+    assert_eq!(comline(&["init", "pkg"], root).status.code(), Some(1));
+
+    let output = comline(&["build", "pkg", "--format", "json"], root);
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["current_version"], "0.0.1");
+
+    std::fs::write(root.join("pkg/src/pkg.ids"), "struct Other {\n    a: u8\n}\n").unwrap();
+
+    // Removing `Greeting` is breaking, which is reflected in the exit code
+    let output = comline(&["diff", "pkg", "--format", "json"], root);
+    assert_eq!(output.status.code(), Some(3));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["breaking"], true);
+    assert_eq!(report["current_version"], "1.0.0");
+
+    let output = comline(&["log", "pkg", "--format", "json"], root);
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report.as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn check_fails_on_invalid_schemas() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+
+    assert!(comline(&["init", "pkg"], root).status.success());
+    std::fs::write(root.join("pkg/src/pkg.ids"), "struct A {\n    b: Missing\n}\n").unwrap();
+
+    let output = comline(&["check", "pkg", "--format", "json"], root);
+    assert_eq!(output.status.code(), Some(1));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["valid"], false);
+
+    let output = comline(&["check", "missing"], root);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no configuration file"));
+}
+
+#[test]
+fn check_reports_lints() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+
+    assert!(comline(&["init", "pkg"], root).status.success());
+    std::fs::write(root.join("pkg/src/pkg.ids"), "struct Empty {}
+").unwrap();
+
+    // Lints are warnings by default
+    let output = comline(&["check", "pkg"], root);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("warning[empty_structs]: Struct 'Empty' has no fields"));
+    assert!(stdout.contains("--> pkg:1:8"));
+
+    std::fs::write(
+        root.join("pkg/config.idp"),
+        "congregation pkg\nspecification_version = 1\nlints = {\n    empty_structs = deny\n}\n",
+    ).unwrap();
+
+    let output = comline(&["check", "pkg", "--format", "json"], root);
+    assert_eq!(output.status.code(), Some(1));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["valid"], false);
+    assert_eq!(report["lints"][0]["rule"], "empty_structs");
+    assert_eq!(report["lints"][0]["level"], "deny");
+}
+
+#[test]
+fn generate_checks_the_generator_version() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+
+    assert!(comline(&["init", "pkg"], root).status.success());
+
+    let output = comline(&["generate", "pkg", "--language", "rust#^0.1"], root);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("which does not satisfy the requirement '^0.1'"),
+        "{}", String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = comline(&["generate", "pkg", "--language", "rust#latest"], root);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is not a version requirement"));
+
+    assert!(comline(&["generate", "pkg", "--language", "rust#^0.2"], root).status.success());
+    assert!(root.join("pkg/generated/rust").exists());
+}
+
+#[test]
+fn fmt_checks_and_rewrites() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+
+    assert!(comline(&["init", "pkg"], root).status.success());
+    assert_eq!(comline(&["fmt", "pkg", "--check"], root).status.code(), Some(0));
+
+    std::fs::write(root.join("pkg/src/pkg.ids"), "struct A { b:u8 }").unwrap();
+
+    let output = comline(&["fmt", "pkg", "--check", "--format", "json"], root);
+    assert_eq!(output.status.code(), Some(1));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["unformatted"].as_array().unwrap().len(), 1);
+
+    assert!(comline(&["fmt", "pkg"], root).status.success());
+    let formatted = std::fs::read_to_string(root.join("pkg/src/pkg.ids")).unwrap();
+    assert_eq!(formatted, "struct A {\n    b: u8\n}\n");
+    assert_eq!(comline(&["fmt", "pkg", "--check"], root).status.code(), Some(0));
+}
+
+#[test]
+fn watch_rebuilds_until_quit() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    assert!(comline(&["init", "pkg"], root).status.success());
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_comline"))
+        .args(["watch", "pkg", "--no-generate"])
+        .current_dir(root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to run comline");
+    child.stdin.take().unwrap().write_all(b"build\nquit\n").unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("No frozen versions yet"), "{}", stdout);
+    assert!(stdout.contains("Built initial version 0.0.1"), "{}", stdout);
+    assert!(root.join("pkg/.comline").exists());
+}
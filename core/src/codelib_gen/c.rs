@@ -0,0 +1,392 @@
+// Relative Modules
+
+// Standard Uses
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+// Crate Uses
+use crate::codelib_gen::common::{
+    constant_value, docs, octal_escape, schema_type_name, split_arrays, string_literal, DocStyle
+};
+use crate::codelib_gen::{GeneratedFile, GenerationContext, SchemaUnits};
+use crate::schema::ir::frozen::unit::FrozenUnit;
+use crate::schema::ir::compiler::interpreted::kind_search::{KindValue, Primitive};
+use crate::utils::case::to_screaming_snake_case;
+
+// External Uses
+use eyre::{bail, Result};
+
+
+/// Words C reserves, which generated names are suffixed from
+const KEYWORDS: &[&str] = &[
+    "auto", "bool", "break", "case", "char", "const", "continue", "default", "do", "double",
+    "else", "enum", "extern", "false", "float", "for", "goto", "if", "inline", "int", "long",
+    "register", "restrict", "return", "short", "signed", "sizeof", "static", "struct",
+    "switch", "true", "typedef", "union", "unsigned", "void", "volatile", "while",
+];
+
+const HEADER: &str = "/* Generated by Comline */\n";
+
+
+/// How fields whose length is only known at runtime are laid out
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LengthConvention {
+    /// They have no representation, so generation fails on them
+    None,
+    /// Strings are pointers to null terminated characters, and dynamic arrays
+    /// pointers to their first element along with a count of them
+    Pointer,
+}
+
+/// What is being generated of a schema
+struct Header<'a> {
+    context: &'a GenerationContext,
+    schema: &'a SchemaUnits,
+    lengths: LengthConvention,
+    /// Headers of other schemas the types of which are used
+    includes: BTreeSet<Vec<String>>,
+    /// Whether `size_t` is used
+    sizes: bool,
+    /// Fields with no representation, described
+    unrepresentable: Vec<String>,
+}
+
+impl Header<'_> {
+    /// The C name of a type used in this schema, which is prefixed by the
+    /// namespace declaring it
+    fn type_name(&mut self, type_name: &str) -> String {
+        if self.schema.declares(type_name) {
+            return prefixed(&self.schema.namespace, type_name)
+        }
+
+        match self.context.resolve_type(self.schema, type_name) {
+            Some((namespace, name)) => {
+                let type_name = prefixed(&namespace, &name);
+                self.includes.insert(namespace);
+                type_name
+            }
+            None => type_name.to_owned(),
+        }
+    }
+}
+
+
+/// Generates a header for every schema, at paths mirroring their namespaces
+/// Options:
+/// - `length_convention = pointer` generates strings as null terminated
+///   character pointers and dynamic arrays as a pointer and a count, without
+///   which they fail the generation
+/// - `constants = static` generates constants as `static const` rather than
+///   `#define`
+///
+/// Declarations are prefixed with the namespace of their schema, as C has a
+/// single one
+pub fn generate_c_package(context: &GenerationContext) -> Result<Vec<GeneratedFile>> {
+    let lengths = match context.option("length_convention") {
+        None => LengthConvention::None,
+        Some("pointer") => LengthConvention::Pointer,
+        Some(other) => bail!("Unknown length convention '{}', the known one is 'pointer'", other),
+    };
+    let static_constants = context.option("constants") == Some("static");
+
+    let mut files = vec![];
+    let mut unrepresentable = vec![];
+
+    for schema in &context.schemas {
+        let mut header = Header {
+            context, schema, lengths,
+            includes: BTreeSet::new(), sizes: false, unrepresentable: vec![],
+        };
+        let contents = generate_header(&schema.units, static_constants, &mut header);
+        unrepresentable.append(&mut header.unrepresentable);
+
+        let path = PathBuf::from(schema.namespace.join("/")).with_extension("h");
+        files.push(GeneratedFile { path, contents });
+    }
+
+    if !unrepresentable.is_empty() {
+        bail!(
+            "Some fields have no C representation, `length_convention = pointer` gives \
+            strings and dynamic arrays one:\n{}",
+            unrepresentable.iter().map(|d| format!("  - {}", d)).collect::<Vec<_>>().join("\n")
+        )
+    }
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Generates the header of a single schema, with no prefixes and pointers for
+/// lengths, leaving types of other schemas as named
+pub fn generate_c(units: &[FrozenUnit]) -> Result<String> {
+    let context = GenerationContext {
+        package_name: String::new(),
+        package_version: String::new(),
+        schemas: vec![SchemaUnits { namespace: vec![], units: units.to_vec() }],
+        options: BTreeMap::from([("length_convention".to_owned(), "pointer".to_owned())]),
+        history: vec![],
+    };
+
+    let mut files = generate_c_package(&context)?;
+    Ok(files.remove(0).contents)
+}
+
+fn generate_header(units: &[FrozenUnit], static_constants: bool, header: &mut Header) -> String {
+    let namespace = header.schema.namespace.clone();
+    let mut code = String::new();
+
+    for unit in units {
+        if let FrozenUnit::Constant { docstring, name, kind_value } = unit {
+            let name = prefixed(&namespace, name).to_uppercase();
+            code.push_str(&generate_constant(docstring.as_deref(), &name, kind_value, static_constants));
+        }
+    }
+
+    // Structures are declared ahead, so they can point to each other
+    let structures: Vec<String> = units.iter().filter_map(|unit| match unit {
+        FrozenUnit::Struct { name, .. } => Some(prefixed(&namespace, name)),
+        _ => None,
+    }).collect();
+    for name in &structures {
+        code.push_str(&format!("typedef struct {} {};\n", name, name));
+    }
+    if !structures.is_empty() { code.push('\n') }
+
+    for unit in dependency_order(units) {
+        match unit {
+            FrozenUnit::Struct { docstring, name, fields, .. } => {
+                code.push_str(&generate_struct(docstring.as_deref(), name, fields, header));
+            }
+            FrozenUnit::Enum { docstring, name, variants } => {
+                code.push_str(&generate_enum(docstring.as_deref(), &namespace, name, variants));
+            }
+            _ => {}
+        }
+    }
+
+    let guard = match namespace.is_empty() {
+        true => "COMLINE_SCHEMA_H".to_owned(),
+        false => format!("{}_H", namespace.join("_").to_uppercase()),
+    };
+
+    let mut output = format!("{}#ifndef {}\n#define {}\n\n", HEADER, guard, guard);
+    output.push_str("#include <stdbool.h>\n");
+    if header.sizes { output.push_str("#include <stddef.h>\n") }
+    output.push_str("#include <stdint.h>\n");
+    if !header.includes.is_empty() {
+        output.push('\n');
+        for included in &header.includes {
+            output.push_str(&format!("#include \"{}\"\n", include_path(&namespace, included)));
+        }
+    }
+    output.push_str("\n\n");
+    output.push_str(&code);
+    output.push_str(&format!("#endif /* {} */\n", guard));
+
+    output
+}
+
+/// Structures and enums in the order they are declared, except for those
+/// held by others, which C needs defined before
+fn dependency_order(units: &[FrozenUnit]) -> Vec<&FrozenUnit> {
+    fn visit<'a>(unit: &'a FrozenUnit, units: &'a [FrozenUnit], order: &mut Vec<&'a FrozenUnit>) {
+        if order.iter().any(|visited| std::ptr::eq(*visited, unit)) { return }
+
+        if let FrozenUnit::Struct { fields, .. } = unit {
+            // Marked as visited first, so structures holding each other do not
+            // recurse forever
+            let position = order.len();
+            order.push(unit);
+
+            for field in fields {
+                let FrozenUnit::Field { kind_value: KindValue::Namespaced(kind, _), .. } = field else {
+                    continue
+                };
+                let element = kind.split('[').next().unwrap_or(kind);
+                let held = units.iter().find(|unit| match unit {
+                    FrozenUnit::Struct { name, .. } | FrozenUnit::Enum { name, .. } => name == element,
+                    _ => false,
+                });
+                if let Some(held) = held { visit(held, units, order) }
+            }
+
+            order.remove(position);
+        }
+
+        order.push(unit);
+    }
+
+    let mut order = vec![];
+    for unit in units {
+        if matches!(unit, FrozenUnit::Struct { .. } | FrozenUnit::Enum { .. }) {
+            visit(unit, units, &mut order);
+        }
+    }
+
+    order
+}
+
+fn generate_constant(
+    docstring: Option<&str>, name: &str, kind_value: &KindValue, static_constants: bool
+) -> String {
+    let Some((primitive, value)) = constant_value(kind_value) else { return String::new() };
+
+    let (declaration, definition) = match primitive {
+        Primitive::String(_) => {
+            let literal = string_literal(&value, octal_escape);
+            (format!("static const char *const {} = {};", name, literal), literal)
+        }
+        Primitive::Boolean(_) => (format!("static const bool {} = {};", name, value), value),
+        primitive => {
+            let Some(type_name) = integer_type(primitive.name()) else { return String::new() };
+            let width = type_name.trim_end_matches("_t").to_uppercase();
+            let declaration = format!("static const {} {} = {};", type_name, name, value);
+            (declaration, format!("{}_C({})", width, value))
+        }
+    };
+
+    let mut s = docs(docstring, "", DocStyle::Block);
+    match static_constants {
+        true => s.push_str(&format!("{}\n\n", declaration)),
+        false => s.push_str(&format!("#define {} {}\n\n", name, definition)),
+    }
+    s
+}
+
+fn generate_struct(
+    docstring: Option<&str>, name: &str, fields: &[FrozenUnit], header: &mut Header
+) -> String {
+    let mut s = docs(docstring, "", DocStyle::Block);
+    s.push_str(&format!("struct {} {{\n", prefixed(&header.schema.namespace, name)));
+
+    for field in fields {
+        let FrozenUnit::Field { docstring, name: field_name, kind_value, optional, .. } = field else {
+            continue
+        };
+
+        let declarations = match field_declarations(&identifier(field_name), kind_value, header) {
+            Ok(declarations) => declarations,
+            Err(reason) => {
+                let path = header.schema.namespace.iter().map(String::as_str)
+                    .chain([name, field_name.as_str()])
+                    .collect::<Vec<_>>().join("::");
+                header.unrepresentable.push(format!("'{}' {}", path, reason));
+                continue
+            }
+        };
+
+        s.push_str(&docs(docstring.as_deref(), "    ", DocStyle::Block));
+        if *optional {
+            s.push_str(&format!("    bool has_{};\n", field_name));
+        }
+        for declaration in declarations {
+            s.push_str(&format!("    {};\n", declaration));
+        }
+    }
+
+    s.push_str("};\n\n");
+    s
+}
+
+/// Enums have their variants numbered explicitly, prefixed by the enum
+fn generate_enum(
+    docstring: Option<&str>, namespace: &[String], name: &str, variants: &[FrozenUnit]
+) -> String {
+    let type_name = prefixed(namespace, name);
+    let constant_prefix = prefixed(namespace, &to_screaming_snake_case(name)).to_uppercase();
+    let names = variants.iter().filter_map(|variant| match variant {
+        FrozenUnit::EnumVariant(KindValue::EnumVariant(name, _)) => Some(name.as_str()),
+        _ => None,
+    });
+
+    let mut s = docs(docstring, "", DocStyle::Block);
+    s.push_str(&format!("typedef enum {} {{\n", type_name));
+    for (index, variant) in names.enumerate() {
+        s.push_str(&format!("    {}_{} = {},\n", constant_prefix, to_screaming_snake_case(variant), index));
+    }
+    s.push_str(&format!("}} {};\n\n", type_name));
+    s
+}
+
+/// Declarations a field is laid out as, or why it cannot be
+fn field_declarations(
+    name: &str, kind: &KindValue, header: &mut Header
+) -> Result<Vec<String>, String> {
+    if let KindValue::Union(_) = kind {
+        return Err("is a union, which needs a tag to be told apart".to_owned())
+    }
+    let schema_type = schema_type_name(kind, " | ");
+
+    // Sizes of the arrays, outermost first, as C declares them
+    let (element, sizes) = split_arrays(&schema_type);
+
+    let pointer = header.lengths == LengthConvention::Pointer;
+    let dynamic = match sizes.iter().position(|size| size.is_empty()) {
+        None => false,
+        Some(0) if pointer => true,
+        Some(_) if pointer => return Err(format!("is `{}`, only outermost arrays may be dynamic", schema_type)),
+        Some(_) => return Err(format!("is `{}`, a dynamic array", schema_type)),
+    };
+    let fixed: String = sizes.iter().skip(dynamic as usize).map(|size| format!("[{}]", size)).collect();
+
+    let element_type = match element {
+        "str" | "string" if pointer => "const char *".to_owned(),
+        "str" | "string" => return Err(format!("is `{}`, a string", schema_type)),
+        "bool" => "bool ".to_owned(),
+        "f32" | "float" => "float ".to_owned(),
+        "f64" => "double ".to_owned(),
+        "u128" | "i128" => return Err(format!("is `{}`, which has no standard C type", schema_type)),
+        other => match integer_type(other) {
+            Some(integer) => format!("{} ", integer),
+            None => format!("{} ", header.type_name(other)),
+        },
+    };
+
+    Ok(match dynamic {
+        true => {
+            header.sizes = true;
+            let declarator = match fixed.is_empty() {
+                true => format!("*{}", name),
+                false => format!("(*{}){}", name, fixed),
+            };
+            vec![format!("{}{}", element_type, declarator), format!("size_t {}_count", name)]
+        }
+        false => vec![format!("{}{}{}", element_type, name, fixed)],
+    })
+}
+
+fn integer_type(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "u8" => "uint8_t", "u16" => "uint16_t", "u32" => "uint32_t", "u64" => "uint64_t",
+        "i8" => "int8_t", "i16" => "int16_t", "i32" | "int" => "int32_t", "i64" => "int64_t",
+        _ => return None,
+    })
+}
+
+/// `types_Role` for `Role` declared in `types`
+fn prefixed(namespace: &[String], name: &str) -> String {
+    match namespace.is_empty() {
+        true => name.to_owned(),
+        false => format!("{}_{}", namespace.join("_"), name),
+    }
+}
+
+/// The path a header at `from` includes the header at `to` with
+fn include_path(from: &[String], to: &[String]) -> String {
+    let from_directory = &from[..from.len().saturating_sub(1)];
+    let to_directory = &to[..to.len().saturating_sub(1)];
+    let common = from_directory.iter().zip(to_directory)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    format!("{}{}.h", "../".repeat(from_directory.len() - common), to[common..].join("/"))
+}
+
+/// Names that are C keywords are suffixed
+fn identifier(name: &str) -> String {
+    match KEYWORDS.contains(&name) {
+        true => format!("{}_", name),
+        false => name.to_owned(),
+    }
+}
+
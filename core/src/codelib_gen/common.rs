@@ -0,0 +1,151 @@
+// Helpers every generator writes types, constants and docs with
+
+// Relative Modules
+
+// Standard Uses
+
+// Crate Uses
+use crate::schema::ir::compiler::interpreted::kind_search::{KindValue, Primitive};
+
+// External Uses
+
+
+/// How docstrings are written in the generated language
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DocStyle {
+    /// A comment on each line starting with the marker, like `///` or `#`
+    Lines(&'static str),
+    /// A `/** */` block, on one line for single line docstrings
+    Block,
+    /// A Python docstring statement, after what it documents
+    Statement,
+}
+
+
+/// The name a kind has in schemas, with the kinds of unions joined by the
+/// separator, as in `str | u8[]`
+pub(crate) fn schema_type_name(kind: &KindValue, separator: &str) -> String {
+    match kind {
+        KindValue::Primitive(Primitive::String(_)) => "str".to_owned(),
+        KindValue::Primitive(primitive) => primitive.name().to_owned(),
+        KindValue::Namespaced(name, _) | KindValue::EnumVariant(name, _) => name.clone(),
+        KindValue::Union(kinds) => kinds.iter()
+            .map(|kind| schema_type_name(kind, separator))
+            .collect::<Vec<_>>().join(separator),
+    }
+}
+
+/// The element and size of the outermost array of a type as schemas write it,
+/// which is the last pair of brackets, as in `u8[4][]`, and the size is empty
+/// for dynamic arrays
+pub(crate) fn outer_array(schema_type: &str) -> Option<(&str, &str)> {
+    schema_type.strip_suffix(']')?.rsplit_once('[')
+}
+
+/// The innermost element of a type and the sizes of its arrays, outermost
+/// first, where dynamic arrays have empty sizes
+pub(crate) fn split_arrays(schema_type: &str) -> (&str, Vec<&str>) {
+    let mut element = schema_type;
+    let mut sizes = vec![];
+    while let Some((inner, size)) = outer_array(element) {
+        sizes.push(size);
+        element = inner;
+    }
+
+    (element, sizes)
+}
+
+/// Whether a type name is one of the primitives of schemas
+pub(crate) fn is_primitive(name: &str) -> bool {
+    matches!(
+        name,
+        "bool" | "str" | "string" | "float" | "int" | "f32" | "f64"
+            | "u8" | "u16" | "u32" | "u64" | "u128" | "i8" | "i16" | "i32" | "i64" | "i128"
+    )
+}
+
+/// The primitive of a constant with the value it is given, as written, where
+/// constants without a value have nothing they could be generated as
+pub(crate) fn constant_value(kind_value: &KindValue) -> Option<(&Primitive, String)> {
+    let KindValue::Primitive(primitive) = kind_value else { return None };
+
+    primitive.value_str().map(|value| (primitive, value))
+}
+
+/// A double quoted string literal, with control chars escaped as the
+/// generated language writes them
+pub(crate) fn string_literal(value: &str, control: fn(char) -> String) -> String {
+    let mut literal = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c if c.is_control() => literal.push_str(&control(c)),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// Documentation of a docstring in the style given, empty docstrings have none
+pub(crate) fn docs(docstring: Option<&str>, indent: &str, style: DocStyle) -> String {
+    let Some(docstring) = docstring.filter(|d| !d.is_empty()) else { return String::new() };
+
+    match style {
+        DocStyle::Lines(marker) => docstring.lines()
+            .map(|line| match line.is_empty() {
+                true => format!("{}{}\n", indent, marker),
+                false => format!("{}{} {}\n", indent, marker, line),
+            })
+            .collect(),
+        DocStyle::Block => {
+            let escaped = docstring.replace("*/", "* /");
+            block(&escaped, indent, ("/** ", " */"), ("/**", " *", " */"))
+        }
+        DocStyle::Statement => {
+            let escaped = docstring.replace('\\', "\\\\").replace("\"\"\"", "\\\"\\\"\\\"");
+            block(&escaped, indent, ("\"\"\"", "\"\"\""), ("\"\"\"", "", "\"\"\""))
+        }
+    }
+}
+
+/// Lines between an opening and a closing, or the single line within them
+fn block(
+    text: &str, indent: &str, (before, after): (&str, &str), (open, prefix, close): (&str, &str, &str)
+) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    if let [line] = lines.as_slice() {
+        return format!("{}{}{}{}\n", indent, before, line, after)
+    }
+
+    let mut s = format!("{}{}\n", indent, open);
+    for line in lines {
+        match (line.is_empty(), prefix.is_empty()) {
+            (true, true) => s.push('\n'),
+            (true, false) => s.push_str(&format!("{}{}\n", indent, prefix)),
+            (false, true) => s.push_str(&format!("{}{}\n", indent, line)),
+            (false, false) => s.push_str(&format!("{}{} {}\n", indent, prefix, line)),
+        }
+    }
+    s.push_str(&format!("{}{}\n", indent, close));
+    s
+}
+
+/// `\u000b`, as Go and Python escape control chars
+pub(crate) fn unicode_escape(c: char) -> String {
+    format!("\\u{:04x}", c as u32)
+}
+
+/// `\u{b}`, as TypeScript and Rust escape control chars
+pub(crate) fn braced_unicode_escape(c: char) -> String {
+    format!("\\u{{{:x}}}", c as u32)
+}
+
+/// `\013`, as C escapes control chars
+pub(crate) fn octal_escape(c: char) -> String {
+    format!("\\{:03o}", c as u32)
+}
@@ -0,0 +1,129 @@
+// Relative Modules
+
+// Standard Uses
+
+// Crate Uses
+use crate::codelib_gen::SchemaUnits;
+use crate::schema::ir::frozen::unit::FrozenUnit;
+use crate::schema::ir::compiler::interpreted::kind_search::KindValue;
+use crate::schema::ir::expression::VALIDATORS_PARAMETER;
+
+// External Uses
+use serde_derive::Serialize;
+
+
+/// Field parameter giving the value of a field left out, like `@default=8080`
+pub const DEFAULT_PARAMETER: &str = "default";
+
+/// Maps are the `HashMap` of the standard library, unless a schema declares
+/// its own
+pub const MAP_TYPE: &str = "HashMap";
+
+
+/// Features of the IDL that not every generator can emit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Feature {
+    Unions,
+    Maps,
+    Errors,
+    Defaults,
+    Validators,
+}
+
+impl Feature {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Feature::Unions => "unions",
+            Feature::Maps => "maps",
+            Feature::Errors => "errors",
+            Feature::Defaults => "defaults",
+            Feature::Validators => "validators",
+        }
+    }
+}
+
+/// A feature a schema uses, with the item that uses it, qualified by the
+/// namespace of the schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeatureUse {
+    pub feature: Feature,
+    pub item: String,
+}
+
+
+/// Every use of a feature in the schema, in the order of its items
+pub fn features_used(schema: &SchemaUnits) -> Vec<FeatureUse> {
+    let mut uses = vec![];
+    let mut using = |feature: Feature, path: &[&str]| {
+        let item = schema.namespace.iter().map(String::as_str)
+            .chain(path.iter().copied())
+            .collect::<Vec<_>>().join("::");
+        uses.push(FeatureUse { feature, item });
+    };
+
+    for unit in &schema.units {
+        match unit {
+            FrozenUnit::Constant { name, kind_value, .. } => {
+                for feature in kind_features(schema, kind_value) { using(feature, &[name]) }
+            }
+            FrozenUnit::Struct { name, fields, .. } => {
+                for (field, feature) in field_features(schema, fields) { using(feature, &[name, field]) }
+            }
+            FrozenUnit::Error { name, fields, .. } => {
+                using(Feature::Errors, &[name]);
+                for (field, feature) in field_features(schema, fields) { using(feature, &[name, field]) }
+            }
+            FrozenUnit::Protocol { name: protocol, functions, .. } => {
+                for function in functions {
+                    let FrozenUnit::Function { name, arguments, _return, throws, .. } = function else { continue };
+
+                    let kinds = arguments.iter().map(|argument| &argument.kind).chain(_return);
+                    let mut features: Vec<Feature> = kinds.flat_map(|kind| kind_features(schema, kind)).collect();
+                    if !throws.is_empty() { features.push(Feature::Errors) }
+                    features.sort();
+                    features.dedup();
+
+                    for feature in features { using(feature, &[protocol, name]) }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    uses
+}
+
+fn field_features<'a>(schema: &SchemaUnits, fields: &'a [FrozenUnit]) -> Vec<(&'a str, Feature)> {
+    let mut features = vec![];
+
+    for field in fields {
+        let FrozenUnit::Field { name, kind_value, parameters, .. } = field else { continue };
+
+        for feature in kind_features(schema, kind_value) { features.push((name.as_str(), feature)) }
+        let given = |parameter_name: &str| parameters.iter().any(|parameter| matches!(
+            parameter, FrozenUnit::Parameter { name, .. } if name == parameter_name
+        ));
+        if given(DEFAULT_PARAMETER) { features.push((name.as_str(), Feature::Defaults)) }
+        if given(VALIDATORS_PARAMETER) { features.push((name.as_str(), Feature::Validators)) }
+    }
+
+    features
+}
+
+fn kind_features(schema: &SchemaUnits, kind: &KindValue) -> Vec<Feature> {
+    match kind {
+        KindValue::Union(_) => vec![Feature::Unions],
+        KindValue::Namespaced(name, _) => {
+            // Arrays of maps use maps as well
+            let element = name.split('[').next().unwrap_or(name);
+            let type_name = element.rsplit("::").next().unwrap_or(element);
+
+            match type_name == MAP_TYPE && !schema.declares(MAP_TYPE) {
+                true => vec![Feature::Maps],
+                false => vec![],
+            }
+        }
+        KindValue::Primitive(_) | KindValue::EnumVariant(..) => vec![],
+    }
+}
@@ -0,0 +1,393 @@
+// Relative Modules
+
+// Standard Uses
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+// Crate Uses
+use crate::codelib_gen::common::{
+    constant_value, docs, outer_array, string_literal, unicode_escape, DocStyle
+};
+use crate::codelib_gen::{GeneratedFile, GenerationContext, SchemaUnits};
+use crate::schema::ir::frozen::unit::FrozenUnit;
+use crate::schema::ir::compiler::interpreted::kind_search::{KindValue, Primitive};
+use crate::utils::case::to_pascal_case;
+
+// External Uses
+use eyre::{bail, Result};
+
+
+/// Words Go reserves, which package names are suffixed from
+const KEYWORDS: &[&str] = &[
+    "break", "case", "chan", "const", "continue", "default", "defer", "else", "fallthrough",
+    "for", "func", "go", "goto", "if", "import", "interface", "map", "package", "range",
+    "return", "select", "struct", "switch", "type", "var",
+];
+
+const HEADER: &str = "// Code generated by Comline. DO NOT EDIT.\n";
+
+
+/// What is being generated of a schema
+struct Package<'a> {
+    context: &'a GenerationContext,
+    schema: &'a SchemaUnits,
+    /// Path other packages are imported under
+    module_prefix: &'a str,
+    /// Packages of other schemas, by import path, with the name each is
+    /// referred to by
+    imports: BTreeMap<String, String>,
+    /// Namespaces of the other schemas used
+    dependencies: BTreeSet<Vec<String>>,
+    /// Packages of the standard library used
+    standard: BTreeSet<&'static str>,
+}
+
+impl Package<'_> {
+    /// The Go name of a type used in this schema, qualified by its package
+    /// when another schema declares it
+    fn type_name(&mut self, type_name: &str) -> String {
+        if self.schema.declares(type_name) { return type_name.to_owned() }
+
+        let Some((namespace, name)) = self.context.resolve_type(self.schema, type_name) else {
+            return type_name.to_owned()
+        };
+        let path = import_path(self.module_prefix, &namespace);
+
+        let qualifier = match self.imports.get(&path) {
+            Some(qualifier) => qualifier.clone(),
+            None => {
+                let mut qualifier = package_name(&namespace);
+                let taken = qualifier == package_name(&self.schema.namespace)
+                    || self.imports.values().any(|imported| *imported == qualifier);
+
+                // Clashing packages are aliased after the end of their path
+                if taken {
+                    let segments: Vec<&str> = path.split('/').collect();
+                    let end = &segments[segments.len().saturating_sub(namespace.len() + 1)..];
+                    qualifier = end.join("_").replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+                }
+
+                self.imports.insert(path, qualifier.clone());
+                qualifier
+            }
+        };
+        self.dependencies.insert(namespace);
+
+        format!("{}.{}", qualifier, name)
+    }
+}
+
+
+/// Generates a Go package for every schema, in directories mirroring their
+/// namespaces
+/// Options:
+/// - `module_prefix` is the import path the generated packages are under,
+///   the package name by default
+///
+/// As Go packages cannot import each other, neither can the schemas
+pub fn generate_go_package(context: &GenerationContext) -> Result<Vec<GeneratedFile>> {
+    let module_prefix = context.option("module_prefix").unwrap_or(&context.package_name);
+
+    let mut files = vec![];
+    let mut dependencies: BTreeMap<&[String], BTreeSet<Vec<String>>> = BTreeMap::new();
+
+    for schema in &context.schemas {
+        let mut package = Package {
+            context, schema, module_prefix,
+            imports: BTreeMap::new(), dependencies: BTreeSet::new(), standard: BTreeSet::new(),
+        };
+        let contents = generate_package(&schema.units, &mut package);
+        dependencies.insert(&schema.namespace, package.dependencies);
+
+        let directory = PathBuf::from(schema.namespace.join("/"));
+        let path = directory.join(format!("{}.go", package_name(&schema.namespace)));
+        files.push(GeneratedFile { path, contents });
+    }
+
+    if let Some(cycle) = import_cycle(&dependencies) {
+        bail!(
+            "Go packages cannot import each other, which the schemas {} do",
+            cycle.iter().map(|namespace| format!("'{}'", namespace.join("::"))).collect::<Vec<_>>().join(" -> ")
+        )
+    }
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Generates the code of a single schema, as package `name`, leaving types of
+/// other schemas as named
+pub fn generate_go(name: &str, units: &[FrozenUnit]) -> String {
+    let context = GenerationContext {
+        package_name: String::new(),
+        package_version: String::new(),
+        schemas: vec![],
+        options: BTreeMap::new(),
+        history: vec![],
+    };
+    let schema = SchemaUnits { namespace: vec![name.to_owned()], units: units.to_vec() };
+    let mut package = Package {
+        context: &context, schema: &schema, module_prefix: "",
+        imports: BTreeMap::new(), dependencies: BTreeSet::new(), standard: BTreeSet::new(),
+    };
+
+    generate_package(units, &mut package)
+}
+
+fn generate_package(units: &[FrozenUnit], package: &mut Package) -> String {
+    let mut code = String::new();
+
+    for unit in units {
+        match unit {
+            FrozenUnit::Constant { docstring, name, kind_value } => {
+                code.push_str(&generate_constant(docstring.as_deref(), name, kind_value));
+            }
+            FrozenUnit::Struct { docstring, name, fields, .. } => {
+                code.push_str(&generate_struct(docstring.as_deref(), name, fields, package));
+            }
+            FrozenUnit::Enum { docstring, name, variants } => {
+                package.standard.insert("fmt");
+                code.push_str(&generate_enum(docstring.as_deref(), name, variants));
+            }
+            FrozenUnit::Protocol { docstring, name, functions, .. } => {
+                package.standard.insert("context");
+                code.push_str(&generate_interface(docstring, name, functions, package));
+            }
+            _ => {}
+        }
+    }
+
+    let mut output = format!("{}\npackage {}\n\n", HEADER, package_name(&package.schema.namespace));
+
+    let mut imports: Vec<String> = package.standard.iter().map(|path| format!("\t\"{}\"\n", path)).collect();
+    if !package.standard.is_empty() && !package.imports.is_empty() { imports.push("\n".to_owned()) }
+    for (path, qualifier) in &package.imports {
+        match path.rsplit('/').next() == Some(qualifier.as_str()) {
+            true => imports.push(format!("\t\"{}\"\n", path)),
+            false => imports.push(format!("\t{} \"{}\"\n", qualifier, path)),
+        }
+    }
+    if !imports.is_empty() {
+        output.push_str(&format!("import (\n{})\n\n", imports.concat()));
+    }
+
+    output.push_str(&code);
+    output.truncate(output.trim_end().len());
+    output.push('\n');
+
+    output
+}
+
+fn generate_constant(docstring: Option<&str>, name: &str, kind_value: &KindValue) -> String {
+    let Some((primitive, value)) = constant_value(kind_value) else { return String::new() };
+
+    let (type_name, value) = match primitive {
+        Primitive::String(_) => ("string", string_literal(&value, unicode_escape)),
+        Primitive::Boolean(_) => ("bool", value),
+        primitive => match integer_type(primitive.name()) {
+            Some(type_name) => (type_name, value),
+            None => return String::new(),
+        },
+    };
+
+    let name = to_pascal_case(name);
+    let mut s = docs(docstring, "", DocStyle::Lines("//"));
+    s.push_str(&format!("const {} {} = {}\n\n", name, type_name, value));
+    s
+}
+
+fn generate_struct(
+    docstring: Option<&str>, name: &str, fields: &[FrozenUnit], package: &mut Package
+) -> String {
+    let mut lines = vec![];
+
+    for field in fields {
+        let FrozenUnit::Field { docstring, name, kind_value, optional, .. } = field else { continue };
+
+        let mut type_name = map_kind_to_go_type(kind_value, package);
+        let mut tag = name.clone();
+        if *optional {
+            type_name = format!("*{}", type_name);
+            tag.push_str(",omitempty");
+        }
+
+        lines.push((
+            docs(docstring.as_deref(), "\t", DocStyle::Lines("//")),
+            [to_pascal_case(name), type_name, format!("`json:\"{}\"`", tag)],
+        ));
+    }
+
+    let mut s = docs(docstring, "", DocStyle::Lines("//"));
+    s.push_str(&format!("type {} struct {{\n", name));
+    s.push_str(&aligned(&lines));
+    s.push_str("}\n\n");
+    s
+}
+
+/// Enums are typed integer constants numbering their variants, encoded as the
+/// names of them
+fn generate_enum(docstring: Option<&str>, name: &str, variants: &[FrozenUnit]) -> String {
+    let variants: Vec<&str> = variants.iter().filter_map(|variant| match variant {
+        FrozenUnit::EnumVariant(KindValue::EnumVariant(name, _)) => Some(name.as_str()),
+        _ => None,
+    }).collect();
+    let receiver = name.chars().next().unwrap_or('e').to_ascii_lowercase();
+
+    let mut s = docs(docstring, "", DocStyle::Lines("//"));
+    s.push_str(&format!("type {} int32\n\n", name));
+
+    let constants: Vec<(String, [String; 3])> = variants.iter().enumerate()
+        .map(|(index, variant)| {
+            (String::new(), [format!("{}{}", name, variant), name.to_owned(), format!("= {}", index)])
+        })
+        .collect();
+    s.push_str(&format!("const (\n{})\n\n", aligned(&constants)));
+
+    s.push_str(&format!("func ({} {}) String() string {{\n\tswitch {} {{\n", receiver, name, receiver));
+    for variant in &variants {
+        s.push_str(&format!("\tcase {}{}:\n\t\treturn \"{}\"\n", name, variant, variant));
+    }
+    s.push_str(&format!("\tdefault:\n\t\treturn fmt.Sprintf(\"{}(%d)\", int32({}))\n\t}}\n}}\n\n", name, receiver));
+
+    s.push_str(&format!(
+        "func ({} {}) MarshalText() ([]byte, error) {{\n\treturn []byte({}.String()), nil\n}}\n\n",
+        receiver, name, receiver
+    ));
+
+    s.push_str(&format!(
+        "func ({} *{}) UnmarshalText(text []byte) error {{\n\tswitch string(text) {{\n", receiver, name
+    ));
+    for variant in &variants {
+        s.push_str(&format!("\tcase \"{}\":\n\t\t*{} = {}{}\n", variant, receiver, name, variant));
+    }
+    s.push_str(&format!(
+        "\tdefault:\n\t\treturn fmt.Errorf(\"unknown {} %q\", text)\n\t}}\n\treturn nil\n}}\n\n", name
+    ));
+
+    s
+}
+
+/// An interface of the servers of the protocol, the calls of which take a
+/// context and may fail
+fn generate_interface(
+    docstring: &str, name: &str, functions: &[FrozenUnit], package: &mut Package
+) -> String {
+    let mut s = docs(Some(docstring), "", DocStyle::Lines("//"));
+    s.push_str(&format!("type {} interface {{\n", name));
+
+    for function in functions {
+        let FrozenUnit::Function { docstring, name, arguments, _return, .. } = function else {
+            continue
+        };
+
+        let parameters: String = arguments.iter()
+            .map(|arg| format!(", {} {}", arg.name, map_kind_to_go_type(&arg.kind, package)))
+            .collect();
+        let results = match _return {
+            Some(kind) => format!("({}, error)", map_kind_to_go_type(kind, package)),
+            None => "error".to_owned(),
+        };
+
+        s.push_str(&docs(Some(docstring), "\t", DocStyle::Lines("//")));
+        s.push_str(&format!("\t{}(ctx context.Context{}) {}\n", to_pascal_case(name), parameters, results));
+    }
+
+    s.push_str("}\n\n");
+    s
+}
+
+fn map_kind_to_go_type(kind: &KindValue, package: &mut Package) -> String {
+    match kind {
+        KindValue::Primitive(Primitive::String(_)) => "string".to_owned(),
+        KindValue::Primitive(primitive) => map_str_type(primitive.name(), package),
+        KindValue::Namespaced(name, _) => map_str_type(name, package),
+        KindValue::EnumVariant(name, _) => name.clone(),
+        // Values of any of the kinds are told apart by decoding them
+        KindValue::Union(_) => "any".to_owned(),
+    }
+}
+
+fn map_str_type(s: &str, package: &mut Package) -> String {
+    // The outermost array is the one Go writes first
+    if let Some((element, size)) = outer_array(s) {
+        return format!("[{}]{}", size, map_str_type(element, package))
+    }
+    match s {
+        "string" | "str" => "string".to_owned(),
+        "bool" => "bool".to_owned(),
+        "f32" => "float32".to_owned(),
+        "f64" | "float" => "float64".to_owned(),
+        "u128" | "i128" => {
+            package.standard.insert("math/big");
+            "*big.Int".to_owned()
+        }
+        other => match integer_type(other) {
+            Some(integer) => integer.to_owned(),
+            None => package.type_name(other),
+        },
+    }
+}
+
+fn integer_type(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "u8" => "uint8", "u16" => "uint16", "u32" => "uint32", "u64" => "uint64",
+        "i8" => "int8", "i16" => "int16", "i32" | "int" => "int32", "i64" => "int64",
+        _ => return None,
+    })
+}
+
+/// Namespaces of schemas importing each other, the first also being the last
+fn import_cycle(dependencies: &BTreeMap<&[String], BTreeSet<Vec<String>>>) -> Option<Vec<Vec<String>>> {
+    fn visit(
+        namespace: &[String], dependencies: &BTreeMap<&[String], BTreeSet<Vec<String>>>,
+        path: &mut Vec<Vec<String>>, done: &mut BTreeSet<Vec<String>>,
+    ) -> Option<Vec<Vec<String>>> {
+        if let Some(start) = path.iter().position(|visited| visited == namespace) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(namespace.to_vec());
+            return Some(cycle)
+        }
+        if done.contains(namespace) { return None }
+
+        path.push(namespace.to_vec());
+        for dependency in dependencies.get(namespace).into_iter().flatten() {
+            if let Some(cycle) = visit(dependency, dependencies, path, done) { return Some(cycle) }
+        }
+        path.pop();
+        done.insert(namespace.to_vec());
+
+        None
+    }
+
+    let mut done = BTreeSet::new();
+    dependencies.keys().find_map(|namespace| visit(namespace, dependencies, &mut vec![], &mut done))
+}
+
+fn import_path(module_prefix: &str, namespace: &[String]) -> String {
+    match module_prefix.is_empty() {
+        true => namespace.join("/"),
+        false => format!("{}/{}", module_prefix, namespace.join("/")),
+    }
+}
+
+/// Packages are named after the last segment of their namespace
+fn package_name(namespace: &[String]) -> String {
+    let name = namespace.last().map(|segment| segment.to_lowercase()).unwrap_or_default();
+    match KEYWORDS.contains(&name.as_str()) {
+        true => format!("{}_", name),
+        false => name,
+    }
+}
+
+/// Lines of columns, each after its comment, with the columns aligned as
+/// gofmt does
+fn aligned(lines: &[(String, [String; 3])]) -> String {
+    let widths = [0, 1].map(|column| {
+        lines.iter().map(|(_, columns)| columns[column].chars().count()).max().unwrap_or(0)
+    });
+
+    lines.iter().map(|(comment, [first, second, third])| {
+        format!("{comment}\t{first:w0$} {second:w1$} {third}\n", w0 = widths[0], w1 = widths[1])
+    }).collect()
+}
+
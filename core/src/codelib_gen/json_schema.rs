@@ -0,0 +1,269 @@
+// Relative Modules
+
+// Standard Uses
+use std::path::PathBuf;
+
+// Crate Uses
+use crate::codelib_gen::common::{outer_array, schema_type_name};
+use crate::codelib_gen::features::DEFAULT_PARAMETER;
+use crate::codelib_gen::{GeneratedFile, GenerationContext, SchemaUnits};
+use crate::schema::ir::frozen::unit::FrozenUnit;
+use crate::schema::ir::compiler::interpreted::kind_search::KindValue;
+
+// External Uses
+use eyre::{bail, Result};
+use serde_json::{json, Map, Value};
+
+
+const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Texts floats that are not numbers are written as
+const NON_FINITE: [&str; 3] = ["NaN", "Infinity", "-Infinity"];
+
+
+/// How the kinds of fields are described
+pub(crate) struct Types<'a> {
+    /// The schema of a type declared by name
+    pub reference: &'a dyn Fn(&str) -> Value,
+    /// Whether 64-bit integers are strings
+    pub int64_as_string: bool,
+}
+
+
+/// What is being generated of a schema
+struct Document<'a> {
+    context: &'a GenerationContext,
+    schema: &'a SchemaUnits,
+}
+
+impl Document<'_> {
+    /// A reference to the document of a type used in this schema, relative to
+    /// the documents of this schema
+    fn reference(&self, type_name: &str) -> Value {
+        let path = match self.context.resolve_type(self.schema, type_name) {
+            Some((namespace, name)) => {
+                let from = &self.schema.namespace;
+                let common = from.iter().zip(&namespace).take_while(|(a, b)| a == b).count();
+                let mut segments = vec![".."; from.len() - common];
+                segments.extend(namespace[common..].iter().map(String::as_str));
+                segments.push(&name);
+                format!("{}.schema.json", segments.join("/"))
+            }
+            None => format!("{}.schema.json", type_name),
+        };
+
+        json!({ "$ref": path })
+    }
+}
+
+
+/// Generates a JSON Schema document for every struct and enum, in directories
+/// mirroring the namespaces of their schemas
+/// Options:
+/// - `base_uri` gives documents an `$id` under it, against which references
+///   between them resolve, otherwise they resolve against where documents are
+///   retrieved from
+/// - `additional_properties = false` refuses properties structs do not declare
+/// - `int64_as_string = true` has 64-bit integers as strings, as canonical JSON
+///   written with `int64_as_string` does
+///
+/// Documents describe values as canonical JSON writes them: structs are
+/// objects, requiring their fields that are not optional, enums are the names
+/// of their variants, unions are an object of the kind of the value to the
+/// value, 128-bit integers are strings and floats may be `NaN`, `Infinity` or
+/// `-Infinity`. Fields with a `@default` have it as the default of their property
+pub fn generate_json_schema_package(context: &GenerationContext) -> Result<Vec<GeneratedFile>> {
+    let base_uri = context.option("base_uri").map(|uri| uri.trim_end_matches('/'));
+    let closed = match context.option("additional_properties") {
+        None | Some("true") => false,
+        Some("false") => true,
+        Some(other) => bail!("Option 'additional_properties' is either 'true' or 'false', not '{}'", other),
+    };
+    let int64_as_string = context.option("int64_as_string") == Some("true");
+
+    let mut files = vec![];
+
+    for schema in &context.schemas {
+        let document = Document { context, schema };
+        let directory = PathBuf::from(schema.namespace.join("/"));
+
+        for unit in &schema.units {
+            let (name, mut body) = match unit {
+                FrozenUnit::Struct { docstring, name, fields, .. } => {
+                    let types = Types { reference: &|type_name: &str| document.reference(type_name), int64_as_string };
+                    (name, generate_object(docstring.as_deref(), name, fields, closed, &types))
+                }
+                FrozenUnit::Enum { docstring, name, variants } => {
+                    (name, generate_enum(docstring.as_deref(), name, variants))
+                }
+                _ => continue,
+            };
+
+            let path = directory.join(format!("{}.schema.json", name));
+            let mut header = Map::new();
+            header.insert("$schema".to_owned(), json!(DIALECT));
+            if let Some(base_uri) = base_uri {
+                header.insert("$id".to_owned(), json!(format!("{}/{}", base_uri, path.display())));
+            }
+            header.append(&mut body);
+
+            let contents = serde_json::to_string_pretty(&Value::Object(header))? + "\n";
+            files.push(GeneratedFile { path, contents });
+        }
+    }
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Generates the documents of a single schema, leaving types of other schemas
+/// as named
+pub fn generate_json_schema(units: &[FrozenUnit]) -> Result<Vec<GeneratedFile>> {
+    let context = GenerationContext {
+        package_name: String::new(),
+        package_version: String::new(),
+        schemas: vec![SchemaUnits { namespace: vec![], units: units.to_vec() }],
+        options: Default::default(),
+        history: vec![],
+    };
+
+    generate_json_schema_package(&context)
+}
+
+/// The schema of a struct, with `types` describing the kinds of its fields
+pub(crate) fn generate_object(
+    docstring: Option<&str>, name: &str, fields: &[FrozenUnit], closed: bool, types: &Types,
+) -> Map<String, Value> {
+    let mut properties = Map::new();
+    let mut required = vec![];
+
+    for field in fields {
+        let FrozenUnit::Field { docstring, parameters, name, kind_value, optional } = field else { continue };
+
+        let mut property = described(docstring.as_deref());
+        property.append(&mut kind_schema(kind_value, types));
+        let default = parameters.iter().find_map(|parameter| match parameter {
+            FrozenUnit::Parameter { name, default_value } if name == DEFAULT_PARAMETER => Some(default_value),
+            _ => None,
+        });
+        if let Some(default) = default {
+            let value = default_value(&property, default);
+            property.insert("default".to_owned(), value);
+        }
+        properties.insert(name.clone(), Value::Object(property));
+
+        if !optional { required.push(json!(name)) }
+    }
+
+    let mut object = titled(name, docstring);
+    object.insert("type".to_owned(), json!("object"));
+    object.insert("properties".to_owned(), Value::Object(properties));
+    object.insert("required".to_owned(), Value::Array(required));
+    if closed {
+        object.insert("additionalProperties".to_owned(), json!(false));
+    }
+
+    object
+}
+
+pub(crate) fn generate_enum(docstring: Option<&str>, name: &str, variants: &[FrozenUnit]) -> Map<String, Value> {
+    let names: Vec<&str> = variants.iter().filter_map(|variant| match variant {
+        FrozenUnit::EnumVariant(KindValue::EnumVariant(name, _)) => Some(name.as_str()),
+        _ => None,
+    }).collect();
+
+    let mut schema = titled(name, docstring);
+    schema.insert("type".to_owned(), json!("string"));
+    schema.insert("enum".to_owned(), json!(names));
+
+    schema
+}
+
+/// The schema of a kind, where unions are an object of one of their kinds,
+/// by name, to the value
+pub(crate) fn kind_schema(kind: &KindValue, types: &Types) -> Map<String, Value> {
+    if let KindValue::Union(kinds) = kind {
+        let schemas: Vec<Value> = kinds.iter().map(|kind| {
+            let name = schema_type_name(kind, " | ");
+            json!({
+                "type": "object",
+                "properties": { name.clone(): kind_schema(kind, types) },
+                "required": [name],
+                "additionalProperties": false,
+            })
+        }).collect();
+        return Map::from_iter([("oneOf".to_owned(), Value::Array(schemas))])
+    }
+
+    type_schema(&schema_type_name(kind, " | "), types)
+}
+
+fn type_schema(schema_type: &str, types: &Types) -> Map<String, Value> {
+    if let Some((element, size)) = outer_array(schema_type) {
+        let mut array = Map::from_iter([
+            ("type".to_owned(), json!("array")),
+            ("items".to_owned(), Value::Object(type_schema(element, types))),
+        ]);
+        if let Ok(size) = size.parse::<u64>() {
+            array.insert("minItems".to_owned(), json!(size));
+            array.insert("maxItems".to_owned(), json!(size));
+        }
+        return array
+    }
+
+    let schema = match schema_type {
+        "str" | "string" => json!({ "type": "string" }),
+        "bool" => json!({ "type": "boolean" }),
+        "f32" | "f64" | "float" => float_schema(),
+        "u8" => json!({ "type": "integer", "minimum": 0, "maximum": u8::MAX }),
+        "u16" => json!({ "type": "integer", "minimum": 0, "maximum": u16::MAX }),
+        "u32" => json!({ "type": "integer", "minimum": 0, "maximum": u32::MAX }),
+        "u64" if !types.int64_as_string => json!({ "type": "integer", "minimum": 0, "maximum": u64::MAX }),
+        "i8" => json!({ "type": "integer", "minimum": i8::MIN, "maximum": i8::MAX }),
+        "i16" => json!({ "type": "integer", "minimum": i16::MIN, "maximum": i16::MAX }),
+        "i32" | "int" => json!({ "type": "integer", "minimum": i32::MIN, "maximum": i32::MAX }),
+        "i64" if !types.int64_as_string => json!({ "type": "integer", "minimum": i64::MIN, "maximum": i64::MAX }),
+        // Integers past what many parsers of JSON hold exactly are strings
+        "u64" | "u128" => json!({ "type": "string", "pattern": "^[0-9]+$" }),
+        "i64" | "i128" => json!({ "type": "string", "pattern": "^-?[0-9]+$" }),
+        other => (types.reference)(other),
+    };
+
+    let Value::Object(schema) = schema else { unreachable!() };
+    schema
+}
+
+/// Floats are numbers, or the texts of those that are not
+fn float_schema() -> Value {
+    json!({ "anyOf": [{ "type": "number" }, { "enum": NON_FINITE }] })
+}
+
+/// The value of a default as the type of its property, which annotations
+/// give as written
+fn default_value(property: &Map<String, Value>, default: &str) -> Value {
+    let parsed = match property.get("type").and_then(Value::as_str) {
+        Some("integer") => default.parse::<i64>().map(Value::from).ok()
+            .or_else(|| default.parse::<u64>().map(Value::from).ok()),
+        Some("boolean") => default.parse::<bool>().ok().map(Value::from),
+        None if Some(&float_schema()["anyOf"]) == property.get("anyOf") => {
+            default.parse::<f64>().ok().filter(|value| value.is_finite()).map(Value::from)
+        }
+        _ => None,
+    };
+
+    parsed.unwrap_or_else(|| json!(default))
+}
+
+fn titled(name: &str, docstring: Option<&str>) -> Map<String, Value> {
+    let mut schema = Map::from_iter([("title".to_owned(), json!(name))]);
+    schema.append(&mut described(docstring));
+    schema
+}
+
+/// A description of the docstring, empty docstrings have none
+pub(crate) fn described(docstring: Option<&str>) -> Map<String, Value> {
+    match docstring.filter(|d| !d.is_empty()) {
+        Some(docstring) => Map::from_iter([("description".to_owned(), json!(docstring))]),
+        None => Map::new(),
+    }
+}
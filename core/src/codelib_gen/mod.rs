@@ -1,28 +1,245 @@
+// Relative Modules
+pub mod c;
+pub(crate) mod common;
+pub mod features;
+pub mod go;
+pub mod json_schema;
+pub mod openapi;
+pub mod plugin;
+pub mod protobuf;
+pub mod python;
+pub mod rust;
+pub mod template;
+pub mod typescript;
+
 // Standard Uses
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 
 // Crate Uses
+use crate::codelib_gen::features::Feature;
+use crate::package::build::cas;
 use crate::schema::ir::frozen::unit::FrozenUnit;
+use crate::stdlib;
 
 // External Uses
+use eyre::{bail, Result};
 use once_cell::sync::Lazy;
+use semver::{Version, VersionReq};
+use serde_derive::{Deserialize, Serialize};
+
 
+/// Generates the files of a package, relative to the generation path
+pub type GeneratorFn = fn(&GenerationContext) -> Result<Vec<GeneratedFile>>;
 
-pub type VersionGenerators = Lazy<HashMap<&'static str, GeneratorFn>>;
-pub type GeneratorFn = fn(&Vec<FrozenUnit>) -> String;
-pub type Generator = (GeneratorFn, &'static str);
+/// A built-in generator
+#[derive(Debug)]
+pub struct Generator {
+    /// Name of the language in `code_generation`
+    pub name: &'static str,
+    /// Version of what the generator emits, which the `generator_version`
+    /// requirement of a language is matched against
+    pub version: Version,
+    /// Features of the IDL the generator can emit
+    pub features: &'static [Feature],
+    pub generate: GeneratorFn,
+}
 
+pub static GENERATORS: Lazy<Vec<Generator>> = Lazy::new(|| {
+    use Feature::*;
+    let generator = |name, version, features, generate| Generator {
+        name, version: Version::parse(version).unwrap(), features, generate,
+    };
 
-pub mod rust;
+    vec![
+        generator("c", "0.1.0", &[], c::generate_c_package),
+        generator("go", "0.1.0", &[Unions], go::generate_go_package),
+        generator("json_schema", "0.2.0", &[Unions, Defaults], json_schema::generate_json_schema_package),
+        generator("openapi", "0.2.0", &[Unions, Errors, Defaults], openapi::generate_openapi_package),
+        generator("protobuf", "0.1.0", &[], protobuf::generate_protobuf_package),
+        generator("python", "0.1.0", &[Unions], python::generate_python_package),
+        generator("rust", "0.2.0", &[Unions, Validators, Defaults], rust::generate_rust_package),
+        generator("typescript", "0.1.0", &[Unions], typescript::generate_typescript_package),
+    ]
+});
+
+
+/// Finds the built-in generator of a language, if its version satisfies the
+/// requirement
+pub fn find_generator(name: &str, requirement: &VersionReq) -> Result<&'static Generator> {
+    let Some(generator) = GENERATORS.iter().find(|generator| generator.name == name) else {
+        bail!("No generator found for language named '{}'", name)
+    };
+    if !requirement.matches(&generator.version) {
+        bail!(
+            "Generator '{}' is version {}, which does not satisfy the requirement '{}'",
+            name, generator.version, requirement
+        )
+    }
+
+    Ok(generator)
+}
+
+impl Generator {
+    /// Fails, listing every use of them, when the schemas use features the
+    /// generator cannot emit
+    pub fn check_features(&self, schemas: &[SchemaUnits]) -> Result<()> {
+        let unsupported: Vec<String> = schemas.iter()
+            .flat_map(features::features_used)
+            .filter(|used| !self.features.contains(&used.feature))
+            .map(|used| format!("  - '{}' uses {}", used.item, used.feature.name()))
+            .collect();
+
+        if !unsupported.is_empty() {
+            bail!(
+                "Generator '{}' {} cannot emit features the package uses:\n{}",
+                self.name, self.version, unsupported.join("\n")
+            )
+        }
 
-#[allow(unused)]
-/// Find a generator function from the external codelib-gen library
-pub fn find_generator(name: &str, _version: &str)
-    -> Option<(&'static GeneratorFn, &'static str)>
-{
-    match name {
-        "rust" => Some((&(rust::generate_rust as GeneratorFn), "rust")),
-        _ => None,
+        Ok(())
     }
 }
 
+
+/// A file to generate, relative to the generation path
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeneratedFile {
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+/// A compiled schema of the package code is generated for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaUnits {
+    pub namespace: Vec<String>,
+    pub units: Vec<FrozenUnit>,
+}
+
+impl SchemaUnits {
+    /// Whether the schema declares a type, validator or constant with this name
+    pub fn declares(&self, name: &str) -> bool {
+        self.units.iter().any(|unit| match unit {
+            FrozenUnit::Struct { name: declared, .. }
+            | FrozenUnit::Enum { name: declared, .. }
+            | FrozenUnit::Protocol { name: declared, .. }
+            | FrozenUnit::Validator { name: declared, .. }
+            | FrozenUnit::Constant { name: declared, .. } => declared == name,
+            _ => false,
+        })
+    }
+
+    pub fn imports(&self) -> impl Iterator<Item = &str> {
+        self.units.iter().filter_map(|unit| match unit {
+            FrozenUnit::Import(path) => Some(path.as_str()),
+            _ => None,
+        })
+    }
+}
+
+/// What generators are given of a package
+#[derive(Debug, Clone)]
+pub struct GenerationContext {
+    /// Name of the congregation
+    pub package_name: String,
+    pub package_version: String,
+    pub schemas: Vec<SchemaUnits>,
+    /// Options of the generation target, from `options` in its configuration
+    pub options: BTreeMap<String, String>,
+    /// Versions of the package frozen before, oldest first
+    pub history: Vec<FrozenVersion>,
+}
+
+/// A version of the package as frozen into the CAS
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrozenVersion {
+    pub version: String,
+    /// Compiled schemas, in the order the package had them
+    pub schemas: Vec<SchemaUnits>,
+}
+
+impl FrozenVersion {
+    /// The schema this version froze at the namespace of the schema at
+    /// `index` in the package now
+    pub fn schema(&self, namespace: &[String], index: usize) -> Option<&SchemaUnits> {
+        cas::history::frozen_schema(&self.schemas, namespace, index)
+    }
+}
+
+impl GenerationContext {
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    /// Whether an option is set to `true`
+    pub fn flag(&self, name: &str) -> bool {
+        self.option(name) == Some("true")
+    }
+
+    pub fn schema(&self, namespace: &[String]) -> Option<&SchemaUnits> {
+        self.schemas.iter().find(|schema| schema.namespace == namespace)
+    }
+
+    /// The namespace and name of what a type used in `schema` refers to, when
+    /// it is declared in another schema of the package, either through a path
+    /// written in full or through the imports of `schema`
+    pub fn resolve_type(&self, schema: &SchemaUnits, type_name: &str) -> Option<(Vec<String>, String)> {
+        if let Some((path, name)) = type_name.rsplit_once("::") {
+            let target = self.schema_at(schema, path)?;
+            return target.declares(name).then(|| (target.namespace.clone(), name.to_owned()))
+        }
+
+        if schema.declares(type_name) { return None }
+
+        schema.imports().find_map(|import| {
+            let (path, imported) = import.rsplit_once("::")?;
+            let brought = match imported {
+                "*" => true,
+                _ => imported.trim_start_matches('{').trim_end_matches('}')
+                    .split(',')
+                    .any(|item| item.trim() == type_name),
+            };
+            if !brought { return None }
+
+            let target = self.schema_at(schema, path)?;
+            target.declares(type_name).then(|| (target.namespace.clone(), type_name.to_owned()))
+        })
+    }
+
+    /// The validator declaration a field of `schema` uses by name, either
+    /// declared in the schema or in another one as types are
+    pub fn validator<'a>(&'a self, schema: &'a SchemaUnits, name: &str) -> Option<&'a FrozenUnit> {
+        let (target, name) = match self.resolve_type(schema, name) {
+            Some((namespace, name)) => (self.schema(&namespace).or_else(|| stdlib::schema(&namespace))?, name),
+            None => (schema, name.to_owned()),
+        };
+
+        target.units.iter().find(|unit| matches!(
+            unit, FrozenUnit::Validator { name: declared, .. } if *declared == name
+        ))
+    }
+
+    /// The schema a path written in `schema` leads to, which may start with
+    /// `self`, `parent` or `crate`, with the package name, or with `std` for
+    /// the standard library
+    fn schema_at(&self, schema: &SchemaUnits, path: &str) -> Option<&SchemaUnits> {
+        let segments: Vec<String> = path.split("::").map(str::to_owned).collect();
+        let (first, rest) = segments.split_first()?;
+
+        let namespace = match first.as_str() {
+            "self" => [schema.namespace.as_slice(), rest].concat(),
+            "parent" => {
+                let (_, parent) = schema.namespace.split_last()?;
+                [parent, rest].concat()
+            }
+            "crate" => rest.to_vec(),
+            _ => segments.clone(),
+        };
+
+        self.schema(&namespace).or_else(|| match namespace.split_first() {
+            Some((first, rest)) if *first == self.package_name => self.schema(rest),
+            Some((first, _)) if first == stdlib::PACKAGE_NAME => stdlib::schema(&namespace),
+            _ => None,
+        })
+    }
+}
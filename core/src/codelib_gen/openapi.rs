@@ -0,0 +1,279 @@
+// Relative Modules
+
+// Standard Uses
+use std::path::PathBuf;
+
+// Crate Uses
+use crate::codelib_gen::json_schema::{described, generate_enum, generate_object, kind_schema, Types};
+use crate::codelib_gen::{GeneratedFile, GenerationContext, SchemaUnits};
+use crate::schema::ir::frozen::unit::{FrozenArgument, FrozenUnit};
+use crate::schema::ir::compiler::interpreted::kind_search::KindValue;
+use crate::utils::case::to_snake_case;
+
+// External Uses
+use eyre::{bail, Result};
+use serde_json::{json, Map, Value};
+
+
+/// Function parameter giving the method and path of its operation, like
+/// `@http="GET /users/{arg0}"`, where either may be left out
+pub const HTTP_PARAMETER: &str = "http";
+
+const METHODS: &[&str] = &["GET", "PUT", "POST", "DELETE", "OPTIONS", "HEAD", "PATCH"];
+/// Methods whose requests have no body, so arguments are query parameters
+const BODILESS_METHODS: &[&str] = &["GET", "DELETE", "OPTIONS", "HEAD"];
+
+
+/// Generates an OpenAPI document of the package, with an operation for every
+/// function of its protocols and the structs and enums of every schema as
+/// component schemas
+/// Options:
+/// - `server_url` is the server the operations are served from
+/// - `int64_as_string = true` has 64-bit integers as strings, as canonical JSON
+///   written with `int64_as_string` does
+///
+/// Operations are `POST /<namespace>/<protocol>/<function>` unless the
+/// function says otherwise with `@http`. Path templates may name arguments,
+/// the other arguments are query parameters when the method has no body and
+/// properties of the request body otherwise. Returns are `200` responses, or
+/// `204` for functions returning nothing, and errors thrown are responses with
+/// the status of their `http` parameter, by default `500`
+pub fn generate_openapi_package(context: &GenerationContext) -> Result<Vec<GeneratedFile>> {
+    let mut paths = Map::new();
+    let mut schemas = Map::new();
+    let int64_as_string = context.option("int64_as_string") == Some("true");
+
+    for schema in &context.schemas {
+        let reference = |type_name: &str| component_reference(context, schema, type_name);
+        let types = Types { reference: &reference, int64_as_string };
+
+        for unit in &schema.units {
+            match unit {
+                FrozenUnit::Struct { docstring, name, fields, .. } => {
+                    let object = generate_object(docstring.as_deref(), name, fields, false, &types);
+                    schemas.insert(component_name(&schema.namespace, name), Value::Object(object));
+                }
+                FrozenUnit::Enum { docstring, name, variants } => {
+                    let enumeration = generate_enum(docstring.as_deref(), name, variants);
+                    schemas.insert(component_name(&schema.namespace, name), Value::Object(enumeration));
+                }
+                FrozenUnit::Protocol { name, functions, .. } => {
+                    for function in functions {
+                        add_operation(&mut paths, schema, name, function, &types)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut document = Map::new();
+    document.insert("openapi".to_owned(), json!("3.1.0"));
+    document.insert("info".to_owned(), json!({
+        "title": context.package_name, "version": context.package_version,
+    }));
+    if let Some(url) = context.option("server_url") {
+        document.insert("servers".to_owned(), json!([{ "url": url }]));
+    }
+    document.insert("paths".to_owned(), Value::Object(paths));
+    document.insert("components".to_owned(), json!({ "schemas": schemas }));
+
+    let contents = serde_json::to_string_pretty(&Value::Object(document))? + "\n";
+    Ok(vec![GeneratedFile { path: PathBuf::from("openapi.json"), contents }])
+}
+
+/// Generates the document of a single schema, leaving types of other schemas
+/// as named
+pub fn generate_openapi(units: &[FrozenUnit]) -> Result<String> {
+    let context = GenerationContext {
+        package_name: String::new(),
+        package_version: String::new(),
+        schemas: vec![SchemaUnits { namespace: vec![], units: units.to_vec() }],
+        options: Default::default(),
+        history: vec![],
+    };
+
+    let mut files = generate_openapi_package(&context)?;
+    Ok(files.remove(0).contents)
+}
+
+fn add_operation(
+    paths: &mut Map<String, Value>, schema: &SchemaUnits, protocol: &str, function: &FrozenUnit,
+    types: &Types,
+) -> Result<()> {
+    let FrozenUnit::Function { docstring, parameters, name, arguments, _return, throws, .. } = function else {
+        return Ok(())
+    };
+    let qualified = schema.namespace.iter().map(String::as_str)
+        .chain([protocol, name.as_str()])
+        .collect::<Vec<_>>().join("::");
+
+    let Some((method, path)) = http_route(parameters, &schema.namespace, protocol, name) else {
+        bail!(
+            "Function '{}' has an `@{}` annotation that is not a method and a path like 'GET /users/{{arg0}}'",
+            qualified, HTTP_PARAMETER
+        )
+    };
+
+    let mut operation = Map::new();
+    let operation_id = schema.namespace.iter().map(String::as_str)
+        .chain([protocol, name.as_str()])
+        .collect::<Vec<_>>().join(".");
+    operation.insert("operationId".to_owned(), json!(operation_id));
+    operation.append(&mut described(Some(docstring)));
+    operation.insert("tags".to_owned(), json!([protocol]));
+
+    // Arguments the path template names are path parameters
+    let mut operation_parameters = vec![];
+    let mut body: Vec<&FrozenArgument> = vec![];
+    for argument in arguments {
+        let templated = path.contains(&format!("{{{}}}", argument.name));
+        let location = match (templated, BODILESS_METHODS.contains(&method.as_str())) {
+            (true, _) => "path",
+            (false, true) => "query",
+            (false, false) => {
+                body.push(argument);
+                continue
+            }
+        };
+        operation_parameters.push(json!({
+            "name": argument.name, "in": location, "required": true,
+            "schema": kind_schema(&argument.kind, types),
+        }));
+    }
+    let unknown = path.split('{').skip(1)
+        .filter_map(|segment| segment.split_once('}'))
+        .find(|(argument, _)| !arguments.iter().any(|known| known.name == *argument));
+    if let Some((argument, _)) = unknown {
+        bail!("Function '{}' has no argument '{}' for its path '{}'", qualified, argument, path)
+    }
+
+    if !operation_parameters.is_empty() {
+        operation.insert("parameters".to_owned(), Value::Array(operation_parameters));
+    }
+    if !body.is_empty() {
+        let properties: Map<String, Value> = body.iter()
+            .map(|argument| (argument.name.clone(), Value::Object(kind_schema(&argument.kind, types))))
+            .collect();
+        let required: Vec<&str> = body.iter().map(|argument| argument.name.as_str()).collect();
+
+        operation.insert("requestBody".to_owned(), json!({
+            "required": true,
+            "content": { "application/json": { "schema": {
+                "type": "object", "properties": properties, "required": required,
+            }}},
+        }));
+    }
+
+    operation.insert("responses".to_owned(), Value::Object(responses(_return.as_ref(), throws, types)));
+
+    let path_item = paths.entry(path.clone()).or_insert_with(|| json!({}));
+    let method_key = method.to_lowercase();
+    if path_item.get(&method_key).is_some() {
+        bail!("Function '{}' is a second operation for '{} {}'", qualified, method, path)
+    }
+    path_item[method_key] = Value::Object(operation);
+
+    Ok(())
+}
+
+/// The method and path of a function, or `None` if its `@http` parameter is
+/// neither
+fn http_route(
+    parameters: &[FrozenUnit], namespace: &[String], protocol: &str, function: &str
+) -> Option<(String, String)> {
+    let mut method = "POST".to_owned();
+    let mut path = namespace.iter().map(String::as_str)
+        .chain([to_snake_case(protocol).as_str(), function])
+        .fold(String::new(), |path, segment| format!("{}/{}", path, segment));
+
+    let route = parameters.iter().find_map(|parameter| match parameter {
+        FrozenUnit::Parameter { name, default_value } if name == HTTP_PARAMETER => Some(default_value),
+        _ => None,
+    });
+    let Some(route) = route else { return Some((method, path)) };
+
+    let mut parts = route.split_whitespace();
+    let mut next = parts.next()?;
+    if METHODS.contains(&next.to_uppercase().as_str()) {
+        method = next.to_uppercase();
+        next = match parts.next() {
+            Some(part) => part,
+            None => return Some((method, path)),
+        };
+    }
+    if !next.starts_with('/') || parts.next().is_some() { return None }
+    path = next.to_owned();
+
+    Some((method, path))
+}
+
+fn responses(
+    _return: Option<&KindValue>, throws: &[FrozenUnit], types: &Types
+) -> Map<String, Value> {
+    let mut responses = Map::new();
+
+    match _return {
+        Some(kind) => responses.insert("200".to_owned(), json!({
+            "description": "Success",
+            "content": { "application/json": { "schema": kind_schema(kind, types) } },
+        })),
+        None => responses.insert("204".to_owned(), json!({ "description": "Success, with no content" })),
+    };
+
+    // Errors with the same status are told apart by their schema
+    let mut errors: Vec<(String, Vec<&FrozenUnit>)> = vec![];
+    for error in throws {
+        let FrozenUnit::Error { parameters, .. } = error else { continue };
+        let status = parameters.iter().find_map(|parameter| match parameter {
+            FrozenUnit::Parameter { name, default_value } if name == HTTP_PARAMETER => Some(default_value.clone()),
+            _ => None,
+        }).unwrap_or_else(|| "500".to_owned());
+
+        match errors.iter_mut().find(|(known, _)| *known == status) {
+            Some((_, same)) => same.push(error),
+            None => errors.push((status, vec![error])),
+        }
+    }
+
+    for (status, same) in errors {
+        let mut schemas = vec![];
+        let mut names = vec![];
+        for error in same {
+            let FrozenUnit::Error { docstring, name, message, fields, .. } = error else { continue };
+            let mut schema = generate_object(docstring.as_deref(), name, fields, false, types);
+            if docstring.as_deref().is_none_or(str::is_empty) {
+                schema.append(&mut described(Some(message)));
+            }
+            schemas.push(Value::Object(schema));
+            names.push(name.as_str());
+        }
+
+        let schema = match schemas.len() {
+            1 => schemas.remove(0),
+            _ => json!({ "oneOf": schemas }),
+        };
+        responses.insert(status, json!({
+            "description": names.join(", "),
+            "content": { "application/json": { "schema": schema } },
+        }));
+    }
+
+    responses
+}
+
+/// Component schemas are named after the namespace of their schema too, as
+/// schemas may declare types of the same name
+fn component_name(namespace: &[String], name: &str) -> String {
+    namespace.iter().map(String::as_str).chain([name]).collect::<Vec<_>>().join(".")
+}
+
+fn component_reference(context: &GenerationContext, schema: &SchemaUnits, type_name: &str) -> Value {
+    let name = match context.resolve_type(schema, type_name) {
+        Some((namespace, name)) => component_name(&namespace, &name),
+        None if schema.declares(type_name) => component_name(&schema.namespace, type_name),
+        None => type_name.to_owned(),
+    };
+
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
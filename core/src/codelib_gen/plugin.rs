@@ -0,0 +1,188 @@
+// Relative Modules
+
+// Standard Uses
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Component, PathBuf};
+use std::process::{Command, Stdio};
+
+// Crate Uses
+use crate::codelib_gen::{FrozenVersion, GeneratedFile, GenerationContext, SchemaUnits};
+
+// External Uses
+use eyre::{bail, eyre, Result};
+use serde_derive::{Deserialize, Serialize};
+
+
+/// Option naming the executable of a plugin, which makes a language generated
+/// by it rather than by a built-in generator
+pub const PLUGIN_OPTION: &str = "plugin";
+
+/// Version of the requests and responses exchanged with plugins, which
+/// changes only when something in them does in a way plugins could tell
+pub const PROTOCOL_VERSION: u32 = 1;
+
+
+/// What plugins are given of a package, as JSON on their standard input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginRequest {
+    pub protocol_version: u32,
+    pub package: PackageInfo,
+    /// Options of the generation target, from `options` in its configuration
+    pub options: BTreeMap<String, String>,
+    pub schemas: Vec<SchemaUnits>,
+    /// Versions of the package frozen before, oldest first
+    pub history: Vec<FrozenVersion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageInfo {
+    /// Name of the congregation
+    pub name: String,
+    pub version: String,
+}
+
+/// What plugins answer with, as JSON on their standard output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginResponse {
+    pub protocol_version: u32,
+    /// Files to write, relative to the generation path
+    #[serde(default)]
+    pub files: Vec<GeneratedFile>,
+    #[serde(default)]
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// A problem a plugin found, errors fail the build and warnings are reported
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub level: DiagnosticLevel,
+    pub message: String,
+    /// Namespace of the schema the problem is in, joined by `::`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticLevel {
+    Error,
+    Warning,
+}
+
+impl PluginRequest {
+    pub fn from_context(context: &GenerationContext) -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            package: PackageInfo {
+                name: context.package_name.clone(), version: context.package_version.clone(),
+            },
+            options: context.options.clone(),
+            schemas: context.schemas.clone(),
+            history: context.history.clone(),
+        }
+    }
+}
+
+impl PluginResponse {
+    pub fn new() -> Self {
+        Self { protocol_version: PROTOCOL_VERSION, files: vec![], diagnostics: vec![] }
+    }
+
+    /// Adds a file to write, relative to the generation path
+    pub fn file(&mut self, path: impl Into<PathBuf>, contents: impl Into<String>) -> &mut Self {
+        self.files.push(GeneratedFile { path: path.into(), contents: contents.into() });
+        self
+    }
+
+    /// Adds an error, which fails the build
+    pub fn error(&mut self, schema: Option<&str>, message: impl Into<String>) -> &mut Self {
+        self.diagnostic(DiagnosticLevel::Error, schema, message)
+    }
+
+    pub fn warning(&mut self, schema: Option<&str>, message: impl Into<String>) -> &mut Self {
+        self.diagnostic(DiagnosticLevel::Warning, schema, message)
+    }
+
+    fn diagnostic(&mut self, level: DiagnosticLevel, schema: Option<&str>, message: impl Into<String>) -> &mut Self {
+        self.diagnostics.push(Diagnostic { level, message: message.into(), schema: schema.map(str::to_owned) });
+        self
+    }
+}
+
+impl Default for PluginResponse {
+    fn default() -> Self { Self::new() }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.schema {
+            Some(schema) => write!(f, "{}: {}", schema, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+
+/// Generates the package by running the executable of the `plugin` option,
+/// which is given a [`PluginRequest`] on its standard input and answers with a
+/// [`PluginResponse`] on its standard output
+///
+/// Plugins exiting unsuccessfully or reporting errors fail the generation,
+/// with what they wrote to their standard error or the errors reported
+pub fn generate_plugin_package(context: &GenerationContext) -> Result<Vec<GeneratedFile>> {
+    let Some(executable) = context.option(PLUGIN_OPTION) else {
+        bail!("Plugin generators need the executable of their plugin as the option '{}'", PLUGIN_OPTION)
+    };
+
+    let request = serde_json::to_vec(&PluginRequest::from_context(context))?;
+    let mut child = Command::new(executable)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| eyre!("Could not run plugin '{}': {}", executable, e))?;
+
+    // The request is written while the answer is read, so that neither side
+    // waits on the other with a full pipe
+    let mut stdin = child.stdin.take().unwrap();
+    let writer = std::thread::spawn(move || stdin.write_all(&request));
+    let output = child.wait_with_output()?;
+    let written = writer.join().expect("Writing the plugin request panicked");
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("Plugin '{}' failed with {}:\n{}", executable, output.status, stderr.trim_end())
+    }
+    written.map_err(|e| eyre!("Could not send the request to plugin '{}': {}", executable, e))?;
+
+    let response: PluginResponse = serde_json::from_slice(&output.stdout)
+        .map_err(|e| eyre!("Plugin '{}' did not answer with a response: {}", executable, e))?;
+    if response.protocol_version != PROTOCOL_VERSION {
+        bail!(
+            "Plugin '{}' answered with protocol version {}, while version {} was requested",
+            executable, response.protocol_version, PROTOCOL_VERSION
+        )
+    }
+
+    let (errors, warnings): (Vec<_>, Vec<_>) = response.diagnostics.iter()
+        .partition(|diagnostic| diagnostic.level == DiagnosticLevel::Error);
+    for warning in warnings {
+        tracing::warn!("Plugin '{}': {}", executable, warning);
+    }
+    if !errors.is_empty() {
+        let listing: Vec<String> = errors.iter().map(|error| format!("  - {}", error)).collect();
+        bail!("Plugin '{}' reported errors:\n{}", executable, listing.join("\n"))
+    }
+
+    // Plugins only write inside the generation path
+    for file in &response.files {
+        let escapes = file.path.components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+        if escapes || file.path.as_os_str().is_empty() {
+            bail!("Plugin '{}' generated '{}', which is not inside the generation path", executable, file.path.display())
+        }
+    }
+
+    Ok(response.files)
+}
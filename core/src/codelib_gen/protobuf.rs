@@ -0,0 +1,366 @@
+// Relative Modules
+
+// Standard Uses
+use std::collections::BTreeSet;
+use std::mem::discriminant;
+use std::path::PathBuf;
+
+// Crate Uses
+use crate::codelib_gen::common::{docs, schema_type_name, split_arrays, DocStyle};
+use crate::codelib_gen::{FrozenVersion, GeneratedFile, GenerationContext, SchemaUnits};
+use crate::schema::ir::frozen::unit::{FrozenArgument, FrozenUnit};
+use crate::schema::ir::compiler::interpreted::kind_search::KindValue;
+use crate::utils::case::{to_pascal_case, to_screaming_snake_case};
+
+// External Uses
+use eyre::{bail, Result};
+
+
+const HEADER: &str = "// Generated by Comline\n";
+
+/// Variant enums are given as their zero value when it is declared, instead
+/// of one being added
+const ZERO_VARIANT: &str = "Unspecified";
+
+
+/// What is being generated of a schema
+struct ProtoFile<'a> {
+    context: &'a GenerationContext,
+    schema: &'a SchemaUnits,
+    /// Position of the schema in the package, which is how versions frozen
+    /// before tell it apart
+    index: usize,
+    package_prefix: &'a str,
+    /// Files of other schemas the types of which are used
+    imports: BTreeSet<Vec<String>>,
+    /// Fields with no representation, described
+    unrepresentable: Vec<String>,
+}
+
+impl ProtoFile<'_> {
+    /// The name of a type used in this schema, qualified by the package of the
+    /// schema declaring it when that is another one
+    fn type_name(&mut self, type_name: &str) -> String {
+        if self.schema.declares(type_name) { return type_name.to_owned() }
+
+        match self.context.resolve_type(self.schema, type_name) {
+            Some((namespace, name)) => {
+                let qualified = format!("{}.{}", package(self.package_prefix, &namespace), name);
+                self.imports.insert(namespace);
+                qualified
+            }
+            None => type_name.to_owned(),
+        }
+    }
+}
+
+
+/// Generates a `.proto` file for every schema, at paths mirroring their
+/// namespaces, with structs as messages, enums as enums and protocols as
+/// services. Constants have no counterpart, so they are left out
+/// Options:
+/// - `package_prefix` is what protobuf packages start with, by default the
+///   package name
+///
+/// Fields and variants are numbered in the order they first appeared across
+/// the versions frozen before, so numbers never change between versions, and
+/// the numbers and names of removed ones are reserved
+pub fn generate_protobuf_package(context: &GenerationContext) -> Result<Vec<GeneratedFile>> {
+    let package_prefix = context.option("package_prefix").unwrap_or(&context.package_name);
+
+    let mut files = vec![];
+    let mut unrepresentable = vec![];
+
+    for (index, schema) in context.schemas.iter().enumerate() {
+        let mut file = ProtoFile {
+            context, schema, index, package_prefix,
+            imports: BTreeSet::new(), unrepresentable: vec![],
+        };
+        let contents = generate_proto(&schema.units, &mut file);
+        unrepresentable.append(&mut file.unrepresentable);
+
+        let path = PathBuf::from(schema.namespace.join("/")).with_extension("proto");
+        files.push(GeneratedFile { path, contents });
+    }
+
+    if !unrepresentable.is_empty() {
+        bail!(
+            "Some fields have no Protocol Buffers representation:\n{}",
+            unrepresentable.iter().map(|d| format!("  - {}", d)).collect::<Vec<_>>().join("\n")
+        )
+    }
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Generates the `.proto` file of a single schema, with no history, leaving
+/// types of other schemas as named
+pub fn generate_protobuf(namespace: &str, units: &[FrozenUnit]) -> Result<String> {
+    let context = GenerationContext {
+        package_name: String::new(),
+        package_version: String::new(),
+        schemas: vec![SchemaUnits {
+            namespace: namespace.split("::").map(str::to_owned).collect(), units: units.to_vec(),
+        }],
+        options: Default::default(),
+        history: vec![],
+    };
+
+    let mut files = generate_protobuf_package(&context)?;
+    Ok(files.remove(0).contents)
+}
+
+fn generate_proto(units: &[FrozenUnit], file: &mut ProtoFile) -> String {
+    let mut code = String::new();
+
+    for unit in units {
+        match unit {
+            FrozenUnit::Struct { docstring, name, fields, .. } => {
+                code.push_str(&generate_message(docstring.as_deref(), unit, name, fields, file));
+            }
+            FrozenUnit::Enum { docstring, name, variants } => {
+                code.push_str(&generate_enum(docstring.as_deref(), unit, name, variants, file));
+            }
+            FrozenUnit::Protocol { docstring, name, functions, .. } => {
+                code.push_str(&generate_service(docstring, name, functions, file));
+            }
+            _ => {}
+        }
+    }
+
+    let mut output = format!(
+        "{}syntax = \"proto3\";\n\npackage {};\n\n",
+        HEADER, package(file.package_prefix, &file.schema.namespace)
+    );
+    for namespace in &file.imports {
+        output.push_str(&format!("import \"{}.proto\";\n", namespace.join("/")));
+    }
+    if !file.imports.is_empty() { output.push('\n') }
+
+    output.push_str(&code);
+    output.truncate(output.trim_end().len());
+    output.push('\n');
+
+    output
+}
+
+fn generate_message(
+    docstring: Option<&str>, unit: &FrozenUnit, name: &str, fields: &[FrozenUnit], file: &mut ProtoFile
+) -> String {
+    let order = appearance_order(&file.context.history, file, unit);
+    let current = members(unit);
+
+    let mut s = docs(docstring, "", DocStyle::Lines("//"));
+    s.push_str(&format!("message {} {{\n", name));
+    s.push_str(&reserved(&order, &current, 1, str::to_owned));
+
+    for field in fields {
+        let FrozenUnit::Field { docstring, name: field_name, kind_value, optional, .. } = field else {
+            continue
+        };
+
+        let declaration = match field_type(kind_value, *optional, file) {
+            Ok(declaration) => declaration,
+            Err(reason) => {
+                let path = file.schema.namespace.iter().map(String::as_str)
+                    .chain([name, field_name.as_str()])
+                    .collect::<Vec<_>>().join("::");
+                file.unrepresentable.push(format!("'{}' {}", path, reason));
+                continue
+            }
+        };
+
+        let number = order.iter().position(|member| member == field_name).unwrap() + 1;
+        s.push_str(&docs(docstring.as_deref(), "  ", DocStyle::Lines("//")));
+        s.push_str(&format!("  {} {} = {};\n", declaration, field_name, number));
+    }
+
+    s.push_str("}\n\n");
+    s
+}
+
+/// Enums start from a zero value, which proto3 requires and decoders fall back
+/// to for values they do not know, named after the enum unless declared
+fn generate_enum(
+    docstring: Option<&str>, unit: &FrozenUnit, name: &str, variants: &[FrozenUnit], file: &mut ProtoFile
+) -> String {
+    let prefix = to_screaming_snake_case(name);
+    let value_name = |variant: &str| format!("{}_{}", prefix, to_screaming_snake_case(variant));
+
+    let order: Vec<String> = appearance_order(&file.context.history, file, unit)
+        .into_iter().filter(|variant| variant != ZERO_VARIANT).collect();
+    let current = members(unit);
+
+    let mut s = docs(docstring, "", DocStyle::Lines("//"));
+    s.push_str(&format!("enum {} {{\n", name));
+    s.push_str(&reserved(&order, &current, 1, value_name));
+    s.push_str(&format!("  {} = 0;\n", value_name(ZERO_VARIANT)));
+
+    for variant in variants {
+        let FrozenUnit::EnumVariant(KindValue::EnumVariant(variant, _)) = variant else { continue };
+        if variant == ZERO_VARIANT { continue }
+
+        let number = order.iter().position(|member| member == variant).unwrap() + 1;
+        s.push_str(&format!("  {} = {};\n", value_name(variant), number));
+    }
+
+    s.push_str("}\n\n");
+    s
+}
+
+/// Every function has request and response messages of its own, so that
+/// either can gain fields later on
+fn generate_service(docstring: &str, name: &str, functions: &[FrozenUnit], file: &mut ProtoFile) -> String {
+    let mut service = docs(Some(docstring), "", DocStyle::Lines("//"));
+    service.push_str(&format!("service {} {{\n", name));
+    let mut messages = String::new();
+
+    for function in functions {
+        let FrozenUnit::Function { docstring, name: function_name, arguments, _return, .. } = function else {
+            continue
+        };
+
+        let rpc = to_pascal_case(function_name);
+        let request = format!("{}{}Request", name, rpc);
+        let response = format!("{}{}Response", name, rpc);
+
+        service.push_str(&docs(Some(docstring), "  ", DocStyle::Lines("//")));
+        service.push_str(&format!("  rpc {}({}) returns ({});\n", rpc, request, response));
+
+        let path = |field: &str| {
+            file.schema.namespace.iter().map(String::as_str)
+                .chain([name, function_name.as_str(), field])
+                .collect::<Vec<_>>().join("::")
+        };
+
+        messages.push_str(&format!("message {} {{\n", request));
+        for (index, FrozenArgument { name: argument, kind }) in arguments.iter().enumerate() {
+            match field_type(kind, false, file) {
+                Ok(declaration) => messages.push_str(&format!("  {} {} = {};\n", declaration, argument, index + 1)),
+                Err(reason) => file.unrepresentable.push(format!("'{}' {}", path(argument), reason)),
+            }
+        }
+        messages.push_str("}\n\n");
+
+        messages.push_str(&format!("message {} {{\n", response));
+        if let Some(kind) = _return {
+            match field_type(kind, false, file) {
+                Ok(declaration) => messages.push_str(&format!("  {} value = 1;\n", declaration)),
+                Err(reason) => file.unrepresentable.push(format!("'{}' {}", path("return"), reason)),
+            }
+        }
+        messages.push_str("}\n\n");
+    }
+
+    service.push_str("}\n\n");
+    service.push_str(&messages);
+    service
+}
+
+/// Reserved statements for the members of `order` no longer in `current`,
+/// numbered from `first`
+fn reserved(order: &[String], current: &[&str], first: usize, name: impl Fn(&str) -> String) -> String {
+    let removed: Vec<(usize, &String)> = order.iter().enumerate()
+        .filter(|(_, member)| !current.contains(&member.as_str()))
+        .map(|(index, member)| (index + first, member))
+        .collect();
+    if removed.is_empty() { return String::new() }
+
+    let numbers: Vec<String> = removed.iter().map(|(number, _)| number.to_string()).collect();
+    let names: Vec<String> = removed.iter().map(|(_, member)| format!("\"{}\"", name(member))).collect();
+
+    format!("  reserved {};\n  reserved {};\n\n", numbers.join(", "), names.join(", "))
+}
+
+/// Names of the members of a declaration in every version frozen before and
+/// then the current one, in the order they first appeared. A declaration is
+/// told apart across versions by its kind, name and the namespace of its schema
+fn appearance_order(history: &[FrozenVersion], file: &ProtoFile, unit: &FrozenUnit) -> Vec<String> {
+    let name = declared_name(unit);
+    let past = history.iter()
+        .filter_map(|version| version.schema(&file.schema.namespace, file.index))
+        .filter_map(|schema| schema.units.iter().find(|past| {
+            discriminant(*past) == discriminant(unit) && declared_name(past) == name
+        }));
+
+    let mut order: Vec<String> = vec![];
+    for version in past.chain([unit]) {
+        for member in members(version) {
+            if !order.iter().any(|known| known == member) { order.push(member.to_owned()) }
+        }
+    }
+
+    order
+}
+
+fn declared_name(unit: &FrozenUnit) -> Option<&str> {
+    match unit {
+        FrozenUnit::Struct { name, .. } | FrozenUnit::Enum { name, .. } => Some(name),
+        _ => None,
+    }
+}
+
+/// Names of the fields of a struct or the variants of an enum
+fn members(unit: &FrozenUnit) -> Vec<&str> {
+    match unit {
+        FrozenUnit::Struct { fields, .. } => fields.iter().filter_map(|field| match field {
+            FrozenUnit::Field { name, .. } => Some(name.as_str()),
+            _ => None,
+        }).collect(),
+        FrozenUnit::Enum { variants, .. } => variants.iter().filter_map(|variant| match variant {
+            FrozenUnit::EnumVariant(KindValue::EnumVariant(name, _)) => Some(name.as_str()),
+            _ => None,
+        }).collect(),
+        _ => vec![],
+    }
+}
+
+/// The label and type a field is declared with, or why it cannot be
+fn field_type(kind: &KindValue, optional: bool, file: &mut ProtoFile) -> Result<String, String> {
+    if let KindValue::Union(_) = kind {
+        return Err("is a union, which needs a message of its own".to_owned())
+    }
+    let schema_type = schema_type_name(kind, " | ");
+
+    // Sizes of the arrays, innermost first
+    let (element, mut sizes) = split_arrays(&schema_type);
+    sizes.reverse();
+
+    // Arrays of bytes are bytes, whatever their size
+    let element_type = match element {
+        "u8" if !sizes.is_empty() => {
+            sizes.remove(0);
+            "bytes".to_owned()
+        }
+        "str" | "string" => "string".to_owned(),
+        "bool" => "bool".to_owned(),
+        "f32" => "float".to_owned(),
+        "f64" | "float" => "double".to_owned(),
+        "u8" | "u16" | "u32" => "uint32".to_owned(),
+        "u64" => "uint64".to_owned(),
+        "i8" | "i16" | "i32" | "int" => "int32".to_owned(),
+        "i64" => "int64".to_owned(),
+        "u128" | "i128" => return Err(format!("is `{}`, which no scalar type is large enough for", schema_type)),
+        other => file.type_name(other),
+    };
+
+    match (sizes.len(), optional) {
+        (0, false) => Ok(element_type),
+        (0, true) => Ok(format!("optional {}", element_type)),
+        (1, false) => Ok(format!("repeated {}", element_type)),
+        (1, true) => Err(format!("is an optional `{}`, while repeated fields cannot be optional", schema_type)),
+        _ => Err(format!("is `{}`, an array of arrays", schema_type)),
+    }
+}
+
+/// `teams.members` for `members`, with the prefix `teams`
+fn package(prefix: &str, namespace: &[String]) -> String {
+    let segments = match prefix.is_empty() {
+        true => namespace.to_vec(),
+        false => [&[prefix.to_owned()], namespace].concat(),
+    };
+
+    segments.join(".")
+}
+
@@ -0,0 +1,353 @@
+// Relative Modules
+
+// Standard Uses
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+// Crate Uses
+use crate::codelib_gen::common::{
+    constant_value, docs, outer_array, string_literal, unicode_escape, DocStyle
+};
+use crate::codelib_gen::{GeneratedFile, GenerationContext, SchemaUnits};
+use crate::schema::ir::frozen::unit::FrozenUnit;
+use crate::schema::ir::compiler::interpreted::kind_search::{KindValue, Primitive};
+
+// External Uses
+use eyre::Result;
+
+
+/// Words Python reserves, which generated names are suffixed from
+const KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class",
+    "continue", "def", "del", "elif", "else", "except", "finally", "for", "from", "global",
+    "if", "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return",
+    "try", "while", "with", "yield",
+];
+
+const HEADER: &str = "# Generated by Comline\n";
+
+
+/// What is being generated of a schema
+struct Module<'a> {
+    context: &'a GenerationContext,
+    schema: &'a SchemaUnits,
+    /// The package the module is in, which is itself when it has submodules
+    package: &'a [String],
+    /// Types imported from other modules, by the relative module they are
+    /// imported from, with the local name each is known by
+    imports: BTreeMap<String, BTreeMap<String, String>>,
+    /// Names used from `typing`
+    typing: BTreeSet<&'static str>,
+}
+
+impl Module<'_> {
+    /// The local name of a type declared in another schema, imported on
+    /// first use, aliased when a name of this module would be shadowed
+    fn import(&mut self, type_name: &str) -> Option<String> {
+        let (namespace, name) = self.context.resolve_type(self.schema, type_name)?;
+        let module = relative_module(self.package, &namespace);
+
+        if let Some(local) = self.imports.get(&module).and_then(|names| names.get(&name)) {
+            return Some(local.clone())
+        }
+
+        let taken = self.schema.declares(&name)
+            || self.imports.values().any(|names| names.values().any(|local| *local == name));
+        let local = match taken {
+            true => format!("{}_{}", namespace.join("_"), name),
+            false => name.clone(),
+        };
+
+        self.imports.entry(module).or_default().insert(name, local.clone());
+        Some(local)
+    }
+}
+
+
+/// Generates a Python package, with a module for every schema in a tree
+/// mirroring their namespaces
+/// Options:
+/// - `enum_style = int` generates enums as `IntEnum`s numbering their
+///   variants, rather than `Enum`s valued by the names of them
+///
+/// Types of other schemas are only imported for type checking, so modules
+/// importing each other are not imported circularly
+pub fn generate_python_package(context: &GenerationContext) -> Result<Vec<GeneratedFile>> {
+    let int_enums = context.option("enum_style") == Some("int");
+
+    // Submodules of every module, by namespace, the root one has an empty namespace
+    let mut tree: BTreeMap<&[String], BTreeSet<&str>> = BTreeMap::new();
+    tree.insert(&[], BTreeSet::new());
+    for schema in &context.schemas {
+        for depth in 0..schema.namespace.len() {
+            tree.entry(&schema.namespace[..depth]).or_default()
+                .insert(&schema.namespace[depth]);
+        }
+    }
+
+    let mut modules: BTreeMap<&[String], String> = BTreeMap::new();
+    for schema in &context.schemas {
+        let package = match tree.contains_key(schema.namespace.as_slice()) {
+            true => schema.namespace.as_slice(),
+            false => &schema.namespace[..schema.namespace.len() - 1],
+        };
+        let mut module = Module {
+            context, schema, package, imports: BTreeMap::new(), typing: BTreeSet::new()
+        };
+        modules.insert(&schema.namespace, generate_module(&schema.units, int_enums, &mut module));
+    }
+
+    let namespaces: BTreeSet<&[String]> = tree.keys().chain(modules.keys()).copied().collect();
+    let files = namespaces.into_iter().map(|namespace| {
+        let directory = PathBuf::from(namespace.join("/"));
+
+        // Modules with submodules are packages, as the root one always is
+        let path = match tree.contains_key(namespace) {
+            true => directory.join("__init__.py"),
+            false => directory.with_extension("py"),
+        };
+        let contents = match modules.get(namespace) {
+            Some(module) => module.clone(),
+            None => HEADER.to_owned(),
+        };
+
+        GeneratedFile { path, contents }
+    }).collect();
+
+    Ok(files)
+}
+
+/// Generates the code of a single schema, leaving types of other schemas as
+/// named
+pub fn generate_python(units: &[FrozenUnit]) -> String {
+    let context = GenerationContext {
+        package_name: String::new(),
+        package_version: String::new(),
+        schemas: vec![],
+        options: BTreeMap::new(),
+        history: vec![],
+    };
+    let schema = SchemaUnits { namespace: vec![], units: units.to_vec() };
+    let mut module = Module {
+        context: &context, schema: &schema, package: &[], imports: BTreeMap::new(), typing: BTreeSet::new()
+    };
+
+    generate_module(units, false, &mut module)
+}
+
+fn generate_module(units: &[FrozenUnit], int_enums: bool, module: &mut Module) -> String {
+    let mut code = String::new();
+    let mut standard: BTreeSet<&str> = BTreeSet::new();
+
+    for unit in units {
+        match unit {
+            FrozenUnit::Constant { docstring, name, kind_value } => {
+                code.push_str(&generate_constant(docstring.as_deref(), name, kind_value, module));
+            }
+            FrozenUnit::Struct { docstring, name, fields, .. } => {
+                standard.insert("from dataclasses import dataclass\n");
+                code.push_str(&generate_dataclass(docstring.as_deref(), name, fields, module));
+            }
+            FrozenUnit::Enum { docstring, name, variants } => {
+                standard.insert(match int_enums {
+                    true => "from enum import IntEnum\n",
+                    false => "from enum import Enum\n",
+                });
+                code.push_str(&generate_enum(docstring.as_deref(), name, variants, int_enums));
+            }
+            FrozenUnit::Protocol { docstring, name, functions, .. } => {
+                module.typing.insert("Protocol");
+                code.push_str(&generate_protocol(docstring, name, functions, module));
+            }
+            _ => {}
+        }
+    }
+
+    let mut output = format!("{}from __future__ import annotations\n\n", HEADER);
+    for import in standard {
+        output.push_str(import);
+    }
+    if !module.imports.is_empty() { module.typing.insert("TYPE_CHECKING"); }
+    if !module.typing.is_empty() {
+        let names: Vec<&str> = module.typing.iter().copied().collect();
+        output.push_str(&format!("from typing import {}\n", names.join(", ")));
+    }
+
+    if !module.imports.is_empty() {
+        output.push_str("\nif TYPE_CHECKING:\n");
+        for (relative, names) in &module.imports {
+            let names: Vec<String> = names.iter()
+                .map(|(name, local)| match name == local {
+                    true => name.clone(),
+                    false => format!("{} as {}", name, local),
+                })
+                .collect();
+            output.push_str(&format!("    from {} import {}\n", relative, names.join(", ")));
+        }
+    }
+
+    output.push_str("\n\n");
+    output.push_str(&code);
+    output.truncate(output.trim_end().len());
+    output.push('\n');
+
+    output
+}
+
+fn generate_constant(
+    docstring: Option<&str>, name: &str, kind_value: &KindValue, module: &mut Module
+) -> String {
+    let Some((primitive, value)) = constant_value(kind_value) else { return String::new() };
+
+    let (type_name, value) = match primitive {
+        Primitive::String(_) => ("str", string_literal(&value, unicode_escape)),
+        Primitive::Boolean(_) => match value.as_str() {
+            "true" => ("bool", "True".to_owned()),
+            _ => ("bool", "False".to_owned()),
+        },
+        _ => ("int", value),
+    };
+
+    module.typing.insert("Final");
+    let mut s = format!("{}: Final[{}] = {}\n", identifier(name), type_name, value);
+    s.push_str(&docs(docstring, "", DocStyle::Statement));
+    s.push('\n');
+    s
+}
+
+fn generate_dataclass(
+    docstring: Option<&str>, name: &str, fields: &[FrozenUnit], module: &mut Module
+) -> String {
+    // Keyword only, as optional fields default to `None` wherever they are
+    let mut s = format!("@dataclass(kw_only=True)\nclass {}:\n", name);
+    let mut body = docs(docstring, "    ", DocStyle::Statement);
+    if !body.is_empty() && !fields.is_empty() { body.push('\n') }
+
+    for field in fields {
+        if let FrozenUnit::Field { docstring, name, kind_value, optional, .. } = field {
+            let type_name = map_kind_to_python_type(kind_value, module);
+
+            match optional {
+                true => {
+                    module.typing.insert("Optional");
+                    body.push_str(&format!("    {}: Optional[{}] = None\n", identifier(name), type_name));
+                }
+                false => body.push_str(&format!("    {}: {}\n", identifier(name), type_name)),
+            }
+            body.push_str(&docs(docstring.as_deref(), "    ", DocStyle::Statement));
+        }
+    }
+
+    if body.is_empty() { body.push_str("    pass\n") }
+    s.push_str(&body);
+    s.push_str("\n\n");
+    s
+}
+
+/// Enums are valued by the names of their variants, or numbered by their
+/// order as `IntEnum`s
+fn generate_enum(docstring: Option<&str>, name: &str, variants: &[FrozenUnit], int_enums: bool) -> String {
+    let base = if int_enums { "IntEnum" } else { "Enum" };
+    let mut s = format!("class {}({}):\n", name, base);
+    let mut body = docs(docstring, "    ", DocStyle::Statement);
+    if !body.is_empty() && !variants.is_empty() { body.push('\n') }
+
+    let names = variants.iter().filter_map(|variant| match variant {
+        FrozenUnit::EnumVariant(KindValue::EnumVariant(name, _)) => Some(name.as_str()),
+        _ => None,
+    });
+    for (index, variant) in names.enumerate() {
+        let value = match int_enums {
+            true => index.to_string(),
+            false => string_literal(variant, unicode_escape),
+        };
+        body.push_str(&format!("    {} = {}\n", identifier(variant), value));
+    }
+
+    if body.is_empty() { body.push_str("    pass\n") }
+    s.push_str(&body);
+    s.push_str("\n\n");
+    s
+}
+
+/// A structural type of the servers of the protocol, asynchronous functions
+/// are coroutines
+fn generate_protocol(
+    docstring: &str, name: &str, functions: &[FrozenUnit], module: &mut Module
+) -> String {
+    let mut s = format!("class {}(Protocol):\n", name);
+    let mut body = docs(Some(docstring), "    ", DocStyle::Statement);
+
+    for function in functions {
+        let FrozenUnit::Function { docstring, name, synchronous, arguments, _return, .. } = function else {
+            continue
+        };
+
+        let parameters: String = arguments.iter()
+            .map(|arg| format!(", {}: {}", identifier(&arg.name), map_kind_to_python_type(&arg.kind, module)))
+            .collect();
+        let returned = match _return {
+            Some(kind) => map_kind_to_python_type(kind, module),
+            None => "None".to_owned(),
+        };
+        let qualifier = if *synchronous { "" } else { "async " };
+
+        if !body.is_empty() { body.push('\n') }
+        body.push_str(&format!(
+            "    {}def {}(self{}) -> {}:\n", qualifier, identifier(name), parameters, returned
+        ));
+        body.push_str(&docs(Some(docstring), "        ", DocStyle::Statement));
+        body.push_str("        ...\n");
+    }
+
+    if body.is_empty() { body.push_str("    pass\n") }
+    s.push_str(&body);
+    s.push_str("\n\n");
+    s
+}
+
+fn map_kind_to_python_type(kind: &KindValue, module: &mut Module) -> String {
+    match kind {
+        KindValue::Primitive(Primitive::String(_)) => "str".to_owned(),
+        KindValue::Primitive(primitive) => map_str_type(primitive.name(), module),
+        KindValue::Namespaced(name, _) => map_str_type(name, module),
+        KindValue::EnumVariant(name, _) => name.clone(),
+        KindValue::Union(kinds) => {
+            module.typing.insert("Union");
+            let kinds: Vec<String> = kinds.iter().map(|kind| map_kind_to_python_type(kind, module)).collect();
+            format!("Union[{}]", kinds.join(", "))
+        }
+    }
+}
+
+fn map_str_type(s: &str, module: &mut Module) -> String {
+    // Arrays of any size are lists
+    if let Some((element, _)) = outer_array(s) {
+        return format!("list[{}]", map_str_type(element, module))
+    }
+    match s {
+        "string" | "str" => "str".to_owned(),
+        "bool" => "bool".to_owned(),
+        "f32" | "f64" | "float" => "float".to_owned(),
+        "u8" | "u16" | "u32" | "u64" | "u128" | "i8" | "i16" | "i32" | "i64" | "i128"
+        | "int" => "int".to_owned(),
+        other => module.import(other).unwrap_or_else(|| other.to_owned()),
+    }
+}
+
+/// The relative module a module in `package` imports the module at `to` with
+fn relative_module(package: &[String], to: &[String]) -> String {
+    let common = package.iter().zip(to).take_while(|(a, b)| a == b).count();
+    let dots = ".".repeat(package.len() - common + 1);
+
+    format!("{}{}", dots, to[common..].join("."))
+}
+
+/// Names that are Python keywords are suffixed
+fn identifier(name: &str) -> String {
+    match KEYWORDS.contains(&name) {
+        true => format!("{}_", name),
+        false => name.to_owned(),
+    }
+}
+
@@ -0,0 +1,296 @@
+// Relative Modules
+
+// Standard Uses
+
+// Crate Uses
+use crate::codelib_gen::common::{schema_type_name, split_arrays};
+use crate::schema::ir::compiler::interpreted::kind_search::KindValue;
+
+// External Uses
+use eyre::{bail, Result};
+
+
+/// Name of the module generated code writes canonical JSON with
+pub(super) const JSON_MODULE: &str = "comline_json";
+
+/// Source of the JSON module
+pub(super) const JSON_SOURCE: &str = r#"//! How values serde writes otherwise are written as canonical JSON, where
+//! 128-bit integers are strings, and so are 64-bit integers of packages
+//! generated with `int64_as_string`, and floats that are not numbers are
+//! `NaN`, `Infinity` or `-Infinity`, and how arrays longer than serde
+//! implements its traits for are written at all
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+
+
+/// Values written as canonical JSON, by fields with `#[serde(with = "...")]`
+pub trait Canonical: Sized {
+    fn write<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+    fn read<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+pub fn serialize<T: Canonical, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    value.write(serializer)
+}
+
+pub fn deserialize<'de, T: Canonical, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    T::read(deserializer)
+}
+
+
+macro_rules! integers {
+    ($($integer:ty),*) => {$(
+        impl Canonical for $integer {
+            fn write<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+
+            fn read<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_any(Text(PhantomData))
+            }
+        }
+    )*};
+}
+integers!(u64, i64, u128, i128);
+
+macro_rules! floats {
+    ($($float:ty),*) => {$(
+        impl Canonical for $float {
+            fn write<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                match self {
+                    value if value.is_nan() => serializer.serialize_str("NaN"),
+                    value if value.is_infinite() && *value > 0.0 => serializer.serialize_str("Infinity"),
+                    value if value.is_infinite() => serializer.serialize_str("-Infinity"),
+                    value => value.serialize(serializer),
+                }
+            }
+
+            fn read<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_any(Text(PhantomData))
+            }
+        }
+    )*};
+}
+floats!(f32, f64);
+
+impl<T: Canonical> Canonical for Option<T> {
+    fn write<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Some(value) => serializer.serialize_some(&Write(value)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    fn read<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Option::<Read<T>>::deserialize(deserializer)?.map(|Read(value)| value))
+    }
+}
+
+impl<T: Canonical> Canonical for Vec<T> {
+    fn write<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(Write))
+    }
+
+    fn read<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Vec::<Read<T>>::deserialize(deserializer)?.into_iter().map(|Read(value)| value).collect())
+    }
+}
+
+impl<T: Canonical, const N: usize> Canonical for [T; N] {
+    fn write<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(Write))
+    }
+
+    fn read<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let values = Vec::<T>::read(deserializer)?;
+        let length = values.len();
+        let expected = format!("{} elements", N);
+
+        values.try_into().map_err(|_| de::Error::invalid_length(length, &expected.as_str()))
+    }
+}
+
+
+/// Arrays longer than 32 elements, alone, optional or in dynamic arrays, by
+/// fields with `#[serde(with = "...::long_arrays")]`
+pub mod long_arrays {
+    use serde::de::{self, DeserializeOwned, Deserializer};
+    use serde::{Deserialize, Serialize, Serializer};
+
+
+    pub trait LongArray: Sized {
+        fn write<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+        fn read<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+    }
+
+    pub fn serialize<T: LongArray, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        value.write(serializer)
+    }
+
+    pub fn deserialize<'de, T: LongArray, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        T::read(deserializer)
+    }
+
+    impl<T: Serialize + DeserializeOwned, const N: usize> LongArray for [T; N] {
+        fn write<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self)
+        }
+
+        fn read<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let values = Vec::<T>::deserialize(deserializer)?;
+            let length = values.len();
+            let expected = format!("{} elements", N);
+
+            values.try_into().map_err(|_| de::Error::invalid_length(length, &expected.as_str()))
+        }
+    }
+
+    impl<T: LongArray> LongArray for Option<T> {
+        fn write<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self {
+                Some(value) => serializer.serialize_some(&Write(value)),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        fn read<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Ok(Option::<Read<T>>::deserialize(deserializer)?.map(|Read(value)| value))
+        }
+    }
+
+    impl<T: LongArray> LongArray for Vec<T> {
+        fn write<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.iter().map(Write))
+        }
+
+        fn read<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Ok(Vec::<Read<T>>::deserialize(deserializer)?.into_iter().map(|Read(value)| value).collect())
+        }
+    }
+
+    struct Write<'a, T>(&'a T);
+
+    impl<T: LongArray> Serialize for Write<'_, T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.0.write(serializer)
+        }
+    }
+
+    struct Read<T>(T);
+
+    impl<'de, T: LongArray> Deserialize<'de> for Read<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            T::read(deserializer).map(Read)
+        }
+    }
+}
+
+
+struct Write<'a, T>(&'a T);
+
+impl<T: Canonical> Serialize for Write<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.write(serializer)
+    }
+}
+
+struct Read<T>(T);
+
+impl<'de, T: Canonical> Deserialize<'de> for Read<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::read(deserializer).map(Read)
+    }
+}
+
+/// Reads numbers, or their text, as written with any options
+struct Text<T>(PhantomData<T>);
+
+impl<T: FromStr> Visitor<'_> for Text<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number, or its text")
+    }
+
+    fn visit_str<E: de::Error>(self, text: &str) -> Result<T, E> {
+        text.parse().map_err(|_| E::invalid_value(de::Unexpected::Str(text), &self))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<T, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<T, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_u128<E: de::Error>(self, value: u128) -> Result<T, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_i128<E: de::Error>(self, value: i128) -> Result<T, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<T, E> {
+        self.visit_str(&value.to_string())
+    }
+}
+"#;
+
+
+/// Module of the JSON module writing arrays longer than serde implements its traits for
+pub(super) const LONG_ARRAYS: &str = "long_arrays";
+
+/// Longest array serde implements its traits for
+const LONGEST_SERDE_ARRAY: usize = 32;
+
+
+/// Whether a kind is written through the JSON module, as integers that are
+/// strings or floats that may not be numbers, alone or in arrays
+pub(super) fn is_canonical(kind: &KindValue, int64_as_string: bool) -> bool {
+    if let KindValue::Union(_) = kind { return false }
+
+    let schema_type = schema_type_name(kind, " | ");
+    match split_arrays(&schema_type).0 {
+        "u128" | "i128" | "f32" | "f64" | "float" => true,
+        "u64" | "i64" => int64_as_string,
+        _ => false,
+    }
+}
+
+/// Whether a kind holds arrays longer than serde implements its traits for,
+/// which are written through the long arrays of the JSON module
+pub(super) fn has_long_arrays(kind: &KindValue) -> bool {
+    if let KindValue::Union(_) = kind { return false }
+
+    split_arrays(&schema_type_name(kind, " | ")).1.into_iter().any(is_long)
+}
+
+/// Long arrays are only written alone, optional or in dynamic arrays, as no
+/// fixed array can hold them
+pub(super) fn check_long_arrays(kind: &KindValue) -> Result<()> {
+    if let KindValue::Union(kinds) = kind { return kinds.iter().try_for_each(check_long_arrays) }
+
+    let schema_type = schema_type_name(kind, " | ");
+    let sizes = split_arrays(&schema_type).1;
+    let Some(innermost) = sizes.iter().rposition(|size| is_long(size)) else { return Ok(()) };
+
+    if sizes[..innermost].iter().any(|size| !size.is_empty()) {
+        bail!(
+            "Type '{}' has an array of more than {} elements in a fixed array, which cannot be written",
+            schema_type, LONGEST_SERDE_ARRAY
+        )
+    }
+
+    Ok(())
+}
+
+fn is_long(size: &str) -> bool {
+    size.parse::<usize>().is_ok_and(|size| size > LONGEST_SERDE_ARRAY)
+}
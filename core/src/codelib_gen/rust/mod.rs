@@ -0,0 +1,538 @@
+// Relative Modules
+mod json;
+mod protocol;
+mod validate;
+mod wire;
+
+// Standard Uses
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+// Crate Uses
+use crate::codelib_gen::common::{
+    braced_unicode_escape, constant_value, docs, is_primitive, outer_array, schema_type_name, string_literal,
+    DocStyle
+};
+use crate::codelib_gen::features::DEFAULT_PARAMETER;
+use crate::codelib_gen::{GeneratedFile, GenerationContext};
+use crate::schema::ir::expression::field_validators;
+use crate::schema::ir::frozen::unit::FrozenUnit;
+use crate::schema::ir::compiler::interpreted::kind_search::{KindValue, Primitive};
+use crate::utils::case::to_pascal_case;
+use json::{check_long_arrays, has_long_arrays, is_canonical, JSON_MODULE, JSON_SOURCE, LONG_ARRAYS};
+use protocol::{generate_protocol, TRANSPORT_MODULE, TRANSPORT_SOURCE};
+use validate::{validate_impl, ValidatedField};
+use wire::{enum_impls, struct_impls, union_impls, WIRE_USES};
+
+// External Uses
+use eyre::{bail, Result};
+
+
+/// Words Rust reserves, which generated names are escaped from
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true",
+    "type", "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final",
+    "macro", "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+const HEADER: &str = "// Generated by Comline\n";
+
+
+/// What is being generated of a schema
+struct Module<'a> {
+    /// Enums generated for union kinds, by name, emitted after the other items
+    unions: BTreeMap<String, String>,
+    /// The Rust path of a type declared in another schema
+    resolve: &'a dyn Fn(&str) -> Option<String>,
+    /// The declaration of a validator fields use
+    validator: &'a dyn Fn(&str) -> Option<&'a FrozenUnit>,
+    /// The Rust path of the transport module
+    transport: String,
+    /// The Rust path of the JSON module
+    json: String,
+    /// Whether 64-bit integers are strings in JSON
+    int64_as_string: bool,
+    /// Whether anything is written through the JSON module
+    uses_json: bool,
+    /// Whether types implement the binary encoding of `comline_runtime`
+    wire: bool,
+}
+
+
+/// Generates a module for every schema, in a tree mirroring their namespaces
+/// Options:
+/// - `cargo_crate = true` generates a complete crate, with the modules under `src/`
+/// - `root_module` is the path the root module is reachable at, `crate` by default,
+///   as used for the types of other schemas
+/// - `wire_encoding = true` implements the binary encoding of `comline_runtime`
+///   for every type, as `Encode` and `Decode`
+/// - `runtime_path` is the path `comline_runtime` is depended on from in the
+///   manifest of a crate, rather than its published version
+/// - `int64_as_string = true` writes 64-bit integers as strings in JSON, as
+///   canonical JSON written with `int64_as_string` does
+///
+/// Types serialize as canonical JSON, with unions as an object of the kind of
+/// their value to the value. The 128-bit integers and floats that serde would
+/// write otherwise go through a JSON module in the root module, as do arrays
+/// longer than serde implements its traits for
+///
+/// Structs whose fields use validators get a `validate` method checking them
+///
+/// Packages with protocols have a transport module in the root module too,
+/// which depends on `serde_json`
+pub fn generate_rust_package(context: &GenerationContext) -> Result<Vec<GeneratedFile>> {
+    let cargo_crate = context.flag("cargo_crate");
+    let wire = context.flag("wire_encoding");
+    let root_module = match cargo_crate {
+        true => "crate",
+        false => context.option("root_module").unwrap_or("crate"),
+    };
+    let transport = format!("{}::{}", root_module, TRANSPORT_MODULE);
+    let transport_namespace = [TRANSPORT_MODULE.to_owned()];
+    let json = format!("{}::{}", root_module, JSON_MODULE);
+    let json_namespace = [JSON_MODULE.to_owned()];
+    let int64_as_string = context.flag("int64_as_string");
+
+    // Submodules of every module, by namespace, the root one has an empty namespace
+    let mut tree: BTreeMap<&[String], BTreeSet<&str>> = BTreeMap::new();
+    tree.insert(&[], BTreeSet::new());
+    if has_protocols(context) {
+        tree.get_mut([].as_slice()).unwrap().insert(TRANSPORT_MODULE);
+    }
+    for schema in &context.schemas {
+        for depth in 0..schema.namespace.len() {
+            tree.entry(&schema.namespace[..depth]).or_default()
+                .insert(&schema.namespace[depth]);
+        }
+    }
+
+    let mut modules: BTreeMap<&[String], String> = BTreeMap::new();
+    let mut uses_json = false;
+    for schema in &context.schemas {
+        let resolve = |type_name: &str| {
+            let (namespace, name) = context.resolve_type(schema, type_name)?;
+            let segments = std::iter::once(root_module.to_owned())
+                .chain(namespace.iter().map(|segment| identifier(segment)))
+                .chain(std::iter::once(name));
+
+            Some(segments.collect::<Vec<_>>().join("::"))
+        };
+        let validator = |name: &str| context.validator(schema, name);
+        let (module, json_used) = generate_module(
+            &schema.units, &resolve, &validator, (&transport, &json), wire, int64_as_string
+        )?;
+        modules.insert(&schema.namespace, module);
+        uses_json |= json_used;
+    }
+    if has_protocols(context) {
+        modules.insert(&transport_namespace, TRANSPORT_SOURCE.to_owned());
+    }
+    if uses_json {
+        tree.get_mut([].as_slice()).unwrap().insert(JSON_MODULE);
+        modules.insert(&json_namespace, JSON_SOURCE.to_owned());
+    }
+
+    let source_path = match cargo_crate {
+        true => PathBuf::from("src"),
+        false => PathBuf::new(),
+    };
+    let namespaces: BTreeSet<&[String]> = tree.keys().chain(modules.keys()).copied().collect();
+    let mut files = vec![];
+
+    for namespace in namespaces {
+        let submodules = tree.get(namespace);
+
+        // Modules with submodules are directories, as the root one always is
+        let path = match (namespace.split_last(), submodules) {
+            (None, _) if cargo_crate => PathBuf::from("lib.rs"),
+            (None, _) => PathBuf::from("mod.rs"),
+            (Some(_), Some(_)) => PathBuf::from(namespace.join("/")).join("mod.rs"),
+            (Some(_), None) => PathBuf::from(namespace.join("/")).with_extension("rs"),
+        };
+
+        let mut contents = HEADER.to_owned();
+        if let Some(submodules) = submodules {
+            for submodule in submodules {
+                contents.push_str(&format!("pub mod {};\n", identifier(submodule)));
+            }
+            contents.push('\n');
+        }
+        if let Some(module) = modules.get(namespace) {
+            contents.push_str(module);
+        }
+
+        files.push(GeneratedFile { path: source_path.join(path), contents });
+    }
+
+    if cargo_crate {
+        files.push(GeneratedFile { path: PathBuf::from("Cargo.toml"), contents: cargo_manifest(context) });
+    }
+
+    Ok(files)
+}
+
+fn has_protocols(context: &GenerationContext) -> bool {
+    context.schemas.iter()
+        .any(|schema| schema.units.iter().any(|unit| matches!(unit, FrozenUnit::Protocol { .. })))
+}
+
+fn cargo_manifest(context: &GenerationContext) -> String {
+    let mut manifest = format!(
+        "[package]\nname = \"{}\"\nversion = \"{}\"\nedition = \"2021\"\n\n\
+        [dependencies]\nserde = {{ version = \"1\", features = [\"derive\"] }}\n",
+        context.package_name, context.package_version
+    );
+    if has_protocols(context) {
+        manifest.push_str("serde_json = \"1\"\n");
+    }
+    if context.flag("wire_encoding") {
+        match context.option("runtime_path") {
+            Some(path) => manifest.push_str(&format!("comline-runtime = {{ path = {:?} }}\n", path)),
+            None => manifest.push_str("comline-runtime = \"0.1\"\n"),
+        }
+    }
+
+    manifest
+}
+
+/// Generates the code of a single schema, leaving types of other schemas as
+/// named, with the transport module inside when the schema has protocols and
+/// the JSON module when its types use it
+pub fn generate_rust(units: &[FrozenUnit]) -> Result<String> {
+    let validator = |name: &str| units.iter().find(|unit| matches!(
+        unit, FrozenUnit::Validator { name: declared, .. } if declared == name
+    ));
+    let (module, uses_json) = generate_module(
+        units, &|_| None, &validator, (TRANSPORT_MODULE, JSON_MODULE), false, false
+    )?;
+    let mut output = format!("{}{}", HEADER, module);
+
+    if units.iter().any(|unit| matches!(unit, FrozenUnit::Protocol { .. })) {
+        output.push_str(&format!("pub mod {} {{\n{}}}\n", TRANSPORT_MODULE, TRANSPORT_SOURCE));
+    }
+    if uses_json {
+        output.push_str(&format!("pub mod {} {{\n{}}}\n", JSON_MODULE, JSON_SOURCE));
+    }
+
+    Ok(output)
+}
+
+/// The code of a module with the paths of the transport and JSON modules,
+/// and whether it uses the JSON module
+fn generate_module<'a>(
+    units: &[FrozenUnit], resolve: &'a dyn Fn(&str) -> Option<String>,
+    validator: &'a dyn Fn(&str) -> Option<&'a FrozenUnit>, (transport, json): (&str, &str), wire: bool,
+    int64_as_string: bool,
+) -> Result<(String, bool)> {
+    let mut output = String::new();
+    let mut module = Module {
+        unions: BTreeMap::new(), resolve, validator, transport: transport.to_owned(), json: json.to_owned(),
+        int64_as_string, uses_json: false, wire
+    };
+
+    for kind in kinds(units) {
+        if !is_canonical(kind, int64_as_string) { check_long_arrays(kind)? }
+    }
+
+    output.push_str("use serde::{Serialize, Deserialize};\n");
+    if wire { output.push_str(WIRE_USES) }
+    output.push('\n');
+
+    for unit in units {
+        match unit {
+            FrozenUnit::Constant { docstring, name, kind_value } => {
+                output.push_str(&generate_constant(docstring.as_deref(), name, kind_value));
+            }
+            FrozenUnit::Struct { docstring, name, fields, .. } => {
+                output.push_str(&generate_struct(docstring.as_deref(), name, fields, &mut module)?);
+            }
+            FrozenUnit::Enum { docstring, name, variants } => {
+                output.push_str(&generate_enum(docstring.as_deref(), name, variants, module.wire));
+            }
+            FrozenUnit::Protocol { docstring, name, functions, .. } => {
+                output.push_str(&generate_protocol(docstring, name, functions, &mut module));
+            }
+            _ => {}
+        }
+    }
+
+    for union in module.unions.values() {
+        output.push_str(union);
+    }
+
+    Ok((output, module.uses_json))
+}
+
+fn generate_constant(docstring: Option<&str>, name: &str, kind_value: &KindValue) -> String {
+    let Some((primitive, value)) = constant_value(kind_value) else { return String::new() };
+
+    let (type_name, value) = match primitive {
+        Primitive::String(_) => ("&str".to_owned(), string_literal(&value, braced_unicode_escape)),
+        primitive => (map_str_type(primitive.name(), &|_| None), value),
+    };
+
+    let mut s = docs(docstring, "", DocStyle::Lines("///"));
+    s.push_str(&format!("pub const {}: {} = {};\n\n", identifier(name), type_name, value));
+    s
+}
+
+fn generate_struct(
+    docstring: Option<&str>, name: &str, fields: &Vec<FrozenUnit>, module: &mut Module
+) -> Result<String> {
+    let mut s = docs(docstring, "", DocStyle::Lines("///"));
+    s.push_str(&format!("#[derive(Debug, Clone, Serialize, Deserialize)]\npub struct {} {{\n", name));
+
+    // Functions giving the defaults of fields, and how each field is defaulted
+    let mut defaults = String::new();
+    let mut initializers = vec![];
+    for field in fields {
+        if let FrozenUnit::Field { docstring, name: field_name, kind_value, optional, parameters } = field {
+            let mut type_name = map_kind_to_rust_type(kind_value, module);
+            let field_name = identifier(field_name);
+
+            s.push_str(&docs(docstring.as_deref(), "    ", DocStyle::Lines("///")));
+            let mut serde = vec![];
+            if *optional {
+                serde.push("default, skip_serializing_if = \"Option::is_none\"".to_owned());
+                initializers.push(Some(format!("{}: None", field_name)));
+            } else if let Some(default) = default_parameter(parameters) {
+                let function = format!("default_{}", field_name.trim_start_matches("r#"));
+                serde.push(format!("default = \"{}::{}\"", name, function));
+                defaults.push_str(&format!(
+                    "    pub fn {}() -> {} {{\n        {}\n    }}\n",
+                    function, type_name, default_literal(default, kind_value, &type_name)?
+                ));
+                initializers.push(Some(format!("{}: Self::{}()", field_name, function)));
+            } else {
+                initializers.push(None);
+            }
+            if let Some(json) = json_path(kind_value, module) {
+                serde.push(json);
+            }
+            if !serde.is_empty() {
+                s.push_str(&format!("    #[serde({})]\n", serde.join(", ")));
+            }
+            if *optional {
+                type_name = format!("Option<{}>", type_name);
+            }
+            s.push_str(&format!("    pub {}: {},\n", field_name, type_name));
+        }
+    }
+
+    s.push_str("}\n\n");
+    if !defaults.is_empty() {
+        s.push_str(&format!("impl {} {{\n{}}}\n\n", name, defaults));
+    }
+    // Structs whose every field has a default can be made of them, as decoders
+    // of data missing them do
+    if let Some(initializers) = initializers.into_iter().collect::<Option<Vec<_>>>() {
+        s.push_str(&format!("impl Default for {} {{\n    fn default() -> Self {{\n        Self {{", name));
+        match initializers.is_empty() {
+            true => s.push_str("}\n"),
+            false => {
+                s.push('\n');
+                for initializer in initializers {
+                    s.push_str(&format!("            {},\n", initializer));
+                }
+                s.push_str("        }\n");
+            }
+        }
+        s.push_str("    }\n}\n\n");
+    }
+    if module.wire {
+        let names: Vec<&str> = fields.iter().filter_map(|field| match field {
+            FrozenUnit::Field { name, .. } => Some(name.as_str()),
+            _ => None,
+        }).collect();
+        s.push_str(&struct_impls(name, &names));
+    }
+
+    let mut validated = vec![];
+    for field in fields {
+        if let FrozenUnit::Field { name, kind_value, optional, parameters, .. } = field {
+            let uses = field_validators(parameters)?;
+            if uses.is_empty() { continue }
+
+            validated.push((name.as_str(), *optional, schema_type_name(kind_value, " | "), uses));
+        }
+    }
+    if !validated.is_empty() {
+        let fields: Vec<ValidatedField> = validated.into_iter()
+            .map(|(name, optional, kind, uses)| ValidatedField { name, optional, kind, uses })
+            .collect();
+        s.push_str(&validate_impl(name, &fields, module.validator)?);
+    }
+
+    Ok(s)
+}
+
+fn generate_enum(docstring: Option<&str>, name: &str, variants: &Vec<FrozenUnit>, wire: bool) -> String {
+    let mut s = docs(docstring, "", DocStyle::Lines("///"));
+    s.push_str(&format!("#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]\npub enum {} {{\n", name));
+
+    let mut names = vec![];
+    for variant in variants {
+        if let FrozenUnit::EnumVariant(kv) = variant {
+            // Extract name from KindValue.
+            // Usually EnumVariant(String, Option<Box>) or Primitive?
+            // incremental.rs maps it to EnumVariant(name, None).
+            let variant_name = match kv {
+                KindValue::EnumVariant(n, _) => n.clone(),
+                KindValue::Namespaced(n, _) => n.clone(), // Fallback
+                _ => "Unknown".to_string(),
+            };
+             s.push_str(&format!("    {},\n", variant_name));
+             names.push(variant_name);
+        }
+    }
+
+    s.push_str("}\n\n");
+    if wire { s.push_str(&enum_impls(name, &names)) }
+    s
+}
+
+/// An enum with a variant for each kind of the union, named after them, which
+/// serde writes as an object of the kind to the value as canonical JSON does
+fn generate_union(kinds: &[KindValue], module: &mut Module) -> String {
+    let variants: Vec<(String, String)> = kinds.iter()
+        .map(|kind| (variant_name(&schema_type_name(kind, "_or_")), map_kind_to_rust_type(kind, module)))
+        .collect();
+    let name = variants.iter().map(|(variant, _)| variant.as_str()).collect::<Vec<_>>().join("Or");
+
+    if !module.unions.contains_key(&name) {
+        let mut s = format!("#[derive(Debug, Clone, Serialize, Deserialize)]\npub enum {} {{\n", name);
+        for (kind, (variant, type_name)) in kinds.iter().zip(&variants) {
+            let mut serde = vec![format!("rename = {:?}", schema_type_name(kind, " | "))];
+            serde.extend(json_path(kind, module));
+            s.push_str(&format!("    #[serde({})]\n    {}({}),\n", serde.join(", "), variant, type_name));
+        }
+        s.push_str("}\n\n");
+        if module.wire {
+            let names: Vec<&str> = variants.iter().map(|(variant, _)| variant.as_str()).collect();
+            s.push_str(&union_impls(&name, &names));
+        }
+
+        module.unions.insert(name.clone(), s);
+    }
+
+    name
+}
+
+/// How serde writes a kind through the JSON module, if it does
+fn json_path(kind: &KindValue, module: &mut Module) -> Option<String> {
+    let path = match is_canonical(kind, module.int64_as_string) {
+        true => module.json.clone(),
+        false if has_long_arrays(kind) => format!("{}::{}", module.json, LONG_ARRAYS),
+        false => return None,
+    };
+
+    module.uses_json = true;
+    Some(format!("with = \"{}\"", path))
+}
+
+/// Kinds of the fields, arguments and returns of the units
+fn kinds(units: &[FrozenUnit]) -> Vec<&KindValue> {
+    let mut kinds = vec![];
+    for unit in units {
+        match unit {
+            FrozenUnit::Struct { fields, .. } => kinds.extend(fields.iter().filter_map(|field| match field {
+                FrozenUnit::Field { kind_value, .. } => Some(kind_value),
+                _ => None,
+            })),
+            FrozenUnit::Protocol { functions, .. } => {
+                for function in functions {
+                    let FrozenUnit::Function { arguments, _return, .. } = function else { continue };
+                    kinds.extend(arguments.iter().map(|argument| &argument.kind).chain(_return));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    kinds
+}
+
+/// The Rust expression of a field default, which annotations give as written,
+/// where defaults of enums are the names of their variants
+fn default_literal(default: &str, kind: &KindValue, type_name: &str) -> Result<String> {
+    let schema_type = schema_type_name(kind, " | ");
+    // Numbers are written again as parsed, suffixed with their type
+    let literal = match schema_type.as_str() {
+        "str" | "string" => Some(format!("{}.to_owned()", string_literal(default, braced_unicode_escape))),
+        "bool" => default.parse::<bool>().ok().map(|value| value.to_string()),
+        "float" | "f32" | "f64" => default.parse::<f64>().ok()
+            .filter(|value| value.is_finite())
+            .map(|value| format!("{:?}_{}", value, type_name)),
+        "int" | "u8" | "u16" | "u32" | "u64" | "u128" | "i8" | "i16" | "i32" | "i64" | "i128" =>
+            default.parse::<i128>().map(|value| value.to_string())
+                .or_else(|_| default.parse::<u128>().map(|value| value.to_string())).ok()
+                .map(|value| format!("{}_{}", value, type_name)),
+        _ if is_primitive(&schema_type) || outer_array(&schema_type).is_some()
+            || matches!(kind, KindValue::Union(_)) => None,
+        // Enums, by the name of a variant
+        _ => Some(format!("{}::{}", type_name, default)),
+    };
+
+    match literal {
+        Some(literal) => Ok(literal),
+        None => bail!("Default '{}' is not a value of type '{}'", default, schema_type),
+    }
+}
+
+/// The default annotated on a field, if any
+fn default_parameter(parameters: &[FrozenUnit]) -> Option<&str> {
+    parameters.iter().find_map(|parameter| match parameter {
+        FrozenUnit::Parameter { name, default_value } if name == DEFAULT_PARAMETER => Some(default_value.as_str()),
+        _ => None,
+    })
+}
+
+fn map_kind_to_rust_type(kind: &KindValue, module: &mut Module) -> String {
+    match kind {
+        KindValue::Primitive(_) => map_str_type(&schema_type_name(kind, " | "), module.resolve),
+        KindValue::Namespaced(name, _) => map_str_type(name, module.resolve),
+        KindValue::EnumVariant(name, _) => name.clone(),
+        KindValue::Union(kinds) => generate_union(kinds, module),
+    }
+}
+
+fn map_str_type(s: &str, resolve: &dyn Fn(&str) -> Option<String>) -> String {
+    if let Some((element, size)) = outer_array(s) {
+        return match size {
+            "" => format!("Vec<{}>", map_str_type(element, resolve)),
+            size => format!("[{}; {}]", map_str_type(element, resolve), size),
+        }
+    }
+    match s {
+        "string" | "str" => "String".to_string(),
+        "bool" => "bool".to_string(),
+        "float" => "f64".to_string(),
+        "int" => "i32".to_string(), // default to i32 for generic int
+        other => resolve(other).unwrap_or_else(|| other.to_string()),
+    }
+}
+
+/// `User[]` into `UserList` and `u8[4]` into `U8Array4`
+fn variant_name(schema_type: &str) -> String {
+    if let Some((element, size)) = outer_array(schema_type) {
+        return match size {
+            "" => format!("{}List", variant_name(element)),
+            size => format!("{}Array{}", variant_name(element), size),
+        }
+    }
+
+    to_pascal_case(schema_type)
+}
+
+/// Names that are Rust keywords are escaped as raw identifiers, or suffixed
+/// for the few that cannot be raw
+fn identifier(name: &str) -> String {
+    match name {
+        "self" | "Self" | "super" | "crate" => format!("{}_", name),
+        name if KEYWORDS.contains(&name) => format!("r#{}", name),
+        name => name.to_owned(),
+    }
+}
+
@@ -0,0 +1,350 @@
+// Protocols are generated as a request and a response enum, a server trait
+// that every server implements, and a client calling a server through a
+// transport, which the transport module generated along holds
+
+// Standard Uses
+
+// Crate Uses
+use super::{identifier, json_path, map_kind_to_rust_type, Module};
+use crate::codelib_gen::common::{docs, DocStyle};
+use crate::schema::ir::compiler::interpreted::kind_search::KindValue;
+use crate::schema::ir::frozen::unit::FrozenUnit;
+use crate::utils::case::to_pascal_case;
+
+// External Uses
+
+
+/// Name of the module holding what generated protocols share
+pub(super) const TRANSPORT_MODULE: &str = "comline";
+
+/// Source of the transport module
+pub(super) const TRANSPORT_SOURCE: &str = r#"//! What generated protocols share, the traits between clients and servers
+//! and the transports carrying calls from one to the other
+
+use std::future::Future;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+
+/// Why a call did not get its response
+#[derive(Debug)]
+pub enum TransportError {
+    Io(std::io::Error),
+    Encoding(serde_json::Error),
+    /// The response is of another function than the one called
+    UnexpectedResponse,
+}
+
+impl std::fmt::Display for TransportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Transport failed: {}", e),
+            Self::Encoding(e) => write!(f, "Message could not be encoded: {}", e),
+            Self::UnexpectedResponse => write!(f, "Response does not match the call"),
+        }
+    }
+}
+
+impl std::error::Error for TransportError {}
+
+impl From<std::io::Error> for TransportError {
+    fn from(error: std::io::Error) -> Self { Self::Io(error) }
+}
+
+impl From<serde_json::Error> for TransportError {
+    fn from(error: serde_json::Error) -> Self { Self::Encoding(error) }
+}
+
+
+/// Answers the requests of a protocol, as every server of it does
+pub trait Service<Request, Response> {
+    fn handle(&self, request: Request) -> impl Future<Output = Response>;
+}
+
+/// Carries requests to a server and brings its responses back
+pub trait Transport<Request, Response> {
+    fn call(&self, request: Request) -> impl Future<Output = Result<Response, TransportError>>;
+}
+
+
+/// Calls a server in the same process
+pub struct InProcess<S>(pub S);
+
+impl<S: Service<Request, Response>, Request, Response> Transport<Request, Response> for InProcess<S> {
+    async fn call(&self, request: Request) -> Result<Response, TransportError> {
+        Ok(self.0.handle(request).await)
+    }
+}
+
+
+/// Calls a server over a local socket, a line of JSON for every message,
+/// blocking the calling thread until the response arrives
+pub struct Socket {
+    stream: Mutex<BufReader<TcpStream>>,
+}
+
+impl Socket {
+    pub fn connect(address: impl ToSocketAddrs) -> Result<Self, TransportError> {
+        Ok(Self { stream: Mutex::new(BufReader::new(TcpStream::connect(address)?)) })
+    }
+}
+
+impl<Request: Serialize, Response: DeserializeOwned> Transport<Request, Response> for Socket {
+    async fn call(&self, request: Request) -> Result<Response, TransportError> {
+        let mut stream = self.stream.lock().unwrap_or_else(|e| e.into_inner());
+        writeln!(stream.get_mut(), "{}", serde_json::to_string(&request)?)?;
+
+        let mut response = String::new();
+        if stream.read_line(&mut response)? == 0 {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into())
+        }
+
+        Ok(serde_json::from_str(&response)?)
+    }
+}
+
+/// Answers the calls of every connection to `listener` with `server`, one
+/// connection after the other
+pub fn serve<S, Request, Response>(listener: &TcpListener, server: &S) -> Result<(), TransportError>
+    where S: Service<Request, Response>, Request: DeserializeOwned, Response: Serialize
+{
+    for stream in listener.incoming() {
+        serve_connection(stream?, server)?;
+    }
+
+    Ok(())
+}
+
+/// Answers the calls of a connection with `server`, until it is closed
+pub fn serve_connection<S, Request, Response>(stream: TcpStream, server: &S) -> Result<(), TransportError>
+    where S: Service<Request, Response>, Request: DeserializeOwned, Response: Serialize
+{
+    let mut writer = stream.try_clone()?;
+
+    for line in BufReader::new(stream).lines() {
+        let request: Request = serde_json::from_str(&line?)?;
+        let response = block_on(server.handle(request));
+        writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+    }
+
+    Ok(())
+}
+
+
+/// Runs a future to completion on the current thread
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    struct Unpark(std::thread::Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) { self.0.unpark() }
+    }
+
+    let waker = Waker::from(Arc::new(Unpark(std::thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+"#;
+
+
+/// Methods the client and the service of a protocol have besides its
+/// functions, which functions of the same name are escaped from
+const GENERATED_METHODS: &[&str] = &["new", "handle"];
+
+
+/// A function of a protocol, as the generated items see it
+struct Call {
+    docstring: String,
+    method: String,
+    variant: String,
+    synchronous: bool,
+    /// Names and types of the arguments
+    arguments: Vec<(String, String)>,
+    returns: Option<String>,
+    /// Types of the arguments and return where the messages declare them,
+    /// with how serde writes them through the JSON module
+    message_arguments: Vec<String>,
+    message_returns: Option<String>,
+}
+
+impl Call {
+    /// The variant of the call holding `values`, which are types where the
+    /// variant is declared and names where it is matched or built
+    fn variant(&self, values: &[String]) -> String {
+        match values.is_empty() {
+            true => self.variant.clone(),
+            false => format!("{}({})", self.variant, values.join(", ")),
+        }
+    }
+
+    fn pattern(&self, enumeration: &str, values: &[String]) -> String {
+        format!("{}::{}", enumeration, self.variant(values))
+    }
+
+    fn argument_names(&self) -> Vec<String> {
+        self.arguments.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    fn parameters(&self) -> String {
+        std::iter::once("&self".to_owned())
+            .chain(self.arguments.iter().map(|(name, kind)| format!("{}: {}", name, kind)))
+            .collect::<Vec<_>>().join(", ")
+    }
+
+    fn returned(&self) -> &str {
+        self.returns.as_deref().unwrap_or("()")
+    }
+}
+
+
+/// Name of the method of a function, in the server trait and the client
+fn method(name: &str) -> String {
+    match GENERATED_METHODS.contains(&name) {
+        true => format!("{}_", name),
+        false => identifier(name),
+    }
+}
+
+/// Type of an argument or return in the messages, with how serde writes it
+fn message_type(kind: &KindValue, module: &mut Module) -> String {
+    let type_name = map_kind_to_rust_type(kind, module);
+    match json_path(kind, module) {
+        Some(json) => format!("#[serde({})] {}", json, type_name),
+        None => type_name,
+    }
+}
+
+pub(super) fn generate_protocol(
+    docstring: &str, name: &str, functions: &[FrozenUnit], module: &mut Module
+) -> String {
+    let calls: Vec<Call> = functions.iter().filter_map(|function| {
+        let FrozenUnit::Function { docstring, name, synchronous, arguments, _return, .. } = function else {
+            return None
+        };
+
+        let message_arguments = arguments.iter().map(|arg| message_type(&arg.kind, module)).collect();
+        let message_returns = _return.as_ref().map(|kind| message_type(kind, module));
+
+        let arguments = arguments.iter()
+            .map(|arg| (identifier(&arg.name), map_kind_to_rust_type(&arg.kind, module)))
+            .collect();
+        let returns = _return.as_ref().map(|kind| map_kind_to_rust_type(kind, module));
+
+        Some(Call {
+            docstring: docstring.clone(),
+            method: method(name),
+            variant: to_pascal_case(name),
+            synchronous: *synchronous,
+            arguments,
+            returns,
+            message_arguments,
+            message_returns,
+        })
+    }).collect();
+
+    let request = format!("{}Request", name);
+    let response = format!("{}Response", name);
+    let transport = module.transport.clone();
+
+    let mut s = String::new();
+
+    // Messages
+    s.push_str(&format!("/// Calls of `{}`, with the arguments of each\n", name));
+    s.push_str(&format!("#[derive(Debug, Clone, Serialize, Deserialize)]\npub enum {} {{\n", request));
+    for call in &calls {
+        s.push_str(&format!("    {},\n", call.variant(&call.message_arguments)));
+    }
+    s.push_str("}\n\n");
+
+    s.push_str(&format!("/// What the calls of `{}` return\n", name));
+    s.push_str(&format!("#[derive(Debug, Clone, Serialize, Deserialize)]\npub enum {} {{\n", response));
+    for call in &calls {
+        let types: Vec<String> = call.message_returns.iter().cloned().collect();
+        s.push_str(&format!("    {},\n", call.variant(&types)));
+    }
+    s.push_str("}\n\n");
+
+    // Server
+    s.push_str(&docs(Some(docstring), "", DocStyle::Lines("///")));
+    s.push_str(&format!("pub trait {} {{\n", name));
+    for call in &calls {
+        s.push_str(&docs(Some(&call.docstring), "    ", DocStyle::Lines("///")));
+        match call.synchronous {
+            true => s.push_str(&format!(
+                "    fn {}({}) -> {};\n", call.method, call.parameters(), call.returned()
+            )),
+            false => s.push_str(&format!(
+                "    fn {}({}) -> impl std::future::Future<Output = {}>;\n",
+                call.method, call.parameters(), call.returned()
+            )),
+        }
+    }
+    s.push_str("}\n\n");
+
+    s.push_str(&format!(
+        "impl<T: {}> {}::Service<{}, {}> for T {{\n", name, transport, request, response
+    ));
+    s.push_str(&format!("    async fn handle(&self, request: {}) -> {} {{\n", request, response));
+    s.push_str("        match request {\n");
+    for call in &calls {
+        let arguments = call.argument_names();
+        let invocation = match call.synchronous {
+            true => format!("self.{}({})", call.method, arguments.join(", ")),
+            false => format!("self.{}({}).await", call.method, arguments.join(", ")),
+        };
+        let answer = match call.returns {
+            Some(_) => format!("{}({})", call.pattern(&response, &[]), invocation),
+            None => format!("{{ {}; {} }}", invocation, call.pattern(&response, &[])),
+        };
+        s.push_str(&format!("            {} => {},\n", call.pattern(&request, &arguments), answer));
+    }
+    s.push_str("        }\n    }\n}\n\n");
+
+    // Client
+    s.push_str(&format!("/// Calls `{}` through a transport\npub struct {}Client<T> {{\n", name, name));
+    s.push_str("    pub transport: T,\n}\n\n");
+
+    s.push_str("#[allow(unreachable_patterns)]\n");
+    s.push_str(&format!(
+        "impl<T: {}::Transport<{}, {}>> {}Client<T> {{\n", transport, request, response, name
+    ));
+    s.push_str("    pub fn new(transport: T) -> Self {\n        Self { transport }\n    }\n");
+    for call in &calls {
+        let arguments = call.argument_names();
+        let message = call.pattern(&request, &arguments);
+        let (qualifier, outcome) = match call.synchronous {
+            true => ("", format!("{}::block_on(self.transport.call({}))?", transport, message)),
+            false => ("async ", format!("self.transport.call({}).await?", message)),
+        };
+        let (answer, value) = match call.returns {
+            Some(_) => (call.pattern(&response, &["value".to_owned()]), "value"),
+            None => (call.pattern(&response, &[]), "()"),
+        };
+
+        s.push('\n');
+        s.push_str(&docs(Some(&call.docstring), "    ", DocStyle::Lines("///")));
+        s.push_str(&format!(
+            "    pub {}fn {}({}) -> Result<{}, {}::TransportError> {{\n",
+            qualifier, call.method, call.parameters(), call.returned(), transport
+        ));
+        s.push_str(&format!("        match {} {{\n", outcome));
+        s.push_str(&format!("            {} => Ok({}),\n", answer, value));
+        s.push_str(&format!("            _ => Err({}::TransportError::UnexpectedResponse),\n", transport));
+        s.push_str("        }\n    }\n");
+    }
+    s.push_str("}\n\n");
+
+    s
+}
@@ -0,0 +1,257 @@
+// Relative Modules
+
+// Standard Uses
+
+// Crate Uses
+use super::identifier;
+use crate::schema::ir::expression::{constant, evaluate, Expression, Operator, Scope, Segment, Validator, Value};
+use crate::schema::ir::expression::ValidatorUse;
+use crate::schema::ir::frozen::unit::FrozenUnit;
+
+// External Uses
+use eyre::{bail, Result, WrapErr};
+
+
+/// A field of a struct that uses validators
+pub(super) struct ValidatedField<'a> {
+    pub(super) name: &'a str,
+    pub(super) optional: bool,
+    /// Type as written in schemas, like `str` or `u8[]`
+    pub(super) kind: String,
+    pub(super) uses: Vec<ValidatorUse>,
+}
+
+/// What the Rust code of an expression evaluates to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Bool,
+    Integer,
+    Float,
+    Text,
+    List,
+    Other,
+}
+
+/// What names of an expression are translated into
+struct Translation<'a> {
+    field: &'a ValidatedField<'a>,
+    parameters: &'a [(String, Value)],
+}
+
+
+/// A `validate` method checking the values of fields with their validators,
+/// which gives back what every failing assertion reports
+pub(super) fn validate_impl<'a>(
+    name: &str, fields: &[ValidatedField], lookup: &'a dyn Fn(&str) -> Option<&'a FrozenUnit>,
+) -> Result<String> {
+    let mut s = format!(concat!(
+        "impl {} {{\n",
+        "    /// Checks the fields with the validators they use, giving back every problem found\n",
+        "    pub fn validate(&self) -> Result<(), Vec<String>> {{\n",
+        "        let mut problems = vec![];\n",
+    ), name);
+
+    for field in fields {
+        for used in &field.uses {
+            let Some(unit) = lookup(&used.name) else {
+                bail!("Field '{}::{}' uses validator '{}', which is not declared in the package", name, field.name, used.name)
+            };
+            let validator = Validator::from_unit(unit)?;
+            let parameters = validator.parameters(&used.arguments)
+                .wrap_err_with(|| format!("Field '{}::{}' cannot use validator '{}'", name, field.name, used.name))?;
+
+            let translation = Translation { field, parameters: &parameters };
+            s.push_str(&checks(&validator, &translation)
+                .wrap_err_with(|| format!("Validator '{}' cannot be generated for '{}::{}'", used.name, name, field.name))?);
+        }
+    }
+
+    s.push_str("\n        match problems.is_empty() {\n            true => Ok(()),\n            false => Err(problems),\n        }\n    }\n}\n\n");
+    Ok(s)
+}
+
+/// The assertions of a validator on a field, within a block binding the value
+/// of the field as `value`, which is only checked when there is one
+fn checks(validator: &Validator, translation: &Translation) -> Result<String> {
+    let field = translation.field;
+    let access = format!("self.{}", identifier(field.name));
+    let uses_value = validator.calls.iter().flat_map(Expression::paths)
+        .any(|path| path == "value" || path == "value.length");
+
+    let mut s = match (field.optional, uses_value) {
+        (true, true) => format!("\n        if let Some(value) = &{} {{\n", access),
+        (true, false) => format!("\n        if {}.is_some() {{\n", access),
+        (false, true) => format!("\n        {{\n            let value = &{};\n", access),
+        (false, false) => "\n        {\n".to_owned(),
+    };
+
+    for call in &validator.calls {
+        let (condition, message) = match call {
+            Expression::Call(function, arguments) if function == "assert" => match arguments.as_slice() {
+                [condition] => (condition, None),
+                [condition, message] => (condition, Some(message)),
+                _ => bail!("'{}' should be given a condition and optionally a message", call),
+            },
+            other => bail!("'{}' is not a call validators can make", other),
+        };
+
+        let (code, kind) = translate(condition, translation)?;
+        if kind != Kind::Bool { bail!("'{}' is not a condition", condition) }
+
+        let (text, arguments) = match message {
+            Some(message) => format_string(message, translation)?,
+            None => (escape(&format!("'{}' does not hold", condition)), vec![]),
+        };
+        let problem = format!("'{}' fails {}: {}", escape(field.name), escape(&validator.name), text);
+        let problem = match arguments.is_empty() {
+            true => format!("\"{}\".to_owned()", unescape(&problem)),
+            false => format!("format!(\"{}\", {})", problem, arguments.join(", ")),
+        };
+
+        s.push_str(&format!(
+            "            if !{} {{\n                problems.push({});\n            }}\n", code, problem
+        ));
+    }
+
+    s.push_str("        }\n");
+    Ok(s)
+}
+
+/// Rust code of an expression, with what it evaluates to
+fn translate(expression: &Expression, translation: &Translation) -> Result<(String, Kind)> {
+    Ok(match expression {
+        Expression::Bool(value) => (value.to_string(), Kind::Bool),
+        Expression::Integer(value) => (format!("{}i128", value), Kind::Integer),
+        Expression::Float(value) => (format!("{:?}f64", value), Kind::Float),
+        Expression::String(_) => {
+            let (text, arguments) = format_string(expression, translation)?;
+            match arguments.is_empty() {
+                true => (format!("\"{}\"", unescape(&text)), Kind::Text),
+                false => (format!("format!(\"{}\", {}).as_str()", text, arguments.join(", ")), Kind::Text),
+            }
+        }
+        Expression::Path(path) => path_code(path, translation)?,
+        Expression::Not(operand) => (format!("!{}", condition(operand, translation)?), Kind::Bool),
+        Expression::Binary(left, Operator::Or, right) => (
+            format!("({} || {})", condition(left, translation)?, condition(right, translation)?), Kind::Bool,
+        ),
+        Expression::Binary(left, Operator::And, right) => (
+            format!("({} && {})", condition(left, translation)?, condition(right, translation)?), Kind::Bool,
+        ),
+        Expression::Binary(left, operator, right) => {
+            let (left_code, left_kind) = translate(left, translation)?;
+            let (right_code, right_kind) = translate(right, translation)?;
+
+            let (left_code, right_code) = match (left_kind, right_kind) {
+                (Kind::Integer, Kind::Float) => (format!("({} as f64)", left_code), right_code),
+                (Kind::Float, Kind::Integer) => (left_code, format!("({} as f64)", right_code)),
+                (Kind::Integer, Kind::Integer) | (Kind::Float, Kind::Float) | (Kind::Text, Kind::Text) => {
+                    (left_code, right_code)
+                }
+                (Kind::Bool, Kind::Bool) if matches!(operator, Operator::Equal | Operator::NotEqual) => {
+                    (left_code, right_code)
+                }
+                _ => bail!("'{}' compares values that cannot be compared", expression),
+            };
+
+            (format!("({} {} {})", left_code, operator.symbol(), right_code), Kind::Bool)
+        }
+        Expression::Call(function, _) => bail!("'{}' can only be called by itself", function),
+    })
+}
+
+fn condition(expression: &Expression, translation: &Translation) -> Result<String> {
+    match translate(expression, translation)? {
+        (code, Kind::Bool) => Ok(code),
+        _ => bail!("'{}' is not a condition", expression),
+    }
+}
+
+fn path_code(path: &str, translation: &Translation) -> Result<(String, Kind)> {
+    if let Some(value) = constant(path) { return literal(&value) }
+
+    if let Some(name) = path.strip_prefix("params.") {
+        return match translation.parameters.iter().find(|(parameter, _)| parameter == name) {
+            Some((_, value)) => literal(value),
+            None => bail!("'{}' is not a property of the validator", path),
+        }
+    }
+
+    let kind = field_kind(&translation.field.kind);
+    Ok(match (path, kind) {
+        ("value.name", _) => (format!("{:?}", translation.field.name), Kind::Text),
+        ("value.length", Kind::Text) => ("(value.chars().count() as i128)".to_owned(), Kind::Integer),
+        ("value.length", Kind::List) => ("(value.len() as i128)".to_owned(), Kind::Integer),
+        ("value", Kind::Text) => ("value.as_str()".to_owned(), Kind::Text),
+        ("value", Kind::Integer) => ("(*value as i128)".to_owned(), Kind::Integer),
+        ("value", Kind::Float | Kind::Bool) => ("*value".to_owned(), kind),
+        ("value" | "value.length", _) => {
+            bail!("'{}' is not known of '{}', which is {}", path, translation.field.name, translation.field.kind)
+        }
+        _ => bail!("'{}' is not known here", path),
+    })
+}
+
+fn literal(value: &Value) -> Result<(String, Kind)> {
+    Ok(match value {
+        Value::Bool(value) => (value.to_string(), Kind::Bool),
+        Value::Integer(value) => (format!("{}i128", value), Kind::Integer),
+        Value::Float(value) => (format!("{:?}f64", value), Kind::Float),
+        Value::String(value) => (format!("{:?}", value), Kind::Text),
+        Value::List(_) => bail!("Lists cannot be given to generated validators"),
+    })
+}
+
+/// What the value of a field is, by its type as written in schemas
+fn field_kind(kind: &str) -> Kind {
+    match kind {
+        "str" | "string" => Kind::Text,
+        "bool" => Kind::Bool,
+        "float" | "f32" | "f64" => Kind::Float,
+        kind if kind.ends_with(']') => Kind::List,
+        kind if Value::Integer(0).fits(kind) => Kind::Integer,
+        _ => Kind::Other,
+    }
+}
+
+/// The format string of a message, with the arguments it is given, where
+/// interpolated constants are written into the string itself
+fn format_string(message: &Expression, translation: &Translation) -> Result<(String, Vec<String>)> {
+    let segments = match message {
+        Expression::String(segments) => segments.clone(),
+        other => vec![Segment::Interpolated(other.clone())],
+    };
+
+    let mut text = String::new();
+    let mut arguments = vec![];
+    let scope = Scope { subject: None, parameters: translation.parameters };
+
+    for segment in &segments {
+        match segment {
+            Segment::Text(part) => text.push_str(&escape(part)),
+            Segment::Interpolated(Expression::Path(path)) if path == "value.name" => {
+                text.push_str(&escape(translation.field.name))
+            }
+            Segment::Interpolated(expression) => match evaluate(expression, &scope) {
+                Ok(value) => text.push_str(&escape(&value.to_string())),
+                Err(_) => {
+                    text.push_str("{}");
+                    arguments.push(translate(expression, translation)?.0);
+                }
+            },
+        }
+    }
+
+    Ok((text, arguments))
+}
+
+/// Text as it is written inside the string literal of a format string
+fn escape(text: &str) -> String {
+    let quoted = format!("{:?}", text);
+    quoted[1..quoted.len() - 1].replace('{', "{{").replace('}', "}}")
+}
+
+/// A format string without arguments as a plain string literal
+fn unescape(text: &str) -> String {
+    text.replace("{{", "{").replace("}}", "}")
+}
@@ -0,0 +1,90 @@
+// Relative Modules
+
+// Standard Uses
+
+// Crate Uses
+use super::identifier;
+
+// External Uses
+
+
+/// Imports of the items wire implementations use
+pub(super) const WIRE_USES: &str = "use comline_runtime::wire::{self, Decode, DecodeError, Encode, Reader};\n";
+
+
+/// Encoding and decoding of a struct, as its fields in the order declared
+pub(super) fn struct_impls(name: &str, fields: &[&str]) -> String {
+    let (buffer, reader) = match fields.is_empty() {
+        true => ("_buffer", "_reader"),
+        false => ("buffer", "reader"),
+    };
+
+    let mut s = format!("impl Encode for {} {{\n    fn encode(&self, {}: &mut Vec<u8>) {{\n", name, buffer);
+    for field in fields {
+        s.push_str(&format!("        self.{}.encode(buffer);\n", identifier(field)));
+    }
+    s.push_str("    }\n}\n\n");
+
+    s.push_str(&format!(
+        "impl Decode for {} {{\n    fn decode({}: &mut Reader) -> Result<Self, DecodeError> {{\n        Ok(Self {{\n",
+        name, reader
+    ));
+    for field in fields {
+        s.push_str(&format!("            {}: Decode::decode(reader)?,\n", identifier(field)));
+    }
+    s.push_str("        })\n    }\n}\n\n");
+
+    s
+}
+
+/// Encoding and decoding of an enum, as the index of its variant
+pub(super) fn enum_impls(name: &str, variants: &[String]) -> String {
+    let mut s = format!(concat!(
+        "impl Encode for {} {{\n    fn encode(&self, buffer: &mut Vec<u8>) {{\n",
+        "        wire::write_varint(buffer, *self as u128)\n    }}\n}}\n\n",
+    ), name);
+
+    s.push_str(&format!(concat!(
+        "impl Decode for {} {{\n    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {{\n",
+        "        match reader.read_varint()? {{\n",
+    ), name));
+    for (index, variant) in variants.iter().enumerate() {
+        s.push_str(&format!("            {} => Ok(Self::{}),\n", index, variant));
+    }
+    s.push_str(&unknown_discriminant(name));
+
+    s
+}
+
+/// Encoding and decoding of a union, as the index of the kind of its value
+/// followed by the value
+pub(super) fn union_impls(name: &str, variants: &[&str]) -> String {
+    let mut s = format!(
+        "impl Encode for {} {{\n    fn encode(&self, buffer: &mut Vec<u8>) {{\n        match self {{\n", name
+    );
+    for (index, variant) in variants.iter().enumerate() {
+        s.push_str(&format!(
+            "            Self::{}(value) => {{\n                wire::write_varint(buffer, {});\n                value.encode(buffer);\n            }}\n",
+            variant, index
+        ));
+    }
+    s.push_str("        }\n    }\n}\n\n");
+
+    s.push_str(&format!(concat!(
+        "impl Decode for {} {{\n    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {{\n",
+        "        match reader.read_varint()? {{\n",
+    ), name));
+    for (index, variant) in variants.iter().enumerate() {
+        s.push_str(&format!("            {} => Ok(Self::{}(Decode::decode(reader)?)),\n", index, variant));
+    }
+    s.push_str(&unknown_discriminant(name));
+
+    s
+}
+
+fn unknown_discriminant(name: &str) -> String {
+    format!(concat!(
+        "            discriminant => Err(DecodeError::UnknownDiscriminant {{ type_name: \"{}\", discriminant }}),\n",
+        "        }}\n    }}\n}}\n\n",
+    ), name)
+}
@@ -0,0 +1,395 @@
+// Relative Modules
+
+// Standard Uses
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+// Crate Uses
+use crate::codelib_gen::common::{constant_value, is_primitive, outer_array, schema_type_name, split_arrays};
+use crate::codelib_gen::{GeneratedFile, GenerationContext, SchemaUnits};
+use crate::schema::ir::frozen::unit::FrozenUnit;
+use crate::schema::ir::compiler::interpreted::kind_search::KindValue;
+use crate::utils::case::{to_pascal_case, to_screaming_snake_case, to_snake_case};
+
+// External Uses
+use eyre::{bail, eyre, Result};
+use handlebars::{
+    handlebars_helper, Context, Handlebars, Helper, HelperResult, Output, RenderContext, RenderError,
+};
+use serde_derive::Serialize;
+
+
+/// Option naming the directory of the templates, which makes a language
+/// generated from them rather than by a built-in generator
+pub const TEMPLATES_OPTION: &str = "templates";
+/// File of a template directory with the type mapping tables
+pub const TYPE_TABLES_FILE: &str = "types.toml";
+const TEMPLATE_EXTENSION: &str = "hbs";
+
+/// Version of the view templates are given, which changes only when
+/// something in it does in a way templates could tell
+pub const VIEW_VERSION: u32 = 1;
+
+
+/// What templates are given of a package
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageView {
+    pub view_version: u32,
+    pub name: String,
+    pub version: String,
+    pub options: BTreeMap<String, String>,
+    pub schemas: Vec<SchemaView>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaView {
+    pub namespace: Vec<String>,
+    /// Namespace joined by `::`
+    pub name: String,
+    /// Namespace joined by `/`
+    pub path: String,
+    pub imports: Vec<String>,
+    pub constants: Vec<ConstantView>,
+    pub structs: Vec<StructView>,
+    pub enums: Vec<EnumView>,
+    pub protocols: Vec<ProtocolView>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConstantView {
+    pub name: String,
+    pub docs: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: TypeView,
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StructView {
+    pub name: String,
+    pub docs: Option<String>,
+    pub fields: Vec<FieldView>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldView {
+    pub name: String,
+    pub docs: Option<String>,
+    pub optional: bool,
+    #[serde(rename = "type")]
+    pub kind: TypeView,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EnumView {
+    pub name: String,
+    pub docs: Option<String>,
+    pub variants: Vec<VariantView>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VariantView {
+    pub name: String,
+    pub index: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProtocolView {
+    pub name: String,
+    pub docs: Option<String>,
+    pub functions: Vec<FunctionView>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionView {
+    pub name: String,
+    pub docs: Option<String>,
+    pub asynchronous: bool,
+    pub arguments: Vec<ArgumentView>,
+    pub returns: Option<TypeView>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArgumentView {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: TypeView,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeView {
+    /// As written in the schema, like `u8[4][]`
+    pub schema: String,
+    /// The innermost element, without arrays nor the path it was written with
+    pub element: String,
+    /// Sizes of the arrays, outermost first, with `null` for dynamic ones
+    pub arrays: Vec<Option<u64>>,
+    pub primitive: bool,
+    /// Namespace of the schema declaring the element, when another schema of
+    /// the package does
+    pub namespace: Option<Vec<String>>,
+}
+
+/// What a template rendered for every schema is given
+#[derive(Serialize)]
+struct SchemaTemplateView<'a> {
+    package: &'a PackageView,
+    #[serde(flatten)]
+    schema: &'a SchemaView,
+}
+
+
+/// Generates the files a directory of Handlebars templates describes, which
+/// is given by the `templates` option, relative to the package
+///
+/// Every file ending with `.hbs` is a template, rendered without that
+/// extension. Those with a path that has an expression, like `{{path}}.kt.hbs`,
+/// are rendered for every schema, with the schema in scope and the package as
+/// `package`, and the others once with the package in scope. Other files are
+/// copied as they are, except for `types.toml`, whose tables map schema types
+/// for the `map_type` helper
+pub fn generate_template_package(context: &GenerationContext) -> Result<Vec<GeneratedFile>> {
+    let Some(directory) = context.option(TEMPLATES_OPTION) else {
+        bail!("Template generators need the directory of their templates as the option '{}'", TEMPLATES_OPTION)
+    };
+    let directory = Path::new(directory);
+    if !directory.is_dir() {
+        bail!("Template directory '{}' does not exist", directory.display())
+    }
+
+    let tables = match std::fs::read_to_string(directory.join(TYPE_TABLES_FILE)) {
+        Ok(contents) => type_tables(&contents)
+            .map_err(|e| eyre!("Could not read the type tables of '{}': {}", TYPE_TABLES_FILE, e))?,
+        Err(_) => BTreeMap::new(),
+    };
+    let registry = registry(tables);
+    let package = package_view(context);
+
+    let mut files = vec![];
+    let pattern = format!("{}/**/*", glob::Pattern::escape(&directory.display().to_string()));
+    for entry in glob::glob(&pattern)? {
+        let path = entry?;
+        if !path.is_file() { continue }
+        let relative = path.strip_prefix(directory)?.to_path_buf();
+        if relative == Path::new(TYPE_TABLES_FILE) { continue }
+
+        if relative.extension().and_then(|e| e.to_str()) != Some(TEMPLATE_EXTENSION) {
+            let contents = std::fs::read_to_string(&path)?;
+            files.push(GeneratedFile { path: relative, contents });
+            continue
+        }
+
+        let template = std::fs::read_to_string(&path)?;
+        let target = relative.with_extension("").display().to_string();
+        // The path is rendered from the same view as the contents
+        let render = |view: serde_json::Value| -> Result<GeneratedFile> {
+            let failed = |e: RenderError| eyre!("Could not render template '{}': {}", relative.display(), e);
+            let path = registry.render_template(&target, &view).map_err(failed)?;
+            let contents = registry.render_template(&template, &view).map_err(failed)?;
+            Ok(GeneratedFile { path: PathBuf::from(path), contents })
+        };
+
+        match target.contains("{{") {
+            true => for schema in &package.schemas {
+                files.push(render(serde_json::to_value(SchemaTemplateView { package: &package, schema })?)?);
+            },
+            false => files.push(render(serde_json::to_value(&package)?)?),
+        }
+    }
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// A stable view of the package, which is what templates are rendered with
+pub fn package_view(context: &GenerationContext) -> PackageView {
+    PackageView {
+        view_version: VIEW_VERSION,
+        name: context.package_name.clone(),
+        version: context.package_version.clone(),
+        options: context.options.clone(),
+        schemas: context.schemas.iter().map(|schema| schema_view(context, schema)).collect(),
+    }
+}
+
+fn schema_view(context: &GenerationContext, schema: &SchemaUnits) -> SchemaView {
+    let type_view = |kind: &KindValue| type_view(context, schema, kind);
+    let docs = |docstring: &str| Some(docstring.to_owned()).filter(|d| !d.is_empty());
+
+    let mut view = SchemaView {
+        namespace: schema.namespace.clone(),
+        name: schema.namespace.join("::"),
+        path: schema.namespace.join("/"),
+        imports: schema.imports().map(str::to_owned).collect(),
+        constants: vec![], structs: vec![], enums: vec![], protocols: vec![],
+    };
+
+    for unit in &schema.units {
+        match unit {
+            FrozenUnit::Constant { docstring, name, kind_value } => view.constants.push(ConstantView {
+                name: name.clone(),
+                docs: docstring.as_deref().and_then(docs),
+                kind: type_view(kind_value),
+                value: constant_value(kind_value).map(|(_, value)| value),
+            }),
+            FrozenUnit::Struct { docstring, name, fields, .. } => view.structs.push(StructView {
+                name: name.clone(),
+                docs: docstring.as_deref().and_then(docs),
+                fields: fields.iter().filter_map(|field| match field {
+                    FrozenUnit::Field { docstring, name, optional, kind_value, .. } => Some(FieldView {
+                        name: name.clone(),
+                        docs: docstring.as_deref().and_then(docs),
+                        optional: *optional,
+                        kind: type_view(kind_value),
+                    }),
+                    _ => None,
+                }).collect(),
+            }),
+            FrozenUnit::Enum { docstring, name, variants } => view.enums.push(EnumView {
+                name: name.clone(),
+                docs: docstring.as_deref().and_then(docs),
+                variants: variants.iter().filter_map(|variant| match variant {
+                    FrozenUnit::EnumVariant(KindValue::EnumVariant(name, _)) => Some(name.clone()),
+                    _ => None,
+                }).enumerate().map(|(index, name)| VariantView { name, index }).collect(),
+            }),
+            FrozenUnit::Protocol { docstring, name, functions, .. } => view.protocols.push(ProtocolView {
+                name: name.clone(),
+                docs: docs(docstring),
+                functions: functions.iter().filter_map(|function| match function {
+                    FrozenUnit::Function { docstring, name, synchronous, arguments, _return, .. } => {
+                        Some(FunctionView {
+                            name: name.clone(),
+                            docs: docs(docstring),
+                            asynchronous: !synchronous,
+                            arguments: arguments.iter().map(|argument| ArgumentView {
+                                name: argument.name.clone(), kind: type_view(&argument.kind),
+                            }).collect(),
+                            returns: _return.as_ref().map(type_view),
+                        })
+                    }
+                    _ => None,
+                }).collect(),
+            }),
+            _ => {}
+        }
+    }
+
+    view
+}
+
+fn type_view(context: &GenerationContext, schema: &SchemaUnits, kind: &KindValue) -> TypeView {
+    let written = schema_type_name(kind, " | ");
+
+    let (element, sizes) = split_arrays(&written);
+    let arrays = sizes.iter().map(|size| size.parse().ok()).collect();
+    let primitive = is_primitive(element);
+    let namespace = match primitive {
+        true => None,
+        false => context.resolve_type(schema, element).map(|(namespace, _)| namespace),
+    };
+
+    TypeView {
+        element: element.rsplit("::").next().unwrap_or(element).to_owned(),
+        arrays,
+        primitive,
+        namespace,
+        schema: written,
+    }
+}
+
+
+/// Tables mapping schema types to those of the generated language, by name
+///
+/// Keys are the names of elements, with `str` standing for `string` too, and
+/// `[]` and `[N]` for dynamic and fixed arrays, where `{}` stands for the
+/// mapped element and `{size}` for the size
+type TypeTables = BTreeMap<String, BTreeMap<String, String>>;
+
+/// Table the `map_type` helper uses unless given the name of another
+const DEFAULT_TABLE: &str = "types";
+
+fn type_tables(contents: &str) -> Result<TypeTables> {
+    let document: toml_edit::Document = contents.parse()?;
+    let mut tables = BTreeMap::new();
+
+    for (name, item) in document.iter() {
+        let Some(table) = item.as_table() else {
+            bail!("'{}' is not a table", name)
+        };
+        let mut mappings = BTreeMap::new();
+        for (key, value) in table.iter() {
+            let Some(value) = value.as_str() else {
+                bail!("'{}.{}' is not a string", name, key)
+            };
+            mappings.insert(key.to_owned(), value.to_owned());
+        }
+        tables.insert(name.to_owned(), mappings);
+    }
+
+    Ok(tables)
+}
+
+/// Maps a type written like in schemas through a table, leaving what the
+/// table has no entry for as written
+fn map_type(written: &str, table: &BTreeMap<String, String>) -> String {
+    if let Some((inner, size)) = outer_array(written) {
+        let element = map_type(inner, table);
+        let pattern = match size.is_empty() {
+            true => table.get("[]"),
+            false => table.get("[N]").or_else(|| table.get("[]")),
+        };
+        return match pattern {
+            Some(pattern) => pattern.replace("{size}", size).replace("{}", &element),
+            None => format!("{}[{}]", element, size),
+        }
+    }
+
+    let element = written.rsplit("::").next().unwrap_or(written);
+    let key = match element { "string" => "str", other => other };
+    table.get(key).or_else(|| table.get(element)).cloned().unwrap_or_else(|| element.to_owned())
+}
+
+handlebars_helper!(snake_case: |name: str| to_snake_case(name));
+handlebars_helper!(pascal_case: |name: str| to_pascal_case(name));
+handlebars_helper!(screaming_snake_case: |name: str| to_screaming_snake_case(name));
+handlebars_helper!(camel_case: |name: str| {
+    let pascal = to_pascal_case(name);
+    let mut chars = pascal.chars();
+    chars.next().map(|c| c.to_ascii_lowercase()).into_iter().chain(chars).collect::<String>()
+});
+
+fn registry(tables: TypeTables) -> Handlebars<'static> {
+    let mut registry = Handlebars::new();
+    registry.set_strict_mode(true);
+    // Generated code is not HTML
+    registry.register_escape_fn(handlebars::no_escape);
+
+    registry.register_helper("snake_case", Box::new(snake_case));
+    registry.register_helper("pascal_case", Box::new(pascal_case));
+    registry.register_helper("camel_case", Box::new(camel_case));
+    registry.register_helper("screaming_snake_case", Box::new(screaming_snake_case));
+
+    // `{{map_type field.type}}` or `{{map_type field.type "table"}}`, where the
+    // type is a type of the view or written like in schemas
+    registry.register_helper("map_type", Box::new(
+        move |helper: &Helper, _: &Handlebars, _: &Context, _: &mut RenderContext, out: &mut dyn Output| -> HelperResult {
+            let value = helper.param(0).map(|param| param.value())
+                .ok_or_else(|| RenderError::new("`map_type` needs a type"))?;
+            let written = value.get("schema").unwrap_or(value).as_str()
+                .ok_or_else(|| RenderError::new("`map_type` needs a type or the name of one"))?;
+
+            let table_name = helper.param(1).and_then(|param| param.value().as_str()).unwrap_or(DEFAULT_TABLE);
+            let mapped = match tables.get(table_name) {
+                Some(table) => map_type(written, table),
+                None if table_name == DEFAULT_TABLE => map_type(written, &BTreeMap::new()),
+                None => return Err(RenderError::new(format!("There is no type table named '{}'", table_name))),
+            };
+
+            out.write(&mapped)?;
+            Ok(())
+        }
+    ));
+
+    registry
+}
@@ -0,0 +1,283 @@
+// Relative Modules
+
+// Standard Uses
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+// Crate Uses
+use crate::codelib_gen::common::{
+    braced_unicode_escape, constant_value, docs, outer_array, schema_type_name, string_literal, DocStyle
+};
+use crate::codelib_gen::{GeneratedFile, GenerationContext, SchemaUnits};
+use crate::schema::ir::frozen::unit::FrozenUnit;
+use crate::schema::ir::compiler::interpreted::kind_search::{KindValue, Primitive};
+
+// External Uses
+use eyre::Result;
+
+
+const HEADER: &str = "// Generated by Comline\n";
+
+
+/// What is being generated of a schema
+struct Module<'a> {
+    context: &'a GenerationContext,
+    schema: &'a SchemaUnits,
+    /// Types imported from other modules, by the module specifier they are
+    /// imported from, with the local name each is known by
+    imports: BTreeMap<String, BTreeMap<String, String>>,
+    /// Suffix of module specifiers, as `.js` for ECMAScript modules
+    extension: &'a str,
+    /// Whether 64-bit integers are strings, as canonical JSON writes them
+    /// with `int64_as_string`, rather than numbers
+    int64_as_string: bool,
+}
+
+impl Module<'_> {
+    /// The local name of a type declared in another schema, imported on
+    /// first use, aliased when a name of this module would be shadowed
+    fn import(&mut self, type_name: &str) -> Option<String> {
+        let (namespace, name) = self.context.resolve_type(self.schema, type_name)?;
+        let specifier = format!("{}{}", specifier(&self.schema.namespace, &namespace), self.extension);
+
+        if let Some(local) = self.imports.get(&specifier).and_then(|names| names.get(&name)) {
+            return Some(local.clone())
+        }
+
+        let taken = self.schema.declares(&name)
+            || self.imports.values().any(|names| names.values().any(|local| *local == name));
+        let local = match taken {
+            true => format!("{}_{}", namespace.join("_"), name),
+            false => name.clone(),
+        };
+
+        self.imports.entry(specifier).or_default().insert(name, local.clone());
+        Some(local)
+    }
+}
+
+
+/// Generates a module for every schema, at paths mirroring their namespaces
+/// Options:
+/// - `enum_style = enum` generates enums as TypeScript enums, rather than
+///   unions of string literals
+/// - `import_extension` is appended to the specifiers of imported modules,
+///   as `.js` is needed by ECMAScript modules
+/// - `int64_as_string = true` types 64-bit integers as strings, to match
+///   canonical JSON written with `int64_as_string`, rather than as numbers,
+///   which do not hold them exactly, as 128-bit integers always are
+pub fn generate_typescript_package(context: &GenerationContext) -> Result<Vec<GeneratedFile>> {
+    let enums = context.option("enum_style") == Some("enum");
+    let extension = context.option("import_extension").unwrap_or("");
+    let int64_as_string = context.option("int64_as_string") == Some("true");
+
+    let mut files: Vec<GeneratedFile> = context.schemas.iter().map(|schema| {
+        let mut module = Module { context, schema, imports: BTreeMap::new(), extension, int64_as_string };
+        let code = generate_module(&schema.units, enums, &mut module);
+
+        let mut contents = HEADER.to_owned();
+        for (specifier, names) in &module.imports {
+            let names: Vec<String> = names.iter()
+                .map(|(name, local)| match name == local {
+                    true => name.clone(),
+                    false => format!("{} as {}", name, local),
+                })
+                .collect();
+            contents.push_str(&format!("import type {{ {} }} from \"{}\";\n", names.join(", "), specifier));
+        }
+        if !module.imports.is_empty() { contents.push('\n') }
+        contents.push_str(&code);
+
+        let path = PathBuf::from(schema.namespace.join("/")).with_extension("ts");
+        GeneratedFile { path, contents }
+    }).collect();
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Generates the code of a single schema, leaving types of other schemas as
+/// named
+pub fn generate_typescript(units: &[FrozenUnit]) -> String {
+    let context = GenerationContext {
+        package_name: String::new(),
+        package_version: String::new(),
+        schemas: vec![],
+        options: BTreeMap::new(),
+        history: vec![],
+    };
+    let schema = SchemaUnits { namespace: vec![], units: units.to_vec() };
+    let mut module = Module {
+        context: &context, schema: &schema, imports: BTreeMap::new(), extension: "", int64_as_string: false
+    };
+
+    format!("{}{}", HEADER, generate_module(units, false, &mut module))
+}
+
+fn generate_module(units: &[FrozenUnit], enums: bool, module: &mut Module) -> String {
+    let mut output = String::new();
+
+    for unit in units {
+        match unit {
+            FrozenUnit::Constant { docstring, name, kind_value } => {
+                output.push_str(&generate_constant(docstring.as_deref(), name, kind_value, module));
+            }
+            FrozenUnit::Struct { docstring, name, fields, .. } => {
+                output.push_str(&generate_interface(docstring.as_deref(), name, fields, module));
+            }
+            FrozenUnit::Enum { docstring, name, variants } => {
+                output.push_str(&generate_enum(docstring.as_deref(), name, variants, enums));
+            }
+            FrozenUnit::Protocol { docstring, name, functions, .. } => {
+                output.push_str(&generate_protocol(docstring, name, functions, module));
+            }
+            _ => {}
+        }
+    }
+
+    output
+}
+
+fn generate_constant(
+    docstring: Option<&str>, name: &str, kind_value: &KindValue, module: &mut Module
+) -> String {
+    let Some((primitive, value)) = constant_value(kind_value) else { return String::new() };
+
+    let type_name = map_str_type(&schema_type_name(kind_value, " | "), module);
+    let value = match (primitive, type_name.as_str()) {
+        (Primitive::String(_), _) | (_, "string") => string_literal(&value, braced_unicode_escape),
+        _ => value,
+    };
+
+    let mut s = docs(docstring, "", DocStyle::Block);
+    s.push_str(&format!("export const {}: {} = {};\n\n", name, type_name, value));
+    s
+}
+
+fn generate_interface(
+    docstring: Option<&str>, name: &str, fields: &[FrozenUnit], module: &mut Module
+) -> String {
+    let mut s = docs(docstring, "", DocStyle::Block);
+    s.push_str(&format!("export interface {} {{\n", name));
+
+    for field in fields {
+        if let FrozenUnit::Field { docstring, name, kind_value, optional, .. } = field {
+            let type_name = map_kind_to_typescript_type(kind_value, module);
+            let marker = if *optional { "?" } else { "" };
+
+            s.push_str(&docs(docstring.as_deref(), "    ", DocStyle::Block));
+            s.push_str(&format!("    {}{}: {};\n", name, marker, type_name));
+        }
+    }
+
+    s.push_str("}\n\n");
+    s
+}
+
+/// Enums are encoded as the names of their variants, which are either a
+/// union of those names or a TypeScript enum holding them
+fn generate_enum(docstring: Option<&str>, name: &str, variants: &[FrozenUnit], enums: bool) -> String {
+    let names: Vec<&str> = variants.iter().filter_map(|variant| match variant {
+        FrozenUnit::EnumVariant(KindValue::EnumVariant(name, _)) => Some(name.as_str()),
+        _ => None,
+    }).collect();
+
+    let mut s = docs(docstring, "", DocStyle::Block);
+    match enums {
+        true => {
+            s.push_str(&format!("export enum {} {{\n", name));
+            for variant in names {
+                s.push_str(&format!("    {} = {},\n", variant, string_literal(variant, braced_unicode_escape)));
+            }
+            s.push_str("}\n\n");
+        }
+        false => {
+            let literals: Vec<String> = names.into_iter()
+                .map(|name| string_literal(name, braced_unicode_escape))
+                .collect();
+            let literals = match literals.is_empty() {
+                true => "never".to_owned(),
+                false => literals.join(" | "),
+            };
+            s.push_str(&format!("export type {} = {};\n\n", name, literals));
+        }
+    }
+    s
+}
+
+/// A client interface, every call of which resolves to what it returns
+fn generate_protocol(
+    docstring: &str, name: &str, functions: &[FrozenUnit], module: &mut Module
+) -> String {
+    let mut s = docs(Some(docstring), "", DocStyle::Block);
+    s.push_str(&format!("export interface {} {{\n", name));
+
+    for function in functions {
+        let FrozenUnit::Function { docstring, name, arguments, _return, .. } = function else {
+            continue
+        };
+
+        let parameters: Vec<String> = arguments.iter()
+            .map(|arg| format!("{}: {}", arg.name, map_kind_to_typescript_type(&arg.kind, module)))
+            .collect();
+        let returned = match _return {
+            Some(kind) => map_kind_to_typescript_type(kind, module),
+            None => "void".to_owned(),
+        };
+
+        s.push_str(&docs(Some(docstring), "    ", DocStyle::Block));
+        s.push_str(&format!("    {}({}): Promise<{}>;\n", name, parameters.join(", "), returned));
+    }
+
+    s.push_str("}\n\n");
+    s
+}
+
+fn map_kind_to_typescript_type(kind: &KindValue, module: &mut Module) -> String {
+    match kind {
+        KindValue::Primitive(Primitive::String(_)) => "string".to_owned(),
+        KindValue::Primitive(primitive) => map_str_type(primitive.name(), module),
+        KindValue::Namespaced(name, _) => map_str_type(name, module),
+        KindValue::EnumVariant(name, _) => name.clone(),
+        KindValue::Union(kinds) => {
+            kinds.iter().map(|kind| map_kind_to_typescript_type(kind, module))
+                .collect::<Vec<_>>().join(" | ")
+        }
+    }
+}
+
+fn map_str_type(s: &str, module: &mut Module) -> String {
+    // Arrays of any size are arrays
+    if let Some((element, _)) = outer_array(s) {
+        return format!("{}[]", map_str_type(element, module))
+    }
+    match s {
+        "string" | "str" => "string".to_owned(),
+        "bool" => "boolean".to_owned(),
+        "u8" | "u16" | "u32" | "i8" | "i16" | "i32" | "f32" | "f64" | "float" | "int" => "number".to_owned(),
+        // Past what numbers hold exactly, typed as canonical JSON writes them
+        "u64" | "i64" => match module.int64_as_string {
+            true => "string".to_owned(),
+            false => "number".to_owned(),
+        },
+        "u128" | "i128" => "string".to_owned(),
+        other => module.import(other).unwrap_or_else(|| other.to_owned()),
+    }
+}
+
+/// The specifier a module at `from` imports the module at `to` with
+fn specifier(from: &[String], to: &[String]) -> String {
+    let from_directory = &from[..from.len().saturating_sub(1)];
+    let to_directory = &to[..to.len().saturating_sub(1)];
+    let common = from_directory.iter().zip(to_directory)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let parents = match from_directory.len() - common {
+        0 => "./".to_owned(),
+        depth => "../".repeat(depth),
+    };
+
+    format!("{}{}", parents, to[common..].join("/"))
+}
+
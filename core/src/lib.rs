@@ -9,3 +9,5 @@ pub mod autodoc;
 pub mod utils;
 pub mod report;
 pub mod codelib_gen;
+pub mod wire;
+pub mod stdlib;
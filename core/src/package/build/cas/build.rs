@@ -10,13 +10,20 @@ use super::objects::{Commit, Tree, EntryMode};
 use super::object_store::ObjectStore;
 use super::refs::{main_ref, ref_exists, read_ref, update_ref};
 use super::version::VersionBump;
+use crate::codelib_gen::SchemaUnits;
 use crate::package::config::ir::context::ProjectContext;
-use crate::schema::ir::diff::SchemaChanges;
-use crate::schema::ir::frozen::cas::blob::build_tree_from_schema;
+use crate::schema::ir::diff::{analyze_schema_changes, BreakingChange, NewFeature, SchemaChanges};
+use crate::schema::ir::frozen::cas::blob::{build_tree_from_schema, load_schema_from_tree};
 use crate::schema::ir::frozen::cas::commit::{create_initial_commit, create_version_commit};
+use crate::schema::ir::frozen::unit::FrozenUnit;
+use super::history::frozen_schema;
 use eyre::Result;
 use std::path::Path;
 
+/// Start of the names schemas are kept under in root trees, followed by
+/// their namespace, as in `schema:members::accounts`
+const SCHEMA_ENTRY_PREFIX: &str = "schema:";
+
 /// Information returned from build processing
 pub struct BuildInfo {
     pub version_bump: VersionBump,
@@ -38,7 +45,7 @@ pub fn process_initial_freezing(
     // Build tree from schemas
     let mut root_tree = Tree::new();
     
-    for (idx, schema_ctx) in latest_project.schema_contexts.iter().enumerate() {
+    for schema_ctx in latest_project.schema_contexts.iter() {
         let schema_ref = schema_ctx.borrow();
         let frozen_ref = schema_ref.frozen_schema.borrow();
         
@@ -48,10 +55,7 @@ pub fn process_initial_freezing(
             let tree_bytes = schema_tree.to_bytes()?;
             let tree_hash = store.write(&tree_bytes)?;
             
-            // Use index as name since path field doesn't exist
-            let name = format!("schema_{}", idx);
-            
-            root_tree.add_entry(EntryMode::Tree, name, tree_hash);
+            root_tree.add_entry(EntryMode::Tree, schema_entry_name(&schema_ref.namespace), tree_hash);
         }
     }
     
@@ -106,21 +110,21 @@ pub fn process_changes(
     let mut root_tree = Tree::new();
     let mut current_schemas = vec![];
     
-    for (idx, schema_ctx) in latest_project.schema_contexts.iter().enumerate() {
+    for schema_ctx in latest_project.schema_contexts.iter() {
         let schema_ref = schema_ctx.borrow();
         let frozen_ref = schema_ref.frozen_schema.borrow();
         
         if let Some(frozen_schema) = frozen_ref.as_ref() {
-            current_schemas.push(frozen_schema.clone());
+            current_schemas.push(SchemaUnits {
+                namespace: schema_ref.namespace.clone(), units: frozen_schema.clone(),
+            });
             
             // Build subtree for this schema
             let schema_tree = build_tree_from_schema(frozen_schema, &store)?;
             let tree_bytes = schema_tree.to_bytes()?;
             let tree_hash = store.write(&tree_bytes)?;
             
-            // Use index as name (stable across builds for same file set)
-            let name = format!("schema_{}", idx);
-            root_tree.add_entry(EntryMode::Tree, name, tree_hash);
+            root_tree.add_entry(EntryMode::Tree, schema_entry_name(&schema_ref.namespace), tree_hash);
         }
     }
     
@@ -128,7 +132,12 @@ pub fn process_changes(
     let root_tree_bytes = root_tree.to_bytes()?;
     let root_tree_hash = store.write(&root_tree_bytes)?;
     
-    if root_tree_hash == parent_commit.tree {
+    let prev_schemas = load_schemas_from_root_tree(&store, &prev_tree)?;
+    let (version_bump, all_changes) = aggregate_changes(&prev_schemas, &current_schemas);
+    
+    // Trees written by earlier versions hash the same schemas differently,
+    // so what is compared is the schemas, not their trees
+    if version_bump == VersionBump::None && all_changes.is_empty() {
         // No changes
         tracing::debug!("CAS: No changes detected");
         return Ok(BuildInfo {
@@ -139,32 +148,128 @@ pub fn process_changes(
         });
     }
     
-    // Analyze schema changes using proper multi-file diffing
-    use crate::schema::ir::diff::{analyze_schema_changes, NewFeature, BreakingChange};
-    use crate::schema::ir::frozen::cas::blob::load_schema_from_tree;
-    use crate::schema::ir::frozen::unit::FrozenUnit;
+    let new_version = bump_version(&parent_commit.version, version_bump)?;
     
-    let mut aggregated_bump = VersionBump::None;
-    let mut all_changes = SchemaChanges::default();
+    // Create new commit
+    let commit = create_version_commit(
+        root_tree_hash,
+        parent_hash,
+        &new_version.to_string(),
+        &format!("{:?} version bump", version_bump),
+    );
+    let commit_bytes = commit.to_bytes()?;
+    let commit_hash = store.write(&commit_bytes)?;
     
-    // Load all previous schemas
-    let mut prev_schemas = vec![];
-    for entry in &prev_tree.entries {
+    // Update main ref
+    update_ref(project_path, main_ref(), &commit_hash)?;
+    
+    tracing::info!("CAS: New commit {} created ({})", commit_hash, new_version);
+    
+    // Return aggregated changes
+    let merged_changes = if all_changes.is_empty() {
+        None
+    } else {
+        Some(all_changes)
+    };
+    
+    Ok(BuildInfo {
+        version_bump,
+        previous_version: Some(parent_commit.version.clone()),
+        current_version: new_version.to_string(),
+        schema_changes: merged_changes,
+    })
+}
+
+/// Compare current schemas against the latest commit without writing anything
+/// to the store, returning what `process_changes` would produce
+pub fn preview_changes(
+    project_path: &Path,
+    latest_project: &ProjectContext,
+) -> Result<BuildInfo> {
+    tracing::debug!("CAS: Previewing changes");
+
+    let store = ObjectStore::new(project_path);
+
+    let parent_hash = read_ref(project_path, main_ref())?;
+    let parent_bytes = store.read(&parent_hash)?;
+    let parent_commit = Commit::from_bytes(&parent_bytes)?;
+
+    let prev_tree_bytes = store.read(&parent_commit.tree)?;
+    let prev_tree = Tree::from_bytes(&prev_tree_bytes)?;
+    let prev_schemas = load_schemas_from_root_tree(&store, &prev_tree)?;
+
+    let mut current_schemas = vec![];
+    for schema_ctx in latest_project.schema_contexts.iter() {
+        let schema_ref = schema_ctx.borrow();
+        let frozen_ref = schema_ref.frozen_schema.borrow();
+
+        if let Some(frozen_schema) = frozen_ref.as_ref() {
+            current_schemas.push(SchemaUnits {
+                namespace: schema_ref.namespace.clone(), units: frozen_schema.clone(),
+            });
+        }
+    }
+
+    let (version_bump, all_changes) = aggregate_changes(&prev_schemas, &current_schemas);
+    let new_version = bump_version(&parent_commit.version, version_bump)?;
+
+    Ok(BuildInfo {
+        version_bump,
+        previous_version: Some(parent_commit.version.clone()),
+        current_version: new_version.to_string(),
+        schema_changes: if all_changes.is_empty() { None } else { Some(all_changes) },
+    })
+}
+
+/// Name of the root tree entry a schema is kept under
+fn schema_entry_name(namespace: &[String]) -> String {
+    format!("{}{}", SCHEMA_ENTRY_PREFIX, namespace.join("::"))
+}
+
+/// Load every schema stored as a subtree of a commit's root tree, with its
+/// namespace. Trees written before namespaces were kept named schemas by
+/// their position, as in `schema_0`, so those are given empty namespaces
+pub fn load_schemas_from_root_tree(
+    store: &ObjectStore,
+    root_tree: &Tree,
+) -> Result<Vec<SchemaUnits>> {
+    let mut schemas = vec![];
+    for entry in &root_tree.entries {
         if entry.mode == EntryMode::Tree {
-            let prev_schema_tree_bytes = store.read(&entry.hash)?;
-            let prev_schema_tree = Tree::from_bytes(&prev_schema_tree_bytes)?;
-            let prev_schema = load_schema_from_tree(&store, &prev_schema_tree)?;
-            prev_schemas.push(prev_schema);
+            let schema_tree_bytes = store.read(&entry.hash)?;
+            let schema_tree = Tree::from_bytes(&schema_tree_bytes)?;
+            let namespace = match entry.name.strip_prefix(SCHEMA_ENTRY_PREFIX) {
+                Some(namespace) => namespace.split("::").map(str::to_owned).collect(),
+                None => vec![],
+            };
+            schemas.push(SchemaUnits { namespace, units: load_schema_from_tree(store, &schema_tree)? });
         }
     }
-    
-    let prev_count = prev_schemas.len();
-    let current_count = current_schemas.len();
-    
-    // 1. Compare schemas that exist in both (min of the two counts)
-    let common_count = prev_count.min(current_count);
-    for idx in 0..common_count {
-        let file_changes = analyze_schema_changes(&prev_schemas[idx], &current_schemas[idx]);
+
+    Ok(schemas)
+}
+
+/// Analyze changes across all schema files, aggregating the version bump
+/// required by the most severe change. Schemas are matched by namespace, so
+/// adding a schema before others does not compare them with their neighbours
+fn aggregate_changes(
+    prev_schemas: &[SchemaUnits],
+    current_schemas: &[SchemaUnits],
+) -> (VersionBump, SchemaChanges) {
+    let mut aggregated_bump = VersionBump::None;
+    let mut all_changes = SchemaChanges::default();
+
+    // 1. Compare schemas that exist in both, and collect NEW schemas
+    let mut added = vec![];
+    let mut matched = vec![];
+    for (index, current_schema) in current_schemas.iter().enumerate() {
+        let Some(prev_schema) = frozen_schema(prev_schemas, &current_schema.namespace, index) else {
+            added.push(current_schema);
+            continue
+        };
+        matched.push(prev_schema);
+
+        let file_changes = analyze_schema_changes(&prev_schema.units, &current_schema.units);
         
         let schema_bump = if file_changes.is_breaking() {
             VersionBump::Major
@@ -182,13 +287,13 @@ pub fn process_changes(
         all_changes.modifications.extend(file_changes.modifications);
     }
     
-    // 2. Handle NEW schemas (current_count > prev_count)
-    if current_count > prev_count {
-        tracing::debug!("New schema files detected: {}", current_count - prev_count);
+    // 2. Handle NEW schemas
+    if !added.is_empty() {
+        tracing::debug!("New schema files detected: {}", added.len());
         
-        for idx in prev_count..current_count {
+        for schema in added {
             // All declarations in new files are new features
-            for unit in &current_schemas[idx] {
+            for unit in &schema.units {
                 match unit {
                     FrozenUnit::Struct { name, fields, .. } => {
                         all_changes.new_features.push(NewFeature::AddedStruct {
@@ -216,13 +321,16 @@ pub fn process_changes(
         aggregated_bump = aggregated_bump.max(VersionBump::Minor);
     }
     
-    // 3. Handle REMOVED schemas (prev_count > current_count)
-    if prev_count > current_count {
-        tracing::debug!("Schema files removed: {}", prev_count - current_count);
+    // 3. Handle REMOVED schemas, those no current schema was matched with
+    let removed: Vec<&SchemaUnits> = prev_schemas.iter()
+        .filter(|schema| !matched.iter().any(|known| std::ptr::eq(*known, *schema)))
+        .collect();
+    if !removed.is_empty() {
+        tracing::debug!("Schema files removed: {}", removed.len());
         
-        for idx in current_count..prev_count {
+        for schema in removed {
             // All declarations in removed files are breaking changes
-            for unit in &prev_schemas[idx] {
+            for unit in &schema.units {
                 match unit {
                     FrozenUnit::Struct { name, .. } => {
                         all_changes.breaking_changes.push(BreakingChange::RemovedStruct {
@@ -246,44 +354,19 @@ pub fn process_changes(
         
         aggregated_bump = VersionBump::Major;
     }
-    
-    let version_bump = aggregated_bump;
-    
-    // Parse and bump version
-    let prev_version = semver::Version::parse(&parent_commit.version)?;
+
+    (aggregated_bump, all_changes)
+}
+
+/// Parse a semantic version and apply a bump to it
+fn bump_version(version: &str, version_bump: VersionBump) -> Result<semver::Version> {
+    let prev_version = semver::Version::parse(version)?;
     let new_version = match version_bump {
         VersionBump::Major => semver::Version::new(prev_version.major + 1, 0, 0),
         VersionBump::Minor => semver::Version::new(prev_version.major, prev_version.minor + 1, 0),
         VersionBump::Patch => semver::Version::new(prev_version.major, prev_version.minor, prev_version.patch + 1),
-        VersionBump::None => prev_version.clone(),
+        VersionBump::None => prev_version,
     };
-    
-    // Create new commit
-    let commit = create_version_commit(
-        root_tree_hash,
-        parent_hash,
-        &new_version.to_string(),
-        &format!("{:?} version bump", version_bump),
-    );
-    let commit_bytes = commit.to_bytes()?;
-    let commit_hash = store.write(&commit_bytes)?;
-    
-    // Update main ref
-    update_ref(project_path, main_ref(), &commit_hash)?;
-    
-    tracing::info!("CAS: New commit {} created ({})", commit_hash, new_version);
-    
-    // Return aggregated changes
-    let merged_changes = if all_changes.is_empty() {
-        None
-    } else {
-        Some(all_changes)
-    };
-    
-    Ok(BuildInfo {
-        version_bump,
-        previous_version: Some(parent_commit.version.clone()),
-        current_version: new_version.to_string(),
-        schema_changes: merged_changes,
-    })
+
+    Ok(new_version)
 }
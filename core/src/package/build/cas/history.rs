@@ -0,0 +1,70 @@
+// Commit history traversal for CAS
+// Walks the append-only commit chain backwards from refs/heads/main
+
+use super::build::load_schemas_from_root_tree;
+use super::objects::{Commit, Tree};
+use super::object_store::ObjectStore;
+use super::refs::{main_ref, read_ref, ref_exists};
+use super::storage::Hash;
+use crate::codelib_gen::SchemaUnits;
+use eyre::{bail, Result};
+use std::path::Path;
+
+/// List every commit reachable from the main ref, newest first
+pub fn log(project_path: &Path) -> Result<Vec<(Hash, Commit)>> {
+    let mut history = vec![];
+
+    if !ref_exists(project_path, main_ref()) {
+        return Ok(history);
+    }
+
+    let store = ObjectStore::new(project_path);
+    let mut next = Some(read_ref(project_path, main_ref())?);
+
+    while let Some(hash) = next {
+        let commit = Commit::from_bytes(&store.read(&hash)?)?;
+        // Linear history, so the first parent is the only parent
+        next = commit.parents.first().copied();
+        history.push((hash, commit));
+    }
+
+    Ok(history)
+}
+
+/// Find the commit that froze a given version
+pub fn find_version(project_path: &Path, version: &str) -> Result<Option<(Hash, Commit)>> {
+    Ok(log(project_path)?.into_iter().find(|(_, commit)| commit.version == version))
+}
+
+/// Find the commit with a hash, given in full or as the start of its hex
+/// like abbreviated hashes in git
+pub fn find_commit(project_path: &Path, hash: &str) -> Result<Option<(Hash, Commit)>> {
+    let prefix = hash.to_lowercase();
+    let mut found = log(project_path)?.into_iter()
+        .filter(|(hash, _)| hash.to_hex().starts_with(&prefix));
+
+    let commit = found.next();
+    if found.next().is_some() {
+        bail!("Hash '{}' is the start of more than one commit", hash)
+    }
+
+    Ok(commit)
+}
+
+/// Load the schemas a commit froze, in the order the package had them
+pub fn schemas_of(project_path: &Path, commit: &Commit) -> Result<Vec<SchemaUnits>> {
+    let store = ObjectStore::new(project_path);
+    let root_tree = Tree::from_bytes(&store.read(&commit.tree)?)?;
+
+    load_schemas_from_root_tree(&store, &root_tree)
+}
+
+/// The frozen schema of a namespace, for the schema at `index` of a package.
+/// Commits made before namespaces were kept have schemas without them, which
+/// can only be matched by their position
+pub fn frozen_schema<'a>(frozen: &'a [SchemaUnits], namespace: &[String], index: usize) -> Option<&'a SchemaUnits> {
+    match frozen.iter().all(|schema| schema.namespace.is_empty()) {
+        true => frozen.get(index),
+        false => frozen.iter().find(|schema| schema.namespace == namespace),
+    }
+}
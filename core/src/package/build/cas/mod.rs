@@ -22,6 +22,7 @@ pub mod objects;       // Blob, Tree, Commit types
 pub mod refs;          // Git-style references management
 pub mod build;         // Build process implementation
 pub mod version;       // Version bump types
+pub mod history;       // Commit chain traversal
 mod package;
 mod schema;
 
@@ -3,33 +3,61 @@ pub mod cas;  // CAS module (public for tests)
 
 // Standard Uses
 use std::cell::RefCell;
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 // Crate Uses
-use crate::codelib_gen::{find_generator, GeneratorFn};
+use crate::codelib_gen::{find_generator, plugin, template, FrozenVersion, GenerationContext, GeneratorFn, SchemaUnits};
 use crate::package::config::idl::constants::CONGREGATION_EXTENSION;
 use crate::package::config::ir::interpreter::ProjectInterpreter;
 use crate::package::config::ir::{
     compiler,
-    context::ProjectContext,
+    context::{Origin, ProjectContext},
 };
 use crate::schema::idl::constants::SCHEMA_EXTENSION;
 use crate::schema::ir::{
     context::SchemaContext, diff::SchemaChanges,
+    lint::{self, LintLevel, SchemaLints},
+    validation::{self, ValidationError},
 };
 
 // External Uses
-use eyre::{bail, Result};
+use eyre::{bail, eyre, Result};
 use handlebars::{Handlebars, RenderError};
+use semver::VersionReq;
 use serde_derive::{Deserialize, Serialize};
 
 /// Builds the package, which step-by-step means:
 /// - Compile configuration and schemas
+/// - Generate code for the configured targets, before freezing so that a
+///   target that cannot be generated leaves the CAS untouched
 /// - Freeze the results into CAS (immutable storage)
-/// - Generate code for targets (optional)
 /// - Document changes (optional)
 pub fn build(package_path: &Path) -> Result<BuildResult> {
+    let latest_project = compile(package_path)?;
+    let generated = generate_code_for_targets(&latest_project, package_path)?;
+
+    // Use CAS for immutable version storage
+    let build_info = if cas::refs::ref_exists(package_path, cas::refs::main_ref()) {
+        cas::build::process_changes(package_path, &latest_project)?
+    } else {
+        cas::build::process_initial_freezing(package_path, &latest_project)?
+    };
+
+    Ok(BuildResult {
+        previous_version: build_info.previous_version,
+        current_version: build_info.current_version,
+        schema_changes: build_info.schema_changes,
+        version_bump: build_info.version_bump,
+        generated,
+        context: latest_project,
+    })
+}
+
+/// Compiles the package configuration and its schemas, without freezing
+/// anything into the CAS
+pub fn compile(package_path: &Path) -> Result<ProjectContext> {
     let config_path = package_path.join(format!("config.{}", CONGREGATION_EXTENSION));
     let config_name = config_path.file_name().unwrap().to_str().unwrap();
 
@@ -47,20 +75,60 @@ pub fn build(package_path: &Path) -> Result<BuildResult> {
         interpret_schemas(&latest_project, package_path)?;
     }
 
-    // Use CAS for immutable version storage
-    let build_info = if cas::refs::ref_exists(package_path, cas::refs::main_ref()) {
-        cas::build::process_changes(&package_path, &latest_project)?
-    } else {
-        cas::build::process_initial_freezing(&package_path, &latest_project)?
-    };
+    Ok(latest_project)
+}
+
+/// Compiles, validates and lints the package without committing anything to the CAS
+pub fn check(package_path: &Path) -> Result<CheckResult> {
+    Ok(check_context(compile(package_path)?))
+}
 
-    // generate_code_for_targets(&latest_project, project_path)?;
+/// Validates and lints an already compiled package
+pub fn check_context(context: ProjectContext) -> CheckResult {
+    let mut problems = vec![];
+
+    for schema_context in context.schema_contexts.iter() {
+        let schema_ctx = schema_context.borrow();
+        let frozen_schema = schema_ctx.frozen_schema.borrow();
+        let Some(frozen_schema) = frozen_schema.as_ref() else { continue };
+
+        if let Err(errors) = validation::validate(frozen_schema) {
+            problems.push(SchemaProblems {
+                namespace: schema_ctx.namespace_joined(),
+                errors,
+            });
+        }
+    }
+
+    let lints = lint::lint_package(&context);
+
+    CheckResult { problems, lints, context }
+}
+
+/// Compares the current state of the package against its latest frozen
+/// version, without committing anything to the CAS
+pub fn diff(package_path: &Path) -> Result<BuildResult> {
+    preview(package_path, compile(package_path)?)
+}
+
+/// Compares an already compiled package against its latest frozen version,
+/// without committing anything to the CAS
+pub fn preview(package_path: &Path, latest_project: ProjectContext) -> Result<BuildResult> {
+    if !cas::refs::ref_exists(package_path, cas::refs::main_ref()) {
+        bail!(
+            "Package at '{}' has no frozen versions yet, build it first",
+            package_path.display()
+        )
+    }
+
+    let build_info = cas::build::preview_changes(package_path, &latest_project)?;
 
     Ok(BuildResult {
         previous_version: build_info.previous_version,
         current_version: build_info.current_version,
         schema_changes: build_info.schema_changes,
         version_bump: build_info.version_bump,
+        generated: vec![],
         context: latest_project,
     })
 }
@@ -132,36 +200,118 @@ unsafe fn interpret_schemas(compiled_project: &ProjectContext, package_path: &Pa
 // Removed: freeze_project_auto() - no longer needed with CAS
 // CAS automatically handles freezing via process_initial_freezing/process_changes
 
-#[allow(unused)]
-fn generate_code_for_targets(compiled_project: &ProjectContext, base_path: &Path) -> Result<()> {
+/// Generates code for every language configured in `code_generation`, into
+/// their resolved `path` relative to the package, removing files generated
+/// before that were not generated this time
+///
+/// Every language is checked before any is generated, so that a generator
+/// whose version is not accepted, or that cannot emit features the schemas
+/// use, leaves generated code untouched
+pub fn generate_code_for_targets(
+    compiled_project: &ProjectContext, base_path: &Path
+) -> Result<Vec<GeneratedCode>> {
     use crate::package::config::ir::frozen::FrozenUnit;
 
-    for item in compiled_project.config_frozen.as_ref().unwrap().iter() {
-        if let FrozenUnit::CodeGeneration(details) = item {
-            let Some((name, version)) = details.name.split_once('#') else {
-                panic!()
+    let units = compiled_project.config_frozen.as_deref().unwrap_or_default();
+    let schemas = compiled_schemas(compiled_project)?;
+    let mut targets = vec![];
+
+    for item in units.iter() {
+        let FrozenUnit::CodeGeneration(details) = item else { continue };
+        let (name, version) = details.name.split_once('#').unwrap_or((&details.name, ""));
+
+        // Languages with templates are generated from them, from the package,
+        // and languages with a plugin by running it
+        let mut options = details.options.clone();
+        let gen_fn = if let Some(templates) = options.get_mut(template::TEMPLATES_OPTION) {
+            *templates = base_path.join(&*templates).display().to_string();
+            &(template::generate_template_package as GeneratorFn)
+        } else if let Some(executable) = options.get_mut(plugin::PLUGIN_OPTION) {
+            // Bare names are looked up in `PATH`, paths are of the package
+            if executable.contains('/') {
+                *executable = base_path.join(&*executable).display().to_string();
+            }
+            &(plugin::generate_plugin_package as GeneratorFn)
+        } else {
+            let requirement = match &details.generator_version {
+                Some(requirement) => VersionReq::parse(requirement).map_err(|e| eyre!(
+                    "The generator version of '{}' is not a version requirement: {}", details.name, e
+                ))?,
+                None => VersionReq::STAR,
             };
+            let generator = find_generator(name, &requirement)
+                .map_err(|e| eyre!("Could not generate '{}': {}", details.name, e))?;
+            generator.check_features(&schemas)?;
+
+            &generator.generate
+        };
+
+        let args = Args {
+            default_path: "generated/{{language}}/{{version}}".to_owned(),
+            language: name.to_owned(),
+            version: version.to_owned(),
+        };
+        let path = resolve_path_query(&details.generation_path, args).map_err(|e| {
+            eyre!("Could not resolve the generation path of '{}': {}", details.name, e)
+        })?;
+
+        targets.push((name, version, gen_fn, options, base_path.join(path)));
+    }
 
-            let args = Args {
-                default_path: "generated/{{language}}/{{version}}".to_owned(),
-                language: name.to_owned(),
-                version: version.to_owned(),
-            };
+    let mut generated = vec![];
+    for (name, version, gen_fn, options, path) in targets {
+        let files = generate_code_for_context(compiled_project, gen_fn, &options, &path)?;
+        remove_stale_files(&path, &files)?;
 
-            let path = resolve_path_query(&details.generation_path, args).unwrap();
-            let path = base_path.join(path);
+        generated.push(GeneratedCode {
+            language: name.to_owned(), version: version.to_owned(), path, files,
+        });
+    }
 
-            let Some((gen_fn, extension)) = find_generator(name, version) else {
-                panic!(
-                    "No generator found for language named '{}' with version '{}'",
-                    name, version
-                )
-            };
+    Ok(generated)
+}
 
-            generate_code_for_context(compiled_project, gen_fn, extension, &path)?;
+/// Code generated for a configured language
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedCode {
+    pub language: String,
+    pub version: String,
+    /// Directory the code was generated into
+    pub path: PathBuf,
+    /// Generated files, relative to `path`
+    pub files: Vec<PathBuf>,
+}
+
+/// Lists the files generated into a directory, so that the next generation
+/// knows which ones it owns
+pub const GENERATED_MANIFEST: &str = ".comline-generated";
+
+/// Removes the files the previous generation listed that were not generated
+/// again, along with directories left empty, then lists the new ones
+fn remove_stale_files(target_path: &Path, files: &[PathBuf]) -> Result<()> {
+    let manifest = target_path.join(GENERATED_MANIFEST);
+
+    if let Ok(previous) = std::fs::read_to_string(&manifest) {
+        for stale in previous.lines().map(PathBuf::from).filter(|f| !files.contains(f)) {
+            // Only files inside the target directory are ever removed
+            if stale.is_absolute() || stale.components().any(|c| c.as_os_str() == "..") {
+                continue
+            }
+
+            let stale_path = target_path.join(&stale);
+            if stale_path.is_file() { std::fs::remove_file(&stale_path)? }
+
+            let mut parent = stale_path.parent();
+            while let Some(directory) = parent.filter(|d| *d != target_path) {
+                if std::fs::remove_dir(directory).is_err() { break }
+                parent = directory.parent();
+            }
         }
     }
 
+    let listing: String = files.iter().map(|f| format!("{}\n", f.display())).collect();
+    std::fs::write(manifest, listing)?;
+
     Ok(())
 }
 
@@ -183,31 +333,125 @@ pub fn resolve_path_query(query: &Option<String>, args: Args) -> Result<String,
     }
 }
 
+/// Generates the code of the package into `target_path`, returning the
+/// written files relative to it
 pub fn generate_code_for_context(
     context: &ProjectContext,
     generator: &GeneratorFn,
-    extension: &str,
+    options: &BTreeMap<String, String>,
     target_path: &Path,
-) -> Result<()> {
+) -> Result<Vec<PathBuf>> {
+    let generation_context = generation_context(context, options)?;
+
     std::fs::create_dir_all(target_path)?;
+    let mut files = vec![];
+
+    for file in generator(&generation_context)? {
+        let file_path = target_path.join(&file.path);
+        if let Some(parent) = file_path.parent() { std::fs::create_dir_all(parent)? }
+
+        std::fs::write(&file_path, file.contents)?;
+        files.push(file.path);
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+/// What generators are given of a compiled package
+pub fn generation_context(
+    context: &ProjectContext, options: &BTreeMap<String, String>
+) -> Result<GenerationContext> {
+    use crate::package::config::ir::frozen::{self as config_frozen, MINIMUM_VERSION};
+
+    let config = context.config_frozen.as_deref().unwrap_or_default();
+    let schemas = compiled_schemas(context)?;
+
+    // Packages on disk have the versions they froze before
+    let history = match &context.origin {
+        Origin::Disk(config_path) => match config_path.parent() {
+            Some(package_path) => frozen_history(package_path)?,
+            None => vec![],
+        },
+        Origin::Virtual => vec![],
+    };
+
+    Ok(GenerationContext {
+        package_name: package_name(context).to_owned(),
+        package_version: config_frozen::version(config).unwrap_or(MINIMUM_VERSION).to_owned(),
+        schemas,
+        options: options.clone(),
+        history,
+    })
+}
+
+/// Name of a compiled package, which paths to its types may start with
+pub fn package_name(context: &ProjectContext) -> &str {
+    use crate::package::config::ir::frozen as config_frozen;
+
+    let config = context.config_frozen.as_deref().unwrap_or_default();
+    config_frozen::namespace(config).unwrap_or(&context.config.name.value)
+}
+
+/// The compiled schemas of a package, in the order the package has them
+pub fn compiled_schemas(context: &ProjectContext) -> Result<Vec<SchemaUnits>> {
+    let mut schemas = vec![];
 
     for schema_context in context.schema_contexts.iter() {
         let schema_ctx = schema_context.borrow();
-        let frozen_schema_opt = schema_ctx.frozen_schema.borrow();
-        let frozen_schema = frozen_schema_opt.as_ref().unwrap();
-        let file_path =
-            target_path.join(format!("{}.{}", &schema_ctx.namespace.join("/"), extension));
+        let frozen_schema = schema_ctx.frozen_schema.borrow();
+        let Some(frozen_schema) = frozen_schema.as_ref() else {
+            bail!("Schema '{}' was not compiled", schema_ctx.namespace_joined())
+        };
+
+        schemas.push(SchemaUnits {
+            namespace: schema_ctx.namespace.clone(), units: frozen_schema.clone(),
+        });
+    }
 
-        let code = &*generator(frozen_schema);
+    Ok(schemas)
+}
+
+/// Versions of the package frozen into the CAS, oldest first
+pub fn frozen_history(package_path: &Path) -> Result<Vec<FrozenVersion>> {
+    let mut history = vec![];
 
-        std::fs::write(file_path, code).unwrap();
+    for (_, commit) in cas::history::log(package_path)?.into_iter().rev() {
+        let schemas = cas::history::schemas_of(package_path, &commit)?;
+        history.push(FrozenVersion { version: commit.version, schemas });
     }
 
-    Ok(())
+    Ok(history)
 }
 
 pub struct BuildOptions {}
 
+/// Validation problems found in a single schema
+#[derive(Debug, Clone)]
+pub struct SchemaProblems {
+    pub namespace: String,
+    pub errors: Vec<ValidationError>,
+}
+
+/// Result of checking a package
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    /// Problems found, grouped by schema
+    pub problems: Vec<SchemaProblems>,
+    /// Lints found, grouped by schema
+    pub lints: Vec<SchemaLints>,
+    /// The underlying project context
+    pub context: ProjectContext,
+}
+
+impl CheckResult {
+    /// Check if no schema reported problems nor denied lints
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty() && !self.lints.iter()
+            .any(|schema| schema.lints.iter().any(|lint| lint.level == LintLevel::Deny))
+    }
+}
+
 /// Re-export VersionBump from CAS for public API
 pub use cas::VersionBump;
 
@@ -222,6 +466,8 @@ pub struct BuildResult {
     pub schema_changes: Option<SchemaChanges>,
     /// The type of version bump applied
     pub version_bump: VersionBump,
+    /// Code generated for the configured targets
+    pub generated: Vec<GeneratedCode>,
     /// The underlying project context
     pub context: ProjectContext,
 }
@@ -239,6 +485,14 @@ impl BuildResult {
         self.previous_version.is_none()
     }
 
+    /// Check if breaking changes were detected
+    pub fn is_breaking(&self) -> bool {
+        self.schema_changes
+            .as_ref()
+            .map(|changes| changes.is_breaking())
+            .unwrap_or(false)
+    }
+
     /// Check if the version changed
     pub fn version_changed(&self) -> bool {
         self.previous_version
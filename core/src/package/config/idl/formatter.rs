@@ -0,0 +1,96 @@
+// Canonical layout for package configurations
+
+// Standard Uses
+
+// Crate Uses
+use crate::utils::formatting::{block, render, Line, TokenStream, INDENT, MAX_WIDTH};
+
+// External Uses
+use eyre::{bail, Result};
+
+
+/// Formats a package configuration into its canonical layout:
+/// - One assignment per line as `key = value`, four spaces of indentation
+/// - Dictionaries always broken over lines, blank lines between assignments kept
+/// - Lists kept on one line when short, otherwise one item per line with
+///   trailing commas
+pub fn format_config(source: &str) -> Result<String> {
+    if crate::package::config::idl::grammar::parse(source).is_err() {
+        bail!("Configuration has syntax errors and cannot be formatted")
+    }
+
+    let mut tokens = TokenStream::new(source)?;
+
+    let mut header = Line::new(0);
+    header.push(tokens.expect("congregation")?).text(" ").push(tokens.take()?);
+    header.blank_before = false;
+
+    let mut lines = vec![header];
+    while !tokens.is_done() {
+        lines.extend(assignment(&mut tokens, 0)?);
+    }
+
+    Ok(render(&lines, &tokens.end_comments))
+}
+
+fn assignment(tokens: &mut TokenStream, indent: usize) -> Result<Vec<Line>> {
+    let mut line = Line::new(indent);
+    line.push(tokens.take()?).text(" ").push(tokens.expect("=")?).text(" ");
+
+    value(tokens, line)
+}
+
+/// Lines of a value, the first one being `line`
+fn value(tokens: &mut TokenStream, mut line: Line) -> Result<Vec<Line>> {
+    let indent = line.indent;
+
+    if tokens.peek_is("{") {
+        line.push(tokens.take()?);
+
+        let mut members = vec![];
+        while !tokens.peek_is("}") {
+            members.extend(assignment(tokens, indent + 1)?);
+        }
+
+        return Ok(block(line, members, tokens.expect("}")?))
+    }
+
+    if tokens.peek_is("[") {
+        line.push(tokens.take()?);
+
+        let mut items: Vec<Vec<Line>> = vec![];
+        while !tokens.peek_is("]") {
+            let mut item = value(tokens, Line::new(indent + 1))?;
+            if tokens.peek_is(",") {
+                item.last_mut().unwrap().absorb(tokens.take()?);
+            }
+            items.push(item);
+        }
+        let close = tokens.expect("]")?;
+
+        let is_simple = close.leading.is_empty() && items.iter().all(|item| {
+            item.len() == 1 && item[0].comments.is_empty() && item[0].trailing.is_empty()
+        });
+        if is_simple {
+            let values = items.iter().map(|item| item[0].text.as_str()).collect::<Vec<_>>();
+            let single = format!("{}{}]", line.text, values.join(", "));
+
+            if INDENT.len() * indent + single.len() <= MAX_WIDTH {
+                line.text = single;
+                line.trailing.extend(close.trailing);
+                return Ok(vec![line])
+            }
+        }
+
+        let mut members = vec![];
+        for mut item in items {
+            item.last_mut().unwrap().text(",");
+            members.extend(item);
+        }
+
+        return Ok(block(line, members, close))
+    }
+
+    line.push(tokens.take()?);
+    Ok(vec![line])
+}
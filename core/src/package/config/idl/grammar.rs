@@ -56,19 +56,42 @@ pub mod grammar {
         Identifier(Identifier),
     }
 
+    /// List of values separated by commas, the last one may be followed by a comma too
     #[derive(Debug, Clone)]
     pub struct List {
         #[rust_sitter::leaf(text = "[")]
         _lbracket: (),
-        #[rust_sitter::delimited(
-            #[rust_sitter::leaf(text = ",")]
-            ()
-        )]
-        pub items: Vec<Value>,
+        items: Option<Box<ListItems>>,
         #[rust_sitter::leaf(text = "]")]
         _rbracket: (),
     }
 
+    /// A value, optionally followed by a comma and more values
+    #[derive(Debug, Clone)]
+    pub struct ListItems {
+        value: Value,
+        rest: Option<ListRest>,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct ListRest {
+        #[rust_sitter::leaf(text = ",")]
+        _comma: (),
+        items: Option<Box<ListItems>>,
+    }
+
+    impl List {
+        pub fn items(&self) -> Vec<&Value> {
+            let mut values = vec![];
+            let mut next = self.items.as_deref();
+            while let Some(items) = next {
+                values.push(&items.value);
+                next = items.rest.as_ref().and_then(|rest| rest.items.as_deref());
+            }
+            values
+        }
+    }
+
     #[derive(Debug, Clone)]
     pub struct Dictionary {
         #[rust_sitter::leaf(text = "{")]
@@ -1,5 +1,6 @@
 // Relative Modules
 pub mod grammar;
 pub mod constants;
+pub mod formatter;
 // pub mod parser;
 // pub mod ast;
@@ -2,6 +2,7 @@
 
 // Crate Uses
 use crate::package::config::ir::context::ProjectContext;
+use crate::schema::ir::compiler::interpreter::incremental::attach_docstrings;
 use crate::schema::ir::compiler::interpreter::IncrementalInterpreter;
 use crate::schema::ir::compiler::Compile; // for from_declarations
 
@@ -12,7 +13,11 @@ pub fn interpret_context(project_context: &ProjectContext) -> Result<()> {
     for schema_context in project_context.schema_contexts.iter() {
         let declarations = { schema_context.borrow().declarations.clone() };
 
-        let mut frozen_units = IncrementalInterpreter::from_declarations(declarations);
+        let mut frozen_units = IncrementalInterpreter::from_declarations(declarations.clone());
+
+        if let Some(file) = schema_context.borrow().codemap.files().first() {
+            attach_docstrings(&mut frozen_units, &declarations, file.contents());
+        }
 
         // Inject Namespace unit
         let namespace = schema_context.borrow().namespace_joined();
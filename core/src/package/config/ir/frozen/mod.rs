@@ -4,11 +4,13 @@
 pub mod loader;
 
 // Standard Uses
+use std::collections::BTreeMap;
 use std::slice::Iter;
 use std::iter::FilterMap;
 
 // Crate Uses
 use crate::package::config::ir::context::ProjectContext;
+use crate::schema::ir::lint::{LintLevel, Rule};
 
 // External Uses
 use serde_derive::{Serialize, Deserialize};
@@ -26,7 +28,8 @@ pub enum FrozenUnit {
     SchemaPath(String),
     Dependency(Dependency),
     CodeGeneration(LanguageDetails),
-    PublishRegistry((String, PublishRegistry))
+    PublishRegistry((String, PublishRegistry)),
+    Lint((Rule, LintLevel)),
 }
 
 #[derive(Deserialize, Serialize)]
@@ -42,7 +45,11 @@ pub struct Dependency {
 pub struct LanguageDetails {
     pub name: String,
     pub versions: Vec<String>,
-    pub generation_path: Option<String>
+    /// Versions of the generator the language accepts, as a semver requirement
+    pub generator_version: Option<String>,
+    pub generation_path: Option<String>,
+    /// Options particular to the generator of the language
+    pub options: BTreeMap<String, String>,
 }
 
 // TODO: Optimization might be possible here, and for other structures
@@ -61,6 +68,7 @@ pub enum RegistryKind {
 }
 
 pub const MINIMUM_VERSION: &str = "0.0.1";
+pub const LOCAL_REGISTRY_SCHEME: &str = "local://";
 
 
 pub fn namespace(units: &[FrozenUnit]) -> Option<&str> {
@@ -119,3 +127,15 @@ pub fn publish_registries(
     })
 }
 
+#[allow(clippy::type_complexity)]
+pub fn lints(
+    units: &[FrozenUnit]
+) -> FilterMap<Iter<'_, FrozenUnit>, fn(&FrozenUnit) -> Option<&(Rule, LintLevel)>>
+{
+    units.iter().filter_map(|unit| {
+        match unit {
+            FrozenUnit::Lint(lint) => Some(lint),
+            _ => None
+        }
+    })
+}
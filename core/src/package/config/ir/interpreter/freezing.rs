@@ -1,11 +1,14 @@
 // Standard Uses
+use std::collections::BTreeMap;
 
 // Crate Uses
 use crate::package::config::idl::grammar::{Assignment, Key, Value};
 use crate::package::config::ir::context::ProjectContext;
 use crate::package::config::ir::frozen::{
     FrozenUnit, FrozenWhole, LanguageDetails, PublishRegistry, RegistryKind,
+    LOCAL_REGISTRY_SCHEME,
 };
+use crate::schema::ir::lint::{LintLevel, Rule};
 // use crate::utils::codemap::Span;
 
 // External Uses
@@ -18,6 +21,9 @@ pub fn interpret_node_into_frozen(
     interpret_assignment(context, node)
 }
 
+/// Details a target of `code_generation.languages` may have
+const TARGET_DETAILS: &[&str] = &["package_versions", "generator_version", "path", "options"];
+
 pub fn interpret_assignment(
     _context: &ProjectContext,
     node: &Assignment,
@@ -32,14 +38,12 @@ pub fn interpret_assignment(
     let result = match key_str.as_str() {
         "specification_version" => {
             let Value::Number(version) = &node.value else {
-                panic!(
-                    "'specification_version' should be a number, \
-                    got something else instead."
-                )
+                return Err("'specification_version' should be a number, got something else instead".into())
             };
 
-            // Should parse integer
-            let version_num: u8 = version.value.parse().expect("Invalid version number");
+            let Ok(version_num) = version.value.parse::<u8>() else {
+                return Err(format!("'specification_version' should be a whole number, not '{}'", version.value).into())
+            };
             vec![FrozenUnit::SpecificationVersion(version_num)]
         }
         /*
@@ -54,7 +58,7 @@ pub fn interpret_assignment(
             };
 
             let mut solved = vec![];
-            for path_val in &paths.items {
+            for path_val in paths.items() {
                 let Value::String(path) = path_val else {
                     panic!("Expected path string")
                 };
@@ -71,24 +75,26 @@ pub fn interpret_assignment(
         */
         "code_generation" => {
             let Value::Dictionary(items) = &node.value else {
-                panic!("Expected dictionary for code_generation")
+                return Err("'code_generation' should be a dictionary".into())
             };
 
             interpret_assignment_code_generation(items.assignments.as_ref())?
         }
         "publish_registries" => {
             let Value::Dictionary(items) = &node.value else {
-                panic!("Expected dictionary for publish_registries")
+                return Err("'publish_registries' should be a dictionary".into())
             };
 
             interpret_assigment_publish_registries(items.assignments.as_ref())?
         }
-        any => {
-            // panic!("Assignment '{}' is not a valid assignment", any)
-            // Allow unknown assignments for now or warn?
-            // panic for now to match behavior
-            panic!("Assignment '{}' is not a valid assignment", any)
+        "lints" => {
+            let Value::Dictionary(items) = &node.value else {
+                return Err("'lints' should be a dictionary".into())
+            };
+
+            interpret_assignment_lints(items.assignments.as_ref())?
         }
+        any => return Err(format!("Assignment '{}' is not a valid assignment", any).into()),
     };
 
     Ok(result)
@@ -111,7 +117,7 @@ fn interpret_assignment_code_generation(
             "languages" => {
                 // Value should be Dictionary of Language -> Details
                 let Value::Dictionary(lang_dict) = &assignment.value else {
-                    panic!("languages must be a dictionary")
+                    return Err("'code_generation.languages' should be a dictionary".into())
                 };
 
                 for lang_assign in &lang_dict.assignments {
@@ -122,12 +128,17 @@ fn interpret_assignment_code_generation(
                         Key::DependencyAddress(da) => da.value.clone(),
                     };
 
+                    // Where in the configuration a detail of this target is
+                    let at = |detail: &str| format!("'{}' of code generation target '{}'", detail, lang_name);
+
                     let Value::Dictionary(details) = &lang_assign.value else {
-                        panic!("Language details must be a dictionary")
+                        return Err(format!("Code generation target '{}' should be a dictionary", lang_name).into())
                     };
 
                     let mut versions = vec![];
-                    let path = None;
+                    let mut generator_version = None;
+                    let mut path = None;
+                    let mut options = BTreeMap::new();
 
                     for detail in &details.assignments {
                         let detail_key = match &detail.key {
@@ -140,30 +151,71 @@ fn interpret_assignment_code_generation(
                         match detail_key.as_str() {
                             "package_versions" => {
                                 let Value::List(v_list) = &detail.value else {
-                                    panic!("package_versions must be a list")
+                                    return Err(format!("{} should be a list", at("package_versions")).into())
                                 };
 
-                                for item in &v_list.items {
+                                for item in v_list.items() {
                                     let val_str = match item {
                                         Value::String(s) => s.value.clone(),
                                         Value::Identifier(id) => id.value.clone(),
-                                        _ => panic!("Version must be a string or identifier"),
+                                        _ => return Err(format!(
+                                            "Versions of {} should be strings or identifiers", at("package_versions")
+                                        ).into()),
                                     };
                                     versions.push(val_str);
                                 }
                             }
-                            other => panic!("Not expected: {}", other),
+                            "generator_version" => {
+                                let Value::String(requirement) = &detail.value else {
+                                    return Err(format!("{} should be a string", at("generator_version")).into())
+                                };
+                                generator_version = Some(requirement.value.trim_matches('"').to_owned());
+                            }
+                            "path" => {
+                                let Value::String(p) = &detail.value else {
+                                    return Err(format!("{} should be a string", at("path")).into())
+                                };
+                                path = Some(p.value.trim_matches('"').to_owned());
+                            }
+                            "options" => {
+                                let Value::Dictionary(option_dict) = &detail.value else {
+                                    return Err(format!("{} should be a dictionary", at("options")).into())
+                                };
+
+                                for option in &option_dict.assignments {
+                                    let Key::Identifier(option_key) = &option.key else {
+                                        return Err(format!("Names of {} should be identifiers", at("options")).into())
+                                    };
+                                    let option_value = match &option.value {
+                                        Value::String(s) => s.value.trim_matches('"').to_owned(),
+                                        Value::Number(n) => n.value.clone(),
+                                        Value::Boolean(b) => b.value.clone(),
+                                        Value::Identifier(id) => id.value.clone(),
+                                        _ => return Err(format!(
+                                            "Option '{}' of code generation target '{}' should be a string, \
+                                            number, boolean or identifier", option_key.value, lang_name
+                                        ).into()),
+                                    };
+                                    options.insert(option_key.value.clone(), option_value);
+                                }
+                            }
+                            other => return Err(format!(
+                                "{} is not a known detail, the details are: {}",
+                                at(other), TARGET_DETAILS.join(", ")
+                            ).into()),
                         }
                     }
 
                     languages.push(FrozenUnit::CodeGeneration(LanguageDetails {
                         name: lang_name,
                         versions,
+                        generator_version,
                         generation_path: path,
+                        options,
                     }));
                 }
             }
-            other => panic!("Key not allowed here: {}", other),
+            other => return Err(format!("'code_generation.{}' is not allowed, only 'languages' is", other).into()),
         }
     }
 
@@ -224,26 +276,29 @@ fn interpret_assigment_publish_registries(
                     match item_key.as_str() {
                         "uri" => {
                             if let Value::String(s) = &item.value {
-                                registry_kind = Some(RegistryKind::LocalStorage);
-                                url = Some(s.value.clone());
+                                let uri = s.value.trim_matches('"').to_string();
+                                registry_kind = Some(registry_kind_of(&uri));
+                                url = Some(uri);
                             } else {
-                                panic!("URI should be a string")
+                                return Err(format!("'uri' of publish registry '{}' should be a string", key_str).into())
                             }
                         }
                         // method...
-                        other => panic!("Key not allowed here: {}", other),
+                        other => return Err(format!(
+                            "'{}' of publish registry '{}' is not allowed, only 'uri' is", other, key_str
+                        ).into()),
                     }
                 }
 
-                FrozenUnit::PublishRegistry((
-                    key_str,
-                    PublishRegistry {
-                        kind: registry_kind.unwrap(),
-                        uri: url.unwrap(),
-                    },
-                ))
+                let (Some(kind), Some(uri)) = (registry_kind, url) else {
+                    return Err(format!("Publish registry '{}' has no 'uri'", key_str).into())
+                };
+
+                FrozenUnit::PublishRegistry((key_str, PublishRegistry { kind, uri }))
             }
-            other => panic!("Invalid registry value: {:?}", other),
+            _ => return Err(format!(
+                "Publish registry '{}' should be a string, identifier, namespace or dictionary", key_str
+            ).into()),
         };
 
         targets.push(target);
@@ -252,6 +307,44 @@ fn interpret_assigment_publish_registries(
     Ok(targets)
 }
 
+/// Levels of lint rules, as `rule = allow | warn | deny`
+fn interpret_assignment_lints(items: &Vec<Assignment>) -> Result<Vec<FrozenUnit>, Box<dyn snafu::Error>> {
+    let rules = || Rule::ALL.iter().map(|rule| rule.name()).collect::<Vec<_>>().join(", ");
+    let mut lints = vec![];
+
+    for assignment in items {
+        let Key::Identifier(key) = &assignment.key else {
+            return Err(format!("Lint rules are plain names, such as: {}", rules()).into())
+        };
+        let Some(rule) = Rule::from_name(&key.value) else {
+            return Err(format!("Unknown lint rule '{}', the rules are: {}", key.value, rules()).into())
+        };
+
+        let level = match &assignment.value {
+            Value::Identifier(id) => LintLevel::from_name(&id.value),
+            Value::String(s) => LintLevel::from_name(s.value.trim_matches('"')),
+            _ => None,
+        };
+        let Some(level) = level else {
+            return Err(format!("Level of lint rule '{}' should be one of allow, warn or deny", key.value).into())
+        };
+
+        lints.push(FrozenUnit::Lint((rule, level)));
+    }
+
+    Ok(lints)
+}
+
+/// Registries addressed with the `local://` scheme live on the filesystem,
+/// anything else is expected to be served by a registry server
+fn registry_kind_of(uri: &str) -> RegistryKind {
+    if uri.starts_with(LOCAL_REGISTRY_SCHEME) {
+        RegistryKind::LocalStorage
+    } else {
+        RegistryKind::RegistryServer
+    }
+}
+
 #[allow(unused)]
 pub fn into_frozen_whole(
     context: &ProjectContext,
@@ -44,7 +44,7 @@ impl Compile for ProjectInterpreter {
         // interpret_context(&context)?; // This was in from_config_source
         
         crate::package::config::ir::interpreter::interpret::interpret_context(&context)
-            .map_err(|e| eyre::eyre!("{:?}", e))?;
+            .map_err(|e| eyre::eyre!("{}", e))?;
         
         Ok(context)
     }
@@ -73,7 +73,7 @@ impl ProjectInterpreter {
         context.origin = crate::package::config::ir::context::Origin::Disk(origin.to_path_buf());
         
         context.config_frozen = Some(interpret::interpret_context(&context)
-             .map_err(|e| eyre::eyre!("{:?}", e))?);
+             .map_err(|e| eyre::eyre!("{}", e))?);
         
         Ok(context)
     }
@@ -0,0 +1,50 @@
+// Standard Uses
+use std::path::{Path, PathBuf};
+
+// Crate Uses
+use crate::package::config::idl::constants::CONGREGATION_EXTENSION;
+use crate::package::config::idl::formatter::format_config;
+use crate::schema::idl::constants::SCHEMA_EXTENSION;
+use crate::schema::idl::formatter::format_schema;
+
+// External Uses
+use eyre::{Context, Result};
+
+
+/// Formats a schema or package configuration, going by its extension
+pub fn format_source(path: &Path, source: &str) -> Result<String> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(CONGREGATION_EXTENSION) => format_config(source),
+        _ => format_schema(source),
+    }
+}
+
+/// Formats the configuration and every schema of a package, returning the
+/// files whose layout was not canonical
+///
+/// With `check` nothing is written, which is meant for CI
+pub fn format_package(package_path: &Path, check: bool) -> Result<Vec<PathBuf>> {
+    let mut paths = vec![package_path.join(format!("config.{}", CONGREGATION_EXTENSION))];
+
+    let pattern = format!("{}/src/**/*.{}", package_path.display(), SCHEMA_EXTENSION);
+    for path in glob::glob(&pattern)? {
+        paths.push(path?);
+    }
+
+    let mut unformatted = vec![];
+    for path in paths {
+        let source = std::fs::read_to_string(&path)
+            .wrap_err_with(|| format!("Could not read '{}'", path.display()))?;
+        let formatted = format_source(&path, &source)
+            .wrap_err_with(|| format!("Could not format '{}'", path.display()))?;
+
+        if formatted == source { continue }
+
+        if !check {
+            std::fs::write(&path, formatted)?;
+        }
+        unformatted.push(path);
+    }
+
+    Ok(unformatted)
+}
@@ -1,3 +1,6 @@
 // Relative Modules
 pub mod build;
 pub mod config;
+pub mod format;
+pub mod publish;
+pub mod watch;
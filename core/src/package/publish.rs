@@ -0,0 +1,148 @@
+// Standard Uses
+use std::path::{Path, PathBuf};
+
+// Crate Uses
+use crate::package::build::cas::objects::{Commit, EntryMode, Tree};
+use crate::package::build::cas::{history, main_ref, update_ref, Hash, ObjectStore};
+use crate::package::config::idl::constants::CONGREGATION_EXTENSION;
+use crate::package::config::ir::frozen::{
+    publish_registries, PublishRegistry, RegistryKind, LOCAL_REGISTRY_SCHEME,
+};
+use crate::package::config::ir::interpreter::ProjectInterpreter;
+use crate::utils::templating::recurse_render;
+
+// External Uses
+use eyre::{bail, eyre, Result};
+use serde_derive::Serialize;
+
+
+/// A package version that was published into a registry
+#[derive(Debug, Clone)]
+pub struct Publication {
+    pub registry: String,
+    pub version: String,
+    pub commit: Hash,
+    pub location: PathBuf,
+}
+
+#[derive(Serialize)]
+struct RegistryArgs {
+    package_path: String,
+}
+
+/// Publishes the latest frozen version of a package into its configured
+/// registries, or only into the one named by `registry_name`
+///
+/// Published versions are laid out as a CAS of their own, at
+/// `<registry>/<package>/<version>/`, holding every object reachable from the
+/// published commit so the whole history can be inspected from the registry
+pub fn publish(package_path: &Path, registry_name: Option<&str>) -> Result<Vec<Publication>> {
+    let config_path = package_path.join(format!("config.{}", CONGREGATION_EXTENSION));
+    let context = ProjectInterpreter::from_origin(&config_path)?;
+    let package_name = context.config.name.value.clone();
+
+    let Some((head, commit)) = history::log(package_path)?.into_iter().next() else {
+        bail!("Package '{}' has no frozen versions yet, build it first", package_name)
+    };
+
+    let registries = publish_registries(context.config_frozen.as_deref().unwrap_or_default())
+        .filter(|(name, _)| registry_name.is_none_or(|wanted| wanted == name))
+        .collect::<Vec<_>>();
+
+    if let Some(wanted) = registry_name {
+        if registries.is_empty() {
+            bail!("Package '{}' has no publish registry named '{}'", package_name, wanted)
+        }
+    }
+
+    let mut publications = vec![];
+    for (name, registry) in registries {
+        let root = match local_registry_root(package_path, registry) {
+            Ok(root) => root,
+            // Only fail on registries that were explicitly asked for
+            Err(e) if registry_name.is_none() => {
+                tracing::warn!("Skipping registry '{}': {}", name, e);
+                continue
+            }
+            Err(e) => return Err(e),
+        };
+
+        let location = root.join(&package_name).join(&commit.version);
+        publish_commit(package_path, &location, &head)?;
+
+        publications.push(Publication {
+            registry: name.clone(),
+            version: commit.version.clone(),
+            commit: head,
+            location,
+        });
+    }
+
+    Ok(publications)
+}
+
+fn local_registry_root(package_path: &Path, registry: &PublishRegistry) -> Result<PathBuf> {
+    if registry.kind != RegistryKind::LocalStorage {
+        bail!("Publishing to registry servers is not supported yet ('{}')", registry.uri)
+    }
+
+    let Some(path) = registry.uri.strip_prefix(LOCAL_REGISTRY_SCHEME) else {
+        bail!("Registry '{}' does not point to a local path", registry.uri)
+    };
+
+    let args = RegistryArgs { package_path: package_path.display().to_string() };
+    let path = recurse_render(path, &args).map_err(|e| eyre!("{}", e))?;
+
+    Ok(PathBuf::from(path))
+}
+
+fn publish_commit(package_path: &Path, location: &Path, head: &Hash) -> Result<()> {
+    if location.join(".comline").join(main_ref()).exists() {
+        let published = crate::package::build::cas::read_ref(location, main_ref())?;
+        if published == *head { return Ok(()) }
+
+        bail!(
+            "A different commit was already published at '{}', versions are immutable",
+            location.display()
+        )
+    }
+
+    let source = ObjectStore::new(package_path);
+    let target = ObjectStore::new(location);
+    target.init()?;
+
+    for hash in reachable_objects(&source, head)? {
+        target.write(&source.read(&hash)?)?;
+    }
+
+    update_ref(location, main_ref(), head)?;
+
+    Ok(())
+}
+
+/// Every object reachable from a commit, including its ancestors
+fn reachable_objects(store: &ObjectStore, head: &Hash) -> Result<Vec<Hash>> {
+    let mut objects = vec![];
+    let mut next = Some(*head);
+
+    while let Some(commit_hash) = next {
+        let commit = Commit::from_bytes(&store.read(&commit_hash)?)?;
+        objects.push(commit_hash);
+
+        let mut trees = vec![commit.tree];
+        while let Some(tree_hash) = trees.pop() {
+            objects.push(tree_hash);
+
+            for entry in Tree::from_bytes(&store.read(&tree_hash)?)?.entries {
+                match entry.mode {
+                    EntryMode::Tree => trees.push(entry.hash),
+                    EntryMode::Blob => objects.push(entry.hash),
+                }
+            }
+        }
+
+        next = commit.parents.first().copied();
+    }
+
+    Ok(objects)
+}
@@ -0,0 +1,128 @@
+// Watching a package for changes
+// Files are polled and compared by content, which behaves the same on every
+// platform and ignores saves that leave a file as it was
+
+// Standard Uses
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+// Crate Uses
+use crate::package::build::{self, cas, BuildResult, CheckResult, GeneratedCode};
+use crate::package::config::idl::constants::CONGREGATION_EXTENSION;
+use crate::schema::idl::constants::SCHEMA_EXTENSION;
+
+// External Uses
+use eyre::Result;
+
+
+/// How long files have to stay unchanged before a rebuild
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+/// How often files are looked at
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+
+/// Watches the configuration and the schemas of a package
+pub struct Watcher {
+    package_path: PathBuf,
+    debounce: Duration,
+    snapshot: BTreeMap<PathBuf, blake3::Hash>,
+    /// Paths changed since the last settled set of changes
+    pending: BTreeSet<PathBuf>,
+    last_change: Option<Instant>,
+}
+
+impl Watcher {
+    pub fn new(package_path: &Path, debounce: Duration) -> Self {
+        Self {
+            package_path: package_path.to_path_buf(),
+            debounce,
+            snapshot: snapshot(package_path),
+            pending: BTreeSet::new(),
+            last_change: None,
+        }
+    }
+
+    /// Looks at the files once, returning what changed when files have stayed
+    /// unchanged for the debounce duration since the last change
+    pub fn poll(&mut self) -> Option<Vec<PathBuf>> {
+        let current = snapshot(&self.package_path);
+
+        let changed = current.iter()
+            .filter(|(path, hash)| self.snapshot.get(*path) != Some(hash))
+            .map(|(path, _)| path)
+            .chain(self.snapshot.keys().filter(|path| !current.contains_key(*path)))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if !changed.is_empty() {
+            self.pending.extend(changed);
+            self.last_change = Some(Instant::now());
+        }
+        self.snapshot = current;
+
+        let settled = self.last_change.is_some_and(|at| at.elapsed() >= self.debounce);
+        if !settled { return None }
+
+        self.last_change = None;
+        Some(std::mem::take(&mut self.pending).into_iter().collect())
+    }
+
+    /// Blocks until a settled set of changes
+    pub fn wait(&mut self, poll_interval: Duration) -> Vec<PathBuf> {
+        loop {
+            if let Some(changed) = self.poll() { return changed }
+            std::thread::sleep(poll_interval);
+        }
+    }
+}
+
+/// Hashes of the configuration and every schema under `src/`
+fn snapshot(package_path: &Path) -> BTreeMap<PathBuf, blake3::Hash> {
+    let mut paths = vec![package_path.join(format!("config.{}", CONGREGATION_EXTENSION))];
+
+    let pattern = format!("{}/src/**/*.{}", package_path.display(), SCHEMA_EXTENSION);
+    if let Ok(schemas) = glob::glob(&pattern) {
+        paths.extend(schemas.flatten());
+    }
+
+    // Files that cannot be read, as while being written, count as missing
+    paths.into_iter()
+        .filter_map(|path| std::fs::read(&path).ok().map(|bytes| (path, blake3::hash(&bytes))))
+        .collect()
+}
+
+
+/// What a rebuild found, nothing of it is committed to the CAS
+pub struct Rebuild {
+    pub check: CheckResult,
+    /// Changes against the latest frozen version and the version they lead to,
+    /// if the package is valid and has been built before
+    pub preview: Option<BuildResult>,
+    /// Code generated for the configured targets
+    pub generated: Vec<GeneratedCode>,
+}
+
+/// Compiles, validates and compares the package against its latest frozen
+/// version, then generates code for the configured targets if asked and the
+/// package is valid
+pub fn rebuild(package_path: &Path, generate: bool) -> Result<Rebuild> {
+    let context = build::compile(package_path)?;
+
+    let check = build::check_context(context.clone());
+    if !check.is_valid() {
+        return Ok(Rebuild { check, preview: None, generated: vec![] })
+    }
+
+    let preview = match cas::refs::ref_exists(package_path, cas::refs::main_ref()) {
+        true => Some(build::preview(package_path, context.clone())?),
+        false => None,
+    };
+
+    let generated = match generate {
+        true => build::generate_code_for_targets(&context, package_path)?,
+        false => vec![],
+    };
+
+    Ok(Rebuild { check, preview, generated })
+}
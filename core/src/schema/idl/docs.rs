@@ -0,0 +1,46 @@
+// Doc comment extraction
+// The grammar treats every comment as an extra, so doc comments (`///`) are
+// recovered from the source text using the spans of declaration names
+
+// Standard Uses
+
+// Crate Uses
+
+// External Uses
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+
+/// What may precede a declaration name on its own line, for doc comments
+/// above that line to still belong to the declaration
+static DECLARATION_PREFIX: Lazy<Regex> = Lazy::new(|| Regex::new(
+    r"^(@[a-zA-Z_][a-zA-Z0-9_]*=\S+\s*)*(struct|error|enum|protocol|const|(async\s+)?function|validator|optional)?$"
+).unwrap());
+
+
+/// Collects the `///` comment lines directly above the declaration whose name
+/// starts at `name_offset`, skipping annotation lines in between
+pub fn docstring_before(source: &str, name_offset: usize) -> Option<String> {
+    let line_start = source[..name_offset].rfind('\n').map_or(0, |i| i + 1);
+    if !DECLARATION_PREFIX.is_match(source[line_start..name_offset].trim()) {
+        return None
+    }
+
+    let mut lines = vec![];
+    for line in source[..line_start].lines().rev() {
+        let line = line.trim();
+
+        if let Some(doc) = line.strip_prefix("///") {
+            lines.push(doc.strip_prefix(' ').unwrap_or(doc));
+        } else if line.starts_with('@') {
+            continue
+        } else {
+            break
+        }
+    }
+
+    if lines.is_empty() { return None }
+
+    lines.reverse();
+    Some(lines.join("\n"))
+}
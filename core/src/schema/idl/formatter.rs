@@ -0,0 +1,473 @@
+// Canonical layout for schemas
+// Only schemas that parse are formatted, the layout is then built from the
+// token stream so that every comment survives
+
+// Standard Uses
+
+// Crate Uses
+use crate::schema::ir::expression::{self, Expression};
+use crate::utils::formatting::{align, block, render, Line, Token, TokenStream, INDENT, MAX_WIDTH};
+
+// External Uses
+use eyre::{bail, eyre, Result};
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum UseGroup {
+    Std,
+    Package,
+    Relative,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ItemKind {
+    Use(UseGroup),
+    Const,
+    Block,
+}
+
+struct Item {
+    kind: ItemKind,
+    /// How many of the lines are annotations
+    annotations: usize,
+    lines: Vec<Line>,
+}
+
+
+/// Formats a schema into its canonical layout:
+/// - Four spaces of indentation and one member per line
+/// - Field types aligned on the same column, blank lines between members kept
+/// - Runs of `use` statements sorted and grouped into standard library, package
+///   and relative imports, with multi-imports broken over lines, with trailing
+///   commas, when too long
+/// - One blank line around structs, errors, enums, protocols and validators,
+///   and between the message of errors and their fields
+/// - Annotations on their own lines, above what they annotate, with lists and
+///   calls in their values separated by spaces, like `[Bounds(min=1 max=8)]`
+/// - Calls of validate blocks one per line, with their arguments broken over
+///   lines when too long
+pub fn format_schema(source: &str) -> Result<String> {
+    if crate::schema::idl::grammar::parse(source).is_err() {
+        bail!("Schema has syntax errors and cannot be formatted")
+    }
+
+    let mut tokens = TokenStream::new(source)?;
+    let mut items = vec![];
+    while !tokens.is_done() {
+        let annotations = annotations(&mut tokens, 0)?;
+
+        let Some(token) = tokens.peek() else { bail!("Annotations must precede a declaration") };
+        let mut item = match token.text.as_str() {
+            "use" | "import" => use_statement(&mut tokens)?,
+            "const" => constant(&mut tokens)?,
+            "struct" => structure(&mut tokens)?,
+            "error" => error(&mut tokens)?,
+            "enum" => enumeration(&mut tokens)?,
+            "protocol" => protocol(&mut tokens)?,
+            "validator" => validator(&mut tokens)?,
+            other => bail!("Unexpected '{}' at the top level", other),
+        };
+        annotate(&mut item.lines, annotations, &mut item.annotations);
+        items.push(item);
+    }
+
+    sort_uses(&mut items);
+
+    let mut lines = vec![];
+    let mut previous = None;
+    for item in &mut items {
+        let first = &mut item.lines[0];
+        first.blank_before = match (previous, item.kind) {
+            (None, _) => false,
+            (Some(ItemKind::Use(before)), ItemKind::Use(group)) => before != group,
+            (Some(ItemKind::Const), ItemKind::Const) => first.blank_before,
+            _ => true,
+        };
+        previous = Some(item.kind);
+    }
+    for item in items {
+        lines.extend(item.lines);
+    }
+
+    Ok(render(&lines, &tokens.end_comments))
+}
+
+/// Sorts every run of consecutive imports, comments above a run stay on top of it
+fn sort_uses(items: &mut [Item]) {
+    let mut start = 0;
+    while start < items.len() {
+        if !matches!(items[start].kind, ItemKind::Use(_)) {
+            start += 1;
+            continue
+        }
+
+        let mut end = start;
+        while end < items.len() && matches!(items[end].kind, ItemKind::Use(_)) { end += 1 }
+
+        let run = &mut items[start..end];
+        let header = std::mem::take(&mut run[0].lines[0].comments);
+        run.sort_by(|a, b| {
+            let key = |item: &Item| match item.kind {
+                ItemKind::Use(group) => (group, item.lines[item.annotations].text.clone()),
+                _ => unreachable!(),
+            };
+            key(a).cmp(&key(b))
+        });
+        run[0].lines[0].comments.splice(0..0, header);
+
+        start = end;
+    }
+}
+
+fn use_statement(tokens: &mut TokenStream) -> Result<Item> {
+    let mut line = Line::new(0);
+    line.push(tokens.take()?).text(" ");
+
+    let path = tokens.take()?;
+    let group = match path.text.split("::").next() {
+        Some("std") => UseGroup::Std,
+        Some("self" | "parent" | "crate") => UseGroup::Relative,
+        _ => UseGroup::Package,
+    };
+    let is_multi = path.text.ends_with("::") && tokens.peek_is("{");
+    line.push(path);
+
+    let mut lines = vec![];
+    if is_multi {
+        line.absorb(tokens.expect("{")?);
+
+        let mut names = vec![];
+        while !tokens.peek_is("}") {
+            let name = tokens.take()?;
+            names.push(name.text.clone());
+            line.absorb(name);
+
+            if tokens.peek_is(",") { line.absorb(tokens.take()?); }
+        }
+        line.absorb(tokens.expect("}")?);
+
+        names.sort();
+        names.dedup();
+
+        let single = format!("{}{{{}}}", line.text, names.join(", "));
+        if single.len() <= MAX_WIDTH {
+            line.text = single;
+        } else {
+            line.text("{");
+            let trailing = std::mem::take(&mut line.trailing);
+            lines.push(line);
+
+            for name in names {
+                lines.push(Line { text: format!("{},", name), ..Line::new(1) });
+            }
+            line = Line { trailing, ..Line::new(0) };
+            line.text("}");
+        }
+    }
+
+    if tokens.peek_is("as") {
+        line.text(" ").push(tokens.take()?).text(" ").push(tokens.take()?);
+    }
+    lines.push(line);
+
+    Ok(Item { kind: ItemKind::Use(group), annotations: 0, lines })
+}
+
+fn constant(tokens: &mut TokenStream) -> Result<Item> {
+    let mut line = Line::new(0);
+    line.push(tokens.expect("const")?).text(" ").push(tokens.take()?);
+    line.push(tokens.expect(":")?).text(" ");
+    push_type(tokens, &mut line)?;
+    line.text(" ").push(tokens.expect("=")?).text(" ").push(tokens.take()?);
+
+    Ok(Item { kind: ItemKind::Const, annotations: 0, lines: vec![line] })
+}
+
+fn structure(tokens: &mut TokenStream) -> Result<Item> {
+    let mut header = Line::new(0);
+    header.push(tokens.expect("struct")?).text(" ").push(tokens.take()?);
+    header.text(" ").push(tokens.expect("{")?);
+
+    let members = fields(tokens)?;
+
+    let lines = block(header, members, tokens.expect("}")?);
+    Ok(Item { kind: ItemKind::Block, annotations: 0, lines })
+}
+
+fn error(tokens: &mut TokenStream) -> Result<Item> {
+    let mut header = Line::new(0);
+    header.push(tokens.expect("error")?).text(" ").push(tokens.take()?);
+    header.text(" ").push(tokens.expect("{")?);
+
+    let mut message = Line::new(1);
+    message.push(tokens.expect("message")?).text(" ").push(tokens.expect("=")?).text(" ").push(tokens.take()?);
+
+    let mut members = fields(tokens)?;
+    if let Some(first) = members.first_mut() { first.blank_before = true }
+    members.insert(0, message);
+
+    let lines = block(header, members, tokens.expect("}")?);
+    Ok(Item { kind: ItemKind::Block, annotations: 0, lines })
+}
+
+/// Fields up to the end of their block, with their types aligned
+fn fields(tokens: &mut TokenStream) -> Result<Vec<Line>> {
+    let mut fields: Vec<(Line, String)> = vec![];
+    let mut field_annotations = vec![];
+    while !tokens.peek_is("}") {
+        let mut annotations = annotations(tokens, 1)?;
+        let mut field = Line::new(1);
+        if tokens.peek_is("optional") {
+            field.push(tokens.take()?).text(" ");
+        }
+        field.push(tokens.take()?).push(tokens.expect(":")?);
+
+        // The type is kept apart to be aligned
+        let mut kind = Line::new(0);
+        push_type(tokens, &mut kind)?;
+        field.comments.extend(kind.comments);
+        field.trailing.extend(kind.trailing);
+
+        // The blank line before annotations separates the field from the previous ones
+        if let Some(first) = annotations.first_mut() {
+            field.blank_before = std::mem::take(&mut first.blank_before);
+        }
+        fields.push((field, kind.text));
+        field_annotations.push(annotations);
+    }
+
+    if let Some((first, _)) = fields.first_mut() { first.blank_before = false }
+
+    // Fields separated by blank lines are aligned apart
+    let mut start = 0;
+    for end in 1..=fields.len() {
+        if end == fields.len() || fields[end].0.blank_before {
+            align(&mut fields[start..end], " ");
+            start = end;
+        }
+    }
+
+    let mut members = vec![];
+    for ((field, _), annotations) in fields.into_iter().zip(field_annotations) {
+        let mut lines = vec![field];
+        annotate(&mut lines, annotations, &mut 0);
+        members.extend(lines);
+    }
+
+    Ok(members)
+}
+
+fn enumeration(tokens: &mut TokenStream) -> Result<Item> {
+    let mut header = Line::new(0);
+    header.push(tokens.expect("enum")?).text(" ").push(tokens.take()?);
+    header.text(" ").push(tokens.expect("{")?);
+
+    let mut variants = vec![];
+    while !tokens.peek_is("}") {
+        let annotations = annotations(tokens, 1)?;
+        let mut variant = vec![Line::new(1)];
+        variant[0].push(tokens.take()?);
+
+        annotate(&mut variant, annotations, &mut 0);
+        variants.extend(variant);
+    }
+
+    let lines = block(header, variants, tokens.expect("}")?);
+    Ok(Item { kind: ItemKind::Block, annotations: 0, lines })
+}
+
+fn protocol(tokens: &mut TokenStream) -> Result<Item> {
+    let mut header = Line::new(0);
+    header.push(tokens.expect("protocol")?).text(" ").push(tokens.take()?);
+    header.text(" ").push(tokens.expect("{")?);
+
+    let mut functions = vec![];
+    while !tokens.peek_is("}") {
+        let annotations = annotations(tokens, 1)?;
+
+        let mut line = Line::new(1);
+        if tokens.peek_is("async") {
+            line.push(tokens.take()?).text(" ");
+        }
+        line.push(tokens.expect("function")?).text(" ").push(tokens.take()?);
+        line.push(tokens.expect("(")?);
+        while !tokens.peek_is(")") {
+            push_type(tokens, &mut line)?;
+            if tokens.peek_is(",") {
+                line.push(tokens.take()?).text(" ");
+            }
+        }
+        line.push(tokens.expect(")")?);
+
+        if tokens.peek_is("->") {
+            line.text(" ").push(tokens.take()?).text(" ");
+            push_type(tokens, &mut line)?;
+        }
+        if tokens.peek_is("throws") {
+            line.text(" ").push(tokens.take()?).text(" ").push(tokens.take()?);
+            while tokens.peek_is(",") {
+                line.push(tokens.take()?).text(" ").push(tokens.take()?);
+            }
+        }
+        line.push(tokens.expect(";")?);
+
+        let mut lines = vec![line];
+        annotate(&mut lines, annotations, &mut 0);
+        functions.extend(lines);
+    }
+
+    let lines = block(header, functions, tokens.expect("}")?);
+    Ok(Item { kind: ItemKind::Block, annotations: 0, lines })
+}
+
+fn validator(tokens: &mut TokenStream) -> Result<Item> {
+    let mut header = Line::new(0);
+    header.push(tokens.expect("validator")?).text(" ").push(tokens.take()?);
+    header.text(" ").push(tokens.expect("{")?);
+
+    let mut members = vec![];
+    while !tokens.peek_is("validate") {
+        let mut property = Line::new(1);
+        property.push(tokens.take()?).push(tokens.expect(":")?).text(" ");
+        push_type(tokens, &mut property)?;
+        if tokens.peek_is("=") {
+            property.text(" ").push(tokens.take()?).text(" ").push(tokens.take()?);
+        }
+        property.push(tokens.expect(";")?);
+        members.push(property);
+    }
+
+    let mut validate = Line::new(1);
+    validate.push(tokens.expect("validate")?).text(" ").push(tokens.expect("{")?);
+    validate.blank_before = !members.is_empty();
+
+    let mut calls = vec![];
+    while !tokens.peek_is("}") {
+        calls.extend(call(tokens, 2)?);
+    }
+    members.extend(block(validate, calls, tokens.expect("}")?));
+
+    let lines = block(header, members, tokens.expect("}")?);
+    Ok(Item { kind: ItemKind::Block, annotations: 0, lines })
+}
+
+/// A call of a validate block, on one line or with an argument per line
+fn call(tokens: &mut TokenStream, indent: usize) -> Result<Vec<Line>> {
+    let mut line = Line::new(indent);
+    let mut written = String::new();
+    let mut previous: Option<Token> = None;
+    let mut depth = 0;
+
+    loop {
+        let token = tokens.take()?;
+        match token.text.as_str() {
+            "(" => depth += 1,
+            ")" => depth -= 1,
+            _ => {}
+        }
+
+        // Two character operators are two tokens
+        let joined = token.text == "=" && previous.as_ref()
+            .is_some_and(|previous| ["<", ">", "=", "!"].contains(&previous.text.as_str()));
+        if !written.is_empty() && !joined { written.push(' ') }
+        written.push_str(&token.text);
+        line.absorb(token.clone());
+        previous = Some(token);
+
+        if depth == 0 && previous.as_ref().is_some_and(|token| token.text == ")") { break }
+    }
+
+    let call = expression::parse(&written)
+        .map_err(|error| eyre!("Call '{}' cannot be formatted: {}", written, error))?;
+    let single = call.to_string();
+    let Expression::Call(name, arguments) = call else { bail!("'{}' is not a call", single) };
+
+    if INDENT.len() * indent + single.len() <= MAX_WIDTH || arguments.is_empty() {
+        line.text(&single);
+        return Ok(vec![line])
+    }
+
+    let trailing = std::mem::take(&mut line.trailing);
+    line.text(&format!("{}(", name));
+    let mut lines = vec![line];
+    for (index, argument) in arguments.iter().enumerate() {
+        let comma = if index + 1 < arguments.len() { "," } else { "" };
+        lines.push(Line { text: format!("{}{}", argument, comma), ..Line::new(indent + 1) });
+    }
+    lines.push(Line { text: ")".to_owned(), trailing, ..Line::new(indent) });
+
+    Ok(lines)
+}
+
+/// Annotations, one per line, as `@key=value`
+fn annotations(tokens: &mut TokenStream, indent: usize) -> Result<Vec<Line>> {
+    let mut lines = vec![];
+
+    while tokens.peek_is("@") {
+        let mut line = Line::new(indent);
+        line.push(tokens.take()?).push(tokens.take()?);
+        line.push(tokens.expect("=")?);
+        push_value(tokens, &mut line)?;
+        lines.push(line);
+    }
+
+    // The blank line before the annotated item goes before its annotations
+    for line in lines.iter_mut().skip(1) { line.blank_before = false }
+
+    Ok(lines)
+}
+
+/// Puts annotations above the lines of what they annotate, counting them in `count`
+fn annotate(lines: &mut Vec<Line>, mut annotations: Vec<Line>, count: &mut usize) {
+    if annotations.is_empty() { return }
+
+    annotations[0].blank_before |= std::mem::take(&mut lines[0].blank_before);
+    *count = annotations.len();
+    lines.splice(0..0, annotations);
+}
+
+/// A value of an annotation, where lists and calls are separated by spaces
+/// rather than commas, e.g. `[StringBounds(min_chars=3 max_chars=12) NotEmpty]`
+fn push_value(tokens: &mut TokenStream, line: &mut Line) -> Result<()> {
+    let first = tokens.take()?;
+    let nested = first.text == "[" || tokens.peek_is("(");
+    let mut depth = i32::from(first.text == "[");
+    let mut previous = first.text.clone();
+    line.push(first);
+
+    while nested && (depth > 0 || tokens.peek_is("(")) {
+        let token = tokens.take()?;
+        match token.text.as_str() {
+            "(" | "[" => depth += 1,
+            ")" | "]" => depth -= 1,
+            _ => {}
+        }
+
+        let text = token.text.clone();
+        let spaced = !["(", "[", "="].contains(&previous.as_str())
+            && ![")", "]", "=", "(", ","].contains(&text.as_str());
+        match text.as_str() {
+            "," => { line.absorb(token); }
+            _ => {
+                if spaced { line.text(" "); }
+                line.push(token);
+            }
+        }
+        if text != "," { previous = text }
+    }
+
+    Ok(())
+}
+
+/// A type with its array suffixes, e.g. `u8[16][]`
+fn push_type(tokens: &mut TokenStream, line: &mut Line) -> Result<()> {
+    line.push(tokens.take()?);
+
+    while tokens.peek_is("[") {
+        line.push(tokens.take()?);
+        if !tokens.peek_is("]") { line.push(tokens.take()?); }
+        line.push(tokens.expect("]")?);
+    }
+
+    Ok(())
+}
@@ -5,6 +5,9 @@ pub mod grammar {
     // Suppress dead code warnings for generated fields
     #![allow(dead_code)]
 
+    // Names and type references carry their source span, for tooling
+    pub use rust_sitter::Spanned;
+
     // Whitespace and comment handling
     #[rust_sitter::extra]
     #[derive(Debug)]
@@ -17,6 +20,13 @@ pub mod grammar {
         (),
     );
 
+    #[rust_sitter::extra]
+    #[derive(Debug)]
+    pub struct BlockComment(
+        #[rust_sitter::leaf(pattern = r"/\*([^*]|\*+[^*/])*\*+/")]
+        (),
+    );
+
     /// Document root - supports multiple declarations
     #[derive(Debug)]
     #[rust_sitter::language]
@@ -31,6 +41,8 @@ pub mod grammar {
         Struct(Struct),
         Enum(Enum),
         Protocol(Protocol),
+        Validator(Validator),
+        Error(Error),
     }
 
     // ===== Imports & Constants =====
@@ -38,9 +50,10 @@ pub mod grammar {
     /// Import: import identifier (Legacy - for backward compatibility)
     #[derive(Debug, Clone)]
     pub struct Import {
+        pub annotations: Option<Annotations>,
         #[rust_sitter::leaf(text = "import")]
         _import: (),
-        pub path: ScopedIdentifier,
+        pub path: Spanned<ScopedIdentifier>,
     }
 
     // ===== Use Statements (New Import System) =====
@@ -48,6 +61,7 @@ pub mod grammar {
     /// Use: use path [as alias]
     #[derive(Debug, Clone)]
     pub struct Use {
+        pub annotations: Option<Annotations>,
         #[rust_sitter::leaf(text = "use")]
         _use: (),
         pub path: UsePath,
@@ -57,7 +71,7 @@ pub mod grammar {
     /// Use path - can be absolute, relative, glob, or multi-import
     #[derive(Debug, Clone)]
     pub enum UsePath {
-        Absolute(ScopedIdentifier),
+        Absolute(Spanned<ScopedIdentifier>),
         Relative(RelativePath),
         Glob(GlobPath),
         Multi(MultiPath),
@@ -69,7 +83,7 @@ pub mod grammar {
         pub prefix: RelativePrefix,
         #[rust_sitter::leaf(text = "::")]
         _sep: (),
-        pub path: ScopedIdentifier,
+        pub path: Spanned<ScopedIdentifier>,
     }
 
     /// Relative prefix: self, parent, crate
@@ -101,15 +115,25 @@ pub mod grammar {
         _sep: (),
         #[rust_sitter::leaf(text = "{")]
         _open: (),
-        #[rust_sitter::delimited(
-            #[rust_sitter::leaf(text = ",")]
-            ()
-        )]
-        pub items: Vec<Identifier>,
+        items: MultiPathItems,
         #[rust_sitter::leaf(text = "}")]
         _close: (),
     }
 
+    /// An imported item, optionally followed by a comma and more items
+    #[derive(Debug, Clone)]
+    pub struct MultiPathItems {
+        name: Spanned<Identifier>,
+        rest: Option<MultiPathRest>,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct MultiPathRest {
+        #[rust_sitter::leaf(text = ",")]
+        _comma: (),
+        items: Option<Box<MultiPathItems>>,
+    }
+
     /// Use alias: as NewName
     #[derive(Debug, Clone)]
     pub struct UseAlias {
@@ -121,9 +145,10 @@ pub mod grammar {
     /// Constant: const NAME: TYPE = VALUE
     #[derive(Debug, Clone)]
     pub struct Const {
+        pub annotations: Option<Annotations>,
         #[rust_sitter::leaf(text = "const")]
         _const: (),
-        pub name: Identifier,
+        pub name: Spanned<Identifier>,
         #[rust_sitter::leaf(text = ":")]
         _colon: (),
         pub type_def: Type,
@@ -137,9 +162,10 @@ pub mod grammar {
     /// Struct: struct NAME { fields }
     #[derive(Debug, Clone)]
     pub struct Struct {
+        pub annotations: Option<Annotations>,
         #[rust_sitter::leaf(text = "struct")]
         _struct: (),
-        pub name: Identifier,
+        pub name: Spanned<Identifier>,
         #[rust_sitter::leaf(text = "{")]
         _open: (),
         #[rust_sitter::repeat(non_empty = false)]
@@ -151,22 +177,52 @@ pub mod grammar {
     /// Field: name: Type
     #[derive(Debug, Clone)]
     pub struct Field {
+        pub annotations: Option<Annotations>,
         #[rust_sitter::leaf(text = "optional")]
         pub optional: Option<()>,
-        pub name: Identifier,
+        pub name: Spanned<Identifier>,
         #[rust_sitter::leaf(text = ":")]
         _colon: (),
         pub field_type: Type,
     }
 
+    // ===== Error Definition =====
+
+    /// Error: error NAME { message = "..." fields }
+    #[derive(Debug, Clone)]
+    pub struct Error {
+        pub annotations: Option<Annotations>,
+        #[rust_sitter::leaf(text = "error")]
+        _error: (),
+        pub name: Spanned<Identifier>,
+        #[rust_sitter::leaf(text = "{")]
+        _open: (),
+        pub message: ErrorMessage,
+        #[rust_sitter::repeat(non_empty = false)]
+        pub fields: Vec<Field>,
+        #[rust_sitter::leaf(text = "}")]
+        _close: (),
+    }
+
+    /// Message of an error, which may name its fields, e.g. `message = "{self.id} is missing"`
+    #[derive(Debug, Clone)]
+    pub struct ErrorMessage {
+        #[rust_sitter::leaf(text = "message")]
+        _message: (),
+        #[rust_sitter::leaf(text = "=")]
+        _eq: (),
+        pub value: StringLiteral,
+    }
+
     // ===== Enum Definition =====
 
     /// Enum: enum NAME { variants }
     #[derive(Debug, Clone)]
     pub struct Enum {
+        pub annotations: Option<Annotations>,
         #[rust_sitter::leaf(text = "enum")]
         _enum: (),
-        pub name: Identifier,
+        pub name: Spanned<Identifier>,
         #[rust_sitter::leaf(text = "{")]
         _open: (),
         #[rust_sitter::repeat(non_empty = true)]
@@ -178,12 +234,22 @@ pub mod grammar {
     /// Enum variant: IDENTIFIER
     #[derive(Debug, Clone)]
     pub struct EnumVariant {
-        pub name: Identifier,
+        pub annotations: Option<Annotations>,
+        pub name: Spanned<Identifier>,
     }
 
     // ===== Protocol Definition =====
 
     // ===== Annotation Definition =====
+
+    /// Annotations before a declaration or member, e.g. `@allow=missing_docs`
+    /// Every annotated item shares this node, which keeps the grammar unambiguous
+    #[derive(Debug, Clone)]
+    pub struct Annotations(
+        #[rust_sitter::repeat(non_empty = true)]
+        pub Vec<Annotation>,
+    );
+
     #[derive(Debug, Clone)]
     pub struct Annotation {
         #[rust_sitter::leaf(text = "@")]
@@ -197,11 +263,10 @@ pub mod grammar {
     /// Protocol: protocol NAME { functions }
     #[derive(Debug, Clone)]
     pub struct Protocol {
-        #[rust_sitter::repeat(non_empty = false)]
-        pub annotations: Vec<Annotation>,
+        pub annotations: Option<Annotations>,
         #[rust_sitter::leaf(text = "protocol")]
         _protocol: (),
-        pub name: Identifier,
+        pub name: Spanned<Identifier>,
         #[rust_sitter::leaf(text = "{")]
         _open: (),
         #[rust_sitter::repeat(non_empty = false)]
@@ -210,14 +275,15 @@ pub mod grammar {
         _close: (),
     }
 
-    /// Function: function NAME(args) returns Type
+    /// Function: [async] function NAME(args) [-> Type] [throws Error, ...];
     #[derive(Debug, Clone)]
     pub struct Function {
+        pub annotations: Option<Annotations>,
         #[rust_sitter::repeat(non_empty = false)]
-        pub annotations: Vec<Annotation>,
+        pub asynchronous: Option<AsyncKeyword>,
         #[rust_sitter::leaf(text = "function")]
         _fn: (),
-        pub name: Identifier,
+        pub name: Spanned<Identifier>,
         #[rust_sitter::leaf(text = "(")]
         _open: (),
         #[rust_sitter::repeat(non_empty = false)]
@@ -226,10 +292,16 @@ pub mod grammar {
         _close: (),
         #[rust_sitter::repeat(non_empty = false)]
         pub return_type: Option<ReturnType>,
+        #[rust_sitter::repeat(non_empty = false)]
+        pub throws: Option<Throws>,
         #[rust_sitter::leaf(text = ";")]
         _semi: (),
     }
 
+    #[derive(Debug, Clone)]
+    #[rust_sitter::leaf(text = "async")]
+    pub struct AsyncKeyword;
+
     /// Argument list: first arg, then (comma + arg)*
     #[derive(Debug, Clone)]
     pub struct ArgumentList {
@@ -260,6 +332,94 @@ pub mod grammar {
         pub return_type: Type,
     }
 
+    /// Errors a function throws: throws Error, ...
+    #[derive(Debug, Clone)]
+    pub struct Throws {
+        #[rust_sitter::leaf(text = "throws")]
+        _throws: (),
+        pub first: Spanned<Identifier>,
+        #[rust_sitter::repeat(non_empty = false)]
+        pub rest: Vec<CommaThrown>,
+    }
+
+    /// Comma followed by an error thrown
+    #[derive(Debug, Clone)]
+    pub struct CommaThrown {
+        #[rust_sitter::leaf(text = ",")]
+        _comma: (),
+        pub name: Spanned<Identifier>,
+    }
+
+    // ===== Validator Definition =====
+
+    /// Validator: validator NAME { properties validate { calls } }
+    #[derive(Debug, Clone)]
+    pub struct Validator {
+        pub annotations: Option<Annotations>,
+        #[rust_sitter::leaf(text = "validator")]
+        _validator: (),
+        pub name: Spanned<Identifier>,
+        #[rust_sitter::leaf(text = "{")]
+        _open: (),
+        #[rust_sitter::repeat(non_empty = false)]
+        pub properties: Vec<Property>,
+        pub block: ValidateBlock,
+        #[rust_sitter::leaf(text = "}")]
+        _close: (),
+    }
+
+    /// Property of a validator, which its users may give: name: Type [= default];
+    #[derive(Debug, Clone)]
+    pub struct Property {
+        pub name: Spanned<Identifier>,
+        #[rust_sitter::leaf(text = ":")]
+        _colon: (),
+        pub property_type: Type,
+        pub default: Option<PropertyDefault>,
+        #[rust_sitter::leaf(text = ";")]
+        _semi: (),
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct PropertyDefault {
+        #[rust_sitter::leaf(text = "=")]
+        _eq: (),
+        pub value: PropertyValue,
+    }
+
+    /// Default of a property, a path may name a constant like `u32::MAX`
+    #[derive(Debug, Clone)]
+    pub enum PropertyValue {
+        Integer(IntegerLiteral),
+        String(StringLiteral),
+        Path(ScopedIdentifier),
+    }
+
+    /// Validate block: validate { calls }
+    #[derive(Debug, Clone)]
+    pub struct ValidateBlock {
+        #[rust_sitter::leaf(text = "validate")]
+        _validate: (),
+        #[rust_sitter::leaf(text = "{")]
+        _open: (),
+        #[rust_sitter::repeat(non_empty = false)]
+        pub calls: Vec<Spanned<Call>>,
+        #[rust_sitter::leaf(text = "}")]
+        _close: (),
+    }
+
+    /// Call of the validation language, kept as written for the interpreter of
+    /// expressions, e.g. `assert(value.length > 0, "{value.name} is empty")`
+    /// Arguments may nest parentheses up to two levels deep
+    #[derive(Debug, Clone)]
+    pub struct Call {
+        #[rust_sitter::leaf(
+            pattern = r#"[a-zA-Z_][a-zA-Z0-9_]*\s*\(([^()"]|"[^"]*"|\(([^()"]|"[^"]*"|\(([^()"]|"[^"]*")*\))*\))*\)"#,
+            transform = |s| s.to_string()
+        )]
+        pub text: String,
+    }
+
     // ===== Types =====
 
     /// Type
@@ -278,7 +438,7 @@ pub mod grammar {
         Bool(BoolType),
         Str(StrType),
         String(StringType),
-        Named(ScopedIdentifier),
+        Named(Spanned<ScopedIdentifier>),
         Array(Box<ArrayType>),
     }
 
@@ -354,6 +514,49 @@ pub mod grammar {
         Integer(IntegerLiteral),
         String(StringLiteral),
         Identifier(Identifier),
+        Call(CallExpression),
+        List(ListExpression),
+    }
+
+    /// Call with named arguments, like `StringBounds(min_chars=3 max_chars=12)`
+    #[derive(Debug, Clone)]
+    pub struct CallExpression {
+        pub name: Identifier,
+        #[rust_sitter::leaf(text = "(")]
+        _open: (),
+        #[rust_sitter::repeat(non_empty = false)]
+        pub arguments: Vec<NamedArgument>,
+        #[rust_sitter::leaf(text = ")")]
+        _close: (),
+    }
+
+    /// Argument of a call, optionally followed by a comma
+    #[derive(Debug, Clone)]
+    pub struct NamedArgument {
+        pub name: Identifier,
+        #[rust_sitter::leaf(text = "=")]
+        _eq: (),
+        pub value: Box<Expression>,
+        #[rust_sitter::leaf(text = ",")]
+        _comma: Option<()>,
+    }
+
+    /// List: [items], where items may be separated by commas
+    #[derive(Debug, Clone)]
+    pub struct ListExpression {
+        #[rust_sitter::leaf(text = "[")]
+        _open: (),
+        #[rust_sitter::repeat(non_empty = false)]
+        pub items: Vec<ListItem>,
+        #[rust_sitter::leaf(text = "]")]
+        _close: (),
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct ListItem {
+        pub value: Expression,
+        #[rust_sitter::leaf(text = ",")]
+        _comma: Option<()>,
     }
 
     #[derive(Debug, Clone)]
@@ -384,12 +587,37 @@ pub mod grammar {
 
     // Accessor methods for grammar types
     impl Import {
+        pub fn annotations(&self) -> &[Annotation] {
+            annotations(&self.annotations)
+        }
         pub fn path(&self) -> String {
             self.path.text.clone()
         }
     }
 
+    impl Use {
+        pub fn annotations(&self) -> &[Annotation] {
+            annotations(&self.annotations)
+        }
+    }
+
+    impl MultiPath {
+        /// Imported items, the last one may be followed by a comma too
+        pub fn items(&self) -> Vec<&Spanned<Identifier>> {
+            let mut names = vec![];
+            let mut next = Some(&self.items);
+            while let Some(items) = next {
+                names.push(&items.name);
+                next = items.rest.as_ref().and_then(|rest| rest.items.as_deref());
+            }
+            names
+        }
+    }
+
     impl Const {
+        pub fn annotations(&self) -> &[Annotation] {
+            annotations(&self.annotations)
+        }
         pub fn name(&self) -> String {
             self.name.text.clone()
         }
@@ -402,6 +630,9 @@ pub mod grammar {
     }
 
     impl Struct {
+        pub fn annotations(&self) -> &[Annotation] {
+            annotations(&self.annotations)
+        }
         pub fn name(&self) -> String {
             self.name.text.clone()
         }
@@ -411,6 +642,9 @@ pub mod grammar {
     }
 
     impl Field {
+        pub fn annotations(&self) -> &[Annotation] {
+            annotations(&self.annotations)
+        }
         pub fn optional(&self) -> bool {
             self.optional.is_some()
         }
@@ -422,7 +656,25 @@ pub mod grammar {
         }
     }
 
+    impl Error {
+        pub fn annotations(&self) -> &[Annotation] {
+            annotations(&self.annotations)
+        }
+        pub fn name(&self) -> String {
+            self.name.text.clone()
+        }
+        pub fn message(&self) -> &str {
+            &self.message.value.value
+        }
+        pub fn fields(&self) -> &Vec<Field> {
+            &self.fields
+        }
+    }
+
     impl Enum {
+        pub fn annotations(&self) -> &[Annotation] {
+            annotations(&self.annotations)
+        }
         pub fn name(&self) -> String {
             self.name.text.clone()
         }
@@ -431,9 +683,42 @@ pub mod grammar {
         }
     }
 
+    impl Validator {
+        pub fn annotations(&self) -> &[Annotation] {
+            annotations(&self.annotations)
+        }
+        pub fn name(&self) -> String {
+            self.name.text.clone()
+        }
+        pub fn properties(&self) -> &Vec<Property> {
+            &self.properties
+        }
+        /// Calls of the validate block, as written
+        pub fn calls(&self) -> Vec<&str> {
+            self.block.calls.iter().map(|call| call.text.as_str()).collect()
+        }
+    }
+
+    impl Property {
+        pub fn name(&self) -> String {
+            self.name.text.clone()
+        }
+        pub fn property_type(&self) -> &Type {
+            &self.property_type
+        }
+        /// The default as it would be written in an expression
+        pub fn default(&self) -> Option<String> {
+            self.default.as_ref().map(|default| match &default.value {
+                PropertyValue::Integer(i) => i.value.to_string(),
+                PropertyValue::String(s) => format!("{:?}", s.value),
+                PropertyValue::Path(p) => p.text.clone(),
+            })
+        }
+    }
+
     impl Protocol {
-        pub fn annotations(&self) -> &Vec<Annotation> {
-            &self.annotations
+        pub fn annotations(&self) -> &[Annotation] {
+            annotations(&self.annotations)
         }
         pub fn name(&self) -> String {
             self.name.text.clone()
@@ -444,18 +729,27 @@ pub mod grammar {
     }
 
     impl Function {
-        pub fn annotations(&self) -> &Vec<Annotation> {
-            &self.annotations
+        pub fn annotations(&self) -> &[Annotation] {
+            annotations(&self.annotations)
         }
         pub fn name(&self) -> String {
             self.name.text.clone()
         }
+        /// Whether callers do not wait for the function to complete
+        pub fn is_async(&self) -> bool {
+            self.asynchronous.is_some()
+        }
         pub fn args(&self) -> &Option<ArgumentList> {
             &self.args
         }
         pub fn return_type(&self) -> &Option<ReturnType> {
             &self.return_type
         }
+        /// Names of the errors thrown, as written
+        pub fn throws(&self) -> Vec<&Spanned<Identifier>> {
+            let Some(throws) = &self.throws else { return vec![] };
+            std::iter::once(&throws.first).chain(throws.rest.iter().map(|thrown| &thrown.name)).collect()
+        }
     }
 
     impl ArgumentList {
@@ -482,6 +776,12 @@ pub mod grammar {
         }
     }
 
+    impl AsRef<str> for Identifier {
+        fn as_ref(&self) -> &str {
+            &self.text
+        }
+    }
+
     impl IntegerLiteral {
         pub fn value(&self) -> i64 {
             self.value
@@ -501,8 +801,11 @@ pub mod grammar {
     }
 
     impl EnumVariant {
+        pub fn annotations(&self) -> &[Annotation] {
+            annotations(&self.annotations)
+        }
         pub fn identifier(&self) -> &Identifier {
-            &self.name
+            &self.name.value
         }
     }
 
@@ -527,15 +830,57 @@ pub mod grammar {
         }
     }
 
+    fn annotations(annotations: &Option<Annotations>) -> &[Annotation] {
+        annotations.as_ref().map(|a| a.0.as_slice()).unwrap_or(&[])
+    }
+
+    impl AsRef<str> for ScopedIdentifier {
+        fn as_ref(&self) -> &str {
+            &self.text
+        }
+    }
+
     impl Annotation {
         pub fn key(&self) -> String {
             self.key.text.clone()
         }
         pub fn value(&self) -> String {
             match &self.value {
-                Expression::Integer(i) => i.value.to_string(),
                 Expression::String(s) => s.value.clone(),
+                expression => expression.written(),
+            }
+        }
+    }
+
+    impl Expression {
+        /// The expression as written canonically, with strings quoted
+        pub fn written(&self) -> String {
+            match self {
+                Expression::Integer(i) => i.value.to_string(),
+                Expression::String(s) => format!("{:?}", s.value),
                 Expression::Identifier(i) => i.text.clone(),
+                Expression::Call(call) => {
+                    let arguments: Vec<String> = call.arguments.iter()
+                        .map(|argument| format!("{}={}", argument.name.text, argument.value.written()))
+                        .collect();
+                    format!("{}({})", call.name.text, arguments.join(" "))
+                }
+                Expression::List(list) => {
+                    let items: Vec<String> = list.items.iter().map(|item| item.value.written()).collect();
+                    format!("[{}]", items.join(" "))
+                }
+            }
+        }
+
+        /// Names the expression refers to by themselves, like those of calls
+        pub fn names(&self) -> Vec<&str> {
+            match self {
+                Expression::Integer(_) | Expression::String(_) => vec![],
+                Expression::Identifier(i) => vec![i.as_str()],
+                Expression::Call(call) => std::iter::once(call.name.as_str())
+                    .chain(call.arguments.iter().flat_map(|argument| argument.value.names()))
+                    .collect(),
+                Expression::List(list) => list.items.iter().flat_map(|item| item.value.names()).collect(),
             }
         }
     }
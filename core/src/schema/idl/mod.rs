@@ -1,6 +1,8 @@
 // Relative Modules
 pub mod grammar;  // Rust-sitter generated parser
 pub mod diagnostics;  // Beautiful error reporting
+pub mod docs;  // Doc comment extraction
+pub mod formatter;  // Canonical layout
 
 
 
@@ -5,6 +5,7 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 
 use crate::schema::idl::grammar::{UsePath, RelativePrefix};
+use crate::stdlib;
 
 /// Resolved import information
 #[derive(Debug, Clone)]
@@ -31,7 +32,7 @@ pub struct ImportResolver {
     /// Map of external dependencies: name -> root path
     dependencies: HashMap<String, PathBuf>,
     
-    /// Standard library root path
+    /// Standard library root path, the embedded standard library is used without one
     stdlib_root: Option<PathBuf>,
 }
 
@@ -66,7 +67,7 @@ impl ImportResolver {
                 self.resolve_glob(&glob.path.to_string())
             }
             UsePath::Multi(multi) => {
-                let items: Vec<String> = multi.items.iter().map(|i| i.text.clone()).collect();
+                let items: Vec<String> = multi.items().iter().map(|i| i.text.clone()).collect();
                 self.resolve_multi(&multi.path.to_string(), &items)
             }
         }
@@ -159,7 +160,16 @@ impl ImportResolver {
                 alias: None,
             })
         } else {
-            Err("Standard library not configured".to_string())
+            // The schema the import is of, in the embedded standard library
+            match stdlib::schema_of_import(parts) {
+                Some(_) => Ok(ResolvedImport {
+                    absolute_namespace: parts.to_vec(),
+                    schema_path: None,
+                    symbols: vec![],
+                    alias: None,
+                }),
+                None => Err(format!("'{}' is not in the standard library", parts.join("::"))),
+            }
         }
     }
     
@@ -213,6 +223,10 @@ impl ImportResolver {
                     Err(format!("Parse failed with {} error(s)", errors.len()))
                 }
             }
+        } else if let Some(schema) = stdlib::schema_of_import(&resolved.absolute_namespace) {
+            let source = stdlib::source(&schema.namespace).unwrap();
+            crate::schema::idl::grammar::parse(source)
+                .map_err(|errors| format!("Parse failed with {} error(s)", errors.len()))
         } else {
             // TODO: Load from same package - need package root path
             Err(format!(
@@ -3,10 +3,12 @@
 // Local Uses
 // use crate::schema::idl::ast::unit;
 // use crate::schema::idl::ast::unit::ASTUnit;
-use crate::schema::idl::grammar::Declaration;
+use crate::schema::idl::docs::docstring_before;
+use crate::schema::idl::grammar::{Annotation, Declaration};
 use crate::schema::ir::compiler::interpreted::kind_search::{KindValue, Primitive};
 use crate::schema::ir::compiler::Compile;
 use crate::schema::ir::frozen::unit::FrozenUnit;
+use crate::schema::ir::lint::ALLOW_ANNOTATION;
 
 // External Uses
 
@@ -22,6 +24,14 @@ impl Compile for IncrementalInterpreter {
 
         let mut frozen_units: Vec<FrozenUnit> = vec![];
 
+        // Errors thrown by functions are frozen whole, wherever they are declared in the schema
+        let errors: Vec<FrozenUnit> = declarations.iter()
+            .filter_map(|decl| match decl {
+                Declaration::Error(error) => Some(error_unit(error)),
+                _ => None,
+            })
+            .collect();
+
         for decl in declarations {
             match decl {
                 Declaration::Import(import) => {
@@ -60,15 +70,15 @@ impl Compile for IncrementalInterpreter {
 
                     // Parse value
                     let kind_value = match (type_name, value) {
-                        (
-                            "u8" | "u16" | "u32" | "u64",
-                            crate::schema::idl::grammar::Expression::Integer(int_lit),
-                        ) => KindValue::Primitive(Primitive::U64(Some(int_lit.value() as u64))),
-                        (
-                            "i8" | "i16" | "i32" | "i64",
-                            crate::schema::idl::grammar::Expression::Integer(int_lit),
-                        ) => KindValue::Primitive(Primitive::S64(Some(int_lit.value()))),
-                        ("bool", _) => KindValue::Primitive(Primitive::Boolean(Some(false))),
+                        (_, crate::schema::idl::grammar::Expression::Integer(int_lit)) => {
+                            match integer_primitive(type_name, int_lit.value()) {
+                                Some(primitive) => KindValue::Primitive(primitive),
+                                None => KindValue::Namespaced(type_name.to_string(), None),
+                            }
+                        }
+                        ("bool", crate::schema::idl::grammar::Expression::Identifier(id)) => {
+                            KindValue::Primitive(Primitive::Boolean(Some(id.as_str() == "true")))
+                        }
                         (
                             "str" | "string",
                             crate::schema::idl::grammar::Expression::String(str_lit),
@@ -85,34 +95,14 @@ impl Compile for IncrementalInterpreter {
                     });
                 }
                 Declaration::Struct(struct_def) => {
-                    let struct_name = struct_def.name();
-                    let fields = struct_def.fields();
-
-                    let field_units: Vec<FrozenUnit> = fields
-                        .iter()
-                        .map(|field| {
-                            let fname = field.name();
-                            let field_type = field.field_type();
-
-                            let type_str = type_to_string(field_type);
-
-                            FrozenUnit::Field {
-                                docstring: None,
-                                parameters: vec![],
-                                optional: field.optional(),
-                                name: fname,
-                                kind_value: KindValue::Namespaced(type_str, None),
-                            }
-                        })
-                        .collect();
-
                     frozen_units.push(FrozenUnit::Struct {
                         docstring: None,
-                        parameters: vec![],
-                        name: struct_name,
-                        fields: field_units,
+                        parameters: annotation_parameters(struct_def.annotations()),
+                        name: struct_def.name(),
+                        fields: field_units(struct_def.fields()),
                     });
                 }
+                Declaration::Error(error) => frozen_units.push(error_unit(&error)),
                 Declaration::Enum(enum_def) => {
                     let enum_name = enum_def.name();
                     let variants = enum_def.variants();
@@ -170,13 +160,22 @@ impl Compile for IncrementalInterpreter {
                                 .as_ref()
                                 .map(|rt| type_to_kind_value(rt.return_type()));
 
+                            // Errors not declared in the schema are kept by name, for validation to report
+                            let throws = func.throws().into_iter()
+                                .map(|thrown| errors.iter()
+                                    .find(|error| matches!(error, FrozenUnit::Error { name, .. } if **name == thrown.text))
+                                    .cloned()
+                                    .unwrap_or_else(|| FrozenUnit::Name(thrown.to_string())))
+                                .collect();
+
                             FrozenUnit::Function {
+                                parameters: annotation_parameters(func.annotations()),
                                 name: func_name,
                                 arguments,
                                 _return: return_type,
-                                synchronous: true,
+                                synchronous: !func.is_async(),
                                 docstring: String::new(),
-                                throws: vec![],
+                                throws,
                             }
                         })
                         .collect();
@@ -188,6 +187,23 @@ impl Compile for IncrementalInterpreter {
                         parameters: vec![],
                     });
                 }
+                Declaration::Validator(validator) => {
+                    let properties = validator.properties().iter()
+                        .map(|property| FrozenUnit::Property {
+                            name: property.name(),
+                            kind_value: type_to_kind_value(property.property_type()),
+                            expression: property.default(),
+                        })
+                        .collect();
+                    let function_calls = validator.calls().into_iter().map(str::to_owned).collect();
+
+                    frozen_units.push(FrozenUnit::Validator {
+                        docstring: None,
+                        properties,
+                        name: validator.name(),
+                        expression_block: Box::new(FrozenUnit::ExpressionBlock { function_calls }),
+                    });
+                }
             }
         }
 
@@ -199,6 +215,19 @@ impl Compile for IncrementalInterpreter {
         frozen_units
     }
 
+    fn from_source(source: &str) -> Self::Output {
+        match crate::schema::idl::grammar::parse(source) {
+            Ok(document) => {
+                let mut frozen_units = Self::from_declarations(document.0.clone());
+                attach_docstrings(&mut frozen_units, &document.0, source);
+                frozen_units
+            }
+            Err(e) => {
+                panic!("Parse error: {:?}", e);
+            }
+        }
+    }
+
     /*
     fn from_ast(ast: Vec<ASTUnit>) -> Self::Output {
         // Legacy implementation
@@ -211,6 +240,105 @@ impl Compile for IncrementalInterpreter {
     }
     */
 }
+/// Fill the docstrings of frozen units from the doc comments found in the
+/// source their declarations were parsed from
+///
+/// Expects `units` as produced by `from_declarations`, one unit per declaration
+pub fn attach_docstrings(units: &mut [FrozenUnit], declarations: &[Declaration], source: &str) {
+    for (unit, declaration) in units.iter_mut().zip(declarations) {
+        match (unit, declaration) {
+            (FrozenUnit::Constant { docstring, .. }, Declaration::Const(const_decl)) => {
+                *docstring = docstring_before(source, const_decl.name.span.0);
+            }
+            (FrozenUnit::Struct { docstring, fields, .. }, Declaration::Struct(struct_def)) => {
+                *docstring = docstring_before(source, struct_def.name.span.0);
+                attach_field_docstrings(fields, struct_def.fields(), source);
+            }
+            (FrozenUnit::Error { docstring, fields, .. }, Declaration::Error(error)) => {
+                *docstring = docstring_before(source, error.name.span.0);
+                attach_field_docstrings(fields, error.fields(), source);
+            }
+            (FrozenUnit::Enum { docstring, .. }, Declaration::Enum(enum_def)) => {
+                *docstring = docstring_before(source, enum_def.name.span.0);
+            }
+            (FrozenUnit::Validator { docstring, .. }, Declaration::Validator(validator)) => {
+                *docstring = docstring_before(source, validator.name.span.0);
+            }
+            (FrozenUnit::Protocol { docstring, functions, .. }, Declaration::Protocol(protocol)) => {
+                *docstring = docstring_before(source, protocol.name.span.0).unwrap_or_default();
+
+                for (function, function_def) in functions.iter_mut().zip(protocol.functions()) {
+                    if let FrozenUnit::Function { docstring, .. } = function {
+                        *docstring = docstring_before(source, function_def.name.span.0)
+                            .unwrap_or_default();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Errors thrown are copies of those declared, which now have their docstrings
+    let errors: Vec<FrozenUnit> = units.iter()
+        .filter(|unit| matches!(unit, FrozenUnit::Error { .. }))
+        .cloned()
+        .collect();
+    for unit in units.iter_mut() {
+        let FrozenUnit::Protocol { functions, .. } = unit else { continue };
+
+        for function in functions {
+            let FrozenUnit::Function { throws, .. } = function else { continue };
+
+            for thrown in throws {
+                let FrozenUnit::Error { name: thrown_name, .. } = thrown else { continue };
+                let declared = errors.iter()
+                    .find(|error| matches!(error, FrozenUnit::Error { name, .. } if name == thrown_name));
+                if let Some(declared) = declared { *thrown = declared.clone() }
+            }
+        }
+    }
+}
+
+fn attach_field_docstrings(
+    fields: &mut [FrozenUnit], field_defs: &[crate::schema::idl::grammar::Field], source: &str
+) {
+    for (field, field_def) in fields.iter_mut().zip(field_defs) {
+        if let FrozenUnit::Field { docstring, .. } = field {
+            *docstring = docstring_before(source, field_def.name.span.0);
+        }
+    }
+}
+
+fn field_units(fields: &[crate::schema::idl::grammar::Field]) -> Vec<FrozenUnit> {
+    fields.iter()
+        .map(|field| FrozenUnit::Field {
+            docstring: None,
+            parameters: annotation_parameters(field.annotations()),
+            optional: field.optional(),
+            name: field.name(),
+            kind_value: type_to_kind_value(field.field_type()),
+        })
+        .collect()
+}
+
+fn error_unit(error: &crate::schema::idl::grammar::Error) -> FrozenUnit {
+    FrozenUnit::Error {
+        docstring: None,
+        parameters: annotation_parameters(error.annotations()),
+        name: error.name(),
+        message: error.message().to_owned(),
+        fields: field_units(error.fields()),
+    }
+}
+
+/// Annotations as parameters, leaving out those only lints read
+fn annotation_parameters(annotations: &[Annotation]) -> Vec<FrozenUnit> {
+    annotations.iter()
+        .filter(|annotation| annotation.key() != ALLOW_ANNOTATION)
+        .map(|annotation| FrozenUnit::Parameter { name: annotation.key(), default_value: annotation.value() })
+        .collect()
+}
+
 fn type_to_kind_value(type_def: &crate::schema::idl::grammar::Type) -> KindValue {
     KindValue::Namespaced(type_to_string(type_def), None)
 }
@@ -232,30 +360,52 @@ fn type_to_string(type_def: &crate::schema::idl::grammar::Type) -> String {
         crate::schema::idl::grammar::Type::Str(_) => "str".to_string(),
         crate::schema::idl::grammar::Type::String(_) => "string".to_string(),
         crate::schema::idl::grammar::Type::Named(id) => id.to_string(),
-        crate::schema::idl::grammar::Type::Array(arr) => {
-            format!("{}[]", type_to_string(arr.elem_type()))
-        }
+        crate::schema::idl::grammar::Type::Array(arr) => match &arr.size {
+            Some(size) => format!("{}[{}]", type_to_string(arr.elem_type()), size.value()),
+            None => format!("{}[]", type_to_string(arr.elem_type())),
+        },
     }
 }
 
-/// Extract path string from UsePath enum
-/// TODO: This is a placeholder - should integrate with full resolver
+/// The primitive of an integer constant with its declared width, if the value fits
+fn integer_primitive(type_name: &str, value: i64) -> Option<Primitive> {
+    let primitive = match type_name {
+        "u8" => Primitive::U8(Some(value.try_into().ok()?)),
+        "u16" => Primitive::U16(Some(value.try_into().ok()?)),
+        "u32" => Primitive::U32(Some(value.try_into().ok()?)),
+        "u64" => Primitive::U64(Some(value.try_into().ok()?)),
+        "i8" => Primitive::S8(Some(value.try_into().ok()?)),
+        "i16" => Primitive::S16(Some(value.try_into().ok()?)),
+        "i32" => Primitive::S32(Some(value.try_into().ok()?)),
+        "i64" => Primitive::S64(Some(value)),
+        _ => return None,
+    };
+
+    Some(primitive)
+}
+
+/// Extract path string from UsePath enum, as written in the schema
+/// Relative paths keep their `self`, `parent` or `crate` prefix, to be resolved
+/// against the namespace of the schema by whoever needs it
 fn extract_use_path(use_path: &crate::schema::idl::grammar::UsePath) -> String {
-    use crate::schema::idl::grammar::UsePath;
+    use crate::schema::idl::grammar::{RelativePrefix, UsePath};
     
     match use_path {
         UsePath::Absolute(scoped) => scoped.to_string(),
         UsePath::Relative(rel) => {
-            // Convert parent::path to absolute later in resolver
-            format!("{:?}::{}", rel.prefix, rel.path.to_string())
+            let prefix = match rel.prefix {
+                RelativePrefix::Self_ => "self",
+                RelativePrefix::Parent => "parent",
+                RelativePrefix::Crate => "crate",
+            };
+            format!("{}::{}", prefix, rel.path.to_string())
         }
         UsePath::Glob(glob) => {
             format!("{}::*", glob.path.to_string())
         }
         UsePath::Multi(multi) => {
-            // For now, just use the base path
-            // TODO: Handle multi-imports properly
-            multi.path.to_string()
+            let items: Vec<String> = multi.items().iter().map(|item| item.to_string()).collect();
+            format!("{}::{{{}}}", multi.path.to_string(), items.join(", "))
         }
     }
 }
@@ -3,9 +3,12 @@
 use crate::schema::ir::compiler::interpreted::kind_search::KindValue;
 use crate::schema::ir::frozen::unit::FrozenUnit;
 use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use serde_derive::{Deserialize, Serialize};
 
 /// Structured representation of schema changes between two versions
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SchemaChanges {
     pub breaking_changes: Vec<BreakingChange>,
     pub new_features: Vec<NewFeature>,
@@ -29,7 +32,7 @@ impl SchemaChanges {
 }
 
 /// Breaking changes that require a major version bump
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum BreakingChange {
     RemovedStruct {
         name: String,
@@ -66,7 +69,7 @@ pub enum BreakingChange {
 }
 
 /// New features that require a minor version bump
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum NewFeature {
     AddedStruct {
         name: String,
@@ -98,7 +101,7 @@ pub enum NewFeature {
 }
 
 /// Non-breaking modifications that may warrant a patch bump
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Modification {
     FieldMadeOptional {
         type_name: String,
@@ -107,6 +110,67 @@ pub enum Modification {
     // Future: documentation changes, metadata updates, etc.
 }
 
+impl fmt::Display for BreakingChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BreakingChange::RemovedStruct { name } => write!(f, "Removed struct `{}`", name),
+            BreakingChange::RemovedEnum { name } => write!(f, "Removed enum `{}`", name),
+            BreakingChange::RemovedField { type_name, field_name } => {
+                write!(f, "Removed field `{}` from `{}`", field_name, type_name)
+            }
+            BreakingChange::ChangedFieldType { type_name, field_name, old_type, new_type } => {
+                write!(f, "Changed `{}.{}`: {} → {}", type_name, field_name, old_type, new_type)
+            }
+            BreakingChange::RemovedEnumVariant { enum_name, variant } => {
+                write!(f, "Removed variant `{}::{}`", enum_name, variant)
+            }
+            BreakingChange::RemovedFunction { protocol_name, function_name } => {
+                write!(f, "Removed function `{}::{}()`", protocol_name, function_name)
+            }
+            BreakingChange::ChangedFunctionSignature { protocol_name, function_name, details } => {
+                write!(f, "Changed `{}::{}()`: {}", protocol_name, function_name, details)
+            }
+            BreakingChange::RemovedProtocol { name } => write!(f, "Removed protocol `{}`", name),
+        }
+    }
+}
+
+impl fmt::Display for NewFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NewFeature::AddedStruct { name, field_count } => {
+                write!(f, "Added struct `{}` ({} fields)", name, field_count)
+            }
+            NewFeature::AddedEnum { name, variant_count } => {
+                write!(f, "Added enum `{}` ({} variants)", name, variant_count)
+            }
+            NewFeature::AddedField { type_name, field_name, field_type, optional } => {
+                let opt_marker = if *optional { " (optional)" } else { "" };
+                write!(f, "Added field `{}.{}`: {}{}", type_name, field_name, field_type, opt_marker)
+            }
+            NewFeature::AddedEnumVariant { enum_name, variant } => {
+                write!(f, "Added variant `{}::{}`", enum_name, variant)
+            }
+            NewFeature::AddedFunction { protocol_name, function_name, signature } => {
+                write!(f, "Added function `{}::{}`: {}", protocol_name, function_name, signature)
+            }
+            NewFeature::AddedProtocol { name, function_count } => {
+                write!(f, "Added protocol `{}` ({} functions)", name, function_count)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Modification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Modification::FieldMadeOptional { type_name, field_name } => {
+                write!(f, "Field `{}.{}` marked as optional", type_name, field_name)
+            }
+        }
+    }
+}
+
 /// Analyze changes between two schema versions
 pub fn analyze_schema_changes(
     old_schema: &[FrozenUnit],
@@ -0,0 +1,196 @@
+// Standard Uses
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+// Crate Uses
+use super::{Expression, Operator, Segment};
+
+// External Uses
+use eyre::{bail, Result};
+
+
+/// Integers whose bounds are constants, like `u32::MAX`
+const INTEGERS: &[(&str, i128, i128)] = &[
+    ("u8", 0, u8::MAX as i128), ("u16", 0, u16::MAX as i128),
+    ("u32", 0, u32::MAX as i128), ("u64", 0, u64::MAX as i128),
+    ("i8", i8::MIN as i128, i8::MAX as i128), ("i16", i16::MIN as i128, i16::MAX as i128),
+    ("i32", i32::MIN as i128, i32::MAX as i128), ("i64", i64::MIN as i128, i64::MAX as i128),
+    ("i128", i128::MIN, i128::MAX),
+];
+
+
+/// A value expressions evaluate to
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Integer(i128),
+    Float(f64),
+    String(String),
+    List(Vec<Value>),
+}
+
+impl Value {
+    /// What the value is, as in "should be a bool, not an integer"
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Value::Bool(_) => "a bool",
+            Value::Integer(_) => "an integer",
+            Value::Float(_) => "a float",
+            Value::String(_) => "a string",
+            Value::List(_) => "a list",
+        }
+    }
+
+    /// Whether the value can be of a type as written in schemas, like `u32`
+    pub fn fits(&self, kind: &str) -> bool {
+        match (self, kind) {
+            (Value::Bool(_), "bool") => true,
+            (Value::Integer(_), "float" | "f32" | "f64") | (Value::Float(_), "float" | "f32" | "f64") => true,
+            (Value::Integer(value), kind) => match kind {
+                "u128" => *value >= 0,
+                kind => INTEGERS.iter().any(|(name, min, max)| *name == kind && (min..=max).contains(&value)),
+            },
+            (Value::String(_), "str" | "string") => true,
+            (Value::List(values), kind) => match kind.strip_suffix("[]") {
+                Some(element) => values.iter().all(|value| value.fits(element)),
+                None => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Integer(value) => write!(f, "{}", value),
+            Value::Float(value) => write!(f, "{}", value),
+            Value::String(value) => write!(f, "{}", value),
+            Value::List(values) => {
+                let values: Vec<String> = values.iter().map(ToString::to_string).collect();
+                write!(f, "[{}]", values.join(", "))
+            }
+        }
+    }
+}
+
+
+/// What names refer to while evaluating, where `value` is the value of the
+/// field being validated and `params` the properties of the validator
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Scope<'a> {
+    /// Name of the field being validated, with its value
+    pub subject: Option<(&'a str, &'a Value)>,
+    pub parameters: &'a [(String, Value)],
+}
+
+/// The value of an expression, comparisons of integers and floats compare
+/// them as floats
+pub fn evaluate(expression: &Expression, scope: &Scope) -> Result<Value> {
+    match expression {
+        Expression::Bool(value) => Ok(Value::Bool(*value)),
+        Expression::Integer(value) => Ok(Value::Integer(*value)),
+        Expression::Float(value) => Ok(Value::Float(*value)),
+        Expression::String(segments) => {
+            let mut text = String::new();
+            for segment in segments {
+                match segment {
+                    Segment::Text(part) => text.push_str(part),
+                    Segment::Interpolated(expression) => text.push_str(&evaluate(expression, scope)?.to_string()),
+                }
+            }
+            Ok(Value::String(text))
+        }
+        Expression::Path(path) => resolve(path, scope),
+        Expression::Not(operand) => Ok(Value::Bool(!condition(operand, scope)?)),
+        Expression::Binary(left, Operator::Or, right) => {
+            Ok(Value::Bool(condition(left, scope)? || condition(right, scope)?))
+        }
+        Expression::Binary(left, Operator::And, right) => {
+            Ok(Value::Bool(condition(left, scope)? && condition(right, scope)?))
+        }
+        Expression::Binary(left, operator, right) => {
+            let (left, right) = (evaluate(left, scope)?, evaluate(right, scope)?);
+            let ordering = match (&left, &right) {
+                (Value::Integer(a), Value::Integer(b)) => Some(a.cmp(b)),
+                (Value::Integer(_) | Value::Float(_), Value::Integer(_) | Value::Float(_)) => {
+                    as_float(&left).partial_cmp(&as_float(&right))
+                }
+                (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+                (Value::Bool(a), Value::Bool(b)) if matches!(operator, Operator::Equal | Operator::NotEqual) => {
+                    Some(a.cmp(b))
+                }
+                _ => bail!("{} cannot be compared with {}", left.kind(), right.kind()),
+            };
+
+            Ok(Value::Bool(match operator {
+                Operator::Equal => ordering == Some(Ordering::Equal),
+                Operator::NotEqual => ordering != Some(Ordering::Equal),
+                Operator::Less => ordering == Some(Ordering::Less),
+                Operator::LessOrEqual => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                Operator::Greater => ordering == Some(Ordering::Greater),
+                Operator::GreaterOrEqual => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+                Operator::Or | Operator::And => unreachable!(),
+            }))
+        }
+        Expression::Call(function, _) => bail!("'{}' can only be called by itself in a validate block", function),
+    }
+}
+
+/// The value of a name that needs no scope, like `true` or `u32::MAX`
+pub fn constant(path: &str) -> Option<Value> {
+    match path {
+        "true" => return Some(Value::Bool(true)),
+        "false" => return Some(Value::Bool(false)),
+        _ => {}
+    }
+
+    let (integer, bound) = path.split_once("::")?;
+    let (_, min, max) = INTEGERS.iter().find(|(name, ..)| *name == integer)?;
+    match bound {
+        "MIN" => Some(Value::Integer(*min)),
+        "MAX" => Some(Value::Integer(*max)),
+        _ => None,
+    }
+}
+
+fn resolve(path: &str, scope: &Scope) -> Result<Value> {
+    if let Some(value) = constant(path) { return Ok(value) }
+
+    if let Some(name) = path.strip_prefix("params.") {
+        return match scope.parameters.iter().find(|(parameter, _)| parameter == name) {
+            Some((_, value)) => Ok(value.clone()),
+            None => bail!("'{}' is not a property of the validator", path),
+        }
+    }
+
+    let Some((name, value)) = scope.subject.filter(|_| path.split('.').next() == Some("value")) else {
+        bail!("'{}' is not known here", path)
+    };
+    match path {
+        "value" => Ok(value.clone()),
+        "value.name" => Ok(Value::String(name.to_owned())),
+        "value.length" => match value {
+            Value::String(text) => Ok(Value::Integer(text.chars().count() as i128)),
+            Value::List(values) => Ok(Value::Integer(values.len() as i128)),
+            other => bail!("'value.length' is only known of strings and arrays, not of {}", other.kind()),
+        },
+        path => bail!("'{}' is not known here", path),
+    }
+}
+
+fn condition(expression: &Expression, scope: &Scope) -> Result<bool> {
+    match evaluate(expression, scope)? {
+        Value::Bool(value) => Ok(value),
+        other => bail!("'{}' should be a bool, not {}", expression, other.kind()),
+    }
+}
+
+fn as_float(value: &Value) -> f64 {
+    match value {
+        Value::Integer(value) => *value as f64,
+        Value::Float(value) => *value,
+        _ => f64::NAN,
+    }
+}
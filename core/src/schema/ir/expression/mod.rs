@@ -0,0 +1,322 @@
+// The language validators check values with, as in
+// `assert(value.length <= params.max_chars, "{value.name} is too long")`
+
+// Relative Modules
+mod interpreter;
+mod parser;
+
+// Standard Uses
+use std::fmt::{Display, Formatter};
+
+// Crate Uses
+use crate::schema::ir::compiler::interpreted::kind_search::KindValue;
+use crate::schema::ir::frozen::unit::FrozenUnit;
+
+// External Uses
+use eyre::{bail, Result, WrapErr};
+
+pub use interpreter::{constant, evaluate, Scope, Value};
+pub use parser::{parse, parse_uses};
+
+
+/// Field parameter listing the validators of the field, with the properties
+/// given to them, like `@validators=[StringBounds(min_chars=3 max_chars=12)]`
+pub const VALIDATORS_PARAMETER: &str = "validators";
+
+/// Functions validate blocks may call
+pub const FUNCTIONS: &[&str] = &["assert"];
+
+
+/// An expression, where strings interpolate expressions between braces
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Bool(bool),
+    Integer(i128),
+    Float(f64),
+    String(Vec<Segment>),
+    /// A name, which may be a path like `value.length`, `params.max_chars` or `u32::MAX`
+    Path(String),
+    Not(Box<Expression>),
+    Binary(Box<Expression>, Operator, Box<Expression>),
+    Call(String, Vec<Expression>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Text(String),
+    Interpolated(Expression),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Or,
+    And,
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Operator {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Operator::Or => "or",
+            Operator::And => "and",
+            Operator::Equal => "==",
+            Operator::NotEqual => "!=",
+            Operator::Less => "<",
+            Operator::LessOrEqual => "<=",
+            Operator::Greater => ">",
+            Operator::GreaterOrEqual => ">=",
+        }
+    }
+
+    /// How tightly the operator binds, where comparisons bind the tightest
+    fn precedence(self) -> u8 {
+        match self {
+            Operator::Or => 1,
+            Operator::And => 2,
+            _ => 4,
+        }
+    }
+}
+
+impl Expression {
+    fn precedence(&self) -> u8 {
+        match self {
+            Expression::Binary(_, operator, _) => operator.precedence(),
+            Expression::Not(_) => 3,
+            _ => 5,
+        }
+    }
+
+    /// Paths the expression refers to, including those interpolated into strings
+    pub fn paths(&self) -> Vec<&str> {
+        match self {
+            Expression::Bool(_) | Expression::Integer(_) | Expression::Float(_) => vec![],
+            Expression::String(segments) => segments.iter()
+                .flat_map(|segment| match segment {
+                    Segment::Text(_) => vec![],
+                    Segment::Interpolated(expression) => expression.paths(),
+                })
+                .collect(),
+            Expression::Path(path) => vec![path],
+            Expression::Not(operand) => operand.paths(),
+            Expression::Binary(left, _, right) => [left.paths(), right.paths()].concat(),
+            Expression::Call(_, arguments) => arguments.iter().flat_map(Expression::paths).collect(),
+        }
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // Operands that bind looser than their operator are parenthesized
+        let operand = |operand: &Expression, tightest: u8| match operand.precedence() < tightest {
+            true => format!("({})", operand),
+            false => operand.to_string(),
+        };
+
+        match self {
+            Expression::Bool(value) => write!(f, "{}", value),
+            Expression::Integer(value) => write!(f, "{}", value),
+            Expression::Float(value) => write!(f, "{:?}", value),
+            Expression::String(segments) => {
+                write!(f, "\"")?;
+                for segment in segments {
+                    match segment {
+                        Segment::Text(text) => write!(f, "{}", text
+                            .replace('\\', "\\\\").replace('"', "\\\"")
+                            .replace('{', "{{").replace('}', "}}")
+                        )?,
+                        Segment::Interpolated(expression) => write!(f, "{{{}}}", expression)?,
+                    }
+                }
+                write!(f, "\"")
+            }
+            Expression::Path(path) => write!(f, "{}", path),
+            Expression::Not(value) => write!(f, "not {}", operand(value, 3)),
+            Expression::Binary(left, operator, right) => {
+                let precedence = operator.precedence();
+                let right_tightest = match precedence { 4 => 5, precedence => precedence + 1 };
+                let left_tightest = match precedence { 4 => 5, precedence => precedence };
+
+                write!(
+                    f, "{} {} {}",
+                    operand(left, left_tightest), operator.symbol(), operand(right, right_tightest)
+                )
+            }
+            Expression::Call(name, arguments) => {
+                let arguments: Vec<String> = arguments.iter().map(ToString::to_string).collect();
+                write!(f, "{}({})", name, arguments.join(", "))
+            }
+        }
+    }
+}
+
+
+/// A validator declaration, with the calls of its validate block parsed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Validator {
+    pub name: String,
+    pub properties: Vec<Property>,
+    pub calls: Vec<Expression>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property {
+    pub name: String,
+    /// Type as written in schemas, like `u32`
+    pub kind: String,
+    pub default: Option<Expression>,
+}
+
+/// A validator as a field uses it, with the properties given to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidatorUse {
+    pub name: String,
+    pub arguments: Vec<(String, Expression)>,
+}
+
+impl Validator {
+    pub fn from_unit(unit: &FrozenUnit) -> Result<Self> {
+        let FrozenUnit::Validator { name, properties, expression_block, .. } = unit else {
+            bail!("Only validator declarations can validate values")
+        };
+
+        let properties = properties.iter().filter_map(|property| match property {
+            FrozenUnit::Property { name: property_name, kind_value, expression } => {
+                let kind = match kind_value {
+                    KindValue::Namespaced(kind, _) => kind.clone(),
+                    other => other.name_and_value().0,
+                };
+                let default = expression.as_deref().map(parse).transpose()
+                    .wrap_err_with(|| format!("Default of '{}::{}' cannot be parsed", name, property_name));
+
+                Some(default.map(|default| Property { name: property_name.clone(), kind, default }))
+            }
+            _ => None,
+        }).collect::<Result<Vec<_>>>()?;
+
+        let calls = match expression_block.as_ref() {
+            FrozenUnit::ExpressionBlock { function_calls } => function_calls.iter()
+                .map(|call| parse(call).wrap_err_with(|| format!("Validator '{}' cannot be parsed", name)))
+                .collect::<Result<Vec<_>>>()?,
+            _ => vec![],
+        };
+
+        Ok(Self { name: name.clone(), properties, calls })
+    }
+
+    /// What is wrong with the declaration, that would fail every check
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = vec![];
+
+        for property in &self.properties {
+            let Some(default) = &property.default else { continue };
+            if let Err(error) = self.property_value(property, default) {
+                problems.push(error.to_string())
+            }
+        }
+
+        for call in &self.calls {
+            match call {
+                Expression::Call(function, arguments) if function == "assert" => {
+                    if !(1..=2).contains(&arguments.len()) {
+                        problems.push(format!("'{}' should be given a condition and optionally a message", call))
+                    }
+                }
+                Expression::Call(function, _) => problems.push(format!(
+                    "'{}' is not a function validators may call, which are: {}", function, FUNCTIONS.join(", ")
+                )),
+                _ => problems.push(format!("'{}' is not a call", call)),
+            }
+
+            for path in call.paths() {
+                let known = match path.split_once('.') {
+                    Some(("value", "length" | "name")) => true,
+                    Some(("params", name)) => self.properties.iter().any(|property| property.name == name),
+                    _ => path == "value" || constant(path).is_some(),
+                };
+                if !known {
+                    problems.push(format!("'{}' is not known in validator '{}'", path, self.name))
+                }
+            }
+        }
+
+        problems
+    }
+
+    /// The values of the properties, as given by a use or their defaults
+    pub fn parameters(&self, arguments: &[(String, Expression)]) -> Result<Vec<(String, Value)>> {
+        for (name, _) in arguments {
+            if !self.properties.iter().any(|property| property.name == *name) {
+                bail!("'{}' is not a property of validator '{}'", name, self.name)
+            }
+        }
+
+        self.properties.iter().map(|property| {
+            let given = arguments.iter().find(|(name, _)| *name == property.name).map(|(_, value)| value);
+            let Some(expression) = given.or(property.default.as_ref()) else {
+                bail!("Validator '{}' needs to be given '{}'", self.name, property.name)
+            };
+
+            Ok((property.name.clone(), self.property_value(property, expression)?))
+        }).collect()
+    }
+
+    /// Messages of the assertions a value of the field `name` fails
+    pub fn check(&self, name: &str, value: &Value, parameters: &[(String, Value)]) -> Result<Vec<String>> {
+        let scope = Scope { subject: Some((name, value)), parameters };
+        let mut failures = vec![];
+
+        for call in &self.calls {
+            let Expression::Call(function, arguments) = call else { bail!("'{}' is not a call", call) };
+            let (condition, message) = match (function.as_str(), arguments.as_slice()) {
+                ("assert", [condition]) => (condition, None),
+                ("assert", [condition, message]) => (condition, Some(message)),
+                _ => bail!("'{}' is not a call validators can make", call),
+            };
+
+            match evaluate(condition, &scope)? {
+                Value::Bool(true) => {}
+                Value::Bool(false) => failures.push(match message {
+                    Some(message) => evaluate(message, &scope)?.to_string(),
+                    None => format!("'{}' does not hold", condition),
+                }),
+                other => bail!("'{}' should be a bool, not {}", condition, other.kind()),
+            }
+        }
+
+        Ok(failures)
+    }
+
+    /// A value given to a property, which is only made of constants
+    fn property_value(&self, property: &Property, expression: &Expression) -> Result<Value> {
+        let value = evaluate(expression, &Scope::default())
+            .wrap_err_with(|| format!("'{}' of validator '{}' is not a constant", property.name, self.name))?;
+
+        if !value.fits(&property.kind) {
+            bail!(
+                "'{}' of validator '{}' should be {}, not {}",
+                property.name, self.name, property.kind, value.kind()
+            )
+        }
+
+        Ok(value)
+    }
+}
+
+/// Validators a field uses, from its parameters
+pub fn field_validators(parameters: &[FrozenUnit]) -> Result<Vec<ValidatorUse>> {
+    let written = parameters.iter().find_map(|parameter| match parameter {
+        FrozenUnit::Parameter { name, default_value } if name == VALIDATORS_PARAMETER => Some(default_value),
+        _ => None,
+    });
+
+    match written {
+        Some(written) => parse_uses(written),
+        None => Ok(vec![]),
+    }
+}
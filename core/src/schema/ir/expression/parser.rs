@@ -0,0 +1,308 @@
+// Standard Uses
+
+// Crate Uses
+use super::{Expression, Operator, Segment, ValidatorUse};
+
+// External Uses
+use eyre::{bail, eyre, Result};
+
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(String),
+    /// Contents of a string, with escapes resolved but braces kept
+    String(String),
+    Name(String),
+    Symbol(&'static str),
+}
+
+/// Symbols, longest first so that `<=` is not read as `<`
+const SYMBOLS: &[&str] = &["==", "!=", "<=", ">=", "<", ">", "(", ")", "[", "]", ",", "=", "-"];
+const KEYWORDS: &[&str] = &["and", "or", "not"];
+
+
+/// Parses an expression, like `value.length > params.max_chars or not value.valid`
+pub fn parse(text: &str) -> Result<Expression> {
+    let mut parser = Parser::new(text)?;
+    let expression = parser.expression()?;
+    parser.end()?;
+
+    Ok(expression)
+}
+
+/// Parses validator uses, either a list like `[StringBounds(min_chars=3) NotEmpty]`
+/// or a single one
+pub fn parse_uses(text: &str) -> Result<Vec<ValidatorUse>> {
+    let mut parser = Parser::new(text)?;
+    let mut uses = vec![];
+
+    match parser.eat("[") {
+        true => while !parser.eat("]") {
+            uses.push(parser.validator_use()?);
+            parser.eat(",");
+        },
+        false => uses.push(parser.validator_use()?),
+    }
+    parser.end()?;
+
+    Ok(uses)
+}
+
+
+struct Parser<'a> {
+    text: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Result<Self> {
+        Ok(Self { text, tokens: tokenize(text)?, position: 0 })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token> {
+        let token = self.peek().cloned().ok_or_else(|| eyre!("'{}' ends unexpectedly", self.text))?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    /// Takes the symbol or keyword if it is next
+    fn eat(&mut self, symbol: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Symbol(next)) if *next == symbol);
+        if found { self.position += 1 }
+        found
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<()> {
+        match self.eat(symbol) {
+            true => Ok(()),
+            false => bail!("Expected '{}' in '{}'", symbol, self.text),
+        }
+    }
+
+    fn end(&self) -> Result<()> {
+        match self.peek() {
+            None => Ok(()),
+            Some(token) => bail!("Unexpected {} in '{}'", describe(token), self.text),
+        }
+    }
+
+    fn expression(&mut self) -> Result<Expression> {
+        let mut left = self.and()?;
+        while self.eat("or") {
+            left = Expression::Binary(Box::new(left), Operator::Or, Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expression> {
+        let mut left = self.not()?;
+        while self.eat("and") {
+            left = Expression::Binary(Box::new(left), Operator::And, Box::new(self.not()?));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expression> {
+        match self.eat("not") {
+            true => Ok(Expression::Not(Box::new(self.not()?))),
+            false => self.comparison(),
+        }
+    }
+
+    /// Comparisons do not chain, `a < b < c` has to be parenthesized
+    fn comparison(&mut self) -> Result<Expression> {
+        let left = self.primary()?;
+        let operator = [
+            Operator::Equal, Operator::NotEqual, Operator::LessOrEqual,
+            Operator::GreaterOrEqual, Operator::Less, Operator::Greater,
+        ].into_iter().find(|operator| self.eat(operator.symbol()));
+
+        match operator {
+            Some(operator) => Ok(Expression::Binary(Box::new(left), operator, Box::new(self.primary()?))),
+            None => Ok(left),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expression> {
+        match self.next()? {
+            Token::Number(number) => number_literal(&number),
+            Token::Symbol("-") => match self.next()? {
+                Token::Number(number) => number_literal(&format!("-{}", number)),
+                token => bail!("Expected a number after '-' in '{}', not {}", self.text, describe(&token)),
+            },
+            Token::String(contents) => Ok(Expression::String(segments(&contents)?)),
+            Token::Symbol("(") => {
+                let expression = self.expression()?;
+                self.expect(")")?;
+                Ok(expression)
+            }
+            Token::Name(name) if name == "true" || name == "false" => Ok(Expression::Bool(name == "true")),
+            Token::Name(name) if self.eat("(") => {
+                let mut arguments = vec![];
+                while !self.eat(")") {
+                    if !arguments.is_empty() { self.expect(",")? }
+                    arguments.push(self.expression()?);
+                }
+                Ok(Expression::Call(name, arguments))
+            }
+            Token::Name(name) => Ok(Expression::Path(name)),
+            token => bail!("Unexpected {} in '{}'", describe(&token), self.text),
+        }
+    }
+
+    /// A validator and the properties given to it, like `StringBounds(min_chars=3 max_chars=12)`
+    fn validator_use(&mut self) -> Result<ValidatorUse> {
+        let name = match self.next()? {
+            Token::Name(name) => name,
+            token => bail!("Expected the name of a validator in '{}', not {}", self.text, describe(&token)),
+        };
+
+        let mut arguments = vec![];
+        if self.eat("(") {
+            while !self.eat(")") {
+                let property = match self.next()? {
+                    Token::Name(property) => property,
+                    token => bail!("Expected the name of a property in '{}', not {}", self.text, describe(&token)),
+                };
+                self.expect("=")?;
+                arguments.push((property, self.expression()?));
+                self.eat(",");
+            }
+        }
+
+        Ok(ValidatorUse { name, arguments })
+    }
+}
+
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+            continue
+        }
+
+        if c == '"' {
+            let mut contents = String::new();
+            let mut chars = rest.char_indices().skip(1);
+            let end = loop {
+                match chars.next() {
+                    Some((index, '"')) => break index,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, escaped)) => contents.push(escaped),
+                        None => bail!("Unterminated string in '{}'", text),
+                    },
+                    Some((_, c)) => contents.push(c),
+                    None => bail!("Unterminated string in '{}'", text),
+                }
+            };
+            tokens.push(Token::String(contents));
+            rest = &rest[end + 1..];
+            continue
+        }
+
+        if c.is_ascii_digit() {
+            let length = rest.find(|c: char| !c.is_ascii_digit() && c != '.' && c != '_').unwrap_or(rest.len());
+            tokens.push(Token::Number(rest[..length].to_owned()));
+            rest = &rest[length..];
+            continue
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            let length = name_length(rest);
+            let name = &rest[..length];
+            tokens.push(match KEYWORDS.iter().find(|keyword| **keyword == name) {
+                Some(keyword) => Token::Symbol(keyword),
+                None => Token::Name(name.to_owned()),
+            });
+            rest = &rest[length..];
+            continue
+        }
+
+        let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) else {
+            bail!("Unexpected character '{}' in '{}'", c, text)
+        };
+        tokens.push(Token::Symbol(symbol));
+        rest = &rest[symbol.len()..];
+    }
+
+    Ok(tokens)
+}
+
+/// Length of a name at the start of `text`, which may be a path joined by `.` or `::`
+fn name_length(text: &str) -> usize {
+    let mut end = 0;
+
+    while end < text.len() {
+        let rest = &text[end..];
+        let c = rest.chars().next().unwrap();
+
+        if c.is_alphanumeric() || c == '_' {
+            end += c.len_utf8();
+        } else if c == '.' && rest[1..].starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            end += 1;
+        } else if rest.starts_with("::") {
+            end += 2;
+        } else {
+            break
+        }
+    }
+
+    end
+}
+
+fn number_literal(number: &str) -> Result<Expression> {
+    let digits = number.replace('_', "");
+    if digits.contains('.') {
+        return digits.parse().map(Expression::Float).map_err(|_| eyre!("'{}' is not a number", number))
+    }
+
+    digits.parse().map(Expression::Integer).map_err(|_| eyre!("'{}' is not a number", number))
+}
+
+/// The text and interpolated expressions of a string, where `{{` and `}}`
+/// are literal braces
+fn segments(contents: &str) -> Result<Vec<Segment>> {
+    let mut segments = vec![];
+    let mut text = String::new();
+    let mut rest = contents;
+
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            text.push(c);
+            rest = &rest[2..];
+            continue
+        }
+
+        if c == '{' {
+            let Some(end) = rest.find('}') else { bail!("Unclosed '{{' in \"{}\"", contents) };
+            if !text.is_empty() { segments.push(Segment::Text(std::mem::take(&mut text))) }
+            segments.push(Segment::Interpolated(parse(&rest[1..end])?));
+            rest = &rest[end + 1..];
+            continue
+        }
+
+        text.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    if !text.is_empty() { segments.push(Segment::Text(text)) }
+
+    Ok(segments)
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Number(number) => format!("number '{}'", number),
+        Token::String(contents) => format!("string \"{}\"", contents),
+        Token::Name(name) => format!("'{}'", name),
+        Token::Symbol(symbol) => format!("'{}'", symbol),
+    }
+}
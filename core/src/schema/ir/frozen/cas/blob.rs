@@ -4,22 +4,46 @@
 use crate::package::build::cas::objects::{Blob, Tree, TreeEntry, EntryMode};
 use crate::package::build::cas::object_store::ObjectStore;
 use crate::package::build::cas::storage::Hash;
+use crate::schema::ir::frozen::cas::legacy::LegacyUnit;
 use crate::schema::ir::frozen::unit::FrozenUnit;
-use eyre::{eyre, Result};
+use eyre::{bail, eyre, Result};
+
+/// Start of blobs of frozen units, followed by the version of their layout.
+/// Unversioned blobs start with the index of their variant as a little endian
+/// u32, which is never as large as this
+pub const UNIT_BLOB_MAGIC: &[u8] = b"CLU";
+
+/// Version of the layout of frozen units, bumped whenever a change to
+/// `FrozenUnit` moves where bincode finds its fields
+pub const UNIT_BLOB_VERSION: u8 = 1;
 
 /// Convert a FrozenUnit to a Blob
 pub fn frozen_unit_to_blob(unit: &FrozenUnit) -> Result<Blob> {
-    // Serialize FrozenUnit using bincode
-    let serialized = bincode::serialize(unit)
+    // Serialize FrozenUnit using bincode, after the version of its layout
+    let mut content = UNIT_BLOB_MAGIC.to_vec();
+    content.push(UNIT_BLOB_VERSION);
+    bincode::serialize_into(&mut content, unit)
         .map_err(|e| eyre!("Failed to serialize FrozenUnit: {}", e))?;
     
-    Ok(Blob::new(serialized))
+    Ok(Blob::new(content))
 }
 
-/// Deserialize a Blob back to FrozenUnit
+/// Deserialize a Blob back to FrozenUnit, from the layout it was written in
 pub fn blob_to_frozen_unit(blob: &Blob) -> Result<FrozenUnit> {
-    bincode::deserialize(&blob.content)
-        .map_err(|e| eyre!("Failed to deserialize FrozenUnit from blob: {}", e))
+    let Some(versioned) = blob.content.strip_prefix(UNIT_BLOB_MAGIC) else {
+        let legacy: LegacyUnit = bincode::deserialize(&blob.content)
+            .map_err(|e| eyre!("Failed to deserialize FrozenUnit from unversioned blob: {}", e))?;
+        return legacy.upgrade()
+    };
+
+    match versioned.split_first() {
+        Some((&UNIT_BLOB_VERSION, content)) => bincode::deserialize(content)
+            .map_err(|e| eyre!("Failed to deserialize FrozenUnit from blob: {}", e)),
+        Some((version, _)) => bail!(
+            "Blob has frozen units of layout version {}, which this version of Comline does not read", version
+        ),
+        None => bail!("Blob of frozen units has no layout version"),
+    }
 }
 
 /// Build a tree from a collection of FrozenUnits
@@ -0,0 +1,145 @@
+// Layout of frozen units in blobs written before blobs were versioned
+// Bincode reads fields by position, so these blobs can only be read as the
+// units were laid out then, and are converted into the units of now
+
+// Standard Uses
+
+// Crate Uses
+use crate::schema::ir::compiler::interpreted::kind_search::KindValue;
+use crate::schema::ir::frozen::unit::{FrozenArgument, FrozenUnit};
+
+// External Uses
+use eyre::{bail, Result};
+use serde_derive::Deserialize;
+
+
+/// `FrozenUnit` as it was, with the same variants in the same order
+#[derive(Deserialize)]
+pub(super) enum LegacyUnit {
+    Namespace(String),
+    Name(String),
+    Import(String),
+    Constant {
+        docstring: Option<String>,
+        name: String,
+        kind_value: KindValue
+    },
+    /// Properties had no kind, though nothing froze them yet
+    Property {
+        name: String,
+        _expression: Option<String>
+    },
+    Parameter {
+        name: String,
+        default_value: String
+    },
+    ExpressionBlock {
+        function_calls: Vec<String>
+    },
+    Enum {
+        docstring: Option<String>,
+        name: String,
+        variants: Vec<LegacyUnit>
+    },
+    EnumVariant(KindValue),
+    Settings {
+        docstring: Option<String>,
+        name: String,
+        parameters: Vec<LegacyUnit>,
+    },
+    Struct {
+        docstring: Option<String>,
+        parameters: Vec<LegacyUnit>,
+        name: String,
+        fields: Vec<LegacyUnit>,
+    },
+    Protocol {
+        docstring: String,
+        parameters: Vec<LegacyUnit>,
+        name: String,
+        functions: Vec<LegacyUnit>
+    },
+    /// Functions had no parameters
+    Function {
+        docstring: String,
+        name: String,
+        synchronous: bool,
+        arguments: Vec<FrozenArgument>,
+        _return: Option<KindValue>,
+        throws: Vec<LegacyUnit>
+    },
+    Error {
+        docstring: Option<String>,
+        parameters: Vec<LegacyUnit>,
+        name: String,
+        message: String,
+        fields: Vec<LegacyUnit>
+    },
+    Validator {
+        docstring: Option<String>,
+        properties: Vec<LegacyUnit>,
+        name: String,
+        expression_block: Box<LegacyUnit>
+    },
+    Field {
+        docstring: Option<String>,
+        parameters: Vec<LegacyUnit>,
+        optional: bool,
+        name: String,
+        kind_value: KindValue,
+    }
+}
+
+impl LegacyUnit {
+    /// The unit as it is laid out now
+    pub(super) fn upgrade(self) -> Result<FrozenUnit> {
+        let upgraded = match self {
+            LegacyUnit::Namespace(name) => FrozenUnit::Namespace(name),
+            LegacyUnit::Name(name) => FrozenUnit::Name(name),
+            LegacyUnit::Import(path) => FrozenUnit::Import(path),
+            LegacyUnit::Constant { docstring, name, kind_value } => {
+                FrozenUnit::Constant { docstring, name, kind_value }
+            }
+            LegacyUnit::Property { name, .. } => {
+                bail!("Property '{}' was frozen without a kind, which no version ever did", name)
+            }
+            LegacyUnit::Parameter { name, default_value } => FrozenUnit::Parameter { name, default_value },
+            LegacyUnit::ExpressionBlock { function_calls } => FrozenUnit::ExpressionBlock { function_calls },
+            LegacyUnit::Enum { docstring, name, variants } => {
+                FrozenUnit::Enum { docstring, name, variants: upgrade_all(variants)? }
+            }
+            LegacyUnit::EnumVariant(kind_value) => FrozenUnit::EnumVariant(kind_value),
+            LegacyUnit::Settings { docstring, name, parameters } => {
+                FrozenUnit::Settings { docstring, name, parameters: upgrade_all(parameters)? }
+            }
+            LegacyUnit::Struct { docstring, parameters, name, fields } => FrozenUnit::Struct {
+                docstring, parameters: upgrade_all(parameters)?, name, fields: upgrade_all(fields)?
+            },
+            LegacyUnit::Protocol { docstring, parameters, name, functions } => FrozenUnit::Protocol {
+                docstring, parameters: upgrade_all(parameters)?, name, functions: upgrade_all(functions)?
+            },
+            LegacyUnit::Function { docstring, name, synchronous, arguments, _return, throws } => {
+                FrozenUnit::Function {
+                    docstring, parameters: vec![], name, synchronous, arguments, _return,
+                    throws: upgrade_all(throws)?
+                }
+            }
+            LegacyUnit::Error { docstring, parameters, name, message, fields } => FrozenUnit::Error {
+                docstring, parameters: upgrade_all(parameters)?, name, message, fields: upgrade_all(fields)?
+            },
+            LegacyUnit::Validator { docstring, properties, name, expression_block } => FrozenUnit::Validator {
+                docstring, properties: upgrade_all(properties)?, name,
+                expression_block: Box::new(expression_block.upgrade()?)
+            },
+            LegacyUnit::Field { docstring, parameters, optional, name, kind_value } => FrozenUnit::Field {
+                docstring, parameters: upgrade_all(parameters)?, optional, name, kind_value
+            },
+        };
+
+        Ok(upgraded)
+    }
+}
+
+fn upgrade_all(units: Vec<LegacyUnit>) -> Result<Vec<FrozenUnit>> {
+    units.into_iter().map(LegacyUnit::upgrade).collect()
+}
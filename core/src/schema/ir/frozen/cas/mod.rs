@@ -1,5 +1,6 @@
 // Relative Modules
 pub mod blob;    // FrozenUnit ↔ Blob conversion
+mod legacy;      // FrozenUnit layout of unversioned blobs
 pub mod commit;  // Commit creation utilities
 pub mod tree;
 pub mod meta;
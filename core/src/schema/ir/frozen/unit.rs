@@ -28,6 +28,7 @@ pub enum FrozenUnit {
     },
     Property {
         name: String,
+        kind_value: KindValue,
         expression: Option<String>
     },
     Parameter {
@@ -63,6 +64,7 @@ pub enum FrozenUnit {
     },
     Function {
         docstring: String,
+        parameters: Vec<FrozenUnit>,
         name: String,
         synchronous: bool,
         // direction: Box<FrozenUnit>,
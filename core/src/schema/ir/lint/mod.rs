@@ -0,0 +1,219 @@
+// Relative Modules
+mod rules;
+
+// Standard Uses
+use std::collections::HashMap;
+
+// Crate Uses
+use crate::package::config::ir::context::ProjectContext;
+use crate::package::config::ir::frozen::{self as config_frozen, FrozenUnit as ConfigUnit};
+use crate::schema::idl::grammar::{Annotation, Declaration};
+
+// External Uses
+use serde_derive::{Deserialize, Serialize};
+
+
+/// Annotation key that suppresses lints on the annotated item and its members,
+/// e.g. `@allow=missing_docs` or `@allow="empty_structs, naming_convention"`
+pub const ALLOW_ANNOTATION: &str = "allow";
+
+
+/// A named check that is not a hard error, unlike what the validator reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rule {
+    /// PascalCase types, variants and protocols, snake_case fields and
+    /// functions, SCREAMING_SNAKE_CASE constants
+    NamingConvention,
+    UnusedImports,
+    /// Constants that no schema of the package refers to
+    UnusedConstants,
+    EmptyStructs,
+    EmptyProtocols,
+    /// Declarations named like something imported into the same schema
+    ShadowedImports,
+    /// Declarations and functions without a `///` doc comment
+    MissingDocs,
+}
+
+impl Rule {
+    pub const ALL: &'static [Rule] = &[
+        Rule::NamingConvention, Rule::UnusedImports, Rule::UnusedConstants,
+        Rule::EmptyStructs, Rule::EmptyProtocols, Rule::ShadowedImports, Rule::MissingDocs,
+    ];
+
+    /// Name of the rule in configurations and annotations
+    pub fn name(self) -> &'static str {
+        match self {
+            Rule::NamingConvention => "naming_convention",
+            Rule::UnusedImports => "unused_imports",
+            Rule::UnusedConstants => "unused_constants",
+            Rule::EmptyStructs => "empty_structs",
+            Rule::EmptyProtocols => "empty_protocols",
+            Rule::ShadowedImports => "shadowed_imports",
+            Rule::MissingDocs => "missing_docs",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|rule| rule.name() == name)
+    }
+
+    /// Level of the rule when the configuration does not set one. Constants are
+    /// often only meant for the generated code, and documentation is opt-in
+    pub fn default_level(self) -> LintLevel {
+        match self {
+            Rule::UnusedConstants | Rule::MissingDocs => LintLevel::Allow,
+            _ => LintLevel::Warn,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintLevel {
+    /// The rule is not checked
+    Allow,
+    /// Findings are reported
+    Warn,
+    /// Findings are reported and make the package check fail
+    Deny,
+}
+
+impl LintLevel {
+    pub fn name(self) -> &'static str {
+        match self {
+            LintLevel::Allow => "allow",
+            LintLevel::Warn => "warn",
+            LintLevel::Deny => "deny",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [LintLevel::Allow, LintLevel::Warn, LintLevel::Deny].into_iter()
+            .find(|level| level.name() == name)
+    }
+}
+
+/// Level of every rule, set from the `lints` of the package configuration
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintLevels(HashMap<Rule, LintLevel>);
+
+impl LintLevels {
+    pub fn from_config(units: &[ConfigUnit]) -> Self {
+        let mut levels = Self::default();
+        for (rule, level) in config_frozen::lints(units) {
+            levels.set(*rule, *level);
+        }
+
+        levels
+    }
+
+    pub fn set(&mut self, rule: Rule, level: LintLevel) {
+        self.0.insert(rule, level);
+    }
+
+    pub fn level(&self, rule: Rule) -> LintLevel {
+        self.0.get(&rule).copied().unwrap_or_else(|| rule.default_level())
+    }
+}
+
+/// A finding of a rule, on the span of the name it is about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    pub rule: Rule,
+    pub level: LintLevel,
+    pub message: String,
+    pub span: (usize, usize),
+}
+
+/// A parsed schema to lint
+#[derive(Debug, Clone, Copy)]
+pub struct LintTarget<'a> {
+    pub namespace: &'a [String],
+    pub source: &'a str,
+    pub declarations: &'a [Declaration],
+}
+
+/// Lints found in a single schema
+#[derive(Debug, Clone)]
+pub struct SchemaLints {
+    pub namespace: String,
+    pub source: String,
+    pub lints: Vec<Lint>,
+}
+
+impl SchemaLints {
+    /// Line and column, both starting at 1, of where a lint starts
+    pub fn line_column(&self, lint: &Lint) -> (usize, usize) {
+        let before = &self.source[..lint.span.0];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+    }
+}
+
+
+/// Lints every schema of a compiled package, with the levels of its configuration
+pub fn lint_package(context: &ProjectContext) -> Vec<SchemaLints> {
+    let levels = LintLevels::from_config(context.config_frozen.as_deref().unwrap_or_default());
+
+    let schemas: Vec<_> = context.schema_contexts.iter().map(|s| s.borrow()).collect();
+    let sources: Vec<String> = schemas.iter()
+        .map(|s| s.codemap.files().first().map(|f| f.contents().to_owned()).unwrap_or_default())
+        .collect();
+    let targets: Vec<_> = schemas.iter().zip(&sources).map(|(schema, source)| LintTarget {
+        namespace: &schema.namespace,
+        source,
+        declarations: &schema.declarations,
+    }).collect();
+
+    lint_schemas(&targets, &levels).into_iter()
+        .zip(schemas.iter().zip(sources))
+        .filter(|(lints, _)| !lints.is_empty())
+        .map(|(lints, (schema, source))| SchemaLints {
+            namespace: schema.namespace_joined(), source, lints,
+        })
+        .collect()
+}
+
+/// Lints schemas of the same package, some rules look across all of them.
+/// Returns the lints of each target, in the order of the targets
+pub fn lint_schemas(targets: &[LintTarget], levels: &LintLevels) -> Vec<Vec<Lint>> {
+    let package_names = rules::package_names(targets);
+
+    targets.iter().map(|target| {
+        let mut linter = Linter { levels, lints: vec![] };
+        rules::check(&mut linter, target, &package_names);
+
+        linter.lints.sort_by_key(|lint| lint.span);
+        linter.lints
+    }).collect()
+}
+
+
+struct Linter<'a> {
+    levels: &'a LintLevels,
+    lints: Vec<Lint>,
+}
+
+impl Linter<'_> {
+    /// Records a finding, unless the rule is allowed by the configuration or
+    /// by an annotation of the item or any item it is in
+    fn report(
+        &mut self, rule: Rule, scopes: &[&[Annotation]], span: (usize, usize), message: String
+    ) {
+        let level = self.levels.level(rule);
+        if level == LintLevel::Allow || scopes.iter().any(|a| allows(a, rule)) { return }
+
+        self.lints.push(Lint { rule, level, message, span });
+    }
+}
+
+fn allows(annotations: &[Annotation], rule: Rule) -> bool {
+    annotations.iter()
+        .filter(|annotation| annotation.key() == ALLOW_ANNOTATION)
+        .any(|annotation| annotation.value().split(',').any(|name| name.trim() == rule.name()))
+}
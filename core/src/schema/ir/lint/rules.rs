@@ -0,0 +1,355 @@
+// Standard Uses
+use std::collections::HashSet;
+
+// Crate Uses
+use super::{LintTarget, Linter, Rule};
+use crate::schema::idl::docs::docstring_before;
+use crate::schema::idl::grammar::{
+    Annotation, Declaration, Expression, Spanned, Type, UsePath,
+};
+use crate::utils::case;
+
+// External Uses
+
+
+/// A name brought into a schema by an import
+struct ImportedName<'a> {
+    name: String,
+    span: (usize, usize),
+    annotations: &'a [Annotation],
+}
+
+#[derive(Clone, Copy)]
+enum Convention {
+    Pascal,
+    Snake,
+    ScreamingSnake,
+}
+
+impl Convention {
+    fn matches(self, name: &str) -> bool {
+        match self {
+            Convention::Pascal => case::is_pascal_case(name),
+            Convention::Snake => case::is_snake_case(name),
+            Convention::ScreamingSnake => case::is_screaming_snake_case(name),
+        }
+    }
+
+    fn convert(self, name: &str) -> String {
+        match self {
+            Convention::Pascal => case::to_pascal_case(name),
+            Convention::Snake => case::to_snake_case(name),
+            Convention::ScreamingSnake => case::to_screaming_snake_case(name),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Convention::Pascal => "PascalCase",
+            Convention::Snake => "snake_case",
+            Convention::ScreamingSnake => "SCREAMING_SNAKE_CASE",
+        }
+    }
+}
+
+
+/// Names any schema of the package refers to, either through imports,
+/// types or expressions
+pub(super) fn package_names(targets: &[LintTarget]) -> HashSet<String> {
+    let mut names = HashSet::new();
+
+    for target in targets {
+        for declaration in target.declarations {
+            match declaration {
+                Declaration::Import(import) => {
+                    names.insert(last_segment(import.path.as_str()).to_owned());
+                }
+                Declaration::Use(use_decl) => match &use_decl.path {
+                    UsePath::Absolute(path) => {
+                        names.insert(last_segment(path.as_str()).to_owned());
+                    }
+                    UsePath::Relative(relative) => {
+                        names.insert(last_segment(relative.path.as_str()).to_owned());
+                    }
+                    UsePath::Multi(multi) => {
+                        names.extend(multi.items().iter().map(|item| item.to_string()));
+                    }
+                    UsePath::Glob(_) => {}
+                },
+                _ => {}
+            }
+        }
+        names.extend(local_names(target.declarations));
+    }
+
+    names
+}
+
+pub(super) fn check(linter: &mut Linter, target: &LintTarget, package_names: &HashSet<String>) {
+    let imports = imported_names(target.declarations);
+    let used = local_names(target.declarations);
+
+    for import in &imports {
+        if !used.contains(&import.name) {
+            linter.report(
+                Rule::UnusedImports, &[import.annotations], import.span,
+                format!("Unused import '{}'", import.name),
+            );
+        }
+    }
+
+    let source = target.source;
+    for declaration in target.declarations {
+        match declaration {
+            Declaration::Import(_) | Declaration::Use(_) => {}
+            Declaration::Const(constant) => {
+                let scopes = [constant.annotations()];
+                let name = &constant.name;
+
+                item(linter, source, &scopes, &imports, "Constant", name, Convention::ScreamingSnake);
+                if !package_names.contains(name.as_str()) {
+                    linter.report(
+                        Rule::UnusedConstants, &scopes, name.span,
+                        format!("Constant '{}' is never used", name.as_str()),
+                    );
+                }
+            }
+            Declaration::Struct(structure) => {
+                let scopes = [structure.annotations()];
+                let name = &structure.name;
+
+                item(linter, source, &scopes, &imports, "Struct", name, Convention::Pascal);
+                if structure.fields().is_empty() {
+                    linter.report(
+                        Rule::EmptyStructs, &scopes, name.span,
+                        format!("Struct '{}' has no fields", name.as_str()),
+                    );
+                }
+
+                for field in structure.fields() {
+                    let scopes = [structure.annotations(), field.annotations()];
+                    naming(linter, &scopes, "Field", &field.name, Convention::Snake);
+                }
+            }
+            Declaration::Error(error) => {
+                let scopes = [error.annotations()];
+
+                item(linter, source, &scopes, &imports, "Error", &error.name, Convention::Pascal);
+                for field in error.fields() {
+                    let scopes = [error.annotations(), field.annotations()];
+                    naming(linter, &scopes, "Field", &field.name, Convention::Snake);
+                }
+            }
+            Declaration::Enum(enumeration) => {
+                let scopes = [enumeration.annotations()];
+                let name = &enumeration.name;
+
+                item(linter, source, &scopes, &imports, "Enum", name, Convention::Pascal);
+                for variant in enumeration.variants() {
+                    let scopes = [enumeration.annotations(), variant.annotations()];
+                    naming(linter, &scopes, "Variant", &variant.name, Convention::Pascal);
+                }
+            }
+            Declaration::Protocol(protocol) => {
+                let scopes = [protocol.annotations()];
+                let name = &protocol.name;
+
+                item(linter, source, &scopes, &imports, "Protocol", name, Convention::Pascal);
+                if protocol.functions().is_empty() {
+                    linter.report(
+                        Rule::EmptyProtocols, &scopes, name.span,
+                        format!("Protocol '{}' has no functions", name.as_str()),
+                    );
+                }
+
+                for function in protocol.functions() {
+                    let scopes = [protocol.annotations(), function.annotations()];
+                    naming(linter, &scopes, "Function", &function.name, Convention::Snake);
+                    documentation(linter, source, &scopes, "Function", &function.name);
+                }
+            }
+            Declaration::Validator(validator) => {
+                let scopes = [validator.annotations()];
+
+                item(linter, source, &scopes, &imports, "Validator", &validator.name, Convention::Pascal);
+                for property in validator.properties() {
+                    naming(linter, &scopes, "Property", &property.name, Convention::Snake);
+                }
+            }
+        }
+    }
+}
+
+/// Checks shared by every top level declaration
+fn item<T: AsRef<str>>(
+    linter: &mut Linter, source: &str, scopes: &[&[Annotation]], imports: &[ImportedName],
+    kind: &str, name: &Spanned<T>, convention: Convention,
+) {
+    naming(linter, scopes, kind, name, convention);
+    documentation(linter, source, scopes, kind, name);
+
+    let name_str = name.value.as_ref();
+    if let Some(import) = imports.iter().find(|import| import.name == name_str) {
+        linter.report(
+            Rule::ShadowedImports, scopes, name.span,
+            format!("{} '{}' shadows the imported '{}'", kind, name_str, import.name),
+        );
+    }
+}
+
+fn naming<T: AsRef<str>>(
+    linter: &mut Linter, scopes: &[&[Annotation]],
+    kind: &str, name: &Spanned<T>, convention: Convention,
+) {
+    let name_str = name.value.as_ref();
+    if convention.matches(name_str) { return }
+
+    linter.report(
+        Rule::NamingConvention, scopes, name.span,
+        format!(
+            "{} '{}' should have a {} name, such as '{}'",
+            kind, name_str, convention.label(), convention.convert(name_str)
+        ),
+    );
+}
+
+fn documentation<T: AsRef<str>>(
+    linter: &mut Linter, source: &str, scopes: &[&[Annotation]], kind: &str, name: &Spanned<T>,
+) {
+    if docstring_before(source, name.span.0).is_some() { return }
+
+    linter.report(
+        Rule::MissingDocs, scopes, name.span,
+        format!("{} '{}' is missing documentation", kind, name.value.as_ref()),
+    );
+}
+
+fn imported_names(declarations: &[Declaration]) -> Vec<ImportedName<'_>> {
+    let mut imports = vec![];
+
+    for declaration in declarations {
+        match declaration {
+            Declaration::Import(import) => {
+                imports.push(imported_path(&import.path, None, import.annotations()))
+            }
+            Declaration::Use(use_decl) => {
+                let alias = use_decl.alias.as_ref().map(|alias| alias.name.to_string());
+                let annotations = use_decl.annotations();
+
+                match &use_decl.path {
+                    UsePath::Absolute(path) => {
+                        imports.push(imported_path(path, alias, annotations))
+                    }
+                    UsePath::Relative(relative) => {
+                        imports.push(imported_path(&relative.path, alias, annotations))
+                    }
+                    UsePath::Multi(multi) => {
+                        imports.extend(multi.items().into_iter().map(|item| ImportedName {
+                            name: item.to_string(), span: item.span, annotations,
+                        }))
+                    }
+                    // What a glob brings in is only known once the package is compiled
+                    UsePath::Glob(_) => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    imports
+}
+
+fn imported_path<'a, T: AsRef<str>>(
+    path: &Spanned<T>, alias: Option<String>, annotations: &'a [Annotation],
+) -> ImportedName<'a> {
+    let last = last_segment(path.value.as_ref());
+
+    ImportedName {
+        name: alias.unwrap_or_else(|| last.to_owned()),
+        span: (path.span.1 - last.len(), path.span.1),
+        annotations,
+    }
+}
+
+/// Names a schema refers to by themselves, that is the leading segment of type
+/// paths and identifiers in constant and annotation values
+fn local_names(declarations: &[Declaration]) -> HashSet<String> {
+    let mut names = HashSet::new();
+
+    for declaration in declarations {
+        match declaration {
+            Declaration::Import(_) | Declaration::Use(_) => {}
+            Declaration::Const(constant) => {
+                add_annotations(&mut names, constant.annotations());
+                add_type(&mut names, constant.type_def());
+                if let Expression::Identifier(identifier) = constant.value() {
+                    names.insert(identifier.to_string());
+                }
+            }
+            Declaration::Struct(structure) => {
+                add_annotations(&mut names, structure.annotations());
+                for field in structure.fields() {
+                    add_annotations(&mut names, field.annotations());
+                    add_type(&mut names, field.field_type());
+                }
+            }
+            Declaration::Error(error) => {
+                add_annotations(&mut names, error.annotations());
+                for field in error.fields() {
+                    add_annotations(&mut names, field.annotations());
+                    add_type(&mut names, field.field_type());
+                }
+            }
+            Declaration::Enum(enumeration) => {
+                add_annotations(&mut names, enumeration.annotations());
+                for variant in enumeration.variants() {
+                    add_annotations(&mut names, variant.annotations());
+                }
+            }
+            Declaration::Protocol(protocol) => {
+                add_annotations(&mut names, protocol.annotations());
+                for function in protocol.functions() {
+                    add_annotations(&mut names, function.annotations());
+
+                    if let Some(arguments) = function.args() {
+                        add_type(&mut names, arguments.first().arg_type());
+                        for rest in arguments.rest() {
+                            add_type(&mut names, rest.arg_type().arg_type());
+                        }
+                    }
+                    if let Some(return_type) = function.return_type() {
+                        add_type(&mut names, return_type.return_type());
+                    }
+                    names.extend(function.throws().into_iter().map(|thrown| thrown.to_string()));
+                }
+            }
+            Declaration::Validator(validator) => {
+                add_annotations(&mut names, validator.annotations());
+                for property in validator.properties() {
+                    add_type(&mut names, property.property_type());
+                }
+            }
+        }
+    }
+
+    names
+}
+
+fn add_type(names: &mut HashSet<String>, type_def: &Type) {
+    let mut type_def = type_def;
+    while let Type::Array(array) = type_def { type_def = array.elem_type() }
+
+    if let Type::Named(path) = type_def {
+        names.insert(path.as_str().split("::").next().unwrap().to_owned());
+    }
+}
+
+fn add_annotations(names: &mut HashSet<String>, annotations: &[Annotation]) {
+    for annotation in annotations {
+        names.extend(annotation.value.names().into_iter().map(str::to_owned));
+    }
+}
+
+fn last_segment(path: &str) -> &str {
+    path.rsplit("::").next().unwrap()
+}
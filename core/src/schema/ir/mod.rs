@@ -4,5 +4,7 @@ pub mod context;
 pub mod compiler;
 pub mod diff;
 pub mod validation;
+pub mod lint;
+pub mod expression;
 
 // Standard Uses
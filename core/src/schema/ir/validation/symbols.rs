@@ -9,6 +9,8 @@ pub enum SymbolType {
     Function,
     Constant,
     Import,
+    Validator,
+    Error,
 }
 
 pub struct SymbolTable<'a> {
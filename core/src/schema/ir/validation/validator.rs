@@ -1,6 +1,8 @@
 use super::{ValidationError, symbols::{SymbolTable, SymbolType}};
 use crate::schema::ir::frozen::unit::FrozenUnit;
 use crate::schema::ir::compiler::interpreted::kind_search::KindValue;
+use crate::schema::ir::expression::{self, Validator};
+use crate::stdlib;
 use std::collections::{HashMap, HashSet};
 
 pub fn validate(units: &[FrozenUnit]) -> Result<(), Vec<ValidationError>> {
@@ -15,6 +17,8 @@ pub fn validate(units: &[FrozenUnit]) -> Result<(), Vec<ValidationError>> {
             FrozenUnit::Protocol { name, .. } => (name.as_str(), SymbolType::Protocol),
             FrozenUnit::Constant { name, .. } => (name.as_str(), SymbolType::Constant),
             FrozenUnit::Import(path) => (path.as_str(), SymbolType::Import),
+            FrozenUnit::Validator { name, .. } => (name.as_str(), SymbolType::Validator),
+            FrozenUnit::Error { name, .. } => (name.as_str(), SymbolType::Error),
             // TODO: Function handling if they become top-level
             _ => continue,
         };
@@ -35,11 +39,15 @@ pub fn validate(units: &[FrozenUnit]) -> Result<(), Vec<ValidationError>> {
     // Pass 2: Type Resolution & Usage
     for unit in units {
         match unit {
-            FrozenUnit::Struct { name, fields, .. } => {
+            FrozenUnit::Import(path) => validate_import(path, &mut errors),
+            FrozenUnit::Struct { name, fields, .. } | FrozenUnit::Error { name, fields, .. } => {
+                let kind = match unit { FrozenUnit::Error { .. } => "Error", _ => "Struct" };
                 for field in fields {
                     match field {
-                        FrozenUnit::Field { name: field_name, kind_value, .. } => {
-                            validate_type(kind_value, &symbols, &mut errors, &format!("Struct '{}', field '{}'", name, field_name));
+                        FrozenUnit::Field { name: field_name, kind_value, parameters, .. } => {
+                            let context = format!("{} '{}', field '{}'", kind, name, field_name);
+                            validate_type(kind_value, &symbols, &mut errors, &context);
+                            validate_uses(parameters, units, &symbols, &mut errors, &context);
                         }
                         _ => {}
                     }
@@ -48,18 +56,42 @@ pub fn validate(units: &[FrozenUnit]) -> Result<(), Vec<ValidationError>> {
             FrozenUnit::Protocol { name, functions, .. } => {
                 for func in functions {
                     match func {
-                        FrozenUnit::Function { name: func_name, arguments, _return, .. } => {
+                        FrozenUnit::Function { name: func_name, arguments, _return, throws, .. } => {
                             for arg in arguments {
                                 validate_type(&arg.kind, &symbols, &mut errors, &format!("Protocol '{}', function '{}', arg '{}'", name, func_name, arg.name));
                             }
                             if let Some(ret_type) = _return {
                                 validate_type(ret_type, &symbols, &mut errors, &format!("Protocol '{}', function '{}' return", name, func_name));
                             }
+                            // Errors thrown are frozen whole when declared in the schema, and by name otherwise
+                            for thrown in throws {
+                                let FrozenUnit::Name(error) = thrown else { continue };
+                                errors.push(ValidationError {
+                                    message: format!("Unknown error '{}', errors thrown have to be declared in the schema", error),
+                                    context: format!("Protocol '{}', function '{}'", name, func_name),
+                                });
+                            }
                         }
                         _ => {}
                     }
                 }
             }
+            FrozenUnit::Validator { name, properties, .. } => {
+                let context = format!("Validator '{}'", name);
+                for property in properties {
+                    if let FrozenUnit::Property { kind_value, .. } = property {
+                        validate_type(kind_value, &symbols, &mut errors, &context);
+                    }
+                }
+
+                let problems = match Validator::from_unit(unit) {
+                    Ok(validator) => validator.problems(),
+                    Err(error) => vec![format!("{:#}", error)],
+                };
+                errors.extend(problems.into_iter().map(|message| ValidationError {
+                    message, context: context.clone(),
+                }));
+            }
             FrozenUnit::Constant { name, kind_value, .. } => {
                 // Constants usually primitive, but check if namespaced
                 if let KindValue::Namespaced(type_name, _) = kind_value {
@@ -138,8 +170,8 @@ fn detect_cycle<'a>(
 fn validate_type(kind: &KindValue, symbols: &SymbolTable, errors: &mut Vec<ValidationError>, context: &str) {
     match kind {
         KindValue::Namespaced(type_name, _) => {
-            // Handle array syntax e.g. "User[]", "User[][]"
-            let base_type = type_name.trim_end_matches("[]");
+            // Handle array syntax e.g. "User[]", "User[][]", "User[4]"
+            let base_type = type_name.split('[').next().unwrap_or(type_name);
             
             // Allow primitives
             if is_primitive(base_type) {
@@ -163,6 +195,74 @@ fn validate_type(kind: &KindValue, symbols: &SymbolTable, errors: &mut Vec<Valid
     }
 }
 
+/// Validators a field uses have to be declared or imported, and those
+/// declared in the schema have to be given properties they have
+fn validate_uses(
+    parameters: &[FrozenUnit], units: &[FrozenUnit], symbols: &SymbolTable,
+    errors: &mut Vec<ValidationError>, context: &str
+) {
+    let uses = match expression::field_validators(parameters) {
+        Ok(uses) => uses,
+        Err(error) => {
+            errors.push(ValidationError { message: format!("{:#}", error), context: context.to_string() });
+            return
+        }
+    };
+
+    for used in uses {
+        let declared = units.iter().find(|unit| matches!(
+            unit, FrozenUnit::Validator { name, .. } if *name == used.name
+        ));
+
+        let message = match declared {
+            Some(unit) => match Validator::from_unit(unit).and_then(|v| v.parameters(&used.arguments)) {
+                Ok(_) => continue,
+                Err(error) => format!("{:#}", error),
+            },
+            None if is_imported(&used.name, symbols) => continue,
+            None => format!("Unknown validator '{}'", used.name),
+        };
+        errors.push(ValidationError { message, context: context.to_string() });
+    }
+}
+
+/// Imports of the standard library have to be of schemas it has, and of
+/// items those declare, other imports are only known once the package is compiled
+fn validate_import(path: &str, errors: &mut Vec<ValidationError>) {
+    let parts: Vec<String> = path.split("::").map(str::to_owned).collect();
+    if parts[0] != stdlib::PACKAGE_NAME { return }
+
+    let context = format!("Import '{}'", path);
+    let Some(schema) = stdlib::schema_of_import(&parts) else {
+        errors.push(ValidationError { message: format!("'{}' is not in the standard library", path), context });
+        return
+    };
+
+    let items = parts[schema.namespace.len()..].join("::");
+    for item in items.trim_matches(|c| c == '{' || c == '}').split(',').map(str::trim) {
+        // Aliased items are declared by the name before the alias
+        let item = item.split(" as ").next().unwrap_or(item).trim();
+        if item.is_empty() || item == "*" || schema.declares(item) { continue }
+
+        errors.push(ValidationError {
+            message: format!("'{}' is not declared in {}", item, schema.namespace.join("::")),
+            context: context.clone(),
+        });
+    }
+}
+
+/// Whether an import may bring in the name, which is only certain once the
+/// package is compiled
+fn is_imported(name: &str, symbols: &SymbolTable) -> bool {
+    symbols.symbols.iter().any(|(path, kind)| {
+        if *kind != SymbolType::Import { return false }
+
+        let Some((_, imported)) = path.rsplit_once("::") else { return false };
+        imported == name || imported == "*" || imported.trim_matches(|c| c == '{' || c == '}')
+            .split(',').any(|item| item.trim() == name)
+    })
+}
+
 fn is_primitive(name: &str) -> bool {
     matches!(name, 
         "bool" | "u8" | "u16" | "u32" | "u64" | "u128" | 
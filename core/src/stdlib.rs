@@ -0,0 +1,66 @@
+// The standard library package, whose schemas comline-core-stdlib embeds
+// and are compiled the first time they are asked for
+
+// Relative Modules
+
+// Standard Uses
+
+// Crate Uses
+use crate::codelib_gen::SchemaUnits;
+use crate::schema::idl::grammar;
+use crate::schema::ir::compiler::interpreter::incremental::{attach_docstrings, IncrementalInterpreter};
+use crate::schema::ir::compiler::Compile;
+
+// External Uses
+use eyre::{eyre, Result};
+use once_cell::sync::Lazy;
+pub use comline_core_stdlib::SOURCES;
+
+
+/// Name of the standard library package, which imports of it start with
+pub const PACKAGE_NAME: &str = "std";
+
+static SCHEMAS: Lazy<Vec<SchemaUnits>> = Lazy::new(|| {
+    compile().unwrap_or_else(|error| panic!("The standard library does not compile: {:#}", error))
+});
+
+
+/// The frozen schemas of the standard library, whose namespaces start with
+/// the package name, as in `std::validators::string_bounds`
+pub fn schemas() -> &'static [SchemaUnits] {
+    &SCHEMAS
+}
+
+/// The standard library schema at a namespace starting with the package name
+pub fn schema(namespace: &[String]) -> Option<&'static SchemaUnits> {
+    schemas().iter().find(|schema| schema.namespace == namespace)
+}
+
+/// The source of the standard library schema at a namespace starting with
+/// the package name
+pub fn source(namespace: &[String]) -> Option<&'static str> {
+    let (package, path) = namespace.split_first()?;
+    if package != PACKAGE_NAME { return None }
+
+    let path = path.join("/");
+    SOURCES.iter().find(|(schema, _)| *schema == path).map(|(_, source)| *source)
+}
+
+/// The schema an import of the standard library brings items from, like
+/// `std::validators::string_bounds` for `std::validators::string_bounds::StringBounds`
+pub fn schema_of_import(path: &[String]) -> Option<&'static SchemaUnits> {
+    (1..=path.len()).rev().find_map(|length| schema(&path[..length]))
+}
+
+fn compile() -> Result<Vec<SchemaUnits>> {
+    SOURCES.iter().map(|(path, source)| {
+        let document = grammar::parse(source)
+            .map_err(|errors| eyre!("'{}' has {} parse error(s): {:?}", path, errors.len(), errors))?;
+
+        let mut units = IncrementalInterpreter::from_declarations(document.0.clone());
+        attach_docstrings(&mut units, &document.0, source);
+
+        let namespace = std::iter::once(PACKAGE_NAME).chain(path.split('/')).map(str::to_owned).collect();
+        Ok(SchemaUnits { namespace, units })
+    }).collect()
+}
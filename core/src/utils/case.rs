@@ -0,0 +1,74 @@
+// Identifier casing
+// Names are split into words on underscores and on case changes, so that
+// `userID`, `user_id` and `UserId` all read as the same words
+
+// Standard Uses
+
+// Crate Uses
+
+// External Uses
+
+
+/// Words of an identifier, e.g. `HTTPServer_port` is `HTTP`, `Server` and `port`
+pub fn words(name: &str) -> Vec<&str> {
+    let mut words = vec![];
+
+    for part in name.split('_').filter(|p| !p.is_empty()) {
+        let chars: Vec<(usize, char)> = part.char_indices().collect();
+        let mut start = 0;
+
+        for window in 1..chars.len() {
+            let (index, current) = chars[window];
+            let previous = chars[window - 1].1;
+            let next = chars.get(window + 1).map(|(_, c)| *c);
+
+            let lower_to_upper = current.is_uppercase()
+                && (previous.is_lowercase() || previous.is_ascii_digit());
+            // The last capital of an acronym starts the next word, as in `HTTPServer`
+            let acronym_end = current.is_uppercase() && previous.is_uppercase()
+                && next.is_some_and(|c| c.is_lowercase());
+
+            if lower_to_upper || acronym_end {
+                words.push(&part[start..index]);
+                start = index;
+            }
+        }
+        words.push(&part[start..]);
+    }
+
+    words
+}
+
+pub fn is_pascal_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+pub fn is_snake_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+pub fn is_screaming_snake_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase() || c == '_')
+        && name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// `user_id` into `UserId`
+pub fn to_pascal_case(name: &str) -> String {
+    words(name).iter().map(|word| {
+        let mut chars = word.chars();
+        let first = chars.next().map(|c| c.to_ascii_uppercase()).unwrap_or_default();
+        std::iter::once(first).chain(chars.map(|c| c.to_ascii_lowercase())).collect::<String>()
+    }).collect()
+}
+
+/// `UserId` into `user_id`
+pub fn to_snake_case(name: &str) -> String {
+    words(name).iter().map(|w| w.to_ascii_lowercase()).collect::<Vec<_>>().join("_")
+}
+
+/// `maxLength` into `MAX_LENGTH`
+pub fn to_screaming_snake_case(name: &str) -> String {
+    words(name).iter().map(|w| w.to_ascii_uppercase()).collect::<Vec<_>>().join("_")
+}
@@ -0,0 +1,307 @@
+// Building blocks shared by the schema and configuration formatters
+// Comments are extras to the grammars, so formatters work on a token stream
+// where every comment is attached to the code token it belongs with
+
+// Standard Uses
+
+// Crate Uses
+
+// External Uses
+use eyre::{bail, eyre, Result};
+
+
+/// Width past which lists are broken into one item per line
+pub const MAX_WIDTH: usize = 100;
+pub const INDENT: &str = "    ";
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Word,
+    String,
+    Punctuation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    pub text: String,
+    /// Whether an empty line separates the comment from what comes before it
+    pub blank_before: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub text: String,
+    /// Whether an empty line separates the token, or its comments, from what comes before
+    pub blank_before: bool,
+    /// Comments on their own lines right before the token
+    pub leading: Vec<Comment>,
+    /// Comments after the token on the same line
+    pub trailing: Vec<String>,
+}
+
+pub struct TokenStream {
+    tokens: Vec<Token>,
+    position: usize,
+    /// Comments after the last token
+    pub end_comments: Vec<Comment>,
+}
+
+impl TokenStream {
+    pub fn new(source: &str) -> Result<Self> {
+        let mut tokens: Vec<Token> = vec![];
+        let mut pending: Vec<Comment> = vec![];
+        let mut newlines = 0;
+        let mut rest = source;
+
+        while let Some(c) = rest.chars().next() {
+            if c.is_whitespace() {
+                if c == '\n' { newlines += 1 }
+                rest = &rest[c.len_utf8()..];
+                continue
+            }
+
+            let length = token_length(rest)?;
+            let text = &rest[..length];
+            rest = &rest[length..];
+
+            if text.starts_with("//") || text.starts_with("/*") {
+                match tokens.last_mut() {
+                    Some(previous) if newlines == 0 && pending.is_empty() => {
+                        previous.trailing.push(text.to_owned())
+                    }
+                    _ => pending.push(Comment {
+                        text: text.to_owned(),
+                        blank_before: newlines > 1 && !(tokens.is_empty() && pending.is_empty()),
+                    }),
+                }
+            } else {
+                let kind = match c {
+                    '"' => TokenKind::String,
+                    '@' | '*' | ':' if length == 1 => TokenKind::Punctuation,
+                    _ if is_word_char(c) || (c == '-' && length > 1) => TokenKind::Word,
+                    _ => TokenKind::Punctuation,
+                };
+                let blank_before = match pending.first() {
+                    Some(comment) => comment.blank_before,
+                    None => newlines > 1 && !tokens.is_empty(),
+                };
+
+                tokens.push(Token {
+                    kind,
+                    text: text.to_owned(),
+                    blank_before,
+                    leading: std::mem::take(&mut pending),
+                    trailing: vec![],
+                });
+            }
+
+            newlines = 0;
+        }
+
+        Ok(Self { tokens, position: 0, end_comments: pending })
+    }
+
+    pub fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    pub fn peek_is(&self, text: &str) -> bool {
+        self.peek().is_some_and(|t| t.text == text)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.position >= self.tokens.len()
+    }
+
+    pub fn take(&mut self) -> Result<Token> {
+        let token = self.tokens.get(self.position).cloned()
+            .ok_or_else(|| eyre!("Unexpected end of file"))?;
+        self.position += 1;
+
+        Ok(token)
+    }
+
+    pub fn expect(&mut self, text: &str) -> Result<Token> {
+        let token = self.take()?;
+        if token.text != text {
+            bail!("Expected '{}' but found '{}'", text, token.text)
+        }
+
+        Ok(token)
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '.' | '#' | '@' | ':' | '*')
+}
+
+/// Length of the token at the start of `text`
+fn token_length(text: &str) -> Result<usize> {
+    if text.starts_with("//") {
+        return Ok(text.find('\n').unwrap_or(text.len()))
+    }
+    if let Some(body) = text.strip_prefix("/*") {
+        let end = body.find("*/").ok_or_else(|| eyre!("Unterminated block comment"))?;
+        return Ok(end + 4)
+    }
+    if text.starts_with("->") {
+        return Ok(2)
+    }
+
+    let mut chars = text.char_indices();
+    let (_, first) = chars.next().unwrap();
+    match first {
+        '"' => {
+            let mut escaped = false;
+            for (index, c) in chars {
+                match c {
+                    '\\' if !escaped => escaped = true,
+                    '"' if !escaped => return Ok(index + 1),
+                    '\n' => break,
+                    _ => escaped = false,
+                }
+            }
+            bail!("Unterminated string literal")
+        }
+        // Single colons separate names from types, double ones are part of paths
+        ':' if !text.starts_with("::") => Ok(1),
+        '@' | '*' => Ok(1),
+        '-' if text[1..].starts_with(|c: char| c.is_ascii_digit()) => Ok(word_length(text, 1)),
+        c if is_word_char(c) => Ok(word_length(text, 0)),
+        c if "{}[]()<>,;=!".contains(c) => Ok(1),
+        c => bail!("Unexpected character '{}'", c),
+    }
+}
+
+fn word_length(text: &str, start: usize) -> usize {
+    let bytes = text.as_bytes();
+    let mut end = start;
+
+    while end < text.len() {
+        let c = text[end..].chars().next().unwrap();
+        if c == ':' {
+            // Only `::` continues a path
+            if bytes.get(end + 1) != Some(&b':') { break }
+            end += 2;
+            continue
+        }
+        if !is_word_char(c) { break }
+        end += c.len_utf8();
+    }
+
+    end
+}
+
+
+/// An output line, with the comments that go above and after it
+#[derive(Debug, Clone, Default)]
+pub struct Line {
+    pub indent: usize,
+    pub blank_before: bool,
+    pub comments: Vec<Comment>,
+    pub text: String,
+    pub trailing: Vec<String>,
+}
+
+impl Line {
+    pub fn new(indent: usize) -> Self {
+        Self { indent, ..Default::default() }
+    }
+
+    /// Appends a token to the line, taking over its comments
+    pub fn push(&mut self, token: Token) -> &mut Self {
+        if self.text.is_empty() && self.comments.is_empty() {
+            self.blank_before = token.blank_before;
+        }
+        self.comments.extend(token.leading);
+        self.trailing.extend(token.trailing);
+        self.text.push_str(&token.text);
+
+        self
+    }
+
+    /// Takes over the comments of a token without writing it
+    pub fn absorb(&mut self, token: Token) -> &mut Self {
+        let text = String::new();
+        self.push(Token { text, ..token })
+    }
+
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        self.text.push_str(text);
+        self
+    }
+}
+
+/// Renders lines, `blank_before` having already been decided by the formatter
+pub fn render(lines: &[Line], end_comments: &[Comment]) -> String {
+    let mut out = String::new();
+
+    for line in lines {
+        let indent = INDENT.repeat(line.indent);
+
+        if line.blank_before && !out.is_empty() { out.push('\n') }
+        for (index, comment) in line.comments.iter().enumerate() {
+            if index > 0 && comment.blank_before { out.push('\n') }
+            out.push_str(&format!("{}{}\n", indent, comment.text));
+        }
+
+        if line.text.is_empty() && line.trailing.is_empty() { continue }
+
+        out.push_str(&indent);
+        out.push_str(&line.text);
+        for comment in &line.trailing {
+            if !line.text.is_empty() { out.push(' ') }
+            out.push_str(comment);
+        }
+        out.push('\n');
+    }
+
+    for comment in end_comments {
+        if comment.blank_before && !out.is_empty() { out.push('\n') }
+        out.push_str(&comment.text);
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Pads the first part of aligned lines so their second parts start on the same column
+pub fn align(rows: &mut [(Line, String)], separator: &str) {
+    let width = rows.iter().map(|(line, _)| line.text.chars().count()).max().unwrap_or(0);
+
+    for (line, second) in rows.iter_mut() {
+        let padding = width - line.text.chars().count();
+        let text = format!("{}{}{}", " ".repeat(padding), separator, second);
+        line.text.push_str(&text);
+    }
+}
+
+/// Header, members and closing brace, or all on one line if there are no members
+/// nor comments before the brace
+pub fn block(mut header: Line, mut members: Vec<Line>, close: Token) -> Vec<Line> {
+    let indent = header.indent;
+    if let Some(first) = members.first_mut() { first.blank_before = false }
+
+    if members.is_empty() && close.leading.is_empty() {
+        header.push(close);
+        return vec![header]
+    }
+
+    let mut lines = vec![header];
+    lines.extend(members);
+
+    // Comments before the closing brace stay inside the block
+    let mut closing = Line::new(indent);
+    if !close.leading.is_empty() {
+        let mut comments = Line::new(indent + 1);
+        comments.comments = close.leading.clone();
+        comments.blank_before = close.blank_before && lines.len() > 1;
+        lines.push(comments);
+    }
+    closing.push(Token { leading: vec![], blank_before: false, ..close });
+    lines.push(closing);
+
+    lines
+}
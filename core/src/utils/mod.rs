@@ -1,2 +1,4 @@
+pub mod case;
 pub mod codemap;
+pub mod formatting;
 pub mod templating;
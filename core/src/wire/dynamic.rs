@@ -0,0 +1,558 @@
+// Standard Uses
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+// Crate Uses
+use crate::codelib_gen::{GenerationContext, SchemaUnits};
+use crate::package::build::{self, cas};
+use crate::package::config::ir::context::ProjectContext;
+use crate::schema::ir::context::SchemaContext;
+use crate::schema::ir::expression::{self, Validator, Value};
+use crate::wire::frozen_schemas;
+use crate::wire::shape::{declared, fields, kind_name, shape_of_kind, split_path, Field, Integer, Shape};
+
+// External Uses
+use comline_runtime::wire::{self, Decode, DecodeError, Encode, Reader};
+use eyre::{bail, eyre, Result};
+
+
+/// Compiled schemas that values are handled after, for tools that have no
+/// code generated for them
+#[derive(Debug, Clone)]
+pub struct DynamicSchemas {
+    context: GenerationContext,
+}
+
+impl DynamicSchemas {
+    /// Schemas of a package, where paths to types may start with its name
+    pub fn new(package_name: &str, schemas: Vec<SchemaUnits>) -> Self {
+        Self {
+            context: GenerationContext {
+                package_name: package_name.to_owned(),
+                package_version: String::new(),
+                schemas,
+                options: BTreeMap::new(),
+                history: vec![],
+            },
+        }
+    }
+
+    /// The schemas of a compiled package
+    pub fn from_project(context: &ProjectContext) -> Result<Self> {
+        Ok(Self::new(build::package_name(context), build::compiled_schemas(context)?))
+    }
+
+    /// A compiled schema on its own, whose types can only be of each other
+    pub fn from_schema(schema: &SchemaContext) -> Result<Self> {
+        let Some(units) = schema.frozen_schema.borrow().clone() else {
+            bail!("Schema '{}' was not compiled", schema.namespace_joined())
+        };
+
+        Ok(Self::new("", vec![SchemaUnits { namespace: schema.namespace.clone(), units }]))
+    }
+
+    /// The schemas a commit of the package at `package_path` froze, given by
+    /// its hash or the start of it
+    pub fn from_commit(package_path: &Path, hash: &str) -> Result<Self> {
+        let Some((_, commit)) = cas::history::find_commit(package_path, hash)? else {
+            bail!("Package at '{}' has no commit with hash '{}'", package_path.display(), hash)
+        };
+
+        // Schemas frozen before namespaces were kept take those the package
+        // has now at their positions
+        let context = build::compile(package_path)?;
+        let current = build::compiled_schemas(&context)?;
+
+        Ok(Self::new(build::package_name(&context), frozen_schemas(package_path, &commit, &current)?))
+    }
+
+    /// The struct or enum of a path, like `members::Member`, which cannot be
+    /// of types that are not declared in the schemas
+    pub fn type_of(&self, type_path: &str) -> Result<DynamicType<'_>> {
+        let (namespace, name) = split_path(type_path)?;
+        let shape = declared(&self.context, &namespace, name)
+            .ok_or_else(|| eyre!("'{}' is not a struct nor an enum of the schemas", type_path))?;
+
+        let r#type = DynamicType { context: &self.context, path: type_path.to_owned(), shape };
+        let mut problems = vec![];
+        r#type.undeclared(&r#type.shape, type_path, &mut HashSet::new(), &mut problems);
+        if !problems.is_empty() {
+            bail!("'{}' cannot be handled dynamically:\n  - {}", type_path, problems.join("\n  - "))
+        }
+
+        Ok(r#type)
+    }
+}
+
+
+/// A struct or enum of the schemas, that values are checked, encoded and
+/// decoded after
+#[derive(Debug, Clone)]
+pub struct DynamicType<'a> {
+    pub(super) context: &'a GenerationContext,
+    pub(super) path: String,
+    pub(super) shape: Shape<'a>,
+}
+
+impl<'a> DynamicType<'a> {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Checks that a value is of the type, with every problem it has
+    pub fn validate(&self, value: &DynamicValue) -> Result<()> {
+        let mut problems = vec![];
+        self.check(&self.shape, value, &self.path, &mut problems);
+
+        match problems.is_empty() {
+            true => Ok(()),
+            false => bail!("Value is not a valid '{}':\n  - {}", self.path, problems.join("\n  - ")),
+        }
+    }
+
+    /// Encodes a value of the type, after validating it
+    pub fn encode(&self, value: &DynamicValue) -> Result<Vec<u8>> {
+        self.validate(value)?;
+
+        let mut buffer = vec![];
+        self.encode_shape(&self.shape, value, &mut buffer);
+        Ok(buffer)
+    }
+
+    /// Decodes a value of the type from bytes, which must hold nothing else
+    pub fn decode(&self, bytes: &[u8]) -> Result<DynamicValue, DecodeError> {
+        let mut reader = Reader::new(bytes);
+        let value = self.decode_shape(&self.shape, &mut reader)?;
+
+        match reader.remaining() {
+            0 => Ok(value),
+            remaining => Err(DecodeError::TrailingBytes(remaining)),
+        }
+    }
+
+    /// Types the shape may hold that are not declared
+    fn undeclared(&self, shape: &Shape<'a>, item: &str, visited: &mut HashSet<String>, problems: &mut Vec<String>) {
+        match shape {
+            Shape::Array(element, _) => self.undeclared(element, item, visited, problems),
+            Shape::Struct(declaration, units) => {
+                if !visited.insert(declaration.path.clone()) { return }
+
+                for field in fields(units) {
+                    let item = format!("{}::{}", declaration.path, field.name);
+                    let field_shape = shape_of_kind(self.context, declaration.schema, field.kind);
+                    self.undeclared(&field_shape, &item, visited, problems);
+                }
+            }
+            Shape::Union(schema, kinds) => for kind in kinds {
+                self.undeclared(&shape_of_kind(self.context, *schema, kind), item, visited, problems)
+            },
+            Shape::Unknown(name) => {
+                problems.push(format!("'{}' is of {}, which is not declared in the schemas", item, name))
+            }
+            _ => {}
+        }
+    }
+
+    fn check(&self, shape: &Shape<'a>, value: &DynamicValue, at: &str, problems: &mut Vec<String>) {
+        match (shape, value) {
+            (Shape::Bool, DynamicValue::Bool(_))
+            | (Shape::Float(_), DynamicValue::Float(_))
+            | (Shape::String, DynamicValue::String(_)) => {}
+            (Shape::Integer(integer), DynamicValue::Unsigned(_) | DynamicValue::Signed(_)) => {
+                if integer_value(integer, value).is_none() {
+                    problems.push(format!("'{}' is {}, which does not fit {}", at, value, integer.name()))
+                }
+            }
+            (Shape::Array(element, count), DynamicValue::Array(elements)) => {
+                if let Some(count) = count.filter(|count| *count != elements.len()) {
+                    problems.push(format!("'{}' has {} elements instead of {}", at, elements.len(), count))
+                }
+                for (index, value) in elements.iter().enumerate() {
+                    self.check(element, value, &format!("{}[{}]", at, index), problems)
+                }
+            }
+            (Shape::Struct(declaration, units), DynamicValue::Struct(values)) => {
+                let fields = fields(units);
+
+                for field in &fields {
+                    let at = format!("{}.{}", at, field.name);
+                    match value.field(field.name) {
+                        Some(value) => {
+                            let field_shape = shape_of_kind(self.context, declaration.schema, field.kind);
+                            let found = problems.len();
+                            self.check(&field_shape, value, &at, problems);
+
+                            // Validators are only given values of the right shape
+                            if problems.len() == found {
+                                self.run_validators(declaration.schema, field, value, &at, problems)
+                            }
+                        }
+                        None if !field.optional => problems.push(format!("'{}' is missing", at)),
+                        None => {}
+                    }
+                }
+
+                let mut given = HashSet::new();
+                for (name, _) in values {
+                    if !fields.iter().any(|field| field.name == name) {
+                        problems.push(format!("'{}.{}' is not a field of {}", at, name, declaration.path))
+                    } else if !given.insert(name) {
+                        problems.push(format!("'{}.{}' is given more than once", at, name))
+                    }
+                }
+            }
+            (Shape::Enum(declaration, variants), DynamicValue::Enum(variant)) => {
+                if !variants.contains(&variant.as_str()) {
+                    problems.push(format!("'{}' is '{}', which is not a variant of {}", at, variant, declaration.path))
+                }
+            }
+            (Shape::Union(schema, kinds), DynamicValue::Union(kind, value)) => {
+                match kinds.iter().find(|candidate| kind_name(candidate) == *kind) {
+                    Some(found) => self.check(&shape_of_kind(self.context, *schema, found), value, at, problems),
+                    None => problems.push(format!(
+                        "'{}' is of kind {}, which is not a kind of {}", at, kind, shape.describe()
+                    )),
+                }
+            }
+            (shape, value) => problems.push(format!(
+                "'{}' should be {}, not {}", at, shape.describe(), value.kind()
+            )),
+        }
+    }
+
+    /// Checks the value of a field with the validators the field uses
+    fn run_validators(&self, schema: usize, field: &Field, value: &DynamicValue, at: &str, problems: &mut Vec<String>) {
+        let uses = match expression::field_validators(field.parameters) {
+            Ok(uses) => uses,
+            Err(error) => return problems.push(format!("'{}' has validators that cannot be read: {:#}", at, error)),
+        };
+
+        for used in uses {
+            let Some(unit) = self.context.validator(&self.context.schemas[schema], &used.name) else {
+                problems.push(format!("'{}' uses {}, which is not declared in the schemas", at, used.name));
+                continue
+            };
+            let failures = Validator::from_unit(unit).and_then(|validator| {
+                let Some(value) = value.expression_value() else {
+                    bail!("structs are not values validators can check")
+                };
+                validator.check(field.name, &value, &validator.parameters(&used.arguments)?)
+            });
+
+            match failures {
+                Ok(failures) => problems.extend(failures.into_iter().map(|failure| {
+                    format!("'{}' fails {}: {}", at, used.name, failure)
+                })),
+                Err(error) => problems.push(format!("'{}' cannot be checked by {}: {:#}", at, used.name, error)),
+            }
+        }
+    }
+
+    /// Encodes a value that was validated
+    fn encode_shape(&self, shape: &Shape<'a>, value: &DynamicValue, buffer: &mut Vec<u8>) {
+        match (shape, value) {
+            (Shape::Bool, DynamicValue::Bool(value)) => value.encode(buffer),
+            (Shape::Integer(integer), value) => integer.write(buffer, integer_value(integer, value).unwrap()),
+            (Shape::Float(32), DynamicValue::Float(value)) => (*value as f32).encode(buffer),
+            (Shape::Float(_), DynamicValue::Float(value)) => value.encode(buffer),
+            (Shape::String, DynamicValue::String(value)) => value.encode(buffer),
+            (Shape::Array(element, count), DynamicValue::Array(elements)) => {
+                if count.is_none() { wire::write_varint(buffer, elements.len() as u128) }
+                for value in elements { self.encode_shape(element, value, buffer) }
+            }
+            (Shape::Struct(declaration, units), value) => for field in fields(units) {
+                let field_shape = shape_of_kind(self.context, declaration.schema, field.kind);
+
+                match (field.optional, value.field(field.name)) {
+                    (true, None) => buffer.push(0),
+                    (true, Some(value)) => {
+                        buffer.push(1);
+                        self.encode_shape(&field_shape, value, buffer)
+                    }
+                    (false, value) => self.encode_shape(&field_shape, value.unwrap(), buffer),
+                }
+            },
+            (Shape::Enum(_, variants), DynamicValue::Enum(variant)) => {
+                let index = variants.iter().position(|candidate| candidate == variant).unwrap();
+                wire::write_varint(buffer, index as u128)
+            }
+            (Shape::Union(schema, kinds), DynamicValue::Union(kind, value)) => {
+                let index = kinds.iter().position(|candidate| kind_name(candidate) == *kind).unwrap();
+                wire::write_varint(buffer, index as u128);
+                self.encode_shape(&shape_of_kind(self.context, *schema, kinds[index]), value, buffer)
+            }
+            _ => unreachable!("Values are validated before they are encoded"),
+        }
+    }
+
+    fn decode_shape(&self, shape: &Shape<'a>, reader: &mut Reader) -> Result<DynamicValue, DecodeError> {
+        Ok(match shape {
+            Shape::Bool => DynamicValue::Bool(reader.read_tag()?),
+            Shape::Integer(integer) => {
+                let value = integer.read(reader)?;
+
+                // Varints may hold more than the integer does
+                match integer.signed {
+                    true if integer.value_of_signed(value).is_some() => DynamicValue::Signed(value),
+                    false if integer.value_of_unsigned(value as u128).is_some() => DynamicValue::Unsigned(value as u128),
+                    true => return Err(DecodeError::OutOfRange { type_name: integer.name(), value: wire::zigzag(value) }),
+                    false => return Err(DecodeError::OutOfRange { type_name: integer.name(), value: value as u128 }),
+                }
+            }
+            Shape::Float(32) => DynamicValue::Float(f32::decode(reader)? as f64),
+            Shape::Float(_) => DynamicValue::Float(f64::decode(reader)?),
+            Shape::String => DynamicValue::String(String::decode(reader)?),
+            Shape::Array(element, count) => {
+                let count = match count {
+                    Some(count) => *count,
+                    None => reader.read_count()?,
+                };
+
+                // Counts are not trusted for how much to allocate up front
+                let mut elements = Vec::with_capacity(count.min(reader.remaining()));
+                for _ in 0..count { elements.push(self.decode_shape(element, reader)?) }
+                DynamicValue::Array(elements)
+            }
+            Shape::Struct(declaration, units) => {
+                let mut values = vec![];
+                for field in fields(units) {
+                    if field.optional && !reader.read_tag()? { continue }
+
+                    let field_shape = shape_of_kind(self.context, declaration.schema, field.kind);
+                    values.push((field.name.to_owned(), self.decode_shape(&field_shape, reader)?));
+                }
+                DynamicValue::Struct(values)
+            }
+            Shape::Enum(_, variants) => {
+                let discriminant = reader.read_varint()?;
+                let variant = usize::try_from(discriminant).ok().and_then(|index| variants.get(index))
+                    .ok_or(DecodeError::UnknownDiscriminant { type_name: "enum", discriminant })?;
+                DynamicValue::Enum(variant.to_string())
+            }
+            Shape::Union(schema, kinds) => {
+                let discriminant = reader.read_varint()?;
+                let kind = usize::try_from(discriminant).ok().and_then(|index| kinds.get(index))
+                    .ok_or(DecodeError::UnknownDiscriminant { type_name: "union", discriminant })?;
+                let value = self.decode_shape(&shape_of_kind(self.context, *schema, kind), reader)?;
+                DynamicValue::Union(kind_name(kind), Box::new(value))
+            }
+            Shape::Unknown(_) => unreachable!("Types are checked to be declared when they are found"),
+        })
+    }
+}
+
+/// The value of an integer, as [`Integer::read`] gives them
+pub(super) fn integer_value(integer: &Integer, value: &DynamicValue) -> Option<i128> {
+    match value {
+        DynamicValue::Unsigned(value) => integer.value_of_unsigned(*value),
+        DynamicValue::Signed(value) => integer.value_of_signed(*value),
+        _ => None,
+    }
+}
+
+
+/// A value of a type of the schemas, which [`DynamicType`] checks against it
+#[derive(Debug, Clone, PartialEq)]
+pub enum DynamicValue {
+    Bool(bool),
+    Unsigned(u128),
+    Signed(i128),
+    Float(f64),
+    String(String),
+    Array(Vec<DynamicValue>),
+    /// Fields by name, where optional fields that are absent are left out
+    Struct(Vec<(String, DynamicValue)>),
+    /// Name of the variant
+    Enum(String),
+    /// Kind of the value as the union is written with, like `u64` or
+    /// `Member[]`, and the value
+    Union(String, Box<DynamicValue>),
+}
+
+impl DynamicValue {
+    /// A struct of fields, in any order
+    pub fn structure<N: Into<String>>(fields: impl IntoIterator<Item = (N, DynamicValue)>) -> Self {
+        Self::Struct(fields.into_iter().map(|(name, value)| (name.into(), value)).collect())
+    }
+
+    pub fn variant(name: &str) -> Self {
+        Self::Enum(name.to_owned())
+    }
+
+    pub fn union(kind: &str, value: impl Into<DynamicValue>) -> Self {
+        Self::Union(kind.to_owned(), Box::new(value.into()))
+    }
+
+    /// A field of a struct, which is absent for other values
+    pub fn field(&self, name: &str) -> Option<&DynamicValue> {
+        match self {
+            Self::Struct(fields) => fields.iter().find(|(field, _)| field == name).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn field_mut(&mut self, name: &str) -> Option<&mut DynamicValue> {
+        match self {
+            Self::Struct(fields) => fields.iter_mut().find(|(field, _)| field == name).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Sets a field of a struct, giving back its previous value, while
+    /// other values are left as they are
+    pub fn set_field(&mut self, name: &str, value: impl Into<DynamicValue>) -> Option<DynamicValue> {
+        let Self::Struct(fields) = self else { return None };
+
+        match fields.iter_mut().find(|(field, _)| field == name) {
+            Some((_, previous)) => Some(std::mem::replace(previous, value.into())),
+            None => {
+                fields.push((name.to_owned(), value.into()));
+                None
+            }
+        }
+    }
+
+    /// Removes a field of a struct, which makes an optional field absent
+    pub fn remove_field(&mut self, name: &str) -> Option<DynamicValue> {
+        let Self::Struct(fields) = self else { return None };
+
+        let index = fields.iter().position(|(field, _)| field == name)?;
+        Some(fields.remove(index).1)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_u128(&self) -> Option<u128> {
+        match self {
+            Self::Unsigned(value) => Some(*value),
+            Self::Signed(value) => u128::try_from(*value).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_i128(&self) -> Option<i128> {
+        match self {
+            Self::Unsigned(value) => i128::try_from(*value).ok(),
+            Self::Signed(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The string, or the name of the variant of an enum
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) | Self::Enum(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[DynamicValue]> {
+        match self {
+            Self::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    /// What kind of value it is, for reports
+    /// The value as expressions of validators see it, which do not see structs
+    pub fn expression_value(&self) -> Option<Value> {
+        Some(match self {
+            DynamicValue::Bool(value) => Value::Bool(*value),
+            DynamicValue::Unsigned(value) => match i128::try_from(*value) {
+                Ok(value) => Value::Integer(value),
+                Err(_) => Value::Float(*value as f64),
+            },
+            DynamicValue::Signed(value) => Value::Integer(*value),
+            DynamicValue::Float(value) => Value::Float(*value),
+            DynamicValue::String(value) | DynamicValue::Enum(value) => Value::String(value.clone()),
+            DynamicValue::Array(values) => {
+                Value::List(values.iter().map(DynamicValue::expression_value).collect::<Option<_>>()?)
+            }
+            DynamicValue::Union(_, value) => value.expression_value()?,
+            DynamicValue::Struct(_) => return None,
+        })
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Bool(_) => "a bool",
+            Self::Unsigned(_) | Self::Signed(_) => "an integer",
+            Self::Float(_) => "a float",
+            Self::String(_) => "a string",
+            Self::Array(_) => "an array",
+            Self::Struct(_) => "a struct",
+            Self::Enum(_) => "an enum variant",
+            Self::Union(..) => "a union",
+        }
+    }
+}
+
+impl std::fmt::Display for DynamicValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bool(value) => write!(f, "{}", value),
+            Self::Unsigned(value) => write!(f, "{}", value),
+            Self::Signed(value) => write!(f, "{}", value),
+            Self::Float(value) => write!(f, "{}", value),
+            Self::String(value) => write!(f, "{:?}", value),
+            Self::Array(elements) => {
+                write!(f, "[")?;
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 { write!(f, ", ")? }
+                    write!(f, "{}", element)?;
+                }
+                write!(f, "]")
+            }
+            Self::Struct(fields) => {
+                write!(f, "{{")?;
+                for (index, (name, value)) in fields.iter().enumerate() {
+                    if index > 0 { write!(f, ",")? }
+                    write!(f, " {}: {}", name, value)?;
+                }
+                write!(f, " }}")
+            }
+            Self::Enum(variant) => write!(f, "{}", variant),
+            Self::Union(kind, value) => write!(f, "{}({})", kind, value),
+        }
+    }
+}
+
+macro_rules! from_values {
+    ($variant:ident as $inner:ty: $($type:ty),*) => {$(
+        impl From<$type> for DynamicValue {
+            fn from(value: $type) -> Self { Self::$variant(value as $inner) }
+        }
+    )*};
+}
+
+from_values!(Unsigned as u128: u8, u16, u32, u64, u128);
+from_values!(Signed as i128: i8, i16, i32, i64, i128);
+from_values!(Float as f64: f32, f64);
+
+impl From<bool> for DynamicValue {
+    fn from(value: bool) -> Self { Self::Bool(value) }
+}
+
+impl From<&str> for DynamicValue {
+    fn from(value: &str) -> Self { Self::String(value.to_owned()) }
+}
+
+impl From<String> for DynamicValue {
+    fn from(value: String) -> Self { Self::String(value) }
+}
+
+impl<T: Into<DynamicValue>> From<Vec<T>> for DynamicValue {
+    fn from(value: Vec<T>) -> Self { Self::Array(value.into_iter().map(Into::into).collect()) }
+}
@@ -0,0 +1,406 @@
+// Standard Uses
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+// Crate Uses
+use crate::codelib_gen::features::DEFAULT_PARAMETER;
+use crate::codelib_gen::{GenerationContext, SchemaUnits};
+use crate::package::build::{self, cas};
+use crate::wire::frozen_schemas;
+use crate::wire::shape::{declared, fields, parameter, shape_of_kind, split_path, Declaration, Integer, Shape};
+use crate::schema::ir::frozen::unit::FrozenUnit;
+
+// External Uses
+use comline_runtime::wire::{self, Decode, DecodeError, Encode, Reader};
+use eyre::{bail, eyre, Result};
+
+
+/// Parameter of a struct or field naming what it was called before, like
+/// `@renamed_from=username`, so that data written under the old name is read
+pub const RENAMED_FROM_PARAMETER: &str = "renamed_from";
+
+
+/// How data that a version of a package wrote is read as a type of the
+/// package as it is now
+///
+/// The data is translated into the bytes the current version would have
+/// written, so that whatever decodes the current version decodes it:
+/// - Fields are matched by name, or by the name the `@renamed_from` of a
+///   field or struct gives, and fields that were removed are skipped
+/// - Fields that were added are absent when optional, or their `@default`
+/// - Integers and floats may have widened, fixed arrays may have become
+///   dynamic, and enums and unions may have gained variants and kinds
+///
+/// Any other change is not wire compatible, which is reported when planning
+/// rather than found while translating
+#[derive(Debug)]
+pub struct Evolution {
+    root: Plan,
+    structs: Vec<StructPlan>,
+}
+
+impl Evolution {
+    /// Plans reading data of `type_path`, like `members::Member`, that was
+    /// written with the `writer` schemas, as the `reader` package has it
+    pub fn new(writer: &[SchemaUnits], reader: &GenerationContext, type_path: &str) -> Result<Self> {
+        let writer = GenerationContext {
+            package_name: reader.package_name.clone(),
+            package_version: reader.package_version.clone(),
+            schemas: writer.to_vec(),
+            options: BTreeMap::new(),
+            history: vec![],
+        };
+
+        let (namespace, name) = split_path(type_path)?;
+
+        let reader_shape = declared(reader, &namespace, name)
+            .ok_or_else(|| eyre!("'{}' is not a struct nor an enum of the package", type_path))?;
+        let previous = match &reader_shape {
+            Shape::Struct(declaration, _) => parameter(declaration.parameters, RENAMED_FROM_PARAMETER),
+            _ => None,
+        };
+        let writer_shape = declared(&writer, &namespace, previous.unwrap_or(name))
+            .ok_or_else(|| eyre!("'{}' was not declared when the data was written", type_path))?;
+
+        let mut planner = Planner {
+            writer: &writer, reader,
+            structs: vec![], planned: HashMap::new(), problems: vec![],
+        };
+        let root = planner.plan(&writer_shape, &reader_shape, false, type_path);
+
+        if !planner.problems.is_empty() {
+            bail!(
+                "'{}' changed in ways that are not wire compatible:\n  - {}",
+                type_path, planner.problems.join("\n  - ")
+            )
+        }
+
+        Ok(Self { root, structs: planner.structs })
+    }
+
+    /// Plans reading data of `type_path` that was written with the version
+    /// frozen by a commit, given by its hash or the start of it, as the
+    /// package at `package_path` has it now
+    pub fn from_commit(package_path: &Path, hash: &str, type_path: &str) -> Result<Self> {
+        let Some((hash, commit)) = cas::history::find_commit(package_path, hash)? else {
+            bail!("Package at '{}' has no commit with hash '{}'", package_path.display(), hash)
+        };
+
+        let context = build::compile(package_path)?;
+        let reader = build::generation_context(&context, &BTreeMap::new())?;
+        let writer = frozen_schemas(package_path, &commit, &reader.schemas)?;
+
+        Self::new(&writer, &reader, type_path).map_err(|e| eyre!(
+            "Data of version {} (commit {}) cannot be read: {}", commit.version, &hash.to_hex()[..12], e
+        ))
+    }
+
+    /// Translates the bytes of a value the writer wrote into the bytes the
+    /// reader would have written, which must hold nothing else
+    pub fn translate(&self, bytes: &[u8]) -> Result<Vec<u8>, DecodeError> {
+        let mut reader = Reader::new(bytes);
+        let mut buffer = vec![];
+        self.translate_plan(&self.root, &mut reader, &mut buffer)?;
+
+        match reader.remaining() {
+            0 => Ok(buffer),
+            remaining => Err(DecodeError::TrailingBytes(remaining)),
+        }
+    }
+
+    /// Decodes the bytes of a value the writer wrote as a type of the reader
+    pub fn decode<T: Decode>(&self, bytes: &[u8]) -> Result<T, DecodeError> {
+        wire::decode(&self.translate(bytes)?)
+    }
+
+    fn translate_plan(&self, plan: &Plan, reader: &mut Reader, buffer: &mut Vec<u8>) -> Result<(), DecodeError> {
+        match plan {
+            Plan::Bool => buffer.push(reader.read_tag()? as u8),
+            Plan::Integer(from, to) => to.write(buffer, from.read(reader)?),
+            Plan::Float(32, 64) => (f32::decode(reader)? as f64).encode(buffer),
+            Plan::Float(bits, _) => buffer.extend(reader.read_bytes(*bits as usize / 8)?),
+            Plan::String => {
+                let length = reader.read_length()?;
+                wire::write_varint(buffer, length as u128);
+                buffer.extend(reader.read_bytes(length)?);
+            }
+            Plan::Array { fixed, counted, element } => {
+                let count = match fixed {
+                    Some(count) => *count,
+                    None => reader.read_count()?,
+                };
+                if *counted { wire::write_varint(buffer, count as u128) }
+
+                for _ in 0..count { self.translate_plan(element, reader, buffer)? }
+            }
+            Plan::Struct(index) => {
+                let plan = &self.structs[*index];
+
+                // Fields are read in the order they were written, then
+                // written in the order they are now
+                let mut values = Vec::with_capacity(plan.reads.len());
+                for read in &plan.reads {
+                    let present = !read.optional || reader.read_tag()?;
+                    let mut value = vec![];
+                    if present { self.translate_plan(&read.plan, reader, &mut value)? }
+                    values.push(present.then_some(value));
+                }
+
+                for write in &plan.writes {
+                    match write.source.and_then(|source| values[source].take()) {
+                        Some(value) => {
+                            if write.optional { buffer.push(1) }
+                            buffer.extend(value);
+                        }
+                        None => buffer.extend(&write.fallback),
+                    }
+                }
+            }
+            Plan::Enum(variants) => {
+                let discriminant = reader.read_varint()?;
+                let variant = usize::try_from(discriminant).ok().and_then(|index| variants.get(index))
+                    .ok_or(DecodeError::UnknownDiscriminant { type_name: "enum", discriminant })?;
+                wire::write_varint(buffer, *variant);
+            }
+            Plan::Union(kinds) => {
+                let discriminant = reader.read_varint()?;
+                let (kind, plan) = usize::try_from(discriminant).ok().and_then(|index| kinds.get(index))
+                    .ok_or(DecodeError::UnknownDiscriminant { type_name: "union", discriminant })?;
+                wire::write_varint(buffer, *kind);
+                self.translate_plan(plan, reader, buffer)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+
+/// How a value the writer wrote is translated
+#[derive(Debug)]
+enum Plan {
+    Bool,
+    Integer(Integer, Integer),
+    /// Bits of the float written and of the float read
+    Float(u8, u8),
+    String,
+    Array { fixed: Option<usize>, counted: bool, element: Box<Plan> },
+    Struct(usize),
+    /// Variant of the reader for every variant of the writer
+    Enum(Vec<u128>),
+    /// Kind of the reader for every kind of the writer, with how it is translated
+    Union(Vec<(u128, Plan)>),
+}
+
+#[derive(Debug)]
+struct StructPlan {
+    /// Fields of the writer, in the order they were written
+    reads: Vec<FieldRead>,
+    /// Fields of the reader, in the order they are written now
+    writes: Vec<FieldWrite>,
+}
+
+#[derive(Debug)]
+struct FieldRead {
+    optional: bool,
+    plan: Plan,
+}
+
+#[derive(Debug)]
+struct FieldWrite {
+    /// Field of the writer that holds the value
+    source: Option<usize>,
+    optional: bool,
+    /// Bytes written when the writer did not give a value
+    fallback: Vec<u8>,
+}
+
+
+struct Planner<'a> {
+    writer: &'a GenerationContext,
+    reader: &'a GenerationContext,
+    structs: Vec<StructPlan>,
+    /// Structs planned by the paths of the writer and the reader, and whether
+    /// the reader is the writer, which is how skipped fields are read through
+    planned: HashMap<(String, String, bool), usize>,
+    problems: Vec<String>,
+}
+
+impl<'a> Planner<'a> {
+    /// Plans translating a value of the writer, where `identity` reads it as
+    /// the writer has it, for values that are skipped
+    fn plan(&mut self, writer: &Shape<'a>, reader: &Shape<'a>, identity: bool, item: &str) -> Plan {
+        match (writer, reader) {
+            (Shape::Bool, Shape::Bool) => Plan::Bool,
+            (Shape::Integer(from), Shape::Integer(to)) if from.widens_to(to) => Plan::Integer(*from, *to),
+            (Shape::Float(from), Shape::Float(to)) if from <= to => Plan::Float(*from, *to),
+            (Shape::String, Shape::String) => Plan::String,
+            (Shape::Array(from, from_count), Shape::Array(to, to_count)) => {
+                let counted = match (from_count, to_count) {
+                    (Some(from), Some(to)) if from == to => false,
+                    (_, None) => true,
+                    _ => return self.changed(writer, reader, item),
+                };
+                let element = self.plan(from, to, identity, item);
+
+                Plan::Array { fixed: *from_count, counted, element: Box::new(element) }
+            }
+            (Shape::Struct(from, from_fields), Shape::Struct(to, to_fields)) if same(from, to) => {
+                self.struct_plan(from, from_fields, to, to_fields, identity)
+            }
+            (Shape::Enum(from, from_variants), Shape::Enum(to, to_variants)) if same(from, to) => {
+                let mut variants = vec![];
+                for variant in from_variants {
+                    match to_variants.iter().position(|to| to == variant) {
+                        Some(index) => variants.push(index as u128),
+                        None => {
+                            self.problem(format!("'{}' has no variant '{}' anymore", to.path, variant));
+                            variants.push(0);
+                        }
+                    }
+                }
+
+                Plan::Enum(variants)
+            }
+            (Shape::Union(from_schema, from_kinds), Shape::Union(to_schema, to_kinds)) => {
+                let reader_context = self.reader_context(identity);
+                let to_shapes: Vec<Shape<'a>> = to_kinds.iter()
+                    .map(|kind| shape_of_kind(reader_context, *to_schema, kind)).collect();
+
+                let mut kinds = vec![];
+                for kind in from_kinds {
+                    let from = shape_of_kind(self.writer, *from_schema, kind);
+                    match to_shapes.iter().position(|to| to.describe() == from.describe()) {
+                        Some(index) => {
+                            let plan = self.plan(&from, &to_shapes[index], identity, item);
+                            kinds.push((index as u128, plan));
+                        }
+                        None => {
+                            self.problem(format!("'{}' has no kind {} anymore", item, from.describe()));
+                            kinds.push((0, Plan::Bool));
+                        }
+                    }
+                }
+
+                Plan::Union(kinds)
+            }
+            (Shape::Unknown(name), _) | (_, Shape::Unknown(name)) => {
+                self.problem(format!("'{}' is of {}, which is not declared in the package", item, name));
+                Plan::Bool
+            }
+            _ => self.changed(writer, reader, item),
+        }
+    }
+
+    fn struct_plan(
+        &mut self, writer: &Declaration<'a>, writer_fields: &'a [FrozenUnit],
+        reader: &Declaration<'a>, reader_fields: &'a [FrozenUnit], identity: bool,
+    ) -> Plan {
+        // Planned before its fields, which may be of the struct itself
+        let key = (writer.path.clone(), reader.path.clone(), identity);
+        if let Some(index) = self.planned.get(&key) { return Plan::Struct(*index) }
+
+        let index = self.structs.len();
+        self.structs.push(StructPlan { reads: vec![], writes: vec![] });
+        self.planned.insert(key, index);
+
+        let reader_context = self.reader_context(identity);
+        let writer_fields = fields(writer_fields);
+        let mut targets: Vec<Option<(Shape<'a>, String)>> = writer_fields.iter().map(|_| None).collect();
+
+        let mut writes = vec![];
+        for field in fields(reader_fields) {
+            let item = format!("{}::{}", reader.path, field.name);
+            let previous = parameter(field.parameters, RENAMED_FROM_PARAMETER).unwrap_or(field.name);
+            let source = writer_fields.iter().position(|written| written.name == previous);
+            let field_shape = shape_of_kind(reader_context, reader.schema, field.kind);
+
+            let fallback = match (field.optional, parameter(field.parameters, DEFAULT_PARAMETER)) {
+                (true, _) => Some(vec![0]),
+                (false, Some(default)) => Some(encode_default(&field_shape, default).unwrap_or_else(|| {
+                    self.problem(format!(
+                        "'{}' has a default '{}' that is not a {}", item, default, field_shape.describe()
+                    ));
+                    vec![]
+                })),
+                (false, None) => None,
+            };
+
+            match source {
+                Some(source) if writer_fields[source].optional && fallback.is_none() => {
+                    self.problem(format!("'{}' became required without a default", item))
+                }
+                None if fallback.is_none() => self.problem(format!(
+                    "'{}' was added without being optional nor having a default", item
+                )),
+                _ => {}
+            }
+
+            if let Some(source) = source { targets[source] = Some((field_shape, item)) }
+            writes.push(FieldWrite { source, optional: field.optional, fallback: fallback.unwrap_or_default() });
+        }
+
+        let mut reads = vec![];
+        for (field, target) in writer_fields.iter().zip(targets) {
+            let writer_shape = shape_of_kind(self.writer, writer.schema, field.kind);
+            let plan = match target {
+                Some((reader_shape, item)) => self.plan(&writer_shape, &reader_shape, identity, &item),
+                // Removed fields are still read through to be skipped
+                None => {
+                    let item = format!("{}::{}", writer.path, field.name);
+                    self.plan(&writer_shape, &writer_shape, true, &item)
+                }
+            };
+
+            reads.push(FieldRead { optional: field.optional, plan });
+        }
+
+        self.structs[index] = StructPlan { reads, writes };
+        Plan::Struct(index)
+    }
+
+    fn reader_context(&self, identity: bool) -> &'a GenerationContext {
+        match identity {
+            true => self.writer,
+            false => self.reader,
+        }
+    }
+
+    fn changed(&mut self, writer: &Shape, reader: &Shape, item: &str) -> Plan {
+        self.problem(format!("'{}' changed from {} to {}", item, writer.describe(), reader.describe()));
+        Plan::Bool
+    }
+
+    fn problem(&mut self, problem: String) {
+        if !self.problems.contains(&problem) { self.problems.push(problem) }
+    }
+}
+
+/// Whether two declarations are the same one, under the name it has now or
+/// the name it was renamed from
+fn same(writer: &Declaration, reader: &Declaration) -> bool {
+    writer.path == reader.path
+        || (writer.namespace == reader.namespace
+            && parameter(reader.parameters, RENAMED_FROM_PARAMETER) == Some(writer.name))
+}
+
+/// The bytes of a default, which annotations give as written
+fn encode_default(shape: &Shape, text: &str) -> Option<Vec<u8>> {
+    let mut buffer = vec![];
+
+    match shape {
+        Shape::Bool => text.parse::<bool>().ok()?.encode(&mut buffer),
+        Shape::Integer(integer) => integer.write(&mut buffer, integer.parse(text)?),
+        Shape::Float(32) => text.parse::<f32>().ok()?.encode(&mut buffer),
+        Shape::Float(_) => text.parse::<f64>().ok()?.encode(&mut buffer),
+        Shape::String => text.encode(&mut buffer),
+        Shape::Enum(_, variants) => {
+            let index = variants.iter().position(|variant| *variant == text)?;
+            wire::write_varint(&mut buffer, index as u128)
+        }
+        _ => return None,
+    }
+
+    Some(buffer)
+}
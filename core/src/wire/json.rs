@@ -0,0 +1,217 @@
+// Standard Uses
+use std::str::FromStr;
+
+// Crate Uses
+use crate::wire::dynamic::{integer_value, DynamicType, DynamicValue};
+use crate::wire::shape::{fields, kind_name, shape_of_kind, Shape};
+
+// External Uses
+use eyre::{bail, eyre, Result};
+use serde_json::{Map, Number, Value};
+
+
+/// How values are written as JSON, which is read back whichever options it
+/// was written with:
+/// - `bool` is `true` or `false`, and `str` and `string` are strings
+/// - Integers are numbers, but 128-bit integers are strings, and so are
+///   64-bit integers with `int64_as_string`, as many parsers of JSON only
+///   hold integers up to 2^53 exactly
+/// - Floats are numbers, or the strings `NaN`, `Infinity` and `-Infinity`
+/// - Arrays are arrays, where those like `T[N]` must have `N` elements
+/// - Structs are objects of their fields in the order they are declared, where
+///   absent optional fields are left out or `null`, and no other field is read
+/// - Enums are the names of their variants, or their indexes
+/// - Unions are an object of the kind of the value, as the union writes it
+///   like `u64` or `Member[]`, to the value
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JsonOptions {
+    pub int64_as_string: bool,
+    pub enums: EnumJson,
+    pub absent_fields: AbsentJson,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EnumJson {
+    #[default]
+    Name,
+    Index,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AbsentJson {
+    #[default]
+    Omitted,
+    Null,
+}
+
+
+/// Transcodes JSON of a type into its binary encoding
+pub fn json_to_binary(r#type: &DynamicType, json: &Value) -> Result<Vec<u8>> {
+    r#type.encode(&r#type.from_json(json)?)
+}
+
+/// Transcodes the binary encoding of a type into JSON
+pub fn binary_to_json(r#type: &DynamicType, bytes: &[u8], options: &JsonOptions) -> Result<Value> {
+    let value = r#type.decode(bytes)
+        .map_err(|e| eyre!("Bytes are not a valid '{}': {}", r#type.path(), e))?;
+
+    r#type.to_json(&value, options)
+}
+
+
+impl<'a> DynamicType<'a> {
+    /// A value of the type as JSON, after validating it
+    pub fn to_json(&self, value: &DynamicValue, options: &JsonOptions) -> Result<Value> {
+        self.validate(value)?;
+        Ok(self.shape_to_json(&self.shape, value, options))
+    }
+
+    /// A value of the type from JSON, as it is written with any options
+    pub fn from_json(&self, json: &Value) -> Result<DynamicValue> {
+        let mut problems = vec![];
+        let value = self.shape_from_json(&self.shape, json, &self.path, &mut problems);
+
+        if !problems.is_empty() {
+            bail!("JSON is not a valid '{}':\n  - {}", self.path, problems.join("\n  - "))
+        }
+
+        self.validate(&value)?;
+        Ok(value)
+    }
+
+    /// JSON of a value that was validated
+    fn shape_to_json(&self, shape: &Shape<'a>, value: &DynamicValue, options: &JsonOptions) -> Value {
+        match (shape, value) {
+            (Shape::Bool, DynamicValue::Bool(value)) => Value::Bool(*value),
+            (Shape::Integer(integer), value) => {
+                let number = integer_value(integer, value).unwrap();
+                let text = match integer.signed {
+                    true => number.to_string(),
+                    false => (number as u128).to_string(),
+                };
+
+                match integer.bits == 128 || (integer.bits == 64 && options.int64_as_string) {
+                    true => Value::String(text),
+                    false => Value::Number(Number::from_str(&text).unwrap()),
+                }
+            }
+            (Shape::Float(bits), DynamicValue::Float(value)) => match value {
+                value if value.is_nan() => Value::String("NaN".to_owned()),
+                value if value.is_infinite() && *value > 0.0 => Value::String("Infinity".to_owned()),
+                value if value.is_infinite() => Value::String("-Infinity".to_owned()),
+                // The shortest text that reads back as the same float
+                value => match bits {
+                    32 => Value::Number(Number::from_str(&(*value as f32).to_string()).unwrap()),
+                    _ => Value::Number(Number::from_f64(*value).unwrap()),
+                },
+            },
+            (Shape::String, DynamicValue::String(value)) => Value::String(value.clone()),
+            (Shape::Array(element, _), DynamicValue::Array(elements)) => Value::Array(
+                elements.iter().map(|value| self.shape_to_json(element, value, options)).collect()
+            ),
+            (Shape::Struct(declaration, units), value) => {
+                let mut object = Map::new();
+                for field in fields(units) {
+                    let json = match value.field(field.name) {
+                        Some(value) => {
+                            let field_shape = shape_of_kind(self.context, declaration.schema, field.kind);
+                            self.shape_to_json(&field_shape, value, options)
+                        }
+                        None if options.absent_fields == AbsentJson::Null => Value::Null,
+                        None => continue,
+                    };
+                    object.insert(field.name.to_owned(), json);
+                }
+                Value::Object(object)
+            }
+            (Shape::Enum(_, variants), DynamicValue::Enum(variant)) => match options.enums {
+                EnumJson::Name => Value::String(variant.clone()),
+                EnumJson::Index => Value::from(variants.iter().position(|candidate| candidate == variant).unwrap()),
+            },
+            (Shape::Union(schema, kinds), DynamicValue::Union(kind, value)) => {
+                let found = kinds.iter().find(|candidate| kind_name(candidate) == *kind).unwrap();
+                let value = self.shape_to_json(&shape_of_kind(self.context, *schema, found), value, options);
+                Value::Object(Map::from_iter([(kind.clone(), value)]))
+            }
+            _ => unreachable!("Values are validated before they are written as JSON"),
+        }
+    }
+
+    fn shape_from_json(&self, shape: &Shape<'a>, json: &Value, at: &str, problems: &mut Vec<String>) -> DynamicValue {
+        match (shape, json) {
+            (Shape::Bool, Value::Bool(value)) => return DynamicValue::Bool(*value),
+            (Shape::Integer(integer), Value::Number(_) | Value::String(_)) => {
+                let text = match json {
+                    Value::String(text) => text.clone(),
+                    number => number.to_string(),
+                };
+                let value = match integer.signed {
+                    true => text.parse().ok().map(DynamicValue::Signed),
+                    false => text.parse().ok().map(DynamicValue::Unsigned),
+                };
+                if let Some(value) = value { return value }
+            }
+            (Shape::Float(_), Value::Number(number)) => {
+                if let Some(value) = number.as_f64() { return DynamicValue::Float(value) }
+            }
+            (Shape::Float(_), Value::String(text)) => match text.as_str() {
+                "NaN" => return DynamicValue::Float(f64::NAN),
+                "Infinity" => return DynamicValue::Float(f64::INFINITY),
+                "-Infinity" => return DynamicValue::Float(f64::NEG_INFINITY),
+                _ => {}
+            },
+            (Shape::String, Value::String(value)) => return DynamicValue::String(value.clone()),
+            (Shape::Array(element, _), Value::Array(elements)) => {
+                return DynamicValue::Array(elements.iter().enumerate().map(|(index, json)| {
+                    self.shape_from_json(element, json, &format!("{}[{}]", at, index), problems)
+                }).collect())
+            }
+            (Shape::Struct(declaration, units), Value::Object(object)) => {
+                let fields = fields(units);
+                let mut values = vec![];
+
+                for (name, json) in object {
+                    let at = format!("{}.{}", at, name);
+                    let Some(field) = fields.iter().find(|field| field.name == name) else {
+                        problems.push(format!("'{}' is not a field of {}", at, declaration.path));
+                        continue
+                    };
+                    if field.optional && json.is_null() { continue }
+
+                    let field_shape = shape_of_kind(self.context, declaration.schema, field.kind);
+                    values.push((name.clone(), self.shape_from_json(&field_shape, json, &at, problems)));
+                }
+                return DynamicValue::Struct(values)
+            }
+            (Shape::Enum(..), Value::String(variant)) => return DynamicValue::Enum(variant.clone()),
+            (Shape::Enum(declaration, variants), Value::Number(index)) => {
+                let variant = index.as_u64().and_then(|index| variants.get(usize::try_from(index).ok()?));
+                match variant {
+                    Some(variant) => return DynamicValue::Enum(variant.to_string()),
+                    None => {
+                        problems.push(format!("'{}' is {}, which is not a variant of {}", at, index, declaration.path));
+                        return DynamicValue::Bool(false)
+                    }
+                }
+            }
+            (Shape::Union(schema, kinds), Value::Object(object)) if object.len() == 1 => {
+                let (kind, json) = object.iter().next().unwrap();
+                match kinds.iter().find(|candidate| kind_name(candidate) == *kind) {
+                    Some(found) => {
+                        let kind_shape = shape_of_kind(self.context, *schema, found);
+                        let value = self.shape_from_json(&kind_shape, json, at, problems);
+                        return DynamicValue::Union(kind.clone(), Box::new(value))
+                    }
+                    None => problems.push(format!(
+                        "'{}' is of kind {}, which is not a kind of {}", at, kind, shape.describe()
+                    )),
+                }
+                return DynamicValue::Bool(false)
+            }
+            _ => {}
+        }
+
+        problems.push(format!("'{}' should be {}, not {}", at, shape.describe(), json));
+        DynamicValue::Bool(false)
+    }
+}
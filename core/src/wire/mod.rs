@@ -0,0 +1,32 @@
+// Relative Modules
+pub mod dynamic;
+pub mod evolution;
+pub mod json;
+mod shape;
+
+// Standard Uses
+use std::path::Path;
+
+// Crate Uses
+use crate::codelib_gen::SchemaUnits;
+use crate::package::build::cas;
+use crate::package::build::cas::objects::Commit;
+
+// External Uses
+use eyre::Result;
+
+
+/// The schemas a commit froze, with the namespaces they had. Commits made
+/// before the CAS kept namespaces only have schemas by position, which are
+/// given the namespaces the schemas of the reader have at the same positions
+pub(crate) fn frozen_schemas(package_path: &Path, commit: &Commit, reader: &[SchemaUnits]) -> Result<Vec<SchemaUnits>> {
+    let schemas = cas::history::schemas_of(package_path, commit)?;
+
+    Ok(schemas.into_iter().enumerate().map(|(index, schema)| match schema.namespace.is_empty() {
+        true => SchemaUnits {
+            namespace: reader.get(index).map(|schema| schema.namespace.clone()).unwrap_or_default(),
+            units: schema.units,
+        },
+        false => schema,
+    }).collect())
+}
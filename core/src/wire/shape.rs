@@ -0,0 +1,244 @@
+// Standard Uses
+
+// Crate Uses
+use crate::codelib_gen::common::{outer_array, schema_type_name};
+use crate::codelib_gen::GenerationContext;
+use crate::schema::ir::compiler::interpreted::kind_search::KindValue;
+use crate::schema::ir::frozen::unit::FrozenUnit;
+
+// External Uses
+use comline_runtime::wire::{self, DecodeError, Reader};
+use eyre::{bail, Result};
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Integer {
+    pub(super) signed: bool,
+    pub(super) bits: u8,
+}
+
+impl Integer {
+    const ALL: [(&'static str, Integer); 10] = [
+        ("u8", Integer { signed: false, bits: 8 }), ("u16", Integer { signed: false, bits: 16 }),
+        ("u32", Integer { signed: false, bits: 32 }), ("u64", Integer { signed: false, bits: 64 }),
+        ("u128", Integer { signed: false, bits: 128 }),
+        ("i8", Integer { signed: true, bits: 8 }), ("i16", Integer { signed: true, bits: 16 }),
+        ("i32", Integer { signed: true, bits: 32 }), ("i64", Integer { signed: true, bits: 64 }),
+        ("i128", Integer { signed: true, bits: 128 }),
+    ];
+
+    pub(super) fn named(name: &str) -> Option<Self> {
+        Self::ALL.iter().find(|(candidate, _)| *candidate == name).map(|(_, integer)| *integer)
+    }
+
+    pub(super) fn name(&self) -> &'static str {
+        Self::ALL.iter().find(|(_, integer)| integer == self).map(|(name, _)| *name).unwrap()
+    }
+
+    /// Whether every value of this integer is a value of the other
+    pub(super) fn widens_to(&self, other: &Integer) -> bool {
+        self == other || (other.bits > self.bits && (other.signed || !self.signed))
+    }
+
+    /// Reads the integer, where a `u128` above `i128::MAX` wraps around and
+    /// is unwrapped again when written
+    pub(super) fn read(&self, reader: &mut Reader) -> Result<i128, DecodeError> {
+        Ok(match (self.bits, self.signed) {
+            (8, false) => reader.read_byte()? as i128,
+            (8, true) => reader.read_byte()? as i8 as i128,
+            (_, false) => reader.read_varint()? as i128,
+            (_, true) => wire::unzigzag(reader.read_varint()?),
+        })
+    }
+
+    pub(super) fn write(&self, buffer: &mut Vec<u8>, value: i128) {
+        match (self.bits, self.signed) {
+            (8, _) => buffer.push(value as u8),
+            (_, false) => wire::write_varint(buffer, value as u128),
+            (_, true) => wire::write_varint(buffer, wire::zigzag(value)),
+        }
+    }
+
+    /// A value of this integer, as [`Integer::read`] gives them
+    pub(super) fn value_of_unsigned(&self, value: u128) -> Option<i128> {
+        match (self.signed, self.bits) {
+            (false, 128) => Some(value as i128),
+            _ => i128::try_from(value).ok().and_then(|value| self.value_of_signed(value)),
+        }
+    }
+
+    /// A value of this integer, as [`Integer::read`] gives them
+    pub(super) fn value_of_signed(&self, value: i128) -> Option<i128> {
+        let range = match (self.signed, self.bits) {
+            (true, 128) => i128::MIN..=i128::MAX,
+            (false, 128) => 0..=i128::MAX,
+            (true, bits) => -(1 << (bits - 1))..=(1 << (bits - 1)) - 1,
+            (false, bits) => 0..=(1 << bits) - 1,
+        };
+
+        range.contains(&value).then_some(value)
+    }
+
+    /// Parses a value of this integer, as [`Integer::read`] gives them
+    pub(super) fn parse(&self, text: &str) -> Option<i128> {
+        match text.parse::<i128>() {
+            Ok(value) => self.value_of_signed(value),
+            Err(_) => self.value_of_unsigned(text.parse().ok()?),
+        }
+    }
+}
+
+/// A type as the wire sees it
+#[derive(Debug, Clone)]
+pub(super) enum Shape<'a> {
+    Bool,
+    Integer(Integer),
+    /// Bits of the float
+    Float(u8),
+    String,
+    Array(Box<Shape<'a>>, Option<usize>),
+    Struct(Declaration<'a>, &'a [FrozenUnit]),
+    Enum(Declaration<'a>, Vec<&'a str>),
+    /// Kinds, with the schema they are written in
+    Union(usize, Vec<&'a KindValue>),
+    /// A type that is not declared in the package
+    Unknown(String),
+}
+
+impl Shape<'_> {
+    pub(super) fn describe(&self) -> String {
+        match self {
+            Shape::Bool => "bool".to_owned(),
+            Shape::Integer(integer) => integer.name().to_owned(),
+            Shape::Float(32) => "f32".to_owned(),
+            Shape::Float(_) => "float".to_owned(),
+            Shape::String => "str".to_owned(),
+            Shape::Array(element, Some(count)) => format!("{}[{}]", element.describe(), count),
+            Shape::Array(element, None) => format!("{}[]", element.describe()),
+            Shape::Struct(declaration, _) | Shape::Enum(declaration, _) => declaration.path.clone(),
+            Shape::Union(_, kinds) => kinds.iter().map(|kind| kind_name(kind)).collect::<Vec<_>>().join(" | "),
+            Shape::Unknown(name) => name.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Declaration<'a> {
+    /// Position of the schema that declares it
+    pub(super) schema: usize,
+    pub(super) namespace: &'a [String],
+    pub(super) name: &'a str,
+    pub(super) path: String,
+    pub(super) parameters: &'a [FrozenUnit],
+}
+
+pub(super) struct Field<'a> {
+    pub(super) optional: bool,
+    pub(super) name: &'a str,
+    pub(super) parameters: &'a [FrozenUnit],
+    pub(super) kind: &'a KindValue,
+}
+
+
+pub(super) fn fields(units: &[FrozenUnit]) -> Vec<Field<'_>> {
+    units.iter().filter_map(|unit| match unit {
+        FrozenUnit::Field { optional, name, parameters, kind_value, .. } => Some(Field {
+            optional: *optional, name, parameters, kind: kind_value,
+        }),
+        _ => None,
+    }).collect()
+}
+
+pub(super) fn parameter<'a>(parameters: &'a [FrozenUnit], name: &str) -> Option<&'a str> {
+    parameters.iter().find_map(|parameter| match parameter {
+        FrozenUnit::Parameter { name: key, default_value } if key == name => Some(default_value.as_str()),
+        _ => None,
+    })
+}
+
+
+/// The namespace and name of a path to a type, like `members::Member`
+pub(super) fn split_path(type_path: &str) -> Result<(Vec<String>, &str)> {
+    let Some((namespace, name)) = type_path.rsplit_once("::") else {
+        bail!("'{}' is not the path of a type, like 'members::Member'", type_path)
+    };
+
+    Ok((namespace.split("::").map(str::to_owned).collect(), name))
+}
+
+/// The struct or enum with a name in the schema of a namespace
+pub(super) fn declared<'a>(context: &'a GenerationContext, namespace: &[String], name: &str) -> Option<Shape<'a>> {
+    let schema = context.schemas.iter().position(|schema| schema.namespace == namespace)?;
+    declaration(context, schema, name)
+}
+
+pub(super) fn declaration<'a>(context: &'a GenerationContext, schema: usize, name: &str) -> Option<Shape<'a>> {
+    let units = &context.schemas[schema];
+    let declaration = |name: &'a str, parameters: &'a [FrozenUnit]| Declaration {
+        schema, namespace: &units.namespace, name,
+        path: units.namespace.iter().map(String::as_str).chain([name]).collect::<Vec<_>>().join("::"),
+        parameters,
+    };
+
+    units.units.iter().find_map(|unit| match unit {
+        FrozenUnit::Struct { name: declared, parameters, fields, .. } if declared == name => {
+            Some(Shape::Struct(declaration(declared, parameters), fields))
+        }
+        FrozenUnit::Enum { name: declared, variants, .. } if declared == name => {
+            let variants = variants.iter().filter_map(|variant| match variant {
+                FrozenUnit::EnumVariant(KindValue::EnumVariant(name, _)) => Some(name.as_str()),
+                _ => None,
+            }).collect();
+            Some(Shape::Enum(declaration(declared, &[]), variants))
+        }
+        _ => None,
+    })
+}
+
+pub(super) fn shape_of_kind<'a>(context: &'a GenerationContext, schema: usize, kind: &'a KindValue) -> Shape<'a> {
+    match kind {
+        KindValue::Union(kinds) => Shape::Union(schema, kinds.iter().collect()),
+        _ => shape_of(context, schema, &kind_name(kind)),
+    }
+}
+
+/// The shape of a type as schemas write it, where the outermost array is the
+/// last pair of brackets, as in `u8[4][]`
+pub(super) fn shape_of<'a>(context: &'a GenerationContext, schema: usize, type_name: &str) -> Shape<'a> {
+    if let Some((element, count)) = outer_array(type_name) {
+        let element = Box::new(shape_of(context, schema, element));
+        return match count {
+            "" => Shape::Array(element, None),
+            count => match count.parse() {
+                Ok(count) => Shape::Array(element, Some(count)),
+                Err(_) => Shape::Unknown(type_name.to_owned()),
+            },
+        }
+    }
+
+    match type_name {
+        "bool" => return Shape::Bool,
+        "str" | "string" => return Shape::String,
+        "float" | "f64" => return Shape::Float(64),
+        "f32" => return Shape::Float(32),
+        _ => {}
+    }
+    if let Some(integer) = Integer::named(type_name) { return Shape::Integer(integer) }
+
+    let units = &context.schemas[schema];
+    let found = match units.declares(type_name) {
+        true => Some((schema, type_name.to_owned())),
+        false => context.resolve_type(units, type_name).and_then(|(namespace, name)| {
+            let index = context.schemas.iter().position(|schema| schema.namespace == namespace)?;
+            Some((index, name))
+        }),
+    };
+
+    found.and_then(|(schema, name)| declaration(context, schema, &name))
+        .unwrap_or_else(|| Shape::Unknown(type_name.to_owned()))
+}
+
+/// The name of a kind, which the kinds of unions are told apart by
+pub(super) fn kind_name(kind: &KindValue) -> String {
+    schema_type_name(kind, " | ")
+}
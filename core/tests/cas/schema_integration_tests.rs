@@ -6,7 +6,8 @@ use comline_core::schema::ir::frozen::cas::blob::{
     blob_to_frozen_unit, build_tree_from_schema, frozen_unit_to_blob,
     load_schema_from_tree,
 };
-use comline_core::schema::ir::frozen::unit::FrozenUnit;
+use comline_core::package::build::cas::objects::Blob;
+use comline_core::schema::ir::frozen::unit::{FrozenArgument, FrozenUnit};
 use tempfile::TempDir;
 
 #[test]
@@ -29,6 +30,37 @@ fn test_frozen_unit_blob_roundtrip() {
     }
 }
 
+#[test]
+fn test_read_blobs_of_unversioned_layout() {
+    // A protocol frozen by a version from before functions had parameters
+    let legacy = concat!(
+        "0b0000000b0000000000000046696e647320757365727300000000000000000500000000000000557365727301000000",
+        "000000000c0000000000000000000000040000000000000066696e6401010000000000000002000000000000006964",
+        "0000000004000000000103000000040000000000000055736572000000000000000000",
+    );
+    let content = (0..legacy.len()).step_by(2).map(|i| u8::from_str_radix(&legacy[i..i + 2], 16).unwrap()).collect();
+
+    let unit = blob_to_frozen_unit(&Blob::new(content)).unwrap();
+    assert_eq!(unit, FrozenUnit::Protocol {
+        docstring: "Finds users".to_owned(), parameters: vec![], name: "Users".to_owned(),
+        functions: vec![FrozenUnit::Function {
+            docstring: String::new(), parameters: vec![], name: "find".to_owned(), synchronous: true,
+            arguments: vec![FrozenArgument { name: "id".to_owned(), kind: KindValue::Primitive(Primitive::U64(None)) }],
+            _return: Some(KindValue::Namespaced("User".to_owned(), None)),
+            throws: vec![],
+        }],
+    });
+
+    // Blobs written now are versioned, and newer versions are refused
+    let mut content = frozen_unit_to_blob(&unit).unwrap().content;
+    assert!(content.starts_with(b"CLU\x01"));
+    content[3] = 2;
+    assert_eq!(
+        blob_to_frozen_unit(&Blob::new(content)).unwrap_err().to_string(),
+        "Blob has frozen units of layout version 2, which this version of Comline does not read"
+    );
+}
+
 #[test]
 fn test_build_tree_from_schema() {
     let temp_dir = TempDir::new().unwrap();
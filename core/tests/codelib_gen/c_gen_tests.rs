@@ -0,0 +1,176 @@
+// Standard Uses
+use std::process::Command;
+
+// Crate Uses
+use super::{file, generate_from_source, package, TEAMS};
+
+// External Uses
+use comline_core::codelib_gen::GeneratedFile;
+use comline_core::codelib_gen::c::{generate_c, generate_c_package};
+
+
+/// Compiles `source` along the generated headers, panicking with the compiler
+/// output if it does not compile
+fn assert_compiles(files: &[GeneratedFile], source: &str) {
+    let dir = tempfile::tempdir().unwrap();
+    for generated in files {
+        let path = dir.path().join(&generated.path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, &generated.contents).unwrap();
+    }
+    std::fs::write(dir.path().join("main.c"), source).unwrap();
+
+    let output = Command::new("cc")
+        .args(["-std=c11", "-Wall", "-Wextra", "-Werror", "-fsyntax-only", "main.c"])
+        .current_dir(dir.path())
+        .output()
+        .expect("The C compiler could not be run");
+
+    assert!(
+        output.status.success(),
+        "Generated headers do not compile:\n{}", String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+
+#[test]
+fn test_generate_structs() {
+    let output = generate_from_source(r#"
+/// A sensor reading
+struct Reading {
+    /// Milliseconds since boot
+    time: u32
+    value: i16
+    valid: bool
+    optional calibration: u8
+    samples: u16[8]
+    matrix: u8[4][2]
+}
+"#, generate_c).unwrap();
+
+    assert!(output.contains(concat!(
+        "typedef struct Reading Reading;\n\n",
+        "/** A sensor reading */\nstruct Reading {\n",
+        "    /** Milliseconds since boot */\n    uint32_t time;\n",
+        "    int16_t value;\n    bool valid;\n",
+        "    bool has_calibration;\n    uint8_t calibration;\n",
+        "    uint16_t samples[8];\n    uint8_t matrix[2][4];\n};\n"
+    )));
+    assert!(!output.contains("#include <stddef.h>"));
+}
+
+#[test]
+fn test_generate_include_guards() {
+    let files = generate_c_package(&package(&[("sensors::readings", "struct Reading {\n    time: u32\n}\n")], &[]))
+        .unwrap();
+
+    let header = file(&files, "sensors/readings.h");
+    assert!(header.starts_with(concat!(
+        "/* Generated by Comline */\n#ifndef SENSORS_READINGS_H\n#define SENSORS_READINGS_H\n\n",
+        "#include <stdbool.h>\n#include <stdint.h>\n\n\n"
+    )));
+    assert!(header.ends_with("};\n\n#endif /* SENSORS_READINGS_H */\n"));
+    // Declarations are prefixed by their namespace
+    assert!(header.contains("struct sensors_readings_Reading {\n"));
+}
+
+#[test]
+fn test_generate_enums() {
+    let output = generate_from_source(
+        "/// Power states\nenum PowerState {\n    Off\n    LowPower\n    On\n}\n",
+        generate_c,
+    ).unwrap();
+
+    assert!(output.contains(concat!(
+        "/** Power states */\ntypedef enum PowerState {\n",
+        "    POWER_STATE_OFF = 0,\n    POWER_STATE_LOW_POWER = 1,\n    POWER_STATE_ON = 2,\n",
+        "} PowerState;\n"
+    )));
+}
+
+#[test]
+fn test_generate_constants() {
+    let source = concat!(
+        "/// Largest payload\n",
+        "const MAX_SIZE: u16 = 512\n",
+        "const OFFSET: i8 = -4\n",
+        "const ENABLED: bool = true\n",
+        "const NAME: str = \"node\\t1\"\n",
+    );
+
+    let output = generate_from_source(source, generate_c).unwrap();
+    assert!(output.contains("/** Largest payload */\n#define MAX_SIZE UINT16_C(512)\n"));
+    assert!(output.contains("#define OFFSET INT8_C(-4)\n"));
+    assert!(output.contains("#define ENABLED true\n"));
+    assert!(output.contains("#define NAME \"node\\\\t1\"\n"));
+
+    let files = generate_c_package(&package(&[("config", source)], &[("constants", "static")])).unwrap();
+    let header = file(&files, "config.h");
+    assert!(header.contains("static const uint16_t CONFIG_MAX_SIZE = 512;\n"));
+    assert!(header.contains("static const bool CONFIG_ENABLED = true;\n"));
+    assert!(header.contains("static const char *const CONFIG_NAME = \"node\\\\t1\";\n"));
+}
+
+#[test]
+fn test_require_length_conventions() {
+    let schemas = [("devices", "struct Device {\n    name: str\n    readings: u16[]\n    ok: u8[4]\n}\n")];
+
+    let error = generate_c_package(&package(&schemas, &[])).unwrap_err();
+    assert_eq!(error.to_string(), concat!(
+        "Some fields have no C representation, `length_convention = pointer` gives ",
+        "strings and dynamic arrays one:\n",
+        "  - 'devices::Device::name' is `str`, a string\n",
+        "  - 'devices::Device::readings' is `u16[]`, a dynamic array"
+    ));
+
+    let files = generate_c_package(&package(&schemas, &[("length_convention", "pointer")])).unwrap();
+    let header = file(&files, "devices.h");
+    assert!(header.contains("#include <stddef.h>\n"));
+    assert!(header.contains(
+        "    const char *name;\n    uint16_t *readings;\n    size_t readings_count;\n    uint8_t ok[4];\n"
+    ));
+
+    let error = generate_c_package(&package(&[("grid", "struct Grid {\n    rows: u8[][4]\n}\n")], &[]))
+        .unwrap_err();
+    assert!(error.to_string().ends_with("'grid::Grid::rows' is `u8[][4]`, a dynamic array"));
+}
+
+#[test]
+fn test_order_held_structs_first() {
+    let output = generate_from_source(concat!(
+        "struct Node {\n    position: Point\n    children: Node[]\n}\n\n",
+        "struct Point {\n    axis: Axis\n}\n\n",
+        "enum Axis {\n    X\n    Y\n}\n",
+    ), generate_c).unwrap();
+
+    let axis = output.find("typedef enum Axis").unwrap();
+    let point = output.find("struct Point {").unwrap();
+    let node = output.find("struct Node {").unwrap();
+    assert!(axis < point && point < node);
+
+    assert_compiles(&[GeneratedFile { path: "nodes.h".into(), contents: output }], "#include \"nodes.h\"\n");
+}
+
+#[test]
+fn test_generated_headers_compile() {
+    let files = generate_c_package(&package(TEAMS, &[("length_convention", "pointer")])).unwrap();
+
+    let paths: Vec<_> = files.iter().map(|f| f.path.to_str().unwrap()).collect();
+    assert_eq!(paths, vec!["members/accounts.h", "members.h", "teams.h", "types.h"]);
+
+    // Headers of other schemas are included relatively
+    assert!(file(&files, "members.h").contains("#include \"members/accounts.h\"\n#include \"types.h\"\n"));
+    assert!(file(&files, "members/accounts.h").contains("#include \"../types.h\"\n"));
+    assert!(file(&files, "members.h").contains("    members_accounts_Account account;\n"));
+    assert!(file(&files, "teams.h").contains("    teams_Tag *tags;\n"));
+
+    assert_compiles(&files, concat!(
+        "#include \"teams.h\"\n#include \"members.h\"\n\n",
+        "int main(void) {\n",
+        "    types_Tag tag = { .name = \"core\" };\n",
+        "    members_Member member = { .role = TYPES_ROLE_OWNER, .tags = &tag, .tags_count = 1 };\n",
+        "    teams_Team team = { .members = &member, .members_count = 1, .role = TYPES_ROLE_MEMBER };\n",
+        "    return (int)team.members_count - 1;\n",
+        "}\n",
+    ));
+}
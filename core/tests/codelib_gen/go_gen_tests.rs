@@ -0,0 +1,148 @@
+// Standard Uses
+
+// Crate Uses
+use super::{file, generate_from_source, package, TEAMS};
+
+// External Uses
+use comline_core::codelib_gen::go::{generate_go, generate_go_package};
+
+
+/// Schemas using each other in one direction only, as Go packages need
+const SHOP: &[(&str, &str)] = &[
+    ("types", "enum Currency {\n    Euro\n    Dollar\n}\n"),
+    ("catalog", concat!(
+        "use crate::types::Currency\n\n",
+        "struct Price {\n    amount: u64\n    currency: Currency\n}\n",
+    )),
+    ("catalog::items", concat!(
+        "struct Item {\n    price: parent::Price\n    optional discount: parent::Price\n}\n\n",
+        "protocol Items {\n    function get(u64) -> Item;\n}\n",
+    )),
+];
+
+
+#[test]
+fn test_generate_structs() {
+    let output = generate_from_source(r#"
+/// A registered user
+struct User {
+    /// Unique identifier
+    id: u64
+    display_name: str
+    optional nickname: str
+    digest: u8[32]
+    chunks: u8[4][]
+    scores: f64[]
+}
+"#, |units| generate_go("users", units));
+
+    assert!(output.starts_with("// Code generated by Comline. DO NOT EDIT.\n\npackage users\n\n"));
+    assert!(output.contains(concat!(
+        "// A registered user\ntype User struct {\n",
+        "\t// Unique identifier\n",
+        "\tId          uint64     `json:\"id\"`\n",
+        "\tDisplayName string     `json:\"display_name\"`\n",
+        "\tNickname    *string    `json:\"nickname,omitempty\"`\n",
+        "\tDigest      [32]uint8  `json:\"digest\"`\n",
+        "\tChunks      [][4]uint8 `json:\"chunks\"`\n",
+        "\tScores      []float64  `json:\"scores\"`\n",
+        "}\n"
+    )));
+}
+
+#[test]
+fn test_generate_enums() {
+    let output = generate_from_source(
+        "/// Roles of members\nenum Role {\n    Owner\n    Member\n}\n",
+        |units| generate_go("users", units),
+    );
+
+    assert!(output.contains("import (\n\t\"fmt\"\n)\n"));
+    assert!(output.contains(concat!(
+        "// Roles of members\ntype Role int32\n\n",
+        "const (\n\tRoleOwner  Role = 0\n\tRoleMember Role = 1\n)\n"
+    )));
+    // Encoded by the names of their variants
+    assert!(output.contains("func (r Role) MarshalText() ([]byte, error) {\n\treturn []byte(r.String()), nil\n}\n"));
+    assert!(output.contains("\tcase \"Member\":\n\t\t*r = RoleMember\n"));
+}
+
+#[test]
+fn test_generate_constants() {
+    let output = generate_from_source(concat!(
+        "/// Largest size\n",
+        "const MAX_SIZE: u16 = 512\n",
+        "const ENABLED: bool = true\n",
+        "const GREETING: str = \"Hello \\there\"\n",
+    ), |units| generate_go("users", units));
+
+    assert!(output.contains("// Largest size\nconst MaxSize uint16 = 512\n"));
+    assert!(output.contains("const Enabled bool = true\n"));
+    assert!(output.contains("const Greeting string = \"Hello \\\\there\"\n"));
+}
+
+#[test]
+fn test_generate_interfaces() {
+    let output = generate_from_source(r#"
+struct User {
+    id: u64
+}
+
+/// Looks users up
+protocol Users {
+    /// Finds a user by identifier
+    async function get(u64) -> User;
+    function add(u64, User);
+}
+"#, |units| generate_go("users", units));
+
+    assert!(output.contains("import (\n\t\"context\"\n)\n"));
+    assert!(output.contains(concat!(
+        "// Looks users up\ntype Users interface {\n",
+        "\t// Finds a user by identifier\n\tGet(ctx context.Context, arg0 uint64) (User, error)\n",
+        "\tAdd(ctx context.Context, arg0 uint64, arg1 User) error\n}\n"
+    )));
+}
+
+#[test]
+fn test_generate_packages() {
+    let files = generate_go_package(&package(SHOP, &[("module_prefix", "example.com/shop/api")])).unwrap();
+
+    let paths: Vec<_> = files.iter().map(|f| f.path.to_str().unwrap()).collect();
+    assert_eq!(paths, vec!["catalog/catalog.go", "catalog/items/items.go", "types/types.go"]);
+
+    let catalog = file(&files, "catalog/catalog.go");
+    assert!(catalog.contains("package catalog\n\nimport (\n\t\"example.com/shop/api/types\"\n)\n"));
+    assert!(catalog.contains("\tCurrency types.Currency `json:\"currency\"`\n"));
+
+    let items = file(&files, "catalog/items/items.go");
+    assert!(items.contains("import (\n\t\"context\"\n\n\t\"example.com/shop/api/catalog\"\n)\n"));
+    assert!(items.contains("\tPrice    catalog.Price  `json:\"price\"`\n"));
+    assert!(items.contains("\tDiscount *catalog.Price `json:\"discount,omitempty\"`\n"));
+
+    // The module prefix is the package name by default
+    let files = generate_go_package(&package(SHOP, &[])).unwrap();
+    assert!(file(&files, "catalog/catalog.go").contains("\t\"teams/types\"\n"));
+}
+
+#[test]
+fn test_alias_clashing_packages() {
+    let files = generate_go_package(&package(&[
+        ("types", "struct Tag {\n    name: str\n}\n"),
+        ("teams::types", "struct Label {\n    tag: crate::types::Tag\n}\n"),
+    ], &[])).unwrap();
+
+    let types = file(&files, "teams/types/types.go");
+    assert!(types.contains("import (\n\tteams_types \"teams/types\"\n)\n"));
+    assert!(types.contains("\tTag teams_types.Tag `json:\"tag\"`\n"));
+}
+
+#[test]
+fn test_refuse_import_cycles() {
+    let error = generate_go_package(&package(TEAMS, &[])).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "Go packages cannot import each other, which the schemas 'members' -> 'members::accounts' -> 'members' do"
+    );
+}
@@ -0,0 +1,125 @@
+// Standard Uses
+
+// Crate Uses
+use super::{file, generate_from_source, package, validate, TEAMS};
+
+// External Uses
+use comline_core::codelib_gen::GeneratedFile;
+use comline_core::codelib_gen::json_schema::{generate_json_schema, generate_json_schema_package};
+use serde_json::{json, Value};
+
+
+fn document(files: &[GeneratedFile], path: &str) -> Value {
+    serde_json::from_str(file(files, path)).unwrap()
+}
+
+#[test]
+fn test_generate_objects() {
+    let files = generate_from_source(r#"
+/// A registered user
+struct User {
+    /// Unique identifier
+    id: u64
+    optional nickname: str
+    level: i8
+    digest: u8[32]
+    scores: f64[]
+}
+"#, generate_json_schema).unwrap();
+
+    assert_eq!(document(&files, "User.schema.json"), json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "User",
+        "description": "A registered user",
+        "type": "object",
+        "properties": {
+            "id": {
+                "description": "Unique identifier",
+                "type": "integer", "minimum": 0, "maximum": 18446744073709551615u64
+            },
+            "nickname": { "type": "string" },
+            "level": { "type": "integer", "minimum": -128, "maximum": 127 },
+            "digest": {
+                "type": "array",
+                "items": { "type": "integer", "minimum": 0, "maximum": 255 },
+                "minItems": 32, "maxItems": 32
+            },
+            "scores": { "type": "array", "items": {
+                "anyOf": [{ "type": "number" }, { "enum": ["NaN", "Infinity", "-Infinity"] }]
+            } }
+        },
+        "required": ["id", "level", "digest", "scores"]
+    }));
+    // Properties are in the order fields are declared
+    let contents = file(&files, "User.schema.json");
+    assert!(contents.find("\"id\"") < contents.find("\"scores\""));
+}
+
+#[test]
+fn test_generate_defaults() {
+    let files = generate_from_source(concat!(
+        "struct Server {\n    @default=8080\n    port: u16\n",
+        "    @default=\"localhost\"\n    host: str\n    @default=true\n    secure: bool\n}\n",
+    ), generate_json_schema).unwrap();
+    let server = document(&files, "Server.schema.json");
+
+    // Defaults are of the type of their property
+    assert_eq!(server["properties"]["port"]["default"], json!(8080));
+    assert_eq!(server["properties"]["host"]["default"], json!("localhost"));
+    assert_eq!(server["properties"]["secure"]["default"], json!(true));
+}
+
+#[test]
+fn test_generate_enums() {
+    let files = generate_from_source(
+        "/// Roles of members\nenum Role {\n    Owner\n    Member\n}\n",
+        generate_json_schema,
+    ).unwrap();
+
+    assert_eq!(document(&files, "Role.schema.json"), json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Role",
+        "description": "Roles of members",
+        "type": "string",
+        "enum": ["Owner", "Member"]
+    }));
+}
+
+#[test]
+fn test_reference_across_namespaces() {
+    let files = generate_json_schema_package(&package(TEAMS, &[
+        ("base_uri", "https://example.com/teams/"), ("additional_properties", "false"),
+    ])).unwrap();
+
+    let paths: Vec<_> = files.iter().map(|f| f.path.to_str().unwrap()).collect();
+    assert_eq!(paths, vec![
+        "members/Member.schema.json", "members/accounts/Account.schema.json",
+        "teams/Tag.schema.json", "teams/Team.schema.json", "types/Role.schema.json", "types/Tag.schema.json",
+    ]);
+
+    let member = document(&files, "members/Member.schema.json");
+    assert_eq!(member["$id"], "https://example.com/teams/members/Member.schema.json");
+    assert_eq!(member["properties"]["role"], json!({ "$ref": "../types/Role.schema.json" }));
+    assert_eq!(member["properties"]["account"], json!({ "$ref": "accounts/Account.schema.json" }));
+    assert_eq!(member["additionalProperties"], json!(false));
+
+    let account = document(&files, "members/accounts/Account.schema.json");
+    assert_eq!(account["properties"]["role"], json!({ "$ref": "../../types/Role.schema.json" }));
+}
+
+#[test]
+fn test_generated_documents_validate() {
+    let files = generate_json_schema_package(&package(TEAMS, &[
+        ("base_uri", "https://example.com/teams"), ("additional_properties", "false"),
+    ])).unwrap();
+    let team = |members: Value| json!({ "members": members, "tags": [], "role": "Owner" });
+
+    assert!(validate(&files, "teams/Team.schema.json", team(json!([
+        { "role": "Member", "tags": [{ "name": "core" }], "account": { "role": "Owner" } }
+    ]))));
+    // Required fields, enum variants and undeclared properties are checked
+    // through the documents referenced
+    assert!(!validate(&files, "teams/Team.schema.json", team(json!([{ "tags": [] }]))));
+    assert!(!validate(&files, "teams/Team.schema.json", team(json!([{ "role": "Guest", "tags": [] }]))));
+    assert!(!validate(&files, "teams/Team.schema.json", team(json!([{ "role": "Owner", "tags": [], "age": 3 }]))));
+}
@@ -1 +1,96 @@
+// Relative Modules
+mod c_gen_tests;
+mod go_gen_tests;
+mod json_schema_gen_tests;
+mod openapi_gen_tests;
+mod plugin_gen_tests;
+mod protobuf_gen_tests;
+mod python_gen_tests;
+mod registry_tests;
 mod rust_gen_tests;
+mod template_gen_tests;
+mod typescript_gen_tests;
+
+// Standard Uses
+use std::path::Path;
+use std::process::Command;
+
+// Crate Uses
+
+// External Uses
+use comline_core::codelib_gen::{GeneratedFile, GenerationContext, SchemaUnits};
+use comline_core::schema::ir::compiler::interpreter::IncrementalInterpreter;
+use comline_core::schema::ir::compiler::Compile;
+use comline_core::schema::ir::frozen::unit::FrozenUnit;
+use serde_json::{json, Value};
+
+
+/// A package of schemas by namespace, with generation options
+pub fn package(schemas: &[(&str, &str)], options: &[(&str, &str)]) -> GenerationContext {
+    GenerationContext {
+        package_name: "teams".to_owned(),
+        package_version: "1.2.0".to_owned(),
+        schemas: schemas.iter().map(|(namespace, source)| SchemaUnits {
+            namespace: namespace.split("::").map(str::to_owned).collect(),
+            units: IncrementalInterpreter::from_source(source),
+        }).collect(),
+        options: options.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        history: vec![],
+    }
+}
+
+/// What a generator makes of the units of a single schema
+pub fn generate_from_source<T>(source: &str, generate: impl FnOnce(&[FrozenUnit]) -> T) -> T {
+    generate(&IncrementalInterpreter::from_source(source))
+}
+
+pub fn file<'a>(files: &'a [GeneratedFile], path: &str) -> &'a str {
+    &files.iter().find(|f| f.path == Path::new(path))
+        .unwrap_or_else(|| panic!("'{}' was not generated", path))
+        .contents
+}
+
+/// Validates `instance` against the document at `path` with every generated
+/// document registered, returning whether it is valid
+pub fn validate(files: &[GeneratedFile], path: &str, instance: Value) -> bool {
+    let documents: Vec<Value> = files.iter().map(|f| serde_json::from_str(&f.contents).unwrap()).collect();
+    let script = r#"
+import json, sys
+from jsonschema import Draft202012Validator
+from referencing import Registry, Resource
+
+documents, root, instance = json.load(sys.stdin)
+registry = Registry().with_resources([(d["$id"], Resource.from_contents(d)) for d in documents])
+validator = Draft202012Validator({"$ref": root}, registry=registry)
+sys.exit(0 if validator.is_valid(instance) else 1)
+"#;
+
+    let mut child = Command::new("python3")
+        .args(["-c", script])
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .expect("Python could not be run");
+    let root = format!("https://example.com/teams/{}", path);
+    serde_json::to_writer(child.stdin.take().unwrap(), &json!([documents, root, instance])).unwrap();
+
+    child.wait().unwrap().success()
+}
+
+pub const TEAMS: &[(&str, &str)] = &[
+    ("types", "enum Role {\n    Owner\n    Member\n}\n\nstruct Tag {\n    name: str\n}\n"),
+    ("members", concat!(
+        "use crate::types::{Role, Tag}\n\n",
+        "struct Member {\n    role: Role\n    tags: Tag[]\n    optional account: self::accounts::Account\n}\n",
+    )),
+    ("members::accounts", concat!(
+        "use teams::types::*\n\n",
+        "struct Account {\n    role: Role\n}\n\n",
+        "protocol Accounts {\n    async function find(parent::Member) -> Account;\n}\n",
+    )),
+    ("teams", concat!(
+        "use members::Member\n\n",
+        "/// Shadows the tag of `types`\n",
+        "struct Tag {\n    label: str\n}\n\n",
+        "struct Team {\n    members: Member[]\n    tags: Tag[]\n    role: types::Role\n}\n",
+    )),
+];
@@ -0,0 +1,186 @@
+// Standard Uses
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// Crate Uses
+use super::{file, generate_from_source, package, TEAMS};
+
+// External Uses
+use comline_core::codelib_gen::openapi::{generate_openapi, generate_openapi_package};
+use serde_json::{json, Value};
+
+
+fn document(generated: &str) -> Value {
+    serde_json::from_str(generated).unwrap()
+}
+
+/// Checks the document against the OpenAPI specification, panicking with why
+/// it is not valid
+fn assert_valid(document: &str) {
+    let mut child = Command::new("python3")
+        .args(["-c", "import json, sys\nfrom openapi_spec_validator import validate\nvalidate(json.load(sys.stdin))"])
+        .stdin(Stdio::piped()).stderr(Stdio::piped())
+        .spawn()
+        .expect("Python could not be run");
+    child.stdin.take().unwrap().write_all(document.as_bytes()).unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "Document is not valid:\n{}", String::from_utf8_lossy(&output.stderr));
+}
+
+
+#[test]
+fn test_generate_default_operations() {
+    let document = document(&generate_from_source(r#"
+struct User {
+    id: u64
+}
+
+/// Looks users up
+protocol UserDirectory {
+    /// Finds a user by identifier
+    function find(u64, str) -> User;
+    function forget(u64);
+}
+"#, generate_openapi).unwrap());
+
+    assert_eq!(document["paths"]["/user_directory/find"]["post"], json!({
+        "operationId": "UserDirectory.find",
+        "description": "Finds a user by identifier",
+        "tags": ["UserDirectory"],
+        "requestBody": {
+            "required": true,
+            "content": { "application/json": { "schema": {
+                "type": "object",
+                "properties": {
+                    "arg0": { "type": "integer", "minimum": 0, "maximum": 18446744073709551615u64 },
+                    "arg1": { "type": "string" }
+                },
+                "required": ["arg0", "arg1"]
+            }}}
+        },
+        "responses": {
+            "200": {
+                "description": "Success",
+                "content": { "application/json": { "schema": { "$ref": "#/components/schemas/User" } } }
+            }
+        }
+    }));
+    assert_eq!(
+        document["paths"]["/user_directory/forget"]["post"]["responses"],
+        json!({ "204": { "description": "Success, with no content" } })
+    );
+}
+
+#[test]
+fn test_route_with_http_annotations() {
+    let document = document(&generate_from_source(r#"
+protocol Users {
+    @http="GET /users/{arg0}"
+    function get(u64, bool) -> str;
+    @http=DELETE
+    function remove(u64);
+    @http="/users/rename"
+    function rename(u64, str);
+}
+"#, generate_openapi).unwrap());
+
+    let get = &document["paths"]["/users/{arg0}"]["get"];
+    assert_eq!(get["parameters"], json!([
+        { "name": "arg0", "in": "path", "required": true,
+          "schema": { "type": "integer", "minimum": 0, "maximum": 18446744073709551615u64 } },
+        { "name": "arg1", "in": "query", "required": true, "schema": { "type": "boolean" } }
+    ]));
+    assert!(get.get("requestBody").is_none());
+
+    assert!(document["paths"]["/users/remove"]["delete"].is_object());
+    assert!(document["paths"]["/users/rename"]["post"]["requestBody"].is_object());
+}
+
+#[test]
+fn test_refuse_invalid_routes() {
+    let error = generate_from_source(
+        "protocol Users {\n    @http=\"FETCH users\"\n    function get(u64);\n}\n", generate_openapi
+    ).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Function 'Users::get' has an `@http` annotation that is not a method and a path like 'GET /users/{arg0}'"
+    );
+
+    let error = generate_from_source(
+        "protocol Users {\n    @http=\"GET /users/{id}\"\n    function get(u64);\n}\n", generate_openapi
+    ).unwrap_err();
+    assert_eq!(error.to_string(), "Function 'Users::get' has no argument 'id' for its path '/users/{id}'");
+
+    let error = generate_from_source(concat!(
+        "protocol Users {\n    @http=\"GET /users\"\n    function list();\n",
+        "    @http=\"GET /users\"\n    function all();\n}\n",
+    ), generate_openapi).unwrap_err();
+    assert_eq!(error.to_string(), "Function 'Users::all' is a second operation for 'GET /users'");
+}
+
+#[test]
+fn test_generate_error_responses() {
+    let document = document(&generate_from_source(r#"
+@http=404
+error NotFound {
+    message = "NotFound happened"
+    reason: str
+}
+
+/// Was there once
+@http=404
+error Gone {
+    message = "Gone happened"
+    reason: str
+}
+
+error Crashed {
+    message = "Crashed happened"
+    reason: str
+}
+
+protocol Users {
+    function get(u64) throws NotFound, Gone, Crashed;
+}
+"#, generate_openapi).unwrap());
+    let responses = &document["paths"]["/users/get"]["post"]["responses"];
+
+    // Errors of the same status are either of their schemas
+    assert_eq!(responses["404"]["description"], "NotFound, Gone");
+    assert_eq!(responses["404"]["content"]["application/json"]["schema"]["oneOf"][1]["title"], "Gone");
+    assert_eq!(responses["404"]["content"]["application/json"]["schema"]["oneOf"][1]["description"], "Was there once");
+    assert_eq!(responses["500"]["content"]["application/json"]["schema"], json!({
+        "title": "Crashed",
+        "type": "object",
+        "properties": { "reason": { "type": "string" } },
+        "required": ["reason"],
+        "description": "Crashed happened"
+    }));
+}
+
+#[test]
+fn test_generate_valid_package_document() {
+    let files = generate_openapi_package(&package(TEAMS, &[("server_url", "https://api.example.com")])).unwrap();
+    let contents = file(&files, "openapi.json");
+    let document: Value = serde_json::from_str(contents).unwrap();
+
+    assert_eq!(document["info"], json!({ "title": "teams", "version": "1.2.0" }));
+    assert_eq!(document["servers"], json!([{ "url": "https://api.example.com" }]));
+
+    // Component schemas are named after their namespace, so types of the
+    // same name do not clash
+    let schemas = document["components"]["schemas"].as_object().unwrap();
+    let names: Vec<&str> = schemas.keys().map(String::as_str).collect();
+    assert_eq!(names, vec!["types.Role", "types.Tag", "members.Member", "members.accounts.Account", "teams.Tag", "teams.Team"]);
+    assert_eq!(schemas["teams.Team"]["properties"]["tags"]["items"], json!({ "$ref": "#/components/schemas/teams.Tag" }));
+    assert_eq!(
+        schemas["members.Member"]["properties"]["account"],
+        json!({ "$ref": "#/components/schemas/members.accounts.Account" })
+    );
+
+    let find = &document["paths"]["/members/accounts/accounts/find"]["post"];
+    assert_eq!(find["operationId"], "members.accounts.Accounts.find");
+
+    assert_valid(contents);
+}
@@ -0,0 +1,104 @@
+// Standard Uses
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+// Crate Uses
+use super::{file, package, TEAMS};
+
+// External Uses
+use comline_core::codelib_gen::plugin::{generate_plugin_package, PluginRequest, PROTOCOL_VERSION};
+use tempfile::TempDir;
+
+
+/// An executable Python plugin with the given body, which has the request
+/// as `request`
+fn plugin(body: &str) -> (TempDir, PathBuf) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("plugin");
+    let script = format!("#!/usr/bin/env python3\nimport json, sys\nrequest = json.load(sys.stdin)\n{}\n", body);
+    fs::write(&path, script).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+
+    (dir, path)
+}
+
+fn generate(plugin: &Path) -> eyre::Result<Vec<comline_core::codelib_gen::GeneratedFile>> {
+    generate_plugin_package(&package(TEAMS, &[("plugin", plugin.to_str().unwrap()), ("suffix", "kt")]))
+}
+
+
+#[test]
+fn test_generate_with_plugin() {
+    let (_dir, path) = plugin(r#"
+files = []
+for schema in request["schemas"]:
+    names = [next(iter(unit.values()))["name"] for unit in schema["units"] if "Struct" in unit]
+    path = "/".join(schema["namespace"]) + "." + request["options"]["suffix"]
+    files.append({"path": path, "contents": " ".join(names) + "\n"})
+json.dump({"protocol_version": request["protocol_version"], "files": files}, sys.stdout)
+"#);
+
+    let files = generate(&path).unwrap();
+
+    let paths: Vec<_> = files.iter().map(|f| f.path.to_str().unwrap()).collect();
+    assert_eq!(paths, vec!["types.kt", "members.kt", "members/accounts.kt", "teams.kt"]);
+    assert_eq!(file(&files, "teams.kt"), "Tag Team\n");
+}
+
+#[test]
+fn test_send_request_of_package() {
+    // The plugin answers with its request as a file, to be read back
+    let (_dir, path) = plugin(r#"
+json.dump({"protocol_version": 1, "files": [{"path": "request.json", "contents": json.dumps(request)}]}, sys.stdout)
+"#);
+
+    let files = generate(&path).unwrap();
+    let request: PluginRequest = serde_json::from_str(file(&files, "request.json")).unwrap();
+
+    assert_eq!(request.protocol_version, PROTOCOL_VERSION);
+    assert_eq!((request.package.name.as_str(), request.package.version.as_str()), ("teams", "1.2.0"));
+    assert_eq!(request.options["suffix"], "kt");
+    assert_eq!(request.schemas.len(), 4);
+    assert_eq!(request.schemas[2].namespace, vec!["members", "accounts"]);
+    assert_eq!(request.schemas[2].units, package(TEAMS, &[]).schemas[2].units);
+}
+
+#[test]
+fn test_report_plugin_errors() {
+    let (_dir, path) = plugin(r#"
+json.dump({"protocol_version": 1, "diagnostics": [
+    {"level": "warning", "message": "Nothing to say"},
+    {"level": "error", "message": "Tags cannot be shadowed", "schema": "teams"},
+    {"level": "error", "message": "Giving up"},
+]}, sys.stdout)
+"#);
+    let error = generate(&path).unwrap_err();
+    assert_eq!(error.to_string(), format!(
+        "Plugin '{}' reported errors:\n  - teams: Tags cannot be shadowed\n  - Giving up", path.display()
+    ));
+
+    let (_dir, path) = plugin("sys.stderr.write('Unknown option\\n')\nsys.exit(3)");
+    let error = generate(&path).unwrap_err();
+    assert_eq!(error.to_string(), format!(
+        "Plugin '{}' failed with exit status: 3:\nUnknown option", path.display()
+    ));
+
+    let (_dir, path) = plugin("print('Done')");
+    let error = generate(&path).unwrap_err();
+    assert!(error.to_string().contains("did not answer with a response"));
+
+    let (_dir, path) = plugin("json.dump({'protocol_version': 2}, sys.stdout)");
+    let error = generate(&path).unwrap_err();
+    assert!(error.to_string().ends_with("answered with protocol version 2, while version 1 was requested"));
+}
+
+#[test]
+fn test_refuse_files_outside_generation_path() {
+    let (_dir, path) = plugin(r#"
+json.dump({"protocol_version": 1, "files": [{"path": "../escaped.txt", "contents": ""}]}, sys.stdout)
+"#);
+
+    let error = generate(&path).unwrap_err();
+    assert!(error.to_string().ends_with("generated '../escaped.txt', which is not inside the generation path"));
+}
@@ -0,0 +1,177 @@
+// Standard Uses
+
+// Crate Uses
+use super::{file, generate_from_source, package, TEAMS};
+
+// External Uses
+use comline_core::codelib_gen::{FrozenVersion, SchemaUnits};
+use comline_core::codelib_gen::protobuf::{generate_protobuf, generate_protobuf_package};
+use comline_core::schema::ir::compiler::interpreter::IncrementalInterpreter;
+use comline_core::schema::ir::compiler::Compile;
+
+
+/// A version frozen before, with a schema of each namespace
+fn version(schemas: &[(&str, &str)]) -> FrozenVersion {
+    FrozenVersion {
+        version: "0.0.1".to_owned(),
+        schemas: schemas.iter().map(|(namespace, source)| SchemaUnits {
+            namespace: namespace.split("::").filter(|s| !s.is_empty()).map(str::to_owned).collect(),
+            units: IncrementalInterpreter::from_source(source),
+        }).collect(),
+    }
+}
+
+
+#[test]
+fn test_generate_messages() {
+    let output = generate_from_source(r#"
+/// A registered user
+struct User {
+    /// Unique identifier
+    id: u64
+    name: str
+    optional nickname: str
+    level: i16
+    digest: u8[32]
+    chunks: u8[4][]
+    scores: f64[]
+}
+"#, |units| generate_protobuf("users", units)).unwrap();
+
+    assert!(output.starts_with("// Generated by Comline\nsyntax = \"proto3\";\n\npackage users;\n\n"));
+    assert!(output.contains(concat!(
+        "// A registered user\nmessage User {\n",
+        "  // Unique identifier\n  uint64 id = 1;\n",
+        "  string name = 2;\n  optional string nickname = 3;\n  int32 level = 4;\n",
+        "  bytes digest = 5;\n  repeated bytes chunks = 6;\n  repeated double scores = 7;\n}\n"
+    )));
+}
+
+#[test]
+fn test_generate_enums() {
+    let output = generate_from_source(
+        "/// Roles of members\nenum Role {\n    Owner\n    Member\n}\n",
+        |units| generate_protobuf("users", units),
+    ).unwrap();
+
+    assert!(output.contains(concat!(
+        "// Roles of members\nenum Role {\n",
+        "  ROLE_UNSPECIFIED = 0;\n  ROLE_OWNER = 1;\n  ROLE_MEMBER = 2;\n}\n"
+    )));
+
+    // A declared zero value is used instead of adding one
+    let output = generate_from_source(
+        "enum Role {\n    Owner\n    Unspecified\n}\n",
+        |units| generate_protobuf("users", units),
+    ).unwrap();
+    assert!(output.contains("enum Role {\n  ROLE_UNSPECIFIED = 0;\n  ROLE_OWNER = 1;\n}\n"));
+}
+
+#[test]
+fn test_generate_services() {
+    let output = generate_from_source(r#"
+struct User {
+    id: u64
+}
+
+/// Looks users up
+protocol Users {
+    /// Finds a user by identifier
+    async function get(u64) -> User;
+    function add(u64, User);
+}
+"#, |units| generate_protobuf("users", units)).unwrap();
+
+    assert!(output.contains(concat!(
+        "// Looks users up\nservice Users {\n",
+        "  // Finds a user by identifier\n  rpc Get(UsersGetRequest) returns (UsersGetResponse);\n",
+        "  rpc Add(UsersAddRequest) returns (UsersAddResponse);\n}\n\n",
+        "message UsersGetRequest {\n  uint64 arg0 = 1;\n}\n\n",
+        "message UsersGetResponse {\n  User value = 1;\n}\n\n",
+        "message UsersAddRequest {\n  uint64 arg0 = 1;\n  User arg1 = 2;\n}\n\n",
+        "message UsersAddResponse {\n}\n"
+    )));
+}
+
+#[test]
+fn test_generate_package_files() {
+    let files = generate_protobuf_package(&package(TEAMS, &[])).unwrap();
+
+    let paths: Vec<_> = files.iter().map(|f| f.path.to_str().unwrap()).collect();
+    assert_eq!(paths, vec!["members/accounts.proto", "members.proto", "teams.proto", "types.proto"]);
+
+    // Types of other schemas are qualified by their package and imported
+    let members = file(&files, "members.proto");
+    assert!(members.contains(concat!(
+        "package teams.members;\n\n",
+        "import \"members/accounts.proto\";\nimport \"types.proto\";\n\n"
+    )));
+    assert!(members.contains("  repeated teams.types.Tag tags = 2;\n"));
+    assert!(members.contains("  optional teams.members.accounts.Account account = 3;\n"));
+
+    let files = generate_protobuf_package(&package(TEAMS, &[("package_prefix", "acme.teams.v1")])).unwrap();
+    assert!(file(&files, "types.proto").contains("package acme.teams.v1.types;\n"));
+}
+
+#[test]
+fn test_keep_numbers_across_versions() {
+    let mut context = package(&[("users", concat!(
+        "struct User {\n    name: str\n    id: u64\n    email: str\n}\n\n",
+        "enum Role {\n    Member\n    Owner\n}\n",
+    ))], &[]);
+    context.history = vec![
+        version(&[(
+            "users", "struct User {\n    id: u64\n    nickname: str\n}\n\nenum Role {\n    Owner\n    Guest\n}\n"
+        )]),
+        version(&[(
+            "users", "struct User {\n    id: u64\n    nickname: str\n    email: str\n}\n\nenum Role {\n    Owner\n}\n"
+        )]),
+    ];
+
+    let files = generate_protobuf_package(&context).unwrap();
+    let users = file(&files, "users.proto");
+
+    // Fields keep the numbers they first had, new ones are numbered after
+    // every one known before, and removed ones are reserved
+    assert!(users.contains(concat!(
+        "message User {\n  reserved 2;\n  reserved \"nickname\";\n\n",
+        "  string name = 4;\n  uint64 id = 1;\n  string email = 3;\n}\n"
+    )));
+    assert!(users.contains(concat!(
+        "enum Role {\n  reserved 2;\n  reserved \"ROLE_GUEST\";\n\n",
+        "  ROLE_UNSPECIFIED = 0;\n  ROLE_MEMBER = 3;\n  ROLE_OWNER = 1;\n}\n"
+    )));
+}
+
+#[test]
+fn test_match_history_by_namespace() {
+    let users = "struct User {\n    name: str\n    id: u64\n}\n";
+    let mut context = package(&[("accounts", "struct User {\n    handle: str\n}\n"), ("users", users)], &[]);
+    context.history = vec![version(&[("users", "struct User {\n    id: u64\n    name: str\n}\n")])];
+
+    // The schema added before `users` does not take its history
+    let files = generate_protobuf_package(&context).unwrap();
+    assert!(file(&files, "users.proto").contains("message User {\n  string name = 2;\n  uint64 id = 1;\n}\n"));
+    assert!(file(&files, "accounts.proto").contains("message User {\n  string handle = 1;\n}\n"));
+
+    // Versions frozen before namespaces were kept are matched by position
+    context.history = vec![version(&[("", "struct User {\n    handle: str\n    id: u64\n}\n")])];
+    let files = generate_protobuf_package(&context).unwrap();
+    assert!(file(&files, "accounts.proto").contains(
+        "message User {\n  reserved 2;\n  reserved \"id\";\n\n  string handle = 1;\n}\n"
+    ));
+}
+
+#[test]
+fn test_refuse_unrepresentable_fields() {
+    let error = generate_protobuf_package(&package(&[(
+        "grid", "struct Grid {\n    rows: u16[4][]\n    optional tags: str[]\n    total: u128\n    ok: u8[4][2]\n}\n"
+    )], &[])).unwrap_err();
+
+    assert_eq!(error.to_string(), concat!(
+        "Some fields have no Protocol Buffers representation:\n",
+        "  - 'grid::Grid::rows' is `u16[4][]`, an array of arrays\n",
+        "  - 'grid::Grid::tags' is an optional `str[]`, while repeated fields cannot be optional\n",
+        "  - 'grid::Grid::total' is `u128`, which no scalar type is large enough for"
+    ));
+}
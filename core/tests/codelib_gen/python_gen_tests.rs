@@ -0,0 +1,168 @@
+// Standard Uses
+use std::process::Command;
+
+// Crate Uses
+use super::{file, generate_from_source, package, TEAMS};
+
+// External Uses
+use comline_core::codelib_gen::GeneratedFile;
+use comline_core::codelib_gen::python::{generate_python, generate_python_package};
+
+
+/// Runs `script` with the generated package importable as `teams`, panicking
+/// with the interpreter output if it fails
+fn run_python(files: &[GeneratedFile], script: &str) {
+    let dir = tempfile::tempdir().unwrap();
+    for generated in files {
+        let path = dir.path().join("teams").join(&generated.path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, &generated.contents).unwrap();
+    }
+
+    let output = Command::new("python3")
+        .args(["-c", script])
+        .current_dir(dir.path())
+        .output()
+        .expect("Python could not be run");
+
+    assert!(
+        output.status.success(),
+        "Generated code does not run:\n{}", String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+
+#[test]
+fn test_generate_dataclasses() {
+    let output = generate_from_source(r#"
+/// A registered user
+struct User {
+    /// Unique identifier
+    id: u64
+    name: str
+    optional nickname: str
+    class: u8
+    scores: f32[]
+    rows: u16[][2]
+}
+
+struct Empty {}
+"#, generate_python);
+
+    assert!(output.starts_with(concat!(
+        "# Generated by Comline\nfrom __future__ import annotations\n\n",
+        "from dataclasses import dataclass\nfrom typing import Optional\n\n\n"
+    )));
+    assert!(output.contains(concat!(
+        "@dataclass(kw_only=True)\nclass User:\n    \"\"\"A registered user\"\"\"\n\n",
+        "    id: int\n    \"\"\"Unique identifier\"\"\"\n",
+        "    name: str\n    nickname: Optional[str] = None\n    class_: int\n",
+        "    scores: list[float]\n    rows: list[list[int]]\n"
+    )));
+    assert!(output.contains("@dataclass(kw_only=True)\nclass Empty:\n    pass\n"));
+}
+
+#[test]
+fn test_generate_enums() {
+    let source = "/// Roles of members\nenum Role {\n    Owner\n    Member\n}\n";
+
+    let output = generate_from_source(source, generate_python);
+    assert!(output.contains("from enum import Enum\n"));
+    assert!(output.contains(concat!(
+        "class Role(Enum):\n    \"\"\"Roles of members\"\"\"\n\n",
+        "    Owner = \"Owner\"\n    Member = \"Member\"\n"
+    )));
+
+    let files = generate_python_package(&package(&[("types", source)], &[("enum_style", "int")])).unwrap();
+    assert!(file(&files, "types.py").contains("class Role(IntEnum):\n"));
+    assert!(file(&files, "types.py").contains("    Owner = 0\n    Member = 1\n"));
+}
+
+#[test]
+fn test_generate_constants() {
+    let output = generate_from_source(concat!(
+        "/// Largest size\n",
+        "const MAX_SIZE: u16 = 512\n",
+        "const ENABLED: bool = true\n",
+        "const GREETING: str = \"Hello \\there\"\n",
+    ), generate_python);
+
+    assert!(output.contains("from typing import Final\n"));
+    assert!(output.contains("MAX_SIZE: Final[int] = 512\n\"\"\"Largest size\"\"\"\n"));
+    assert!(output.contains("ENABLED: Final[bool] = True\n"));
+    assert!(output.contains("GREETING: Final[str] = \"Hello \\\\there\"\n"));
+}
+
+#[test]
+fn test_generate_protocols() {
+    let output = generate_from_source(r#"
+struct User {
+    id: u64
+}
+
+/// Looks users up
+///
+/// Over any transport
+protocol Users {
+    /// Finds a user by identifier
+    async function get(u64) -> User;
+    function add(u64, User);
+}
+"#, generate_python);
+
+    assert!(output.contains("from typing import Protocol\n"));
+    assert!(output.contains(concat!(
+        "class Users(Protocol):\n    \"\"\"\n    Looks users up\n\n    Over any transport\n    \"\"\"\n\n",
+        "    async def get(self, arg0: int) -> User:\n",
+        "        \"\"\"Finds a user by identifier\"\"\"\n        ...\n\n",
+        "    def add(self, arg0: int, arg1: User) -> None:\n        ...\n"
+    )));
+}
+
+#[test]
+fn test_generate_package_tree() {
+    let files = generate_python_package(&package(TEAMS, &[])).unwrap();
+
+    let paths: Vec<_> = files.iter().map(|f| f.path.to_str().unwrap()).collect();
+    assert_eq!(paths, vec![
+        "__init__.py", "members/__init__.py", "members/accounts.py", "teams.py", "types.py"
+    ]);
+    assert_eq!(file(&files, "__init__.py"), "# Generated by Comline\n");
+
+    // Types of other schemas are imported relatively, for type checking only
+    assert!(file(&files, "members/__init__.py").contains(concat!(
+        "if TYPE_CHECKING:\n",
+        "    from ..types import Role, Tag\n",
+        "    from .accounts import Account\n",
+    )));
+    assert!(file(&files, "members/accounts.py").contains(concat!(
+        "if TYPE_CHECKING:\n",
+        "    from . import Member\n",
+        "    from ..types import Role\n",
+    )));
+    assert!(file(&files, "teams.py").contains("    from .members import Member\n"));
+    assert!(file(&files, "teams.py").contains("    role: Role\n"));
+}
+
+#[test]
+fn test_generated_package_runs() {
+    let files = generate_python_package(&package(TEAMS, &[])).unwrap();
+
+    run_python(&files, r#"
+import asyncio
+from teams.types import Role, Tag
+from teams.members import Member
+from teams.members.accounts import Account, Accounts
+
+class Server:
+    async def find(self, arg0: Member) -> Account:
+        return Account(role=arg0.role)
+
+def lookup(accounts: Accounts, member: Member) -> Account:
+    return asyncio.run(accounts.find(member))
+
+member = Member(role=Role.Owner, tags=[Tag(name="core")])
+assert member.account is None
+assert lookup(Server(), member).role is Role.Owner
+"#);
+}
@@ -0,0 +1,123 @@
+// Standard Uses
+
+// Crate Uses
+use super::{package, TEAMS};
+
+// External Uses
+use comline_core::codelib_gen::{find_generator, SchemaUnits};
+use comline_core::codelib_gen::features::{features_used, Feature, FeatureUse};
+use comline_core::schema::ir::compiler::interpreted::kind_search::KindValue;
+use comline_core::schema::ir::frozen::unit::{FrozenArgument, FrozenUnit};
+use semver::VersionReq;
+
+
+fn schema(namespace: &str, units: Vec<FrozenUnit>) -> SchemaUnits {
+    SchemaUnits { namespace: vec![namespace.to_owned()], units }
+}
+
+fn named(name: &str) -> KindValue {
+    KindValue::Namespaced(name.to_owned(), None)
+}
+
+fn field(name: &str, kind_value: KindValue) -> FrozenUnit {
+    FrozenUnit::Field { docstring: None, parameters: vec![], optional: false, name: name.to_owned(), kind_value }
+}
+
+fn uses(schema: &SchemaUnits) -> Vec<(Feature, String)> {
+    features_used(schema).into_iter().map(|FeatureUse { feature, item }| (feature, item)).collect()
+}
+
+
+#[test]
+fn test_find_generators_by_version() {
+    let generator = find_generator("rust", &VersionReq::parse("^0.2").unwrap()).unwrap();
+    assert_eq!((generator.name, generator.version.to_string().as_str()), ("rust", "0.2.0"));
+
+    let error = find_generator("rust", &VersionReq::parse(">=1.0").unwrap()).unwrap_err();
+    assert_eq!(error.to_string(), "Generator 'rust' is version 0.2.0, which does not satisfy the requirement '>=1.0'");
+
+    let error = find_generator("cobol", &VersionReq::STAR).unwrap_err();
+    assert_eq!(error.to_string(), "No generator found for language named 'cobol'");
+}
+
+#[test]
+fn test_find_features_used() {
+    let context = package(&[(
+        "servers",
+        "struct Server {\n    @default=8080\n    port: u16\n    headers: HashMap[]\n}\n",
+    )], &[]);
+    assert_eq!(uses(&context.schemas[0]), vec![
+        (Feature::Defaults, "servers::Server::port".to_owned()),
+        (Feature::Maps, "servers::Server::headers".to_owned()),
+    ]);
+
+    let units = vec![
+        FrozenUnit::Struct { docstring: None, parameters: vec![], name: "Reply".to_owned(), fields: vec![
+            field("value", KindValue::Union(vec![named("u8"), named("str")])),
+        ]},
+        FrozenUnit::Error {
+            docstring: None, parameters: vec![], name: "Missing".to_owned(),
+            message: "Not found".to_owned(), fields: vec![],
+        },
+        FrozenUnit::Protocol { docstring: String::new(), parameters: vec![], name: "Replies".to_owned(), functions: vec![
+            FrozenUnit::Function {
+                docstring: String::new(), parameters: vec![], name: "get".to_owned(), synchronous: true,
+                arguments: vec![FrozenArgument { name: "arg0".to_owned(), kind: named("HashMap") }],
+                _return: Some(KindValue::Union(vec![named("u8"), named("str")])),
+                throws: vec![FrozenUnit::Error {
+                    docstring: None, parameters: vec![], name: "Missing".to_owned(),
+                    message: "Not found".to_owned(), fields: vec![],
+                }],
+            },
+        ]},
+    ];
+    assert_eq!(uses(&schema("replies", units)), vec![
+        (Feature::Unions, "replies::Reply::value".to_owned()),
+        (Feature::Errors, "replies::Missing".to_owned()),
+        (Feature::Unions, "replies::Replies::get".to_owned()),
+        (Feature::Maps, "replies::Replies::get".to_owned()),
+        (Feature::Errors, "replies::Replies::get".to_owned()),
+    ]);
+
+    // Schemas declaring their own map type use no maps
+    let context = package(&[("maps", "struct HashMap {\n    size: u64\n}\n\nstruct Index {\n    map: HashMap\n}\n")], &[]);
+    assert!(uses(&context.schemas[0]).is_empty());
+}
+
+#[test]
+fn test_check_features_of_generators() {
+    let context = package(&[(
+        "servers",
+        "struct Server {\n    @default=8080\n    port: u16\n    headers: HashMap\n}\n",
+    )], &[]);
+
+    let protobuf = find_generator("protobuf", &VersionReq::STAR).unwrap();
+    let error = protobuf.check_features(&context.schemas).unwrap_err();
+    assert_eq!(error.to_string(), concat!(
+        "Generator 'protobuf' 0.1.0 cannot emit features the package uses:\n",
+        "  - 'servers::Server::port' uses defaults\n",
+        "  - 'servers::Server::headers' uses maps"
+    ));
+
+    let json_schema = find_generator("json_schema", &VersionReq::STAR).unwrap();
+    assert!(json_schema.check_features(&package(&[(
+        "servers", "struct Server {\n    @default=8080\n    port: u16\n}\n"
+    )], &[]).schemas).is_ok());
+
+    // Only OpenAPI documents describe the errors functions throw
+    let errors = package(&[(
+        "users", "error Missing {\n    message = \"Not found\"\n}\n\nprotocol Users {\n    function get(u64) throws Missing;\n}\n"
+    )], &[]);
+    let openapi = find_generator("openapi", &VersionReq::STAR).unwrap();
+    assert!(openapi.check_features(&errors.schemas).is_ok());
+    let rust = find_generator("rust", &VersionReq::STAR).unwrap();
+    assert_eq!(rust.check_features(&errors.schemas).unwrap_err().to_string(), concat!(
+        "Generator 'rust' 0.2.0 cannot emit features the package uses:\n",
+        "  - 'users::Missing' uses errors\n",
+        "  - 'users::Users::get' uses errors"
+    ));
+
+    // Packages using none of them are fine with every generator
+    let c = find_generator("c", &VersionReq::STAR).unwrap();
+    assert!(c.check_features(&package(TEAMS, &[]).schemas).is_ok());
+}
@@ -1,6 +1,16 @@
-use comline_core::codelib_gen::rust::generate_rust;
+// Standard Uses
+use std::path::PathBuf;
+
+// Crate Uses
+use super::{file, generate_from_source, package, TEAMS};
+
+// External Uses
+use comline_core::codelib_gen::GeneratedFile;
+use comline_core::codelib_gen::rust::{generate_rust, generate_rust_package};
 use comline_core::schema::ir::frozen::unit::{FrozenUnit, FrozenArgument};
 use comline_core::schema::ir::compiler::interpreted::kind_search::{KindValue, Primitive};
+use comline_core::wire::dynamic::DynamicSchemas;
+use comline_core::wire::json::{binary_to_json, json_to_binary, JsonOptions};
 
 #[test]
 fn test_generate_simple_struct() {
@@ -35,7 +45,7 @@ fn test_generate_simple_struct() {
         }
     ];
 
-    let output = generate_rust(&units);
+    let output = generate_rust(&units).unwrap();
     
     assert!(output.contains("pub struct User"));
     assert!(output.contains("pub id: i32"));
@@ -56,7 +66,7 @@ fn test_generate_enum() {
         }
     ];
 
-    let output = generate_rust(&units);
+    let output = generate_rust(&units).unwrap();
     
     assert!(output.contains("pub enum Status"));
     assert!(output.contains("Active,"));
@@ -73,6 +83,7 @@ fn test_generate_protocol() {
             functions: vec![
                 FrozenUnit::Function {
                     docstring: "".to_string(),
+                    parameters: vec![],
                     name: "get_user".to_string(),
                     synchronous: true,
                     arguments: vec![
@@ -88,8 +99,694 @@ fn test_generate_protocol() {
         }
     ];
 
-    let output = generate_rust(&units);
+    let output = generate_rust(&units).unwrap();
     
     assert!(output.contains("pub trait UserService"));
-    assert!(output.contains("fn get_user(id: i32) -> User;"));
+    assert!(output.contains("fn get_user(&self, id: i32) -> User;"));
+}
+
+/// Compiles generated code as a library crate depending on serde, panicking
+/// with the compiler output if it does not compile
+fn assert_compiles(code: &str) {
+    assert_crate_compiles(&[
+        GeneratedFile { path: PathBuf::from("Cargo.toml"), contents: concat!(
+            "[package]\nname = \"generated\"\nversion = \"0.0.0\"\nedition = \"2021\"\n\n",
+            "[dependencies]\nserde = { version = \"1\", features = [\"derive\"] }\nserde_json = \"1\"\n"
+        ).to_owned() },
+        GeneratedFile { path: PathBuf::from("src/lib.rs"), contents: code.to_owned() },
+    ]);
+}
+
+fn assert_crate_compiles(files: &[GeneratedFile]) {
+    run_cargo(files, "check");
+}
+
+/// Runs a cargo command on a crate made of `files`, panicking with the
+/// compiler output if it fails
+fn run_cargo(files: &[GeneratedFile], command: &str) {
+    use std::process::Command;
+
+    let dir = tempfile::tempdir().unwrap();
+    for file in files {
+        let path = dir.path().join(&file.path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, &file.contents).unwrap();
+    }
+    // Kept out of the workspace the crate happens to be in
+    let manifest = dir.path().join("Cargo.toml");
+    let contents = std::fs::read_to_string(&manifest).unwrap();
+    std::fs::write(&manifest, format!("{}\n[workspace]\n", contents)).unwrap();
+
+    // Dependencies are built once into the target directory of the tests
+    let target = std::env::current_exe().unwrap()
+        .ancestors().nth(3).unwrap().join("generated-check");
+    let output = Command::new(option_env!("CARGO").unwrap_or("cargo"))
+        .args([command, "--offline", "--quiet"])
+        .env("CARGO_TARGET_DIR", target)
+        .current_dir(dir.path())
+        .output().unwrap();
+
+    let sources: String = files.iter()
+        .map(|file| format!("// {}\n{}\n", file.path.display(), file.contents))
+        .collect();
+    assert!(
+        output.status.success(),
+        "Generated code does not pass `cargo {}`:\n{}\n{}\n{}",
+        command, sources, String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_generate_optional_fields() {
+    let output = generate_from_source(
+        "struct User {\n    id: u64\n    optional nickname: str\n}\n", generate_rust
+    ).unwrap();
+
+    assert!(output.contains(concat!(
+        "    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n",
+        "    pub nickname: Option<String>,\n"
+    )));
+    assert!(output.contains("    pub id: u64,\n"));
+}
+
+#[test]
+fn test_generate_defaults() {
+    let source = concat!(
+        "enum Level {\n    Low\n    High\n}\n\n",
+        "struct Server {\n    @default=8080\n    port: u16\n    @default=\"localhost\"\n    host: str\n",
+        "    @default=true\n    secure: bool\n    @default=2\n    ratio: f64\n    @default=High\n    level: Level\n",
+        "    optional name: str\n}\n\n",
+        "struct Client {\n    @default=3\n    retries: u8\n    server: Server\n}\n",
+    );
+    let output = generate_from_source(source, generate_rust).unwrap();
+
+    assert!(output.contains("    #[serde(default = \"Server::default_port\")]\n    pub port: u16,\n"));
+    assert!(output.contains("    pub fn default_port() -> u16 {\n        8080_u16\n    }\n"));
+    assert!(output.contains("        \"localhost\".to_owned()\n"));
+    assert!(output.contains("        2.0_f64\n"));
+    assert!(output.contains("        Level::High\n"));
+    assert!(output.contains("            name: None,\n"));
+    // Only structs whose every field has a default are `Default`
+    assert!(output.contains("impl Default for Server {\n"));
+    assert!(!output.contains("impl Default for Client"));
+
+    let error = generate_from_source("struct Server {\n    @default=many\n    port: u16\n}\n", generate_rust);
+    assert_eq!(error.unwrap_err().to_string(), "Default 'many' is not a value of type 'u16'");
+
+    let mut files = generate_rust_package(&package(&[("servers", source)], &[("cargo_crate", "true")])).unwrap();
+    let manifest = files.iter_mut().find(|file| file.path.as_os_str() == "Cargo.toml").unwrap();
+    manifest.contents.push_str("\n[dev-dependencies]\nserde_json = \"1\"\n");
+    files.push(GeneratedFile { path: PathBuf::from("tests/defaults.rs"), contents: r##"
+use teams::servers::{Client, Level, Server};
+
+#[test]
+fn fill_in_missing_fields() {
+    let client: Client = serde_json::from_str(r#"{"server": {"port": 80}}"#).unwrap();
+    assert_eq!(client.retries, 3);
+    assert_eq!(client.server.port, 80);
+    assert_eq!(client.server.host, "localhost");
+    assert!(client.server.secure);
+    assert_eq!(client.server.ratio, 2.0);
+    assert_eq!(client.server.level, Level::High);
+    assert_eq!(client.server.name, None);
+
+    let server = Server::default();
+    assert_eq!((server.port, server.name), (8080, None));
+}
+"##.to_owned() });
+
+    run_cargo(&files, "test");
+}
+
+#[test]
+fn test_generate_arrays() {
+    let output = generate_from_source(
+        "struct Buffer {\n    digest: u8[32]\n    chunks: u8[4][]\n    rows: u16[][2]\n}\n",
+        generate_rust,
+    ).unwrap();
+
+    assert!(output.contains("pub digest: [u8; 32],"));
+    assert!(output.contains("pub chunks: Vec<[u8; 4]>,"));
+    assert!(output.contains("pub rows: [Vec<u16>; 2],"));
+}
+
+#[test]
+fn test_generate_constants() {
+    let output = generate_from_source(concat!(
+        "const MAX_SIZE: u16 = 512\n",
+        "const OFFSET: i8 = -4\n",
+        "const ENABLED: bool = true\n",
+        "const GREETING: str = \"Hello \\there\"\n",
+    ), generate_rust).unwrap();
+
+    assert!(output.contains("pub const MAX_SIZE: u16 = 512;"));
+    assert!(output.contains("pub const OFFSET: i8 = -4;"));
+    assert!(output.contains("pub const ENABLED: bool = true;"));
+    assert!(output.contains("pub const GREETING: &str = \"Hello \\\\there\";"));
+}
+
+#[test]
+fn test_generate_docs() {
+    let output = generate_from_source(r#"
+/// A registered user
+///
+/// Spanning paragraphs
+struct User {
+    /// Unique identifier
+    id: u64
+}
+
+/// Looks users up
+protocol Users {
+    /// Finds a user by identifier
+    function get(u64) -> User;
+}
+"#, generate_rust).unwrap();
+
+    assert!(output.contains("/// A registered user\n///\n/// Spanning paragraphs\n#[derive("));
+    assert!(output.contains("    /// Unique identifier\n    pub id: u64,"));
+    assert!(output.contains("/// Looks users up\npub trait Users {"));
+    assert!(output.contains("    /// Finds a user by identifier\n    fn get(&self, arg0: u64) -> User;"));
+}
+
+#[test]
+fn test_generate_unions() {
+    let units = vec![
+        FrozenUnit::Struct {
+            docstring: None,
+            parameters: vec![],
+            name: "Setting".to_string(),
+            fields: vec![
+                FrozenUnit::Field {
+                    docstring: None,
+                    parameters: vec![],
+                    optional: false,
+                    name: "value".to_string(),
+                    kind_value: KindValue::Union(vec![
+                        KindValue::Primitive(Primitive::U32(None)),
+                        KindValue::Primitive(Primitive::String(None)),
+                        KindValue::Namespaced("bool[]".to_string(), None),
+                    ]),
+                },
+            ],
+        }
+    ];
+
+    let output = generate_rust(&units).unwrap();
+
+    assert!(output.contains("pub value: U32OrStrOrBoolList,"));
+    // Variants are named in JSON by their kind
+    assert!(output.contains(concat!(
+        "pub enum U32OrStrOrBoolList {\n",
+        "    #[serde(rename = \"u32\")]\n    U32(u32),\n",
+        "    #[serde(rename = \"str\")]\n    Str(String),\n",
+        "    #[serde(rename = \"bool[]\")]\n    BoolList(Vec<bool>),\n}\n"
+    )));
+    assert_compiles(&output);
+}
+
+#[test]
+fn test_serialize_as_canonical_json() {
+    let mut context = package(&[("readings", concat!(
+        "struct Reading {\n    huge: u128\n    wide: i64\n    ratio: f64\n    optional total: u128\n",
+        "    pairs: u128[2][]\n    small: u16\n}\n",
+    ))], &[("cargo_crate", "true"), ("int64_as_string", "true")]);
+    // Unions cannot be written in schemas yet
+    let FrozenUnit::Struct { fields, .. } = &mut context.schemas[0].units[0] else { unreachable!() };
+    fields.push(FrozenUnit::Field {
+        docstring: None, parameters: vec![], optional: false, name: "value".to_owned(),
+        kind_value: KindValue::Union(vec![
+            KindValue::Namespaced("u8".to_owned(), None), KindValue::Namespaced("i128[]".to_owned(), None),
+        ]),
+    });
+
+    // Canonical JSON reads and writes back the same JSON the crate does
+    let canonical = concat!(
+        r#"{"huge":"340282366920938463463374607431768211455","wide":"-9007199254740993","ratio":"NaN","#,
+        r#""total":"7","pairs":[["1","2"]],"small":3,"value":{"i128[]":["-5"]}}"#,
+    );
+    let schemas = DynamicSchemas::new("teams", context.schemas.clone());
+    let reading = schemas.type_of("readings::Reading").unwrap();
+    let binary = json_to_binary(&reading, &serde_json::from_str(canonical).unwrap()).unwrap();
+    let options = JsonOptions { int64_as_string: true, ..JsonOptions::default() };
+    assert_eq!(binary_to_json(&reading, &binary, &options).unwrap().to_string(), canonical);
+
+    let mut files = generate_rust_package(&context).unwrap();
+    assert!(file(&files, "src/lib.rs").contains("pub mod comline_json;\n"));
+    assert!(file(&files, "src/readings.rs").contains(
+        "    #[serde(default, skip_serializing_if = \"Option::is_none\", with = \"crate::comline_json\")]\n"
+    ));
+    let manifest = files.iter_mut().find(|file| file.path.as_os_str() == "Cargo.toml").unwrap();
+    manifest.contents.push_str("\n[dev-dependencies]\nserde_json = \"1\"\n");
+    files.push(GeneratedFile { path: PathBuf::from("tests/json.rs"), contents: format!(r##"
+use teams::readings::{{Reading, U8OrI128List}};
+
+const CANONICAL: &str = r#"{}"#;
+
+#[test]
+fn write_canonical_json() {{
+    let reading: Reading = serde_json::from_str(CANONICAL).unwrap();
+    assert_eq!(reading.huge, u128::MAX);
+    assert!(reading.ratio.is_nan());
+    assert!(matches!(reading.value, U8OrI128List::I128List(ref values) if values == &[-5]));
+    assert_eq!(serde_json::to_string(&reading).unwrap(), CANONICAL);
+}}
+
+#[test]
+fn read_numbers_too() {{
+    let json = r#"{{"huge":1,"wide":-2,"ratio":0.5,"pairs":[],"small":3,"value":{{"u8":4}}}}"#;
+    let reading: Reading = serde_json::from_str(json).unwrap();
+    assert_eq!((reading.huge, reading.wide, reading.total), (1, -2, None));
+}}
+"##, canonical) });
+
+    run_cargo(&files, "test");
+}
+
+#[test]
+fn test_serialize_long_arrays() {
+    let context = package(&[("keys", concat!(
+        "struct Key {\n    digest: u8[64]\n    optional salt: u8[64]\n    history: u8[64][]\n",
+        "    short: u8[4]\n}\n\n",
+        "protocol Keys {\n    function rotate(u8[64]) -> u8[64];\n}\n",
+    ))], &[("cargo_crate", "true")]);
+
+    let mut files = generate_rust_package(&context).unwrap();
+    let module = file(&files, "src/keys.rs");
+    assert!(module.contains("    #[serde(with = \"crate::comline_json::long_arrays\")]\n    pub digest: [u8; 64],\n"));
+    assert!(module.contains("    pub short: [u8; 4],\n"));
+    assert!(module.contains("    Rotate(#[serde(with = \"crate::comline_json::long_arrays\")] [u8; 64]),\n"));
+
+    let manifest = files.iter_mut().find(|file| file.path.as_os_str() == "Cargo.toml").unwrap();
+    manifest.contents.push_str("\n[dev-dependencies]\nserde_json = \"1\"\n");
+    files.push(GeneratedFile { path: PathBuf::from("tests/keys.rs"), contents: r##"
+use teams::comline::InProcess;
+use teams::keys::{Key, Keys, KeysClient};
+
+struct Server;
+
+impl Keys for Server {
+    fn rotate(&self, key: [u8; 64]) -> [u8; 64] {
+        key.map(|byte| byte.wrapping_add(1))
+    }
+}
+
+#[test]
+fn round_trip() {
+    let key = Key { digest: [7; 64], salt: Some([1; 64]), history: vec![[2; 64]], short: [3; 4] };
+    let json = serde_json::to_string(&key).unwrap();
+    let read: Key = serde_json::from_str(&json).unwrap();
+    assert_eq!((read.digest, read.salt, read.history, read.short), (key.digest, key.salt, key.history, key.short));
+}
+
+#[test]
+fn refuse_other_lengths() {
+    let json = format!(r#"{{"digest":{:?},"history":[],"short":[0,0,0,0]}}"#, [0; 63]);
+    let error = serde_json::from_str::<Key>(&json).unwrap_err();
+    assert!(error.to_string().contains("invalid length 63, expected 64 elements"));
+}
+
+#[test]
+fn call() {
+    let client = KeysClient::new(InProcess(Server));
+    assert_eq!(client.rotate([1; 64]).unwrap(), [2; 64]);
+}
+"##.to_owned() });
+
+    run_cargo(&files, "test");
+
+    let error = generate_from_source("struct Key {\n    digests: u8[64][2]\n}\n", generate_rust).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Type 'u8[64][2]' has an array of more than 32 elements in a fixed array, which cannot be written"
+    );
+}
+
+#[test]
+fn test_generated_code_compiles() {
+    let output = generate_from_source(r#"
+/// Largest amount of members
+const MAX_MEMBERS: u32 = 64
+const NAME: str = "Team"
+
+/// Roles a member can have
+enum Role {
+    Owner
+    Member
+}
+
+/// A member of a team
+struct Member {
+    /// Unique identifier
+    id: u64
+    role: Role
+    optional nickname: string
+    type: u8
+    avatar: u8[16]
+    scores: f32[]
+}
+
+struct Team {
+    members: Member[]
+    optional leader: Member
+}
+
+protocol Teams {
+    function get(u64) -> Team;
+    function add(u64, Member);
+}
+"#, generate_rust).unwrap();
+
+    assert!(output.contains("pub r#type: u8,"));
+    assert_compiles(&output);
+}
+
+#[test]
+fn test_generate_module_tree() {
+    let files = generate_rust_package(&package(TEAMS, &[])).unwrap();
+
+    let paths: Vec<_> = files.iter().map(|f| f.path.to_str().unwrap()).collect();
+    assert_eq!(paths, vec![
+        "mod.rs", "comline.rs", "members/mod.rs", "members/accounts.rs", "teams.rs", "types.rs"
+    ]);
+
+    // Packages with protocols hold the transports of them at their root
+    assert_eq!(
+        file(&files, "mod.rs"),
+        "// Generated by Comline\npub mod comline;\npub mod members;\npub mod teams;\npub mod types;\n\n"
+    );
+    // A schema with schemas under its namespace holds their modules too
+    assert!(file(&files, "members/mod.rs").starts_with(
+        "// Generated by Comline\npub mod accounts;\n\nuse serde::{Serialize, Deserialize};\n"
+    ));
+}
+
+#[test]
+fn test_qualify_imported_types() {
+    let files = generate_rust_package(&package(TEAMS, &[])).unwrap();
+
+    let members = file(&files, "members/mod.rs");
+    assert!(members.contains("pub role: crate::types::Role,"));
+    assert!(members.contains("pub tags: Vec<crate::types::Tag>,"));
+    assert!(members.contains("pub account: Option<crate::members::accounts::Account>,"));
+
+    let accounts = file(&files, "members/accounts.rs");
+    assert!(accounts.contains("pub role: crate::types::Role,"));
+    assert!(accounts.contains(
+        "fn find(&self, arg0: crate::members::Member) -> impl std::future::Future<Output = Account>;"
+    ));
+    assert!(accounts.contains("impl<T: Accounts> crate::comline::Service<AccountsRequest, AccountsResponse> for T {"));
+
+    let teams = file(&files, "teams.rs");
+    assert!(teams.contains("pub members: Vec<crate::members::Member>,"));
+    assert!(teams.contains("pub tags: Vec<Tag>,"));
+    assert!(teams.contains("pub role: crate::types::Role,"));
+
+    // Types are reachable from wherever the root module is
+    let files = generate_rust_package(&package(TEAMS, &[("root_module", "crate::generated")])).unwrap();
+    assert!(file(&files, "teams.rs").contains("pub role: crate::generated::types::Role,"));
+}
+
+#[test]
+fn test_generated_crate_compiles() {
+    let files = generate_rust_package(&package(TEAMS, &[("cargo_crate", "true")])).unwrap();
+
+    assert!(file(&files, "Cargo.toml").starts_with("[package]\nname = \"teams\"\nversion = \"1.2.0\"\n"));
+    assert!(file(&files, "src/lib.rs").contains("pub mod members;"));
+    assert_crate_compiles(&files);
+}
+
+#[test]
+fn test_generate_protocol_messages() {
+    let output = generate_from_source(r#"
+struct User {
+    id: u64
+}
+
+protocol Users {
+    function count() -> u64;
+    async function get(u64) -> User;
+    async function add(u64, User);
+}
+"#, generate_rust).unwrap();
+
+    assert!(output.contains(
+        "pub enum UsersRequest {\n    Count,\n    Get(u64),\n    Add(u64, User),\n}"
+    ));
+    assert!(output.contains(
+        "pub enum UsersResponse {\n    Count(u64),\n    Get(User),\n    Add,\n}"
+    ));
+
+    // Only synchronous functions are not awaited
+    assert!(output.contains("    fn count(&self) -> u64;\n"));
+    assert!(output.contains("    fn get(&self, arg0: u64) -> impl std::future::Future<Output = User>;\n"));
+    assert!(output.contains("    fn add(&self, arg0: u64, arg1: User) -> impl std::future::Future<Output = ()>;\n"));
+
+    assert!(output.contains("    pub fn count(&self) -> Result<u64, comline::TransportError> {\n"));
+    assert!(output.contains("    pub async fn get(&self, arg0: u64) -> Result<User, comline::TransportError> {\n"));
+    assert!(output.contains("pub mod comline {\n"));
+}
+
+#[test]
+fn test_clients_call_servers() {
+    let mut files = generate_rust_package(&package(TEAMS, &[("cargo_crate", "true")])).unwrap();
+    assert!(file(&files, "Cargo.toml").contains("serde_json = \"1\"\n"));
+
+    files.push(GeneratedFile { path: PathBuf::from("tests/calls.rs"), contents: r#"
+use std::net::TcpListener;
+
+use teams::comline::{self, block_on, InProcess, Socket};
+use teams::members::Member;
+use teams::members::accounts::{Account, Accounts, AccountsClient};
+use teams::types::Role;
+
+struct Server;
+
+impl Accounts for Server {
+    async fn find(&self, member: Member) -> Account {
+        Account { role: member.role }
+    }
+}
+
+fn member(role: Role) -> Member {
+    Member { role, tags: vec![], account: None }
+}
+
+#[test]
+fn in_process() {
+    let client = AccountsClient::new(InProcess(Server));
+    let account = block_on(client.find(member(Role::Owner))).unwrap();
+    assert_eq!(account.role as u8, Role::Owner as u8);
+}
+
+#[test]
+fn over_a_socket() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        comline::serve_connection(stream, &Server).unwrap();
+    });
+
+    let client = AccountsClient::new(Socket::connect(address).unwrap());
+    for role in [Role::Member, Role::Owner] {
+        let account = block_on(client.find(member(role))).unwrap();
+        assert_eq!(account.role as u8, role as u8);
+    }
+}
+"#.to_owned() });
+
+    run_cargo(&files, "test");
+}
+
+#[test]
+fn test_escape_functions_named_as_generated_methods() {
+    let context = package(&[(
+        "sessions", "protocol Sessions {\n    function new(u32) -> u32;\n    async function handle(u32) -> u32;\n}\n"
+    )], &[("cargo_crate", "true")]);
+
+    let mut files = generate_rust_package(&context).unwrap();
+    let module = file(&files, "src/sessions.rs");
+    assert!(module.contains("    fn new_(&self, arg0: u32) -> u32;\n"));
+    assert!(module.contains("    fn handle_(&self, arg0: u32) -> impl std::future::Future<Output = u32>;\n"));
+    assert!(module.contains("    pub fn new(transport: T) -> Self {\n"));
+    assert!(module.contains("    pub fn new_(&self, arg0: u32) -> Result<u32, crate::comline::TransportError> {\n"));
+
+    files.push(GeneratedFile { path: PathBuf::from("tests/calls.rs"), contents: r#"
+use teams::comline::{block_on, InProcess};
+use teams::sessions::{Sessions, SessionsClient};
+
+struct Server;
+
+impl Sessions for Server {
+    fn new_(&self, id: u32) -> u32 {
+        id + 1
+    }
+
+    async fn handle_(&self, id: u32) -> u32 {
+        id * 2
+    }
+}
+
+#[test]
+fn call() {
+    let client = SessionsClient::new(InProcess(Server));
+    assert_eq!(client.new_(1).unwrap(), 2);
+    assert_eq!(block_on(client.handle_(3)).unwrap(), 6);
+}
+"#.to_owned() });
+
+    run_cargo(&files, "test");
+}
+
+#[test]
+fn test_generate_wire_impls() {
+    let files = generate_rust_package(&package(TEAMS, &[("wire_encoding", "true")])).unwrap();
+    let types = file(&files, "types.rs");
+
+    assert!(types.contains("use comline_runtime::wire::{self, Decode, DecodeError, Encode, Reader};\n"));
+    assert!(types.contains(concat!(
+        "impl Encode for Tag {\n    fn encode(&self, buffer: &mut Vec<u8>) {\n",
+        "        self.name.encode(buffer);\n    }\n}\n\n",
+        "impl Decode for Tag {\n    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {\n",
+        "        Ok(Self {\n            name: Decode::decode(reader)?,\n        })\n    }\n}\n"
+    )));
+    assert!(types.contains(concat!(
+        "        match reader.read_varint()? {\n",
+        "            0 => Ok(Self::Owner),\n            1 => Ok(Self::Member),\n",
+        "            discriminant => Err(DecodeError::UnknownDiscriminant { type_name: \"Role\", discriminant }),\n",
+    )));
+
+    // Without the option nothing depends on the runtime
+    let files = generate_rust_package(&package(TEAMS, &[("cargo_crate", "true")])).unwrap();
+    assert!(!file(&files, "Cargo.toml").contains("comline-runtime"));
+    assert!(!file(&files, "src/types.rs").contains("Encode"));
+}
+
+#[test]
+fn test_wire_round_trips() {
+    let runtime = concat!(env!("CARGO_MANIFEST_DIR"), "/../runtime");
+    let mut context = package(&[
+        ("kinds", concat!(
+            "enum Level {\n    Low\n    High\n}\n\n",
+            "struct Empty {\n}\n\n",
+            "struct Kinds {\n",
+            "    small: u8\n    signed: i8\n    short: i16\n    medium: u32\n    large: i64\n    huge: u128\n",
+            "    ratio: f64\n    flag: bool\n    name: str\n    text: string\n",
+            "    digest: u8[4]\n    scores: f32[]\n    grid: u16[2][]\n",
+            "    level: Level\n    levels: Level[]\n    empty: Empty\n",
+            "    optional note: str\n",
+            "}\n\n",
+            "struct Pair {\n    first: Kinds\n    optional second: Kinds\n}\n",
+        )),
+    ], &[("cargo_crate", "true"), ("wire_encoding", "true"), ("runtime_path", runtime)]);
+    // Unions cannot be written in schemas yet
+    context.schemas[0].units.push(FrozenUnit::Struct {
+        docstring: None, parameters: vec![], name: "Choice".to_owned(), fields: vec![FrozenUnit::Field {
+            docstring: None, parameters: vec![], optional: false, name: "value".to_owned(),
+            kind_value: KindValue::Union(vec![
+                KindValue::Primitive(Primitive::U32(None)), KindValue::Namespaced("Level[]".to_owned(), None),
+            ]),
+        }],
+    });
+
+    let mut files = generate_rust_package(&context).unwrap();
+    assert!(file(&files, "Cargo.toml").contains(&format!("comline-runtime = {{ path = {:?} }}\n", runtime)));
+
+    files.push(GeneratedFile { path: PathBuf::from("tests/wire.rs"), contents: r#"
+use comline_runtime::wire::{decode, encode, DecodeError};
+use teams::kinds::{Choice, Empty, Kinds, Level, Pair, U32OrLevelList};
+
+fn kinds(note: Option<&str>) -> Kinds {
+    Kinds {
+        small: 200, signed: -5, short: -300, medium: 70_000, large: i64::MIN, huge: u128::MAX,
+        ratio: 0.25, flag: true, name: "Ann".to_owned(), text: "é".to_owned(),
+        digest: [1, 2, 3, 4], scores: vec![1.5, -2.0], grid: vec![[1, 2], [3, 400]],
+        level: Level::High, levels: vec![Level::Low, Level::High], empty: Empty {},
+        note: note.map(str::to_owned),
+    }
+}
+
+#[test]
+fn round_trip() {
+    for value in [Pair { first: kinds(None), second: Some(kinds(Some("Hi"))) }, Pair { first: kinds(None), second: None }] {
+        let bytes = encode(&value);
+        let decoded: Pair = decode(&bytes).unwrap();
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", value));
+        assert_eq!(encode(&decoded), bytes);
+    }
+
+    // Fields are in order, with optionals tagged
+    let bytes = encode(&kinds(None));
+    assert_eq!(&bytes[..5], &[200, 0xfb, 0xd7, 0x04, 0xf0]);
+    assert_eq!(bytes.last(), Some(&0));
+    assert_eq!(&encode(&kinds(Some("Hi")))[bytes.len() - 1..], &[1, 2, b'H', b'i']);
+}
+
+#[test]
+fn round_trip_unions() {
+    for value in [U32OrLevelList::U32(7), U32OrLevelList::LevelList(vec![Level::High])] {
+        let bytes = encode(&Choice { value: value.clone() });
+        let decoded: Choice = decode(&bytes).unwrap();
+        assert_eq!(format!("{:?}", decoded.value), format!("{:?}", value));
+    }
+    assert_eq!(encode(&Choice { value: U32OrLevelList::LevelList(vec![Level::High]) }), vec![1, 1, 1]);
+}
+
+#[test]
+fn refuse_unknown_variants() {
+    assert_eq!(
+        decode::<Level>(&[2]).unwrap_err(),
+        DecodeError::UnknownDiscriminant { type_name: "Level", discriminant: 2 }
+    );
+}
+"#.to_owned() });
+
+    run_cargo(&files, "test");
+}
+
+#[test]
+fn test_generate_validate_methods() {
+    let checks = concat!(
+        "validator Bounds {\n    min: u32 = 1;\n    max: u32;\n\n    validate {\n",
+        "        assert(value.length >= params.min and value.length <= params.max, ",
+        "\"{value.name} should have {params.min} to {params.max} chars, not {value.length}\")\n",
+        "    }\n}\n\n",
+        "validator Positive {\n    validate {\n        assert(value > 0 and value <= 1.5)\n        assert(value.name != \"{{x}}\")\n    }\n}\n",
+    );
+    let teams = concat!(
+        "use crate::checks::{Bounds, Positive}\n\n",
+        "struct Team {\n    @validators=[Bounds(max=4)]\n    name: str\n",
+        "    @validators=[Bounds(min=2 max=3)]\n    optional ranks: u8[]\n",
+        "    @validators=Positive\n    size: u8\n}\n\n",
+        "struct Plain {\n    name: str\n}\n",
+    );
+    let mut files = generate_rust_package(&package(&[("checks", checks), ("teams", teams)], &[("cargo_crate", "true")])).unwrap();
+
+    let module = file(&files, "src/teams.rs");
+    assert!(module.contains("impl Team {\n"));
+    assert!(module.contains("pub fn validate(&self) -> Result<(), Vec<String>> {\n"));
+    assert!(module.contains(
+        "            if !(((value.chars().count() as i128) >= 1i128) && ((value.chars().count() as i128) <= 4i128)) {\n"
+    ));
+    assert!(!module.contains("impl Plain"));
+
+    files.push(GeneratedFile { path: PathBuf::from("tests/validate.rs"), contents: r#"
+use teams::teams::Team;
+
+#[test]
+fn validate() {
+    let team = Team { name: "ann".to_owned(), ranks: None, size: 1 };
+    assert_eq!(team.validate(), Ok(()));
+
+    let team = Team { name: "rangers".to_owned(), ranks: Some(vec![1]), size: 0 };
+    assert_eq!(team.validate().unwrap_err(), [
+        "'name' fails Bounds: name should have 1 to 4 chars, not 7",
+        "'ranks' fails Bounds: ranks should have 2 to 3 chars, not 1",
+        "'size' fails Positive: 'value > 0 and value <= 1.5' does not hold",
+    ]);
+}
+"#.to_owned() });
+
+    run_cargo(&files, "test");
 }
@@ -0,0 +1,130 @@
+// Standard Uses
+use std::fs;
+use std::path::Path;
+
+// Crate Uses
+use super::{file, package, TEAMS};
+
+// External Uses
+use comline_core::codelib_gen::template::{generate_template_package, package_view};
+use tempfile::TempDir;
+
+
+/// A template directory with the given files
+fn templates(files: &[(&str, &str)]) -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    for (path, contents) in files {
+        let path = dir.path().join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    dir
+}
+
+fn options(dir: &Path) -> [(&str, &str); 1] {
+    [("templates", dir.to_str().unwrap())]
+}
+
+
+#[test]
+fn test_render_templates_per_schema() {
+    let dir = templates(&[
+        ("{{path}}.kt.hbs", concat!(
+            "package {{package.name}}.{{#each namespace}}{{this}}{{#unless @last}}.{{/unless}}{{/each}}\n",
+            "{{#each structs}}\ndata class {{name}}(\n",
+            "{{#each fields}}    val {{camel_case name}}: {{map_type type}}{{#if optional}}?{{/if}},\n{{/each}}",
+            ")\n{{/each}}",
+            "{{#each enums}}\nenum class {{name}} { {{#each variants}}{{screaming_snake_case name}}{{#unless @last}}, {{/unless}}{{/each}} }\n{{/each}}",
+        )),
+        ("types.toml", "[types]\nstr = \"String\"\n\"[]\" = \"List<{}>\"\n"),
+    ]);
+
+    let files = generate_template_package(&package(TEAMS, &options(dir.path()))).unwrap();
+
+    let paths: Vec<_> = files.iter().map(|f| f.path.to_str().unwrap()).collect();
+    assert_eq!(paths, vec!["members/accounts.kt", "members.kt", "teams.kt", "types.kt"]);
+
+    assert_eq!(file(&files, "types.kt"), concat!(
+        "package teams.types\n",
+        "data class Tag(\n    val name: String,\n)\n\n",
+        "enum class Role { OWNER, MEMBER }\n",
+    ));
+    assert!(file(&files, "members.kt").contains(
+        "    val role: Role,\n    val tags: List<Tag>,\n    val account: Account?,\n"
+    ));
+}
+
+#[test]
+fn test_render_package_templates() {
+    let dir = templates(&[
+        ("index.md.hbs", concat!(
+            "# {{name}} {{version}}\n",
+            "{{#each schemas}}- {{name}}: {{#each structs}}{{pascal_case name}} {{/each}}\n{{/each}}",
+        )),
+        ("runtime/README", "Copied as it is {{name}}\n"),
+    ]);
+
+    let files = generate_template_package(&package(TEAMS, &options(dir.path()))).unwrap();
+
+    assert_eq!(file(&files, "index.md"), concat!(
+        "# teams 1.2.0\n",
+        "- types: Tag \n- members: Member \n- members::accounts: Account \n- teams: Tag Team \n",
+    ));
+    // Files that are not templates are not rendered
+    assert_eq!(file(&files, "runtime/README"), "Copied as it is {{name}}\n");
+}
+
+#[test]
+fn test_map_types_through_tables() {
+    let dir = templates(&[
+        ("{{snake_case path}}.txt.hbs", concat!(
+            "{{#each structs}}{{#each fields}}",
+            "{{name}}: {{map_type type}} / {{map_type type \"wire\"}}\n",
+            "{{/each}}{{/each}}",
+        )),
+        ("types.toml", concat!(
+            "[types]\nu8 = \"Byte\"\nstr = \"String\"\n\"[]\" = \"List<{}>\"\n\"[N]\" = \"Array<{}, {size}>\"\n\n",
+            "[wire]\nu8 = \"uint8\"\n",
+        )),
+    ]);
+
+    let files = generate_template_package(&package(
+        &[("blobs", "struct Blob {\n    data: u8[4][]\n    label: string\n    kind: Kind\n}\n")],
+        &options(dir.path()),
+    )).unwrap();
+
+    // Tables without an entry leave types as written
+    assert_eq!(file(&files, "blobs.txt"), concat!(
+        "data: List<Array<Byte, 4>> / uint8[4][]\n",
+        "label: String / string\n",
+        "kind: Kind / Kind\n",
+    ));
+}
+
+#[test]
+fn test_report_template_errors() {
+    let dir = templates(&[("{{path}}.txt.hbs", "{{missing}}")]);
+    let error = generate_template_package(&package(TEAMS, &options(dir.path()))).unwrap_err();
+    assert!(error.to_string().starts_with("Could not render template '{{path}}.txt.hbs'"));
+
+    let dir = templates(&[("all.txt.hbs", "{{map_type \"u8\" \"nope\"}}")]);
+    let error = generate_template_package(&package(TEAMS, &options(dir.path()))).unwrap_err();
+    assert!(error.to_string().contains("There is no type table named 'nope'"));
+
+    let error = generate_template_package(&package(TEAMS, &[("templates", "/nowhere")])).unwrap_err();
+    assert_eq!(error.to_string(), "Template directory '/nowhere' does not exist");
+}
+
+#[test]
+fn test_describe_types_in_view() {
+    let view = package_view(&package(TEAMS, &[]));
+    let view = serde_json::to_value(&view).unwrap();
+
+    assert_eq!(view["view_version"], 1);
+    let member = &view["schemas"][1]["structs"][0];
+    assert_eq!(member["fields"][1]["type"], serde_json::json!({
+        "schema": "Tag[]", "element": "Tag", "arrays": [null], "primitive": false, "namespace": ["types"]
+    }));
+    assert_eq!(member["fields"][2]["optional"], true);
+    assert_eq!(view["schemas"][2]["protocols"][0]["functions"][0]["asynchronous"], true);
+}
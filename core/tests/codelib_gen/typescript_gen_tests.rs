@@ -0,0 +1,163 @@
+// Standard Uses
+
+// Crate Uses
+use super::{file, generate_from_source, package, TEAMS};
+
+// External Uses
+use comline_core::codelib_gen::find_generator;
+use comline_core::codelib_gen::typescript::{generate_typescript, generate_typescript_package};
+use semver::VersionReq;
+
+
+
+#[test]
+fn test_generate_interfaces() {
+    let output = generate_from_source(r#"
+/// A registered user
+struct User {
+    /// Unique identifier
+    id: u64
+    name: str
+    optional nickname: str
+    admin: bool
+    digest: u8[32]
+    rows: u16[][2]
+}
+"#, generate_typescript);
+
+    assert!(output.contains(concat!(
+        "/** A registered user */\nexport interface User {\n",
+        "    /** Unique identifier */\n    id: number;\n",
+        "    name: string;\n    nickname?: string;\n    admin: boolean;\n",
+        "    digest: number[];\n    rows: number[][];\n}\n"
+    )));
+}
+
+#[test]
+fn test_generate_enums() {
+    let source = "enum Role {\n    Owner\n    Member\n}\n";
+
+    let output = generate_from_source(source, generate_typescript);
+    assert!(output.contains("export type Role = \"Owner\" | \"Member\";\n"));
+
+    let files = generate_typescript_package(&package(&[("types", source)], &[("enum_style", "enum")])).unwrap();
+    assert!(file(&files, "types.ts").contains(
+        "export enum Role {\n    Owner = \"Owner\",\n    Member = \"Member\",\n}\n"
+    ));
+}
+
+#[test]
+fn test_generate_constants() {
+    let output = generate_from_source(concat!(
+        "/// Largest size\n",
+        "const MAX_SIZE: u16 = 512\n",
+        "const OFFSET: i8 = -4\n",
+        "const ENABLED: bool = true\n",
+        "const GREETING: str = \"Hello \\there\"\n",
+    ), generate_typescript);
+
+    assert!(output.contains("/** Largest size */\nexport const MAX_SIZE: number = 512;\n"));
+    assert!(output.contains("export const OFFSET: number = -4;\n"));
+    assert!(output.contains("export const ENABLED: boolean = true;\n"));
+    assert!(output.contains("export const GREETING: string = \"Hello \\\\there\";\n"));
+}
+
+#[test]
+fn test_generate_wide_integers() {
+    let source = concat!(
+        "const LIMIT: u64 = 9007199254740993\n",
+        "const BALANCE: i64 = -9007199254740993\n",
+        "struct Account {\n    id: u64\n    balance: i128\n    count: u32\n}\n",
+    );
+
+    // As canonical JSON writes them, numbers alone with 64 bits and strings with 128
+    let output = generate_from_source(source, generate_typescript);
+    assert!(output.contains("export const LIMIT: number = 9007199254740993;\n"));
+    assert!(output.contains("export const BALANCE: number = -9007199254740993;\n"));
+    assert!(output.contains("    id: number;\n    balance: string;\n    count: number;\n"));
+
+    // As canonical JSON writes them with `int64_as_string`
+    let files = generate_typescript_package(&package(&[("accounts", source)], &[("int64_as_string", "true")])).unwrap();
+    let output = file(&files, "accounts.ts");
+    assert!(output.contains("export const LIMIT: string = \"9007199254740993\";\n"));
+    assert!(output.contains("    id: string;\n    balance: string;\n    count: number;\n"));
+}
+
+#[test]
+fn test_generate_client_interfaces() {
+    let output = generate_from_source(r#"
+struct User {
+    id: u64
+}
+
+/// Looks users up
+///
+/// Over any transport
+protocol Users {
+    /// Finds a user by identifier
+    async function get(u64) -> User;
+    function add(u64, User);
+}
+"#, generate_typescript);
+
+    assert!(output.contains(concat!(
+        "/**\n * Looks users up\n *\n * Over any transport\n */\nexport interface Users {\n",
+        "    /** Finds a user by identifier */\n    get(arg0: number): Promise<User>;\n",
+        "    add(arg0: number, arg1: User): Promise<void>;\n}\n"
+    )));
+}
+
+#[test]
+fn test_generate_module_tree() {
+    let files = generate_typescript_package(&package(TEAMS, &[])).unwrap();
+
+    let paths: Vec<_> = files.iter().map(|f| f.path.to_str().unwrap()).collect();
+    assert_eq!(paths, vec!["members/accounts.ts", "members.ts", "teams.ts", "types.ts"]);
+    assert_eq!(file(&files, "types.ts"), concat!(
+        "// Generated by Comline\n",
+        "export type Role = \"Owner\" | \"Member\";\n\n",
+        "export interface Tag {\n    name: string;\n}\n\n"
+    ));
+}
+
+#[test]
+fn test_import_types_relatively() {
+    let files = generate_typescript_package(&package(TEAMS, &[])).unwrap();
+
+    assert!(file(&files, "members.ts").starts_with(concat!(
+        "// Generated by Comline\n",
+        "import type { Account } from \"./members/accounts\";\n",
+        "import type { Role, Tag } from \"./types\";\n\n",
+    )));
+    assert!(file(&files, "members.ts").contains("    account?: Account;\n"));
+
+    let accounts = file(&files, "members/accounts.ts");
+    assert!(accounts.starts_with(concat!(
+        "// Generated by Comline\n",
+        "import type { Member } from \"../members\";\n",
+        "import type { Role } from \"../types\";\n\n",
+    )));
+    assert!(accounts.contains("    find(arg0: Member): Promise<Account>;\n"));
+
+    let teams = file(&files, "teams.ts");
+    assert!(teams.contains("import type { Member } from \"./members\";\n"));
+    assert!(teams.contains("import type { Role } from \"./types\";\n"));
+    assert!(teams.contains("    members: Member[];\n    tags: Tag[];\n    role: Role;\n"));
+
+    // Imports shadowing declarations of the module are aliased
+    let files = generate_typescript_package(&package(
+        &[("types", "struct Tag {\n    name: str\n}\n"), ("teams", "struct Tag {\n    parent: types::Tag\n}\n")],
+        &[("import_extension", ".js")]
+    )).unwrap();
+    let teams = file(&files, "teams.ts");
+    assert!(teams.contains("import type { Tag as types_Tag } from \"./types.js\";\n"));
+    assert!(teams.contains("    parent: types_Tag;\n"));
+}
+
+#[test]
+fn test_find_typescript_generator() {
+    let generator = find_generator("typescript", &VersionReq::STAR).unwrap();
+
+    let files = (generator.generate)(&package(TEAMS, &[])).unwrap();
+    assert_eq!(files.len(), 4);
+}
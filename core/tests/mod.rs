@@ -5,3 +5,4 @@ mod autodoc;
 mod utils;
 mod codelib_gen;
 mod cas;
+mod wire;
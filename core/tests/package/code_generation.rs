@@ -0,0 +1,254 @@
+// Standard Uses
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Crate Uses
+
+// External Uses
+use comline_core::package::build::{build, cas::refs, frozen_history, VersionBump, GENERATED_MANIFEST};
+use tempfile::TempDir;
+
+
+fn setup_package(code_generation: &str) -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let config = format!(
+        "congregation test\nspecification_version = 1\n\ncode_generation = {{\n    languages = {{\n{}\n    }}\n}}\n",
+        code_generation
+    );
+    fs::write(dir.path().join("config.idp"), config).unwrap();
+    fs::create_dir_all(dir.path().join("src/nested")).unwrap();
+    fs::write(dir.path().join("src/ping.ids"), "struct Ping {\n    id: u64\n}\n").unwrap();
+    fs::write(dir.path().join("src/nested/pong.ids"), "struct Pong {\n    id: u64\n}\n").unwrap();
+
+    dir
+}
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+
+#[test]
+fn build_generates_into_resolved_paths() {
+    let dir = setup_package(
+        "        rust#1.70.0 = {\n            package_versions = [all]\n            path = \"out/{{language}}-{{version}}\"\n        }"
+    );
+    let package = dir.path();
+
+    let result = build(package).unwrap();
+    assert_eq!(result.generated.len(), 1);
+
+    let generated = &result.generated[0];
+    let target = package.join("out/rust-1.70.0");
+    assert_eq!((generated.language.as_str(), generated.version.as_str()), ("rust", "1.70.0"));
+    assert_eq!(generated.path, target);
+    assert_eq!(generated.files, vec![
+        PathBuf::from("mod.rs"), PathBuf::from("nested/mod.rs"),
+        PathBuf::from("nested/pong.rs"), PathBuf::from("ping.rs"),
+    ]);
+
+    assert!(read(&target.join("ping.rs")).contains("pub struct Ping"));
+    assert!(read(&target.join("nested/pong.rs")).contains("pub struct Pong"));
+    assert_eq!(
+        read(&target.join(GENERATED_MANIFEST)), "mod.rs\nnested/mod.rs\nnested/pong.rs\nping.rs\n"
+    );
+}
+
+#[test]
+fn build_uses_the_default_path() {
+    let dir = setup_package("        rust#1.70.0 = { package_versions = [all] }");
+    let package = dir.path();
+
+    build(package).unwrap();
+    assert!(package.join("generated/rust/1.70.0/ping.rs").exists());
+}
+
+#[test]
+fn build_removes_stale_generated_files() {
+    let dir = setup_package("        rust#1.70.0 = { package_versions = [all] }");
+    let package = dir.path();
+    let target = package.join("generated/rust/1.70.0");
+
+    build(package).unwrap();
+    fs::write(target.join("handwritten.rs"), "// Kept").unwrap();
+    fs::remove_file(package.join("src/nested/pong.ids")).unwrap();
+
+    let result = build(package).unwrap();
+    assert_eq!(result.generated[0].files, vec![PathBuf::from("mod.rs"), PathBuf::from("ping.rs")]);
+
+    assert!(target.join("ping.rs").exists());
+    assert!(!target.join("nested").exists());
+    // Only what was generated before is removed
+    assert!(target.join("handwritten.rs").exists());
+}
+
+#[test]
+fn build_generates_a_cargo_crate() {
+    let dir = setup_package(
+        "        rust#1.70.0 = {\n            package_versions = [all]\n            options = { cargo_crate = true }\n        }"
+    );
+    let package = dir.path();
+    let target = package.join("generated/rust/1.70.0");
+
+    let result = build(package).unwrap();
+    assert_eq!(result.generated[0].files, vec![
+        PathBuf::from("Cargo.toml"), PathBuf::from("src/lib.rs"), PathBuf::from("src/nested/mod.rs"),
+        PathBuf::from("src/nested/pong.rs"), PathBuf::from("src/ping.rs"),
+    ]);
+
+    let manifest = read(&target.join("Cargo.toml"));
+    assert!(manifest.starts_with("[package]\nname = \"test\"\nversion = \"0.0.1\"\n"));
+    assert_eq!(read(&target.join("src/lib.rs")), "// Generated by Comline\npub mod nested;\npub mod ping;\n\n");
+}
+
+#[test]
+fn build_generates_typescript_modules() {
+    let dir = setup_package("        typescript#5.0 = { package_versions = [all] }");
+    let package = dir.path();
+    let target = package.join("generated/typescript/5.0");
+
+    let result = build(package).unwrap();
+    assert_eq!(result.generated[0].files, vec![PathBuf::from("nested/pong.ts"), PathBuf::from("ping.ts")]);
+    assert!(read(&target.join("ping.ts")).contains("export interface Ping {\n    id: number;\n}"));
+}
+
+#[test]
+fn build_reports_generation_failures() {
+    let dir = setup_package("        cobol#85 = { package_versions = [all] }");
+    let package = dir.path();
+
+    let error = build(package).unwrap_err();
+    assert_eq!(
+        error.to_string(), "Could not generate 'cobol#85': No generator found for language named 'cobol'"
+    );
+    // Nothing is frozen when generation fails
+    assert!(!refs::ref_exists(package, refs::main_ref()));
+
+    let dir = setup_package(
+        "        rust#1.70.0 = {\n            package_versions = [all]\n            path = \"out/{{unknown}}\"\n        }"
+    );
+    let error = build(dir.path()).unwrap_err();
+    assert!(error.to_string().starts_with("Could not resolve the generation path of 'rust#1.70.0'"));
+}
+
+#[test]
+fn build_reports_malformed_targets() {
+    let error = |details: &str| {
+        let dir = setup_package(&format!("        rust#1.70.0 = {{\n            {}\n        }}", details));
+        build(dir.path()).unwrap_err().to_string()
+    };
+
+    assert_eq!(error("path = 5"), "'path' of code generation target 'rust#1.70.0' should be a string");
+    assert_eq!(error("paths = \"out\""), concat!(
+        "'paths' of code generation target 'rust#1.70.0' is not a known detail, ",
+        "the details are: package_versions, generator_version, path, options",
+    ));
+    assert_eq!(error("package_versions = all"), "'package_versions' of code generation target 'rust#1.70.0' should be a list");
+    assert_eq!(error("generator_version = 2"), "'generator_version' of code generation target 'rust#1.70.0' should be a string");
+    assert_eq!(error("options = true"), "'options' of code generation target 'rust#1.70.0' should be a dictionary");
+    assert_eq!(error("options = { cargo_crate = [true] }"), concat!(
+        "Option 'cargo_crate' of code generation target 'rust#1.70.0' should be a string, ",
+        "number, boolean or identifier",
+    ));
+}
+
+#[test]
+fn build_keeps_protobuf_field_numbers() {
+    let dir = setup_package("        protobuf#3 = { package_versions = [all] }");
+    let package = dir.path();
+    let target = package.join("generated/protobuf/3/ping.proto");
+
+    build(package).unwrap();
+    assert!(read(&target).contains("message Ping {\n  uint64 id = 1;\n}\n"));
+
+    // The field frozen in the previous version keeps its number reserved
+    fs::write(package.join("src/ping.ids"), "struct Ping {\n    sent: u64\n}\n").unwrap();
+    build(package).unwrap();
+    assert!(read(&target).contains("message Ping {\n  reserved 1;\n  reserved \"id\";\n\n  uint64 sent = 2;\n}\n"));
+}
+
+#[test]
+fn build_matches_frozen_schemas_by_namespace() {
+    let dir = setup_package("        protobuf#3 = { package_versions = [all] }");
+    let package = dir.path();
+    build(package).unwrap();
+
+    // A schema placed before the others is new, rather than taking the place
+    // of the one that was first
+    fs::write(package.join("src/alpha.ids"), "struct Alpha {\n    id: u64\n}\n").unwrap();
+    fs::write(package.join("src/ping.ids"), "struct Ping {\n    sent: u64\n    id: u64\n}\n").unwrap();
+    let result = build(package).unwrap();
+    assert_eq!(result.version_bump, VersionBump::Minor);
+    assert!(read(&package.join("generated/protobuf/3/ping.proto"))
+        .contains("message Ping {\n  uint64 sent = 2;\n  uint64 id = 1;\n}\n"));
+
+    let namespaces: Vec<Vec<String>> = frozen_history(package).unwrap().iter()
+        .map(|version| version.schemas.iter().map(|schema| schema.namespace.join("::")).collect())
+        .collect();
+    assert_eq!(namespaces, [vec!["nested::pong", "ping"], vec!["alpha", "nested::pong", "ping"]]);
+}
+
+#[test]
+fn build_generates_from_templates() {
+    let dir = setup_package(
+        "        kotlin#1 = {\n            package_versions = [all]\n            options = { templates = \"templates/kotlin\" }\n        }"
+    );
+    let package = dir.path();
+    fs::create_dir_all(package.join("templates/kotlin")).unwrap();
+    fs::write(
+        package.join("templates/kotlin/{{path}}.kt.hbs"),
+        "{{#each structs}}data class {{name}}({{#each fields}}val {{name}}: Long{{/each}})\n{{/each}}",
+    ).unwrap();
+
+    let result = build(package).unwrap();
+    assert_eq!(result.generated[0].files, vec![PathBuf::from("nested/pong.kt"), PathBuf::from("ping.kt")]);
+    assert_eq!(read(&package.join("generated/kotlin/1/ping.kt")), "data class Ping(val id: Long)\n");
+}
+
+#[test]
+fn build_generates_with_plugins() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = setup_package(
+        "        kotlin#1 = {\n            package_versions = [all]\n            options = { plugin = \"tools/plugin\" }\n        }"
+    );
+    let package = dir.path();
+    let plugin = package.join("tools/plugin");
+    fs::create_dir_all(plugin.parent().unwrap()).unwrap();
+    fs::write(&plugin, concat!(
+        "#!/bin/sh\ncat > /dev/null\n",
+        "echo '{\"protocol_version\": 1, \"files\": [{\"path\": \"types.kt\", \"contents\": \"class Ping\"}]}'\n",
+    )).unwrap();
+    fs::set_permissions(&plugin, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let result = build(package).unwrap();
+    assert_eq!(result.generated[0].files, vec![PathBuf::from("types.kt")]);
+    assert_eq!(read(&package.join("generated/kotlin/1/types.kt")), "class Ping");
+}
+
+#[test]
+fn build_checks_generators_before_generating() {
+    let dir = setup_package(concat!(
+        "        python#3 = {\n            package_versions = [all]\n        }\n",
+        "        rust#1.70.0 = {\n            package_versions = [all]\n            generator_version = \">=1.0\"\n        }",
+    ));
+    let package = dir.path();
+
+    let error = build(package).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Could not generate 'rust#1.70.0': Generator 'rust' is version 0.2.0, which does not satisfy the requirement '>=1.0'"
+    );
+    // Languages checked before the one failing are not generated either
+    assert!(!package.join("generated").exists());
+
+    let dir = setup_package(
+        "        protobuf#3 = {\n            package_versions = [all]\n            generator_version = \"^0.1\"\n        }"
+    );
+    let package = dir.path();
+    fs::write(package.join("src/ping.ids"), "struct Ping {\n    @default=1\n    id: u64\n}\n").unwrap();
+
+    let error = build(package).unwrap_err();
+    assert!(error.to_string().ends_with("cannot emit features the package uses:\n  - 'ping::Ping::id' uses defaults"));
+    assert!(!package.join("generated").exists());
+}
@@ -0,0 +1,124 @@
+// Standard Uses
+use std::fs;
+use std::path::PathBuf;
+
+// Crate Uses
+use crate::package::schema_loading::{copy_dir_all, setup_test_package};
+
+// External Uses
+use comline_core::package::build::{build, check, diff, VersionBump};
+use comline_core::package::build::cas::history;
+use comline_core::package::publish::publish;
+
+
+#[test]
+fn check_valid_package() {
+    let package_path = setup_test_package("check_valid_package");
+
+    let result = check(&package_path).expect("Check failed");
+    assert!(result.is_valid(), "Unexpected problems: {:?}", result.problems);
+
+    // Checking never freezes anything
+    assert!(!package_path.join(".comline").exists());
+}
+
+#[test]
+fn check_reports_unknown_types() {
+    let package_path = setup_test_package("check_reports_unknown_types");
+    fs::write(package_path.join("src/broken.ids"), "struct Broken {\n    a: Missing\n}\n").unwrap();
+
+    let result = check(&package_path).expect("Check failed");
+    assert!(!result.is_valid());
+    assert_eq!(result.problems.len(), 1);
+    assert_eq!(result.problems[0].namespace, "broken");
+    assert!(result.problems[0].errors[0].message.contains("Missing"));
+}
+
+#[test]
+fn diff_does_not_commit() {
+    let package_path = setup_test_package("diff_does_not_commit");
+    assert!(diff(&package_path).is_err(), "Diff without a frozen version should fail");
+
+    build(&package_path).expect("Initial build failed");
+
+    let ping_file = package_path.join("src/ping.ids");
+    let source = fs::read_to_string(&ping_file).unwrap();
+    fs::write(&ping_file, source.replace("protocol Ping", "protocol Pong")).unwrap();
+
+    let result = diff(&package_path).expect("Diff failed");
+    assert_eq!(result.version_bump, VersionBump::Major);
+    assert_eq!(result.current_version, "1.0.0");
+    assert!(result.is_breaking());
+
+    // The history is untouched by a diff
+    let log = history::log(&package_path).unwrap();
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].1.version, "0.0.1");
+}
+
+#[test]
+fn log_lists_versions_newest_first() {
+    let package_path = setup_test_package("log_lists_versions_newest_first");
+    assert!(history::log(&package_path).unwrap().is_empty());
+
+    build(&package_path).expect("Initial build failed");
+    let ping_file = package_path.join("src/ping.ids");
+    let source = fs::read_to_string(&ping_file).unwrap();
+    fs::write(&ping_file, source + "\nstruct Added {\n    a: u8\n}\n").unwrap();
+    build(&package_path).expect("Second build failed");
+
+    let versions = history::log(&package_path).unwrap()
+        .into_iter().map(|(_, commit)| commit.version).collect::<Vec<_>>();
+    assert_eq!(versions, vec!["0.1.0", "0.0.1"]);
+
+    let (_, commit) = history::find_version(&package_path, "0.0.1").unwrap().unwrap();
+    assert!(commit.is_initial());
+}
+
+#[test]
+fn build_does_not_commit_stores_of_earlier_versions_again() {
+    // Frozen by a version from before blobs were versioned and schemas were
+    // kept by namespace, so the same schemas hash differently now
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let package_path = root.join("target/tmp/build_does_not_commit_stores_of_earlier_versions_again");
+    if package_path.exists() {
+        fs::remove_dir_all(&package_path).unwrap();
+    }
+    copy_dir_all(root.join("tests/fixtures/packages/legacy_store"), &package_path).unwrap();
+
+    let result = build(&package_path).expect("Build failed");
+    assert_eq!(result.version_bump, VersionBump::None);
+    assert_eq!(result.current_version, "0.0.1");
+    assert_eq!(history::log(&package_path).unwrap().len(), 1);
+
+    let ping_file = package_path.join("src/ping.ids");
+    let source = fs::read_to_string(&ping_file).unwrap();
+    fs::write(&ping_file, source + "\nstruct Added {\n    a: u8\n}\n").unwrap();
+
+    let result = build(&package_path).expect("Build failed");
+    assert_eq!(result.version_bump, VersionBump::Minor);
+    let versions = history::log(&package_path).unwrap()
+        .into_iter().map(|(_, commit)| commit.version).collect::<Vec<_>>();
+    assert_eq!(versions, vec!["0.1.0", "0.0.1"]);
+}
+
+#[test]
+fn publish_into_local_registry() {
+    let package_path = setup_test_package("publish_into_local_registry");
+    assert!(publish(&package_path, Some("dev_test_registry")).is_err());
+
+    build(&package_path).expect("Initial build failed");
+
+    // Registry servers are not supported, so only the local registry is published into
+    let publications = publish(&package_path, None).expect("Publish failed");
+    assert_eq!(publications.len(), 1);
+    assert_eq!(publications[0].registry, "dev_test_registry");
+
+    let location = &publications[0].location;
+    assert!(location.ends_with(".temp/registry/test/0.0.1"));
+    assert_eq!(history::log(location).unwrap()[0].0, publications[0].commit);
+
+    // Publishing the same version again is a no-op
+    publish(&package_path, Some("dev_test_registry")).expect("Republish failed");
+    assert!(publish(&package_path, Some("my_registry")).is_err());
+}
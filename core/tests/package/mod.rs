@@ -1 +1,5 @@
 mod schema_loading;
+mod inspection;
+mod watch;
+mod code_generation;
+mod stdlib;
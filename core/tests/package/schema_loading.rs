@@ -3,7 +3,7 @@ use comline_core::package::build::cas::{refs, ObjectStore};
 use std::fs;
 use std::path::{Path, PathBuf};
 
-fn copy_dir_all(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> std::io::Result<()> {
+pub(crate) fn copy_dir_all(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> std::io::Result<()> {
     fs::create_dir_all(&dst)?;
     for entry in fs::read_dir(src)? {
         let entry = entry?;
@@ -17,7 +17,7 @@ fn copy_dir_all(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> std::io::Result
     Ok(())
 }
 
-fn setup_test_package(name: &str) -> PathBuf {
+pub(crate) fn setup_test_package(name: &str) -> PathBuf {
     let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     // Fixtures are in core/tests/fixtures
     let source = root.join("tests/fixtures/packages/test");
@@ -0,0 +1,84 @@
+// Standard Uses
+use std::collections::HashMap;
+
+// Crate Uses
+use crate::codelib_gen::package;
+
+// External Uses
+use comline_core::schema::idl::grammar::{self, Declaration};
+use comline_core::schema::ir::compiler::import_resolver::ImportResolver;
+use comline_core::schema::ir::compiler::interpreter::IncrementalInterpreter;
+use comline_core::schema::ir::compiler::Compile;
+use comline_core::schema::ir::validation::validate;
+use comline_core::stdlib;
+use comline_core::wire::dynamic::{DynamicSchemas, DynamicValue};
+
+
+fn namespace(path: &str) -> Vec<String> {
+    path.split("::").map(str::to_owned).collect()
+}
+
+#[test]
+fn test_embeds_the_standard_library() {
+    let namespaces: Vec<String> = stdlib::schemas().iter().map(|schema| schema.namespace.join("::")).collect();
+    assert_eq!(namespaces, ["std::publish", "std::validators::string_bounds"]);
+
+    let bounds = stdlib::schema(&namespace("std::validators::string_bounds")).unwrap();
+    assert!(bounds.declares("StringBounds"));
+    assert_eq!(
+        stdlib::source(&bounds.namespace).unwrap(),
+        std::fs::read_to_string("../core_stdlib/packages/std/src/validators/string_bounds.ids").unwrap()
+    );
+
+    let imported = stdlib::schema_of_import(&namespace("std::validators::string_bounds::StringBounds"));
+    assert_eq!(imported.map(|schema| &schema.namespace), Some(&bounds.namespace));
+    assert!(stdlib::schema_of_import(&namespace("std::validators")).is_none());
+}
+
+#[test]
+fn test_resolves_imports_without_a_stdlib_root() {
+    let resolver = ImportResolver::new(vec!["teams".to_owned()], HashMap::new(), None);
+    let resolve = |source: &str| {
+        let document = grammar::parse(source).unwrap();
+        let Declaration::Use(use_stmt) = &document.0[0] else { panic!("Expected a use") };
+        resolver.resolve(&use_stmt.path, &[])
+    };
+
+    let resolved = resolve("use std::validators::string_bounds::StringBounds\n").unwrap();
+    let document = resolver.load_schema(&resolved).unwrap();
+    assert!(document.0.iter().any(|declaration| matches!(declaration, Declaration::Validator(_))));
+
+    assert_eq!(resolve("use std::http::Request\n").unwrap_err(), "'std::http::Request' is not in the standard library");
+}
+
+#[test]
+fn test_validates_imports_of_the_standard_library() {
+    let code = concat!(
+        "use std::validators::string_bounds::{StringBounds, Missing}\n",
+        "use std::publish::*\n",
+        "use std::http::Request\n",
+    );
+    let errors = validate(&IncrementalInterpreter::from_source(code)).unwrap_err();
+    let messages: Vec<&str> = errors.iter().map(|error| error.message.as_str()).collect();
+
+    assert_eq!(messages, [
+        "'Missing' is not declared in std::validators::string_bounds",
+        "'std::http::Request' is not in the standard library",
+    ]);
+}
+
+#[test]
+fn test_runs_validators_of_the_standard_library() {
+    let teams = concat!(
+        "use std::validators::string_bounds::StringBounds\n\n",
+        "struct Team {\n    @validators=[StringBounds(max_chars=4)]\n    name: str\n}\n",
+    );
+    let schemas = DynamicSchemas::new("teams", package(&[("teams", teams)], &[]).schemas);
+    let team = schemas.type_of("teams::Team").unwrap();
+
+    assert!(team.validate(&DynamicValue::structure([("name", "ann".into())])).is_ok());
+    assert_eq!(team.validate(&DynamicValue::structure([("name", "rangers".into())])).unwrap_err().to_string(), concat!(
+        "Value is not a valid 'teams::Team':\n",
+        "  - 'teams::Team.name' fails StringBounds: String name must have from 0 to 4 chars",
+    ));
+}
@@ -0,0 +1,105 @@
+// Standard Uses
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+// Crate Uses
+
+// External Uses
+use comline_core::package::build::{build, cas::refs, VersionBump};
+use comline_core::package::watch::{rebuild, Watcher};
+use tempfile::TempDir;
+
+
+fn setup_package() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("config.idp"), "congregation test\nspecification_version = 1\n").unwrap();
+    fs::create_dir(dir.path().join("src")).unwrap();
+    fs::write(dir.path().join("src/ping.ids"), "struct Ping {\n    id: u64\n}\n").unwrap();
+
+    dir
+}
+
+fn write(package: &Path, path: &str, contents: &str) {
+    fs::write(package.join(path), contents).unwrap();
+}
+
+
+#[test]
+fn watcher_reports_settled_changes() {
+    let dir = setup_package();
+    let package = dir.path();
+    let mut watcher = Watcher::new(package, Duration::ZERO);
+
+    assert_eq!(watcher.poll(), None);
+
+    write(package, "src/ping.ids", "struct Ping {\n    id: u32\n}\n");
+    write(package, "src/pong.ids", "struct Pong {\n    id: u64\n}\n");
+    assert_eq!(watcher.poll(), Some(vec![package.join("src/ping.ids"), package.join("src/pong.ids")]));
+    assert_eq!(watcher.poll(), None);
+
+    // Saving a file as it was is not a change
+    write(package, "src/ping.ids", "struct Ping {\n    id: u32\n}\n");
+    assert_eq!(watcher.poll(), None);
+
+    fs::remove_file(package.join("src/pong.ids")).unwrap();
+    write(package, "config.idp", "congregation test\nspecification_version = 2\n");
+    assert_eq!(watcher.poll(), Some(vec![package.join("config.idp"), package.join("src/pong.ids")]));
+}
+
+#[test]
+fn watcher_debounces_changes() {
+    let dir = setup_package();
+    let package = dir.path();
+    let mut watcher = Watcher::new(package, Duration::from_millis(50));
+
+    write(package, "src/ping.ids", "struct Ping {\n    id: u32\n}\n");
+    assert_eq!(watcher.poll(), None);
+    write(package, "src/ping.ids", "struct Ping {\n    id: u16\n}\n");
+    assert_eq!(watcher.poll(), None);
+
+    // Both writes are reported once files settle
+    assert_eq!(watcher.wait(Duration::from_millis(10)), vec![package.join("src/ping.ids")]);
+}
+
+#[test]
+fn rebuild_previews_changes_without_committing() {
+    let dir = setup_package();
+    let package = dir.path();
+
+    // Before the first build there is nothing to compare against
+    let result = rebuild(package, false).unwrap();
+    assert!(result.check.is_valid());
+    assert!(result.preview.is_none());
+
+    build(package).unwrap();
+    let head = refs::read_ref(package, refs::main_ref()).unwrap();
+
+    write(package, "src/pong.ids", "struct Pong {\n    id: u64\n}\n");
+    let result = rebuild(package, false).unwrap();
+    let preview = result.preview.unwrap();
+    assert_eq!(preview.version_bump, VersionBump::Minor);
+    assert_eq!(preview.current_version, "0.1.0");
+    assert!(result.generated.is_empty());
+
+    assert_eq!(refs::read_ref(package, refs::main_ref()).unwrap(), head);
+}
+
+#[test]
+fn rebuild_reports_problems() {
+    let dir = setup_package();
+    let package = dir.path();
+
+    write(package, "src/ping.ids", "struct Ping {\n    id: Missing\n}\n");
+    let result = rebuild(package, true).unwrap();
+    assert!(!result.check.is_valid());
+    assert!(result.preview.is_none());
+    assert!(result.generated.is_empty());
+
+    // Configurations that cannot be interpreted are errors rather than panics
+    write(package, "src/ping.ids", "struct Ping {\n    id: u64\n}\n");
+    write(package, "config.idp", "congregation test\nunknown_key = 1\n");
+    assert_eq!(
+        rebuild(package, false).err().unwrap().to_string(), "Assignment 'unknown_key' is not a valid assignment"
+    );
+}
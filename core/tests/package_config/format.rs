@@ -0,0 +1,79 @@
+// Standard Uses
+
+// Crate Uses
+
+// External Uses
+use comline_core::package::config::idl::formatter::format_config;
+use comline_core::package::format::format_package;
+
+
+#[test]
+fn formats_configuration() {
+    let source = r#"congregation test
+specification_version=1
+code_generation = { languages = {
+  rust#1.70.0 = { package_versions=[all] } // the main one
+  // python#3.11.0 = { package_versions=[all] }
+} }
+
+
+publish_registries = {
+    mine = { uri="local://{{package_path}}/registry/" }
+}
+"#;
+
+    pretty_assertions::assert_eq!(format_config(source).unwrap(), r#"congregation test
+specification_version = 1
+code_generation = {
+    languages = {
+        rust#1.70.0 = {
+            package_versions = [all]
+        } // the main one
+        // python#3.11.0 = { package_versions=[all] }
+    }
+}
+
+publish_registries = {
+    mine = {
+        uri = "local://{{package_path}}/registry/"
+    }
+}
+"#);
+}
+
+#[test]
+fn breaks_long_lists_with_trailing_commas() {
+    let long = "x".repeat(60);
+    let source = format!("congregation test\nschema_paths = [\"{}\", \"{}\"]\n", long, long);
+
+    let formatted = format_config(&source).unwrap();
+    assert_eq!(
+        formatted,
+        format!("congregation test\nschema_paths = [\n    \"{}\",\n    \"{}\",\n]\n", long, long)
+    );
+    assert_eq!(format_config(&formatted).unwrap(), formatted);
+
+    // Short enough again, back on one line
+    let short = "congregation test\nschema_paths = [\n    \"a\",\n    \"b\",\n]\n";
+    assert_eq!(format_config(short).unwrap(), "congregation test\nschema_paths = [\"a\", \"b\"]\n");
+}
+
+#[test]
+fn checks_and_formats_package() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("config.idp"), "congregation test\nspecification_version = 1\n").unwrap();
+    std::fs::write(root.join("src/a.ids"), "struct A {\n    b: u8\n}\n").unwrap();
+    std::fs::write(root.join("src/b.ids"), "struct B{c:u8}").unwrap();
+
+    let unformatted = format_package(root, true).unwrap();
+    assert_eq!(unformatted, vec![root.join("src/b.ids")]);
+    assert_eq!(std::fs::read_to_string(root.join("src/b.ids")).unwrap(), "struct B{c:u8}");
+
+    assert_eq!(format_package(root, false).unwrap(), vec![root.join("src/b.ids")]);
+    assert!(format_package(root, true).unwrap().is_empty());
+
+    std::fs::write(root.join("src/c.ids"), "struct {").unwrap();
+    assert!(format_package(root, true).is_err());
+}
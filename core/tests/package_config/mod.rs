@@ -2,6 +2,7 @@
 mod build;
 mod compile;
 mod parse;
+mod format;
 
 // Standard Uses
 use std::path::{Path, PathBuf};
@@ -155,3 +155,16 @@ key without equals
     let result = grammar::parse(code);
     assert!(result.is_err());
 }
+
+#[test]
+fn test_parse_list_trailing_comma() {
+    let code = "congregation Lists\nitems = [\n    \"a\",\n    \"b\",\n]\nempty = []\n";
+    let congregation = grammar::parse(code).unwrap();
+
+    let grammar::Value::List(items) = &congregation.assignments[0].value else { panic!() };
+    assert_eq!(items.items().len(), 2);
+    let grammar::Value::List(empty) = &congregation.assignments[1].value else { panic!() };
+    assert!(empty.items().is_empty());
+
+    assert!(grammar::parse("congregation Lists\nitems = [\"a\" \"b\"]\n").is_err());
+}
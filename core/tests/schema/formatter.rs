@@ -0,0 +1,241 @@
+// Standard Uses
+
+// Crate Uses
+
+// External Uses
+use comline_core::schema::idl::formatter::format_schema;
+use comline_core::schema::idl::grammar;
+
+
+#[test]
+fn formats_declarations() {
+    let source = r#"// Users
+/// A registered user
+struct   User{ id:u64 // unique
+   optional  email :string
+   /// Free form
+   tags: str[]
+
+   avatar: u8[64]
+}
+enum Role { Admin
+ Member }
+const MAX: u8 = 8
+const MIN: u8 = 1
+
+
+const NAME: str = "x"
+@provider=Any
+protocol Users {
+  @timeout_ms=10
+  function get(u64,Role) -> User;
+
+  async   function ping();
+}
+struct Empty {}
+"#;
+
+    pretty_assertions::assert_eq!(format_schema(source).unwrap(), r#"// Users
+/// A registered user
+struct User {
+    id:             u64 // unique
+    optional email: string
+    /// Free form
+    tags:           str[]
+
+    avatar: u8[64]
+}
+
+enum Role {
+    Admin
+    Member
+}
+
+const MAX: u8 = 8
+const MIN: u8 = 1
+
+const NAME: str = "x"
+
+@provider=Any
+protocol Users {
+    @timeout_ms=10
+    function get(u64, Role) -> User;
+
+    async function ping();
+}
+
+struct Empty {}
+"#);
+}
+
+#[test]
+fn formats_errors_and_what_throws_them() {
+    let source = r#"/// No user has the id
+@http=404
+error   NotFound{ message   =  "No user with id {self.id}"
+  id:u64
+   optional  hint :str }
+protocol Users {
+  function get(u64) -> str   throws NotFound ,Gone;
+}
+"#;
+
+    pretty_assertions::assert_eq!(format_schema(source).unwrap(), r#"/// No user has the id
+@http=404
+error NotFound {
+    message = "No user with id {self.id}"
+
+    id:            u64
+    optional hint: str
+}
+
+protocol Users {
+    function get(u64) -> str throws NotFound, Gone;
+}
+"#);
+}
+
+#[test]
+fn sorts_and_groups_uses() {
+    let source = r#"// Imports
+use zoo::Animal
+use self::local::Thing
+use std::http::Request
+use app::{Post, User, Comment}
+use app::types::Id as Identifier
+
+struct A {
+    b: u8
+}
+"#;
+
+    pretty_assertions::assert_eq!(format_schema(source).unwrap(), r#"// Imports
+use std::http::Request
+
+use app::types::Id as Identifier
+use app::{Comment, Post, User}
+use zoo::Animal
+
+use self::local::Thing
+
+struct A {
+    b: u8
+}
+"#);
+}
+
+#[test]
+fn breaks_long_multi_imports_with_trailing_commas() {
+    let names = (0..12).map(|i| format!("SomeLongTypeName{}", i)).collect::<Vec<_>>();
+    let source = format!("use app::types::{{{}}}\n", names.join(", "));
+
+    let formatted = format_schema(&source).unwrap();
+    let mut sorted = names.clone();
+    sorted.sort();
+    let expected = format!(
+        "use app::types::{{\n{}}}\n",
+        sorted.iter().map(|n| format!("    {},\n", n)).collect::<String>()
+    );
+
+    assert_eq!(formatted, expected);
+    assert!(grammar::parse(&formatted).is_ok());
+    assert_eq!(format_schema(&formatted).unwrap(), formatted);
+}
+
+#[test]
+fn places_annotations_above_members() {
+    let source = r#"use b::Second
+@allow=unused_imports use a::First
+@allow=empty_structs struct Empty {}
+struct Record {
+    id: u64
+    @allow=naming_convention
+    Legacy: str
+
+    @deprecated="use id" old_id: u32
+}
+enum Status { @allow=naming_convention ok }
+"#;
+
+    let formatted = format_schema(source).unwrap();
+    pretty_assertions::assert_eq!(formatted, r#"@allow=unused_imports
+use a::First
+use b::Second
+
+@allow=empty_structs
+struct Empty {}
+
+struct Record {
+    id:     u64
+    @allow=naming_convention
+    Legacy: str
+
+    @deprecated="use id"
+    old_id: u32
+}
+
+enum Status {
+    @allow=naming_convention
+    ok
+}
+"#);
+    assert_eq!(format_schema(&formatted).unwrap(), formatted);
+}
+
+#[test]
+fn formats_validators() {
+    let source = r#"/// Bounds of strings
+validator Bounds{ min :u32=u32::MIN;
+max: u32 = 8 ;
+  validate {
+    assert( value.length>=params.min and (value.length<=params.max) , "{value.name} is {value.length} chars long" )
+    assert(not (value == "root"))  // reserved
+  }
+}
+struct User {
+  @validators=[ Bounds( max=4 , min = 1 ) Other ]
+  name: str
+}
+"#;
+
+    let formatted = format_schema(source).unwrap();
+    pretty_assertions::assert_eq!(formatted, r#"/// Bounds of strings
+validator Bounds {
+    min: u32 = u32::MIN;
+    max: u32 = 8;
+
+    validate {
+        assert(
+            value.length >= params.min and value.length <= params.max,
+            "{value.name} is {value.length} chars long"
+        )
+        assert(not value == "root") // reserved
+    }
+}
+
+struct User {
+    @validators=[Bounds(max=4 min=1) Other]
+    name: str
+}
+"#);
+    assert_eq!(format_schema(&formatted).unwrap(), formatted);
+}
+
+#[test]
+fn keeps_comments_inside_empty_blocks() {
+    let source = "struct Later {\n  // Nothing yet\n}\n// End of file\n";
+    assert_eq!(format_schema(source).unwrap(), "struct Later {\n    // Nothing yet\n}\n// End of file\n");
+}
+
+#[test]
+fn refuses_invalid_schemas() {
+    assert!(format_schema("struct {").is_err());
+}
+
+#[test]
+fn formatting_is_idempotent_on_fixtures() {
+    for path in ["tests/fixtures/packages/test/src/ping.ids", "tests/fixtures/packages/test/src/health.ids"] {
+        let formatted = format_schema(&std::fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(format_schema(&formatted).unwrap(), formatted, "{}", path);
+    }
+}
@@ -233,6 +233,69 @@ protocol EventService {
         }
     }
 
+    #[test]
+    fn test_protocol_async_functions_ir() {
+        let code = r#"
+protocol Jobs {
+    async function run(str) -> u64;
+    function cancel(u64);
+}
+"#;
+        let ir_units = IncrementalInterpreter::from_source(code);
+        assert_eq!(ir_units.len(), 1);
+        match &ir_units[0] {
+            comline_core::schema::ir::frozen::unit::FrozenUnit::Protocol { functions, .. } => {
+                let synchronous: Vec<bool> = functions.iter().map(|function| match function {
+                    comline_core::schema::ir::frozen::unit::FrozenUnit::Function { synchronous, .. } => *synchronous,
+                    _ => panic!("Expected Function"),
+                }).collect();
+                assert_eq!(synchronous, vec![false, true]);
+            }
+            _ => panic!("Expected Protocol"),
+        }
+    }
+
+    #[test]
+    fn test_function_annotations_ir() {
+        use comline_core::schema::ir::frozen::unit::FrozenUnit;
+
+        let code = r#"
+protocol Jobs {
+    @http="GET /jobs/{arg0}"
+    @allow=missing_docs
+    function get(u64) -> str;
+}
+"#;
+        let ir_units = IncrementalInterpreter::from_source(code);
+        match &ir_units[0] {
+            FrozenUnit::Protocol { functions, .. } => match &functions[0] {
+                // Annotations only lints read are left out
+                FrozenUnit::Function { parameters, .. } => assert_eq!(parameters, &vec![
+                    FrozenUnit::Parameter { name: "http".to_owned(), default_value: "GET /jobs/{arg0}".to_owned() }
+                ]),
+                _ => panic!("Expected Function"),
+            },
+            _ => panic!("Expected Protocol"),
+        }
+    }
+
+    #[test]
+    fn test_field_annotations_ir() {
+        use comline_core::schema::ir::frozen::unit::FrozenUnit;
+
+        let code = "struct Server {\n    @default=8080\n    port: u16\n}\n";
+        let ir_units = IncrementalInterpreter::from_source(code);
+        match &ir_units[0] {
+            FrozenUnit::Struct { fields, .. } => match &fields[0] {
+                FrozenUnit::Field { parameters, .. } => assert_eq!(parameters, &vec![
+                    FrozenUnit::Parameter { name: "default".to_owned(), default_value: "8080".to_owned() }
+                ]),
+                _ => panic!("Expected Field"),
+            },
+            _ => panic!("Expected Struct"),
+        }
+    }
+
     #[test]
     fn test_multiple_structs_ir() {
         let code = r#"
@@ -0,0 +1,194 @@
+// Standard Uses
+
+// Crate Uses
+
+// External Uses
+use comline_core::package::config::idl::grammar as config_grammar;
+use comline_core::package::config::ir::context::ProjectContext;
+use comline_core::package::config::ir::interpreter::interpret::interpret_context;
+use comline_core::schema::idl::grammar::{self, Declaration};
+use comline_core::schema::ir::lint::{
+    lint_schemas, Lint, LintLevel, LintLevels, LintTarget, Rule,
+};
+
+
+fn lint_sources(sources: &[(&str, &str)], levels: &LintLevels) -> Vec<Vec<Lint>> {
+    let parsed: Vec<(Vec<String>, Vec<Declaration>)> = sources.iter()
+        .map(|(namespace, source)| {
+            (vec![namespace.to_string()], grammar::parse(source).unwrap().0)
+        })
+        .collect();
+    let targets: Vec<_> = parsed.iter().zip(sources)
+        .map(|((namespace, declarations), (_, source))| LintTarget {
+            namespace, source, declarations,
+        })
+        .collect();
+
+    lint_schemas(&targets, levels)
+}
+
+fn lint_source(source: &str) -> Vec<(Rule, String)> {
+    lint_sources(&[("schema", source)], &LintLevels::default()).remove(0).into_iter()
+        .map(|lint| (lint.rule, lint.message))
+        .collect()
+}
+
+
+#[test]
+fn naming_conventions() {
+    let lints = lint_source(r#"
+const maxSize: u8 = 8
+struct user_account {
+    userName: str
+}
+enum Role {
+    admin
+}
+protocol Users {
+    function GetUser(u64) -> user_account;
+}
+"#);
+
+    pretty_assertions::assert_eq!(lints, vec![
+        (Rule::NamingConvention, "Constant 'maxSize' should have a SCREAMING_SNAKE_CASE name, such as 'MAX_SIZE'".to_owned()),
+        (Rule::NamingConvention, "Struct 'user_account' should have a PascalCase name, such as 'UserAccount'".to_owned()),
+        (Rule::NamingConvention, "Field 'userName' should have a snake_case name, such as 'user_name'".to_owned()),
+        (Rule::NamingConvention, "Variant 'admin' should have a PascalCase name, such as 'Admin'".to_owned()),
+        (Rule::NamingConvention, "Function 'GetUser' should have a snake_case name, such as 'get_user'".to_owned()),
+    ]);
+}
+
+#[test]
+fn unused_and_shadowed_imports() {
+    let lints = lint_source(r#"
+use package::types::{Role, Team}
+use package::users::User
+use package::posts::Post as Entry
+use package::other::*
+
+struct User {
+    role: Role
+    entries: Entry[]
+}
+"#);
+
+    pretty_assertions::assert_eq!(lints, vec![
+        (Rule::UnusedImports, "Unused import 'Team'".to_owned()),
+        // The local `User` is what the name refers to
+        (Rule::UnusedImports, "Unused import 'User'".to_owned()),
+        (Rule::ShadowedImports, "Struct 'User' shadows the imported 'User'".to_owned()),
+    ]);
+}
+
+#[test]
+fn empty_declarations() {
+    let lints = lint_source("struct Empty {}\nprotocol Nothing {}\n");
+
+    pretty_assertions::assert_eq!(lints, vec![
+        (Rule::EmptyStructs, "Struct 'Empty' has no fields".to_owned()),
+        (Rule::EmptyProtocols, "Protocol 'Nothing' has no functions".to_owned()),
+    ]);
+}
+
+#[test]
+fn unused_constants_across_schemas() {
+    let mut levels = LintLevels::default();
+    levels.set(Rule::UnusedConstants, LintLevel::Deny);
+
+    let lints = lint_sources(&[
+        ("limits", "const MAX: u8 = 8\nconst MIN: u8 = 1\nconst DEFAULT: u8 = MIN\nconst UNUSED: u8 = 0\n"),
+        ("users", "use package::limits::MAX\n@max=MAX\nstruct User {\n    id: u64\n}\n"),
+    ], &levels);
+
+    assert_eq!(lints[0].len(), 2);
+    assert_eq!(lints[0][0].message, "Constant 'DEFAULT' is never used");
+    assert_eq!(lints[0][1].message, "Constant 'UNUSED' is never used");
+    assert_eq!(lints[0][1].level, LintLevel::Deny);
+    assert!(lints[1].is_empty());
+}
+
+#[test]
+fn missing_docs() {
+    let source = r#"/// Documented
+@allow=empty_structs
+struct Documented {}
+
+struct Undocumented {
+    id: u64
+}
+
+/// Documented
+protocol Users {
+    function get(u64) -> Documented;
+}
+"#;
+
+    // Documentation is not required unless enabled
+    assert!(lint_source(source).is_empty());
+
+    let mut levels = LintLevels::default();
+    levels.set(Rule::MissingDocs, LintLevel::Warn);
+    let lints = lint_sources(&[("schema", source)], &levels).remove(0);
+
+    let messages: Vec<_> = lints.iter().map(|lint| lint.message.as_str()).collect();
+    assert_eq!(messages, vec![
+        "Struct 'Undocumented' is missing documentation",
+        "Function 'get' is missing documentation",
+    ]);
+
+    let start = source.find("Undocumented").unwrap();
+    assert_eq!(lints[0].span, (start, start + "Undocumented".len()));
+}
+
+#[test]
+fn suppressed_by_annotations() {
+    let lints = lint_source(r#"
+@allow=unused_imports
+use package::types::Role
+
+@allow="naming_convention, empty_structs"
+struct legacy_record {
+    Id: u64
+}
+
+@allow=empty_structs
+struct Empty {}
+
+enum Status {
+    @allow=naming_convention
+    ok
+    failed
+}
+"#);
+
+    pretty_assertions::assert_eq!(lints, vec![
+        (Rule::NamingConvention, "Variant 'failed' should have a PascalCase name, such as 'Failed'".to_owned()),
+    ]);
+}
+
+#[test]
+fn levels_from_config() {
+    let config = config_grammar::parse(
+        "congregation test\nlints = {\n    missing_docs = deny\n    empty_structs = \"allow\"\n}\n"
+    ).unwrap();
+    let units = interpret_context(&ProjectContext::with_config(config)).unwrap();
+    let levels = LintLevels::from_config(&units);
+
+    assert_eq!(levels.level(Rule::MissingDocs), LintLevel::Deny);
+    assert_eq!(levels.level(Rule::EmptyStructs), LintLevel::Allow);
+    assert_eq!(levels.level(Rule::UnusedImports), LintLevel::Warn);
+}
+
+#[test]
+fn unknown_rules_and_levels_in_config() {
+    let error = |lints: &str| {
+        let config = config_grammar::parse(&format!("congregation test\nlints = {{\n    {}\n}}\n", lints)).unwrap();
+        interpret_context(&ProjectContext::with_config(config)).unwrap_err().to_string()
+    };
+
+    assert_eq!(error("not_a_rule = warn"), concat!(
+        "Unknown lint rule 'not_a_rule', the rules are: naming_convention, unused_imports, ",
+        "unused_constants, empty_structs, empty_protocols, shadowed_imports, missing_docs",
+    ));
+    assert_eq!(error("missing_docs = loud"), "Level of lint rule 'missing_docs' should be one of allow, warn or deny");
+}
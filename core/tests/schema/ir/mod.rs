@@ -1,3 +1,4 @@
 pub mod generation;
 pub mod validation;
 pub mod semantics;
+pub mod lint;
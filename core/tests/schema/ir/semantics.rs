@@ -1,6 +1,8 @@
 use comline_core::schema::idl::grammar;
 use comline_core::schema::ir::compiler::interpreter::incremental::IncrementalInterpreter;
+use comline_core::schema::ir::compiler::interpreted::kind_search::KindValue;
 use comline_core::schema::ir::compiler::Compile;
+use comline_core::schema::ir::frozen::unit::FrozenUnit;
 use comline_core::schema::ir::validation::validate;
 
 #[test]
@@ -143,3 +145,40 @@ struct NodeB {
     let errors = result.unwrap_err();
     assert!(errors[0].message.contains("Cycle detected"));
 }
+
+#[test]
+fn test_errors_thrown_are_frozen_whole() {
+    let code = r#"
+/// No user has the id
+@http=404
+error NotFound {
+    message = "No user with id {self.id}"
+
+    id: u64
+}
+
+protocol Users {
+    function get(u64) -> str throws NotFound, Missing;
+}
+"#;
+    let ir = IncrementalInterpreter::from_source(code);
+    let FrozenUnit::Protocol { functions, .. } = &ir[1] else { panic!("Expected a protocol") };
+    let FrozenUnit::Function { throws, .. } = &functions[0] else { panic!("Expected a function") };
+
+    assert_eq!(throws[0], ir[0]);
+    assert_eq!(throws[0], FrozenUnit::Error {
+        docstring: Some("No user has the id".to_owned()),
+        parameters: vec![FrozenUnit::Parameter { name: "http".to_owned(), default_value: "404".to_owned() }],
+        name: "NotFound".to_owned(),
+        message: "No user with id {self.id}".to_owned(),
+        fields: vec![FrozenUnit::Field {
+            docstring: None, parameters: vec![], optional: false,
+            name: "id".to_owned(), kind_value: KindValue::Namespaced("u64".to_owned(), None),
+        }],
+    });
+
+    // Errors not declared in the schema cannot be thrown
+    let errors = validate(&ir).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "Unknown error 'Missing', errors thrown have to be declared in the schema");
+}
@@ -3,4 +3,6 @@ mod unit;
 mod ir;
 mod parser;
 mod versioning;
+mod formatter;
+mod validators;
 // mod stdlib;
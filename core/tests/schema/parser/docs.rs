@@ -0,0 +1,71 @@
+// Doc comment extraction tests
+
+#[cfg(test)]
+mod docs_tests {
+    use comline_core::schema::idl::docs::docstring_before;
+    use comline_core::schema::ir::compiler::interpreter::IncrementalInterpreter;
+    use comline_core::schema::ir::compiler::Compile;
+    use comline_core::schema::ir::frozen::unit::FrozenUnit;
+
+    const SOURCE: &str = r#"
+// Not a doc comment
+/// A registered user
+/// spanning two lines
+struct User {
+    /// Unique identifier
+    id: u64
+    // Plain comment
+    name: string
+}
+
+/// Talks to users
+@provider=Any
+protocol Users {
+    /// Looks a user up
+    function get(u64) -> User;
+}
+"#;
+
+    fn offset_of(name: &str) -> usize {
+        SOURCE.find(name).unwrap()
+    }
+
+    #[test]
+    fn collects_consecutive_doc_lines() {
+        assert_eq!(
+            docstring_before(SOURCE, offset_of("User {")),
+            Some("A registered user\nspanning two lines".to_owned())
+        );
+        assert_eq!(docstring_before(SOURCE, offset_of("id:")), Some("Unique identifier".to_owned()));
+    }
+
+    #[test]
+    fn plain_comments_are_not_docs() {
+        assert_eq!(docstring_before(SOURCE, offset_of("name:")), None);
+    }
+
+    #[test]
+    fn annotations_sit_between_docs_and_declaration() {
+        assert_eq!(docstring_before(SOURCE, offset_of("Users {")), Some("Talks to users".to_owned()));
+    }
+
+    #[test]
+    fn docstrings_reach_the_frozen_units() {
+        let units = IncrementalInterpreter::from_source(SOURCE);
+
+        let Some(FrozenUnit::Struct { docstring, fields, .. }) = units.iter()
+            .find(|u| matches!(u, FrozenUnit::Struct { .. })) else { panic!("No struct") };
+        assert_eq!(docstring.as_deref(), Some("A registered user\nspanning two lines"));
+        assert!(matches!(
+            &fields[0], FrozenUnit::Field { docstring: Some(d), .. } if d == "Unique identifier"
+        ));
+        assert!(matches!(&fields[1], FrozenUnit::Field { docstring: None, .. }));
+
+        let Some(FrozenUnit::Protocol { docstring, functions, .. }) = units.iter()
+            .find(|u| matches!(u, FrozenUnit::Protocol { .. })) else { panic!("No protocol") };
+        assert_eq!(docstring, "Talks to users");
+        assert!(matches!(
+            &functions[0], FrozenUnit::Function { docstring, .. } if docstring == "Looks a user up"
+        ));
+    }
+}
@@ -3,3 +3,4 @@
 pub mod comprehensive;
 pub mod arrays;
 pub mod integration;
+pub mod docs;
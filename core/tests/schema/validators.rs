@@ -0,0 +1,145 @@
+// Standard Uses
+
+// Crate Uses
+
+// External Uses
+use comline_core::schema::ir::compiler::interpreter::incremental::IncrementalInterpreter;
+use comline_core::schema::ir::compiler::Compile;
+use comline_core::schema::ir::expression::{self, evaluate, parse, Scope, Validator, Value};
+use comline_core::schema::ir::frozen::unit::FrozenUnit;
+use comline_core::schema::ir::validation::validate;
+
+
+const BOUNDS: &str = r#"
+/// Checks how many chars strings have
+validator Bounds {
+    min: u32 = 1;
+    max: u32;
+
+    validate {
+        assert(value.length >= params.min and value.length <= params.max, "{value.name} should have {params.min} to {params.max} chars")
+        assert(value != "root")
+    }
+}
+
+struct User {
+    @validators=[Bounds(max=8)]
+    name: str
+}
+"#;
+
+fn bounds() -> Validator {
+    let units = IncrementalInterpreter::from_source(BOUNDS);
+    let unit = units.iter().find(|unit| matches!(unit, FrozenUnit::Validator { .. })).unwrap();
+
+    Validator::from_unit(unit).unwrap()
+}
+
+
+#[test]
+fn test_freezes_validators() {
+    let validator = bounds();
+
+    assert_eq!(validator.name, "Bounds");
+    let properties: Vec<(&str, &str, Option<String>)> = validator.properties.iter()
+        .map(|p| (p.name.as_str(), p.kind.as_str(), p.default.as_ref().map(ToString::to_string)))
+        .collect();
+    assert_eq!(properties, [("min", "u32", Some("1".to_owned())), ("max", "u32", None)]);
+
+    let calls: Vec<String> = validator.calls.iter().map(ToString::to_string).collect();
+    assert_eq!(calls, [
+        "assert(value.length >= params.min and value.length <= params.max, \
+        \"{value.name} should have {params.min} to {params.max} chars\")",
+        "assert(value != \"root\")",
+    ]);
+    assert!(validator.problems().is_empty());
+}
+
+#[test]
+fn test_evaluates_expressions() {
+    let parameters = [("max".to_owned(), Value::Integer(3))];
+    let scope = Scope { subject: Some(("tags", &Value::List(vec![Value::Bool(true)]))), parameters: &parameters };
+    let value = |text: &str| evaluate(&parse(text).unwrap(), &scope).unwrap();
+
+    assert_eq!(value("value.length < params.max and not (1.5 > 2 or false)"), Value::Bool(true));
+    assert_eq!(value("u8::MAX == 255"), Value::Bool(true));
+    assert_eq!(value("\"{value.name} has {{{value.length}}}\""), Value::String("tags has {1}".to_owned()));
+
+    let error = evaluate(&parse("value < 1").unwrap(), &scope).unwrap_err();
+    assert_eq!(error.to_string(), "a list cannot be compared with an integer");
+    assert!(parse("1 < 2 < 3").is_err());
+
+    // Only the parentheses that are needed are written back
+    assert_eq!(parse("(not (a)) and (b or (c == -1))").unwrap().to_string(), "not a and (b or c == -1)");
+}
+
+#[test]
+fn test_checks_values() {
+    let validator = bounds();
+    let parameters = validator.parameters(&expression::parse_uses("Bounds(max=4)").unwrap()[0].arguments).unwrap();
+    let check = |text: &str| validator.check("name", &Value::String(text.to_owned()), &parameters).unwrap();
+
+    assert!(check("ann").is_empty());
+    assert_eq!(check("annabelle"), ["name should have 1 to 4 chars"]);
+    assert_eq!(check("root"), ["'value != \"root\"' does not hold"]);
+}
+
+#[test]
+fn test_checks_properties_given() {
+    let validator = bounds();
+    let error = |text: &str| {
+        let uses = expression::parse_uses(text).unwrap();
+        validator.parameters(&uses[0].arguments).unwrap_err().to_string()
+    };
+
+    assert_eq!(error("Bounds"), "Validator 'Bounds' needs to be given 'max'");
+    assert_eq!(error("Bounds(max=2 size=1)"), "'size' is not a property of validator 'Bounds'");
+    assert_eq!(error("Bounds(max=\"2\")"), "'max' of validator 'Bounds' should be u32, not a string");
+    assert_eq!(error("Bounds(max=-2)"), "'max' of validator 'Bounds' should be u32, not an integer");
+}
+
+#[test]
+fn test_validates_declarations_and_uses() {
+    assert!(validate(&IncrementalInterpreter::from_source(BOUNDS)).is_ok());
+
+    let code = r#"
+import std::validators::string_bounds::StringBounds
+
+validator Short {
+    max: u32 = 8;
+
+    validate {
+        assert(value.length <= params.limit)
+        check(value)
+    }
+}
+
+struct User {
+    @validators=[Short(max=true) StringBounds Unknown]
+    name: str
+}
+"#;
+    let errors = validate(&IncrementalInterpreter::from_source(code)).unwrap_err();
+    let messages: Vec<&str> = errors.iter().map(|error| error.message.as_str()).collect();
+
+    assert_eq!(messages, [
+        "'params.limit' is not known in validator 'Short'",
+        "'check' is not a function validators may call, which are: assert",
+        "'max' of validator 'Short' should be u32, not a bool",
+        "Unknown validator 'Unknown'",
+    ]);
+}
+
+#[test]
+fn test_stdlib_string_bounds() {
+    let source = std::fs::read_to_string("../core_stdlib/packages/std/src/validators/string_bounds.ids").unwrap();
+    let units = IncrementalInterpreter::from_source(&source);
+    let unit = units.iter().find(|unit| matches!(unit, FrozenUnit::Validator { .. })).unwrap();
+    let validator = Validator::from_unit(unit).unwrap();
+
+    let parameters = validator.parameters(&[]).unwrap();
+    let check = |text: &str| validator.check("nickname", &Value::String(text.to_owned()), &parameters).unwrap();
+
+    assert!(check("ann").is_empty());
+    assert_eq!(check(&"a".repeat(1025)), ["String nickname must have from 0 to 1024 chars"]);
+}
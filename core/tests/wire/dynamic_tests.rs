@@ -0,0 +1,221 @@
+// Standard Uses
+use std::fs;
+
+// Crate Uses
+use crate::codelib_gen::package;
+
+// External Uses
+use comline_core::codelib_gen::SchemaUnits;
+use comline_core::package::build::{build, cas, compile};
+use comline_core::schema::ir::compiler::interpreted::kind_search::KindValue;
+use comline_core::schema::ir::frozen::unit::FrozenUnit;
+use comline_core::wire::dynamic::{DynamicSchemas, DynamicValue};
+use comline_core::wire::json::JsonOptions;
+use comline_runtime::wire;
+use serde_json::json;
+
+
+const MEMBERS: &str = concat!(
+    "enum Role {\n    Owner\n    Member\n}\n\n",
+    "struct Tag {\n    name: str\n}\n\n",
+    "struct Member {\n    name: str\n    age: u16\n    optional nickname: str\n",
+    "    role: Role\n    tags: Tag[]\n    key: u8[2]\n}\n",
+);
+
+fn members() -> DynamicSchemas {
+    DynamicSchemas::new("teams", package(&[("members", MEMBERS)], &[]).schemas)
+}
+
+fn ann() -> DynamicValue {
+    DynamicValue::structure([
+        ("name", "ann".into()),
+        ("age", 30u16.into()),
+        ("role", DynamicValue::variant("Member")),
+        ("tags", vec![DynamicValue::structure([("name", "admin".into())])].into()),
+        ("key", vec![1u8, 2].into()),
+    ])
+}
+
+
+#[test]
+fn test_encodes_and_decodes_values() {
+    let schemas = members();
+    let member = schemas.type_of("members::Member").unwrap();
+
+    let bytes = member.encode(&ann()).unwrap();
+    assert_eq!(bytes, [
+        wire::encode("ann"), wire::encode(&30u16), wire::encode(&None::<String>),
+        vec![1], wire::encode(&vec!["admin".to_owned()]), vec![1, 2],
+    ].concat());
+
+    let decoded = member.decode(&bytes).unwrap();
+    assert_eq!(decoded, ann());
+    assert_eq!(decoded.field("age").and_then(DynamicValue::as_u128), Some(30));
+    assert_eq!(decoded.field("role").and_then(DynamicValue::as_str), Some("Member"));
+
+    let mut nicknamed = decoded;
+    assert_eq!(nicknamed.set_field("nickname", "annie"), None);
+    let bytes = member.encode(&nicknamed).unwrap();
+    assert_eq!(member.decode(&bytes).unwrap().field("nickname"), Some(&DynamicValue::from("annie")));
+
+    assert_eq!(
+        member.decode(&[3, b'a', b'n', b'n', 30, 0, 2, 0, 1, 2]).unwrap_err(),
+        wire::DecodeError::UnknownDiscriminant { type_name: "enum", discriminant: 2 }
+    );
+}
+
+#[test]
+fn test_validates_values() {
+    let schemas = members();
+    let member = schemas.type_of("members::Member").unwrap();
+
+    let mut invalid = ann();
+    invalid.set_field("age", 70_000u32);
+    invalid.set_field("role", DynamicValue::variant("Guest"));
+    invalid.set_field("tags", vec![DynamicValue::structure([("name", 5u8.into())])]);
+    invalid.set_field("key", vec![1u8]);
+    invalid.set_field("email", "ann@example.com");
+    invalid.remove_field("name");
+
+    assert_eq!(member.validate(&invalid).unwrap_err().to_string(), concat!(
+        "Value is not a valid 'members::Member':\n",
+        "  - 'members::Member.name' is missing\n",
+        "  - 'members::Member.age' is 70000, which does not fit u16\n",
+        "  - 'members::Member.role' is 'Guest', which is not a variant of members::Role\n",
+        "  - 'members::Member.tags[0].name' should be str, not an integer\n",
+        "  - 'members::Member.key' has 1 elements instead of 2\n",
+        "  - 'members::Member.email' is not a field of members::Member",
+    ));
+    assert!(member.encode(&invalid).is_err());
+}
+
+#[test]
+fn test_runs_validators_of_fields() {
+    let checks = concat!(
+        "validator Bounds {\n    min: u32 = 1;\n    max: u32;\n\n    validate {\n",
+        "        assert(value.length >= params.min and value.length <= params.max, ",
+        "\"{value.name} should have {params.min} to {params.max} chars\")\n    }\n}\n",
+    );
+    let teams = concat!(
+        "use crate::checks::Bounds\n\n",
+        "struct Team {\n    @validators=[Bounds(max=4)]\n    name: str\n",
+        "    @validators=[Bounds(min=2 max=3)]\n    optional ranks: u8[]\n",
+        "    @validators=[Missing]\n    size: u8\n}\n",
+    );
+    let schemas = DynamicSchemas::new("teams", package(&[("checks", checks), ("teams", teams)], &[]).schemas);
+    let team = schemas.type_of("teams::Team").unwrap();
+
+    let value = DynamicValue::structure([
+        ("name", "rangers".into()), ("ranks", vec![1u8].into()), ("size", 3u8.into()),
+    ]);
+    assert_eq!(team.validate(&value).unwrap_err().to_string(), concat!(
+        "Value is not a valid 'teams::Team':\n",
+        "  - 'teams::Team.name' fails Bounds: name should have 1 to 4 chars\n",
+        "  - 'teams::Team.ranks' fails Bounds: ranks should have 2 to 3 chars\n",
+        "  - 'teams::Team.size' uses Missing, which is not declared in the schemas",
+    ));
+}
+
+#[test]
+fn test_converts_values_to_and_from_json() {
+    let schemas = members();
+    let member = schemas.type_of("members::Member").unwrap();
+
+    let json = member.to_json(&ann(), &JsonOptions::default()).unwrap();
+    assert_eq!(json, json!({
+        "name": "ann", "age": 30, "role": "Member", "tags": [{ "name": "admin" }], "key": [1, 2],
+    }));
+    assert_eq!(member.from_json(&json).unwrap(), ann());
+
+    let mut nulled = json.clone();
+    nulled["nickname"] = json!(null);
+    assert_eq!(member.from_json(&nulled).unwrap(), ann());
+
+    let error = member.from_json(&json!({ "name": 1, "age": "many" })).unwrap_err();
+    assert_eq!(error.to_string(), concat!(
+        "JSON is not a valid 'members::Member':\n",
+        "  - 'members::Member.name' should be str, not 1\n",
+        "  - 'members::Member.age' should be u16, not \"many\"",
+    ));
+}
+
+#[test]
+fn test_handles_unions_and_wide_integers() {
+    let field = |name: &str, kind: KindValue| FrozenUnit::Field {
+        docstring: None, parameters: vec![], optional: false, name: name.to_owned(), kind_value: kind,
+    };
+    let schemas = DynamicSchemas::new("ledger", vec![SchemaUnits {
+        namespace: vec!["ledger".to_owned()],
+        units: vec![FrozenUnit::Struct {
+            docstring: None, parameters: vec![], name: "Entry".to_owned(),
+            fields: vec![
+                field("id", KindValue::Namespaced("u128".to_owned(), None)),
+                field("amount", KindValue::Union(vec![
+                    KindValue::Namespaced("i64".to_owned(), None), KindValue::Namespaced("str".to_owned(), None),
+                ])),
+            ],
+        }],
+    }]);
+    let entry = schemas.type_of("ledger::Entry").unwrap();
+
+    let value = DynamicValue::structure([("id", u128::MAX.into()), ("amount", DynamicValue::union("i64", -5i64))]);
+    let bytes = entry.encode(&value).unwrap();
+    assert_eq!(bytes, [wire::encode(&u128::MAX), vec![0], wire::encode(&-5i64)].concat());
+    assert_eq!(entry.decode(&bytes).unwrap(), value);
+
+    let json = entry.to_json(&value, &JsonOptions::default()).unwrap();
+    assert_eq!(json, json!({ "id": u128::MAX.to_string(), "amount": { "i64": -5 } }));
+    assert_eq!(entry.from_json(&json).unwrap(), value);
+
+    let error = entry.validate(&DynamicValue::structure([
+        ("id", 1u8.into()), ("amount", DynamicValue::union("bool", true)),
+    ])).unwrap_err();
+    assert_eq!(error.to_string(), concat!(
+        "Value is not a valid 'ledger::Entry':\n",
+        "  - 'ledger::Entry.amount' is of kind bool, which is not a kind of i64 | str",
+    ));
+}
+
+#[test]
+fn test_reports_types_that_are_not_declared() {
+    let schemas = DynamicSchemas::new("teams", package(&[(
+        "servers", "struct Server {\n    port: u16\n    headers: HashMap[]\n}\n"
+    )], &[]).schemas);
+
+    assert_eq!(schemas.type_of("servers::Server").unwrap_err().to_string(), concat!(
+        "'servers::Server' cannot be handled dynamically:\n",
+        "  - 'servers::Server::headers' is of HashMap, which is not declared in the schemas",
+    ));
+    assert_eq!(
+        schemas.type_of("servers::Client").unwrap_err().to_string(),
+        "'servers::Client' is not a struct nor an enum of the schemas"
+    );
+}
+
+#[test]
+fn test_reads_schemas_of_packages_and_commits() {
+    let dir = tempfile::tempdir().unwrap();
+    let package_path = dir.path();
+    fs::write(package_path.join("config.idp"), "congregation test\nspecification_version = 1\n").unwrap();
+    fs::create_dir(package_path.join("src")).unwrap();
+
+    let schema = package_path.join("src/members.ids");
+    fs::write(&schema, "struct Member {\n    name: str\n}\n").unwrap();
+    build(package_path).unwrap();
+    let (hash, _) = cas::history::log(package_path).unwrap().remove(0);
+
+    fs::write(&schema, "struct Member {\n    name: str\n    age: u8\n}\n").unwrap();
+    fs::write(package_path.join("src/accounts.ids"), "struct Account {\n    id: u64\n}\n").unwrap();
+    build(package_path).unwrap();
+    let current = DynamicSchemas::from_project(&compile(package_path).unwrap()).unwrap();
+    let frozen = DynamicSchemas::from_commit(package_path, &hash.to_hex()[..8]).unwrap();
+
+    let value = DynamicValue::structure([("name", "ann".into())]);
+    assert!(current.type_of("members::Member").unwrap().validate(&value).is_err());
+    assert_eq!(
+        frozen.type_of("members::Member").unwrap().encode(&value).unwrap(),
+        wire::encode("ann")
+    );
+    // Schemas are frozen with their namespaces, not the ones at their positions now
+    assert!(frozen.type_of("accounts::Account").is_err());
+}
@@ -0,0 +1,159 @@
+// Standard Uses
+use std::fs;
+
+// Crate Uses
+use crate::codelib_gen::package;
+
+// External Uses
+use comline_core::package::build::{build, cas};
+use comline_core::wire::evolution::Evolution;
+use comline_runtime::wire;
+
+
+/// Plans reading `type_path` of the `members` schema from one source of it
+/// as another
+fn evolution(writer: &str, reader: &str, type_path: &str) -> eyre::Result<Evolution> {
+    let writer = package(&[("members", writer)], &[]).schemas;
+    Evolution::new(&writer, &package(&[("members", reader)], &[]), type_path)
+}
+
+
+#[test]
+fn test_reads_removed_and_added_fields() {
+    let members = evolution(
+        "struct Member {\n    name: str\n    age: u16\n    optional nickname: str\n}\n",
+        concat!(
+            "struct Member {\n    name: str\n    optional email: str\n",
+            "    @default=8\n    level: u8\n    optional nickname: str\n}\n",
+        ),
+        "members::Member",
+    ).unwrap();
+
+    let written = [wire::encode("ann"), wire::encode(&30u16), wire::encode(&Some("annie".to_owned()))].concat();
+    let read = [
+        wire::encode("ann"), wire::encode(&None::<String>), wire::encode(&8u8),
+        wire::encode(&Some("annie".to_owned())),
+    ].concat();
+    assert_eq!(members.translate(&written).unwrap(), read);
+
+    let written = [wire::encode("bob"), wire::encode(&41u16), wire::encode(&None::<String>)].concat();
+    let read = [wire::encode("bob"), wire::encode(&None::<String>), wire::encode(&8u8), vec![0]].concat();
+    assert_eq!(members.translate(&written).unwrap(), read);
+}
+
+#[test]
+fn test_reads_renamed_fields_and_structs() {
+    let members = evolution(
+        concat!(
+            "struct Person {\n    username: str\n    address: Address\n}\n\n",
+            "struct Address {\n    street: str\n}\n",
+        ),
+        concat!(
+            "@renamed_from=Person\nstruct Member {\n    @renamed_from=username\n    name: str\n",
+            "    address: Address\n}\n\n",
+            "struct Address {\n    optional city: str\n    street: str\n}\n",
+        ),
+        "members::Member",
+    ).unwrap();
+
+    let written = [wire::encode("ann"), wire::encode("Main St")].concat();
+    let read = [wire::encode("ann"), wire::encode(&None::<String>), wire::encode("Main St")].concat();
+    assert_eq!(members.translate(&written).unwrap(), read);
+}
+
+#[test]
+fn test_reads_widened_types() {
+    let members = evolution(
+        concat!(
+            "enum Role {\n    Owner\n    Member\n}\n\n",
+            "struct Member {\n    level: u8\n    score: i16\n    key: u8[2]\n    roles: Role[]\n    required: u32\n}\n",
+        ),
+        concat!(
+            "enum Role {\n    Guest\n    Member\n    Owner\n}\n\n",
+            "struct Member {\n    level: u32\n    score: i64\n    key: u8[]\n    roles: Role[]\n    optional required: u64\n}\n",
+        ),
+        "members::Member",
+    ).unwrap();
+
+    let written = [
+        wire::encode(&200u8), wire::encode(&-300i16), wire::encode(&[1u8, 2]),
+        vec![2, 0, 1], wire::encode(&70_000u32),
+    ].concat();
+    let read = [
+        wire::encode(&200u32), wire::encode(&-300i64), wire::encode(&vec![1u8, 2]),
+        vec![2, 2, 1], wire::encode(&Some(70_000u64)),
+    ].concat();
+    assert_eq!(members.translate(&written).unwrap(), read);
+
+    let roles = evolution(
+        "enum Role {\n    Owner\n    Member\n}\n", "enum Role {\n    Guest\n    Member\n    Owner\n}\n", "members::Role",
+    ).unwrap();
+    assert_eq!(roles.decode::<u32>(&[0]).unwrap(), 2);
+
+    // Data of the writer that is not valid is found while translating
+    assert_eq!(
+        members.translate(&[200, 1, 1, 2, 1, 7]).unwrap_err(),
+        wire::DecodeError::UnknownDiscriminant { type_name: "enum", discriminant: 7 }
+    );
+}
+
+#[test]
+fn test_reports_incompatible_changes() {
+    let error = evolution(
+        concat!(
+            "enum Role {\n    Owner\n    Member\n}\n\n",
+            "struct Member {\n    age: u32\n    role: Role\n    key: u8[]\n    optional nickname: str\n}\n",
+        ),
+        concat!(
+            "enum Role {\n    Owner\n}\n\n",
+            "struct Member {\n    age: u16\n    role: Role\n    key: u8[4]\n    nickname: str\n",
+            "    email: str\n    @default=many\n    level: u8\n}\n",
+        ),
+        "members::Member",
+    ).unwrap_err();
+
+    assert_eq!(error.to_string(), concat!(
+        "'members::Member' changed in ways that are not wire compatible:\n",
+        "  - 'members::Member::nickname' became required without a default\n",
+        "  - 'members::Member::email' was added without being optional nor having a default\n",
+        "  - 'members::Member::level' has a default 'many' that is not a u8\n",
+        "  - 'members::Member::age' changed from u32 to u16\n",
+        "  - 'members::Role' has no variant 'Member' anymore\n",
+        "  - 'members::Member::key' changed from u8[] to u8[4]",
+    ));
+}
+
+#[test]
+fn test_reads_data_of_a_frozen_commit() {
+    let dir = tempfile::tempdir().unwrap();
+    let package_path = dir.path();
+    fs::write(package_path.join("config.idp"), "congregation test\nspecification_version = 1\n").unwrap();
+    fs::create_dir(package_path.join("src")).unwrap();
+
+    let schema = package_path.join("src/members.ids");
+    fs::write(&schema, "struct Member {\n    name: str\n    age: u16\n}\n").unwrap();
+    build(package_path).unwrap();
+    let (hash, _) = cas::history::log(package_path).unwrap().remove(0);
+
+    fs::write(&schema, "struct Member {\n    @renamed_from=name\n    username: str\n    optional email: str\n}\n").unwrap();
+    // Coming before `members`, which is still found by its namespace
+    fs::write(package_path.join("src/accounts.ids"), "struct Account {\n    id: u64\n}\n").unwrap();
+    build(package_path).unwrap();
+
+    let members = Evolution::from_commit(package_path, &hash.to_hex()[..8], "members::Member").unwrap();
+    let written = [wire::encode("ann"), wire::encode(&30u16)].concat();
+    assert_eq!(
+        members.translate(&written).unwrap(),
+        [wire::encode("ann"), wire::encode(&None::<String>)].concat()
+    );
+
+    // The current version cannot read data of the first under a type it did not have
+    let error = Evolution::from_commit(package_path, &hash.to_hex(), "members::Team").unwrap_err();
+    assert_eq!(error.to_string(), format!(
+        "Data of version 0.0.1 (commit {}) cannot be read: 'members::Team' is not a struct nor an enum of the package",
+        &hash.to_hex()[..12]
+    ));
+
+    let error = Evolution::from_commit(package_path, "ffffffff", "members::Member").unwrap_err();
+    assert!(error.to_string().starts_with("Package at"), "{}", error);
+}
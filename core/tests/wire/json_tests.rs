@@ -0,0 +1,153 @@
+// Standard Uses
+
+// Crate Uses
+use crate::codelib_gen::{package, validate};
+
+// External Uses
+use comline_core::codelib_gen::GenerationContext;
+use comline_core::codelib_gen::json_schema::generate_json_schema_package;
+use comline_core::schema::ir::compiler::interpreted::kind_search::KindValue;
+use comline_core::schema::ir::frozen::unit::FrozenUnit;
+use comline_core::wire::dynamic::DynamicSchemas;
+use comline_core::wire::json::{binary_to_json, json_to_binary, AbsentJson, EnumJson, JsonOptions};
+use serde_json::{json, Value};
+
+
+/// A struct of a `value` field for every kind of type
+const GOLDEN: &str = concat!(
+    "enum Role {\n    Owner\n    Member\n}\n\n",
+    "struct Flag {\n    value: bool\n}\n\n",
+    "struct Small {\n    value: i32\n}\n\n",
+    "struct Wide {\n    value: u64\n}\n\n",
+    "struct Signed {\n    value: i64\n}\n\n",
+    "struct Huge {\n    value: u128\n}\n\n",
+    "struct Float {\n    value: float\n}\n\n",
+    "struct Text {\n    value: str\n}\n\n",
+    "struct List {\n    value: u16[]\n}\n\n",
+    "struct Fixed {\n    value: u8[3]\n}\n\n",
+    "struct Optional {\n    optional value: str\n}\n\n",
+    "struct Choice {\n    value: Role\n}\n\n",
+    "struct Nested {\n    value: Text[]\n}\n",
+);
+
+/// Type, binary encoding in hex, JSON as written by default and JSON as
+/// written with [`options`]
+const CASES: &[(&str, &str, &str, &str)] = &[
+    ("Flag", "01", r#"{"value":true}"#, r#"{"value":true}"#),
+    ("Small", "03", r#"{"value":-2}"#, r#"{"value":-2}"#),
+    ("Wide", "8180808080808010", r#"{"value":9007199254740993}"#, r#"{"value":"9007199254740993"}"#),
+    ("Signed", "8180808080808020", r#"{"value":-9007199254740993}"#, r#"{"value":"-9007199254740993"}"#),
+    ("Huge", "80808080808080808002", r#"{"value":"18446744073709551616"}"#, r#"{"value":"18446744073709551616"}"#),
+    ("Float", "000000000000f83f", r#"{"value":1.5}"#, r#"{"value":1.5}"#),
+    ("Float", "000000000000f87f", r#"{"value":"NaN"}"#, r#"{"value":"NaN"}"#),
+    ("Text", "0368c3a9", r#"{"value":"hé"}"#, r#"{"value":"hé"}"#),
+    ("List", "0201ac02", r#"{"value":[1,300]}"#, r#"{"value":[1,300]}"#),
+    ("Fixed", "010203", r#"{"value":[1,2,3]}"#, r#"{"value":[1,2,3]}"#),
+    ("Optional", "00", r#"{}"#, r#"{"value":null}"#),
+    ("Optional", "01026869", r#"{"value":"hi"}"#, r#"{"value":"hi"}"#),
+    ("Choice", "01", r#"{"value":"Member"}"#, r#"{"value":1}"#),
+    ("Nested", "010161", r#"{"value":[{"value":"a"}]}"#, r#"{"value":[{"value":"a"}]}"#),
+    ("Either", "010178", r#"{"value":{"str":"x"}}"#, r#"{"value":{"str":"x"}}"#),
+];
+
+fn options() -> JsonOptions {
+    JsonOptions { int64_as_string: true, enums: EnumJson::Index, absent_fields: AbsentJson::Null }
+}
+
+/// The package of the golden schema, with a union the grammar cannot write yet
+fn golden_package(options: &[(&str, &str)]) -> GenerationContext {
+    let mut context = package(&[("golden", GOLDEN)], options);
+    context.schemas[0].units.push(FrozenUnit::Struct {
+        docstring: None, parameters: vec![], name: "Either".to_owned(),
+        fields: vec![FrozenUnit::Field {
+            docstring: None, parameters: vec![], optional: false, name: "value".to_owned(),
+            kind_value: KindValue::Union(vec![
+                KindValue::Namespaced("u8".to_owned(), None), KindValue::Namespaced("str".to_owned(), None),
+            ]),
+        }],
+    });
+
+    context
+}
+
+fn golden() -> DynamicSchemas {
+    DynamicSchemas::new("golden", golden_package(&[]).schemas)
+}
+
+fn hex(text: &str) -> Vec<u8> {
+    (0..text.len()).step_by(2).map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap()).collect()
+}
+
+
+#[test]
+fn test_golden_transcoding() {
+    let schemas = golden();
+
+    for (name, binary, default_json, options_json) in CASES {
+        let r#type = schemas.type_of(&format!("golden::{}", name)).unwrap();
+        let binary = hex(binary);
+
+        for (json, options) in [(default_json, JsonOptions::default()), (options_json, options())] {
+            let expected: Value = serde_json::from_str(json).unwrap();
+
+            assert_eq!(
+                binary_to_json(&r#type, &binary, &options).unwrap().to_string(), expected.to_string(),
+                "{} as JSON with {:?}", name, options
+            );
+            assert_eq!(json_to_binary(&r#type, &expected).unwrap(), binary, "{} from {}", name, json);
+        }
+    }
+}
+
+#[test]
+fn test_json_is_valid_for_generated_json_schemas() {
+    let schemas = golden();
+
+    for int64_as_string in [false, true] {
+        let files = generate_json_schema_package(&golden_package(&[
+            ("base_uri", "https://example.com/teams"), ("int64_as_string", &int64_as_string.to_string()),
+        ])).unwrap();
+        let options = JsonOptions { int64_as_string, ..JsonOptions::default() };
+
+        for (name, binary, ..) in CASES {
+            let r#type = schemas.type_of(&format!("golden::{}", name)).unwrap();
+            let json = binary_to_json(&r#type, &hex(binary), &options).unwrap();
+
+            assert!(
+                validate(&files, &format!("golden/{}.schema.json", name), json.clone()),
+                "{} is not valid for the schema of {} with {:?}", json, name, options
+            );
+        }
+    }
+}
+
+#[test]
+fn test_transcoding_checks_values() {
+    let schemas = golden();
+    let type_of = |name: &str| schemas.type_of(&format!("golden::{}", name)).unwrap();
+
+    assert_eq!(
+        json_to_binary(&type_of("Fixed"), &json!({ "value": [1, 2] })).unwrap_err().to_string(),
+        "Value is not a valid 'golden::Fixed':\n  - 'golden::Fixed.value' has 2 elements instead of 3"
+    );
+    assert_eq!(
+        json_to_binary(&type_of("Choice"), &json!({ "value": 2, "extra": true })).unwrap_err().to_string(),
+        concat!(
+            "JSON is not a valid 'golden::Choice':\n",
+            "  - 'golden::Choice.value' is 2, which is not a variant of golden::Role\n",
+            "  - 'golden::Choice.extra' is not a field of golden::Choice",
+        )
+    );
+    assert_eq!(
+        json_to_binary(&type_of("Small"), &json!({ "value": "3000000000" })).unwrap_err().to_string(),
+        "Value is not a valid 'golden::Small':\n  - 'golden::Small.value' is 3000000000, which does not fit i32"
+    );
+    assert_eq!(
+        binary_to_json(&type_of("Fixed"), &[1, 2, 3, 4], &JsonOptions::default()).unwrap_err().to_string(),
+        "Bytes are not a valid 'golden::Fixed': 1 bytes are left after the value"
+    );
+    assert_eq!(
+        binary_to_json(&type_of("List"), &[1, 0xf0, 0xa2, 0x04], &JsonOptions::default()).unwrap_err().to_string(),
+        "Bytes are not a valid 'golden::List': 70000 does not fit in `u16`"
+    );
+}
@@ -0,0 +1,4 @@
+// Relative Modules
+mod dynamic_tests;
+mod evolution_tests;
+mod json_tests;
@@ -0,0 +1,9 @@
+//! The standard library package of Comline, whose schemas are embedded here
+//! for the compiler to build with, without finding them on disk
+
+/// Sources of the standard library schemas, by their path in the package
+/// without extension, like `validators/string_bounds`
+pub static SOURCES: &[(&str, &str)] = &[
+    ("publish", include_str!("../packages/std/src/publish.ids")),
+    ("validators/string_bounds", include_str!("../packages/std/src/validators/string_bounds.ids")),
+];
@@ -3,3 +3,29 @@
 fn hello() {
     println!("hello")
 }
+
+/// Every schema of the package is embedded, and as it is on disk
+#[test]
+fn embeds_every_schema() {
+    let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("packages/std/src");
+    let mut directories = vec![root.clone()];
+    let mut schemas = vec![];
+    while let Some(directory) = directories.pop() {
+        for entry in std::fs::read_dir(directory).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                directories.push(path);
+            } else if path.extension().is_some_and(|extension| extension == "ids") {
+                let relative = path.strip_prefix(&root).unwrap().with_extension("");
+                let relative: Vec<_> = relative.components().map(|c| c.as_os_str().to_str().unwrap()).collect();
+                schemas.push((relative.join("/"), std::fs::read_to_string(&path).unwrap()));
+            }
+        }
+    }
+    schemas.sort();
+
+    let embedded: Vec<_> = comline_core_stdlib::SOURCES.iter()
+        .map(|(path, source)| (path.to_string(), source.to_string()))
+        .collect();
+    assert_eq!(embedded, schemas);
+}
@@ -0,0 +1,424 @@
+// Per schema analysis
+// Collects what the language features need out of a parsed schema: declared
+// symbols, type references and imports, all with their source spans
+
+// Standard Uses
+
+// Crate Uses
+
+// External Uses
+use comline_core::schema::idl::docs::docstring_before;
+use comline_core::schema::idl::grammar::{
+    Declaration, Field, RelativePrefix, Spanned, Type, UsePath,
+};
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Constant,
+    Struct,
+    Field,
+    Enum,
+    Variant,
+    Protocol,
+    Function,
+    Validator,
+    Property,
+    Error,
+}
+
+impl SymbolKind {
+    pub fn keyword(self) -> &'static str {
+        match self {
+            SymbolKind::Constant => "const",
+            SymbolKind::Struct => "struct",
+            SymbolKind::Field => "field",
+            SymbolKind::Enum => "enum",
+            SymbolKind::Variant => "variant",
+            SymbolKind::Protocol => "protocol",
+            SymbolKind::Function => "function",
+            SymbolKind::Validator => "validator",
+            SymbolKind::Property => "property",
+            SymbolKind::Error => "error",
+        }
+    }
+
+    /// Whether the symbol can be used where a type is expected
+    pub fn is_type(self) -> bool {
+        matches!(self, SymbolKind::Struct | SymbolKind::Enum)
+    }
+}
+
+/// A declaration, or a member of one
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Span of the symbol's name
+    pub span: (usize, usize),
+    /// Type signature, e.g. `optional email: string` for a field
+    pub detail: String,
+    pub docstring: Option<String>,
+    pub members: Vec<Symbol>,
+}
+
+/// A type used by name, e.g. the `User` in `author: User[]`
+#[derive(Debug, Clone)]
+pub struct TypeReference {
+    pub path: String,
+    pub span: (usize, usize),
+}
+
+impl TypeReference {
+    /// The last segment of the path
+    pub fn name(&self) -> &str {
+        self.path.rsplit("::").next().unwrap()
+    }
+
+    /// Span of the last segment of the path
+    pub fn name_span(&self) -> (usize, usize) {
+        (self.span.1 - self.name().len(), self.span.1)
+    }
+}
+
+/// Where an import points to
+#[derive(Debug, Clone)]
+pub enum ImportedItems {
+    /// `use a::b::Item` or `use a::b::Item as Alias`
+    Single { name: String, span: (usize, usize), alias: Option<String> },
+    /// `use a::b::{First, Second}`
+    Multiple(Vec<(String, (usize, usize))>),
+    /// `use a::b::*`
+    Glob,
+}
+
+#[derive(Debug, Clone)]
+pub struct Import {
+    /// Namespace the items are imported from, the package name may lead it
+    pub namespace: Vec<String>,
+    pub items: ImportedItems,
+}
+
+impl Import {
+    /// The name under which `name` from the imported namespace is visible, if imported
+    pub fn local_name_of<'a>(&'a self, name: &'a str) -> Option<&'a str> {
+        match &self.items {
+            ImportedItems::Single { name: item, alias, .. } if item == name => {
+                Some(alias.as_deref().unwrap_or(item))
+            }
+            ImportedItems::Multiple(items) if items.iter().any(|(item, _)| item == name) => {
+                Some(name)
+            }
+            ImportedItems::Glob => Some(name),
+            _ => None,
+        }
+    }
+
+    /// The imported name that is visible locally as `local`
+    pub fn imported_name_of<'a>(&'a self, local: &'a str) -> Option<&'a str> {
+        match &self.items {
+            ImportedItems::Single { name, alias, .. } => {
+                (alias.as_deref().unwrap_or(name) == local).then_some(name.as_str())
+            }
+            ImportedItems::Multiple(items) => {
+                items.iter().any(|(item, _)| item == local).then_some(local)
+            }
+            ImportedItems::Glob => Some(local),
+        }
+    }
+}
+
+/// Everything known about a schema source
+#[derive(Debug, Clone, Default)]
+pub struct Analysis {
+    pub symbols: Vec<Symbol>,
+    pub references: Vec<TypeReference>,
+    pub imports: Vec<Import>,
+}
+
+impl Analysis {
+    pub fn new(source: &str, declarations: &[Declaration], namespace: &[String]) -> Self {
+        let mut analysis = Self::default();
+
+        for declaration in declarations {
+            match declaration {
+                Declaration::Import(import) => {
+                    let path = split_path(import.path.as_str());
+                    analysis.push_import(path, &import.path, None);
+                }
+                Declaration::Use(use_decl) => {
+                    let alias = use_decl.alias.as_ref().map(|a| a.name.to_string());
+                    analysis.push_use(&use_decl.path, alias, namespace);
+                }
+                Declaration::Const(const_decl) => {
+                    analysis.collect_references(&const_decl.type_def);
+                    analysis.symbols.push(Symbol {
+                        name: const_decl.name(),
+                        kind: SymbolKind::Constant,
+                        span: const_decl.name.span,
+                        detail: type_name(&const_decl.type_def),
+                        docstring: docstring_before(source, const_decl.name.span.0),
+                        members: vec![],
+                    });
+                }
+                Declaration::Struct(struct_def) => {
+                    let fields = analysis.fields(struct_def.fields(), source);
+
+                    analysis.symbols.push(Symbol {
+                        name: struct_def.name(),
+                        kind: SymbolKind::Struct,
+                        span: struct_def.name.span,
+                        detail: format!("struct {}", struct_def.name()),
+                        docstring: docstring_before(source, struct_def.name.span.0),
+                        members: fields,
+                    });
+                }
+                Declaration::Error(error) => {
+                    let fields = analysis.fields(error.fields(), source);
+
+                    analysis.symbols.push(Symbol {
+                        name: error.name(),
+                        kind: SymbolKind::Error,
+                        span: error.name.span,
+                        detail: format!("error {}", error.name()),
+                        docstring: docstring_before(source, error.name.span.0),
+                        members: fields,
+                    });
+                }
+                Declaration::Enum(enum_def) => {
+                    let variants = enum_def.variants().iter().map(|variant| Symbol {
+                        name: variant.identifier().to_string(),
+                        kind: SymbolKind::Variant,
+                        span: variant.name.span,
+                        detail: variant.identifier().to_string(),
+                        docstring: docstring_before(source, variant.name.span.0),
+                        members: vec![],
+                    }).collect();
+
+                    analysis.symbols.push(Symbol {
+                        name: enum_def.name(),
+                        kind: SymbolKind::Enum,
+                        span: enum_def.name.span,
+                        detail: format!("enum {}", enum_def.name()),
+                        docstring: docstring_before(source, enum_def.name.span.0),
+                        members: variants,
+                    });
+                }
+                Declaration::Protocol(protocol) => {
+                    let mut functions = vec![];
+                    for function in protocol.functions() {
+                        let mut arguments = vec![];
+                        if let Some(args) = function.args() {
+                            let rest = args.rest().iter().map(|r| r.arg_type().arg_type());
+                            for arg_type in std::iter::once(args.first().arg_type()).chain(rest) {
+                                analysis.collect_references(arg_type);
+                                arguments.push(type_name(arg_type));
+                            }
+                        }
+
+                        let keyword = if function.is_async() { "async function" } else { "function" };
+                        let mut detail = format!("{} {}({})", keyword, function.name(), arguments.join(", "));
+                        if let Some(return_type) = function.return_type() {
+                            analysis.collect_references(return_type.return_type());
+                            detail.push_str(&format!(" -> {}", type_name(return_type.return_type())));
+                        }
+
+                        let throws = function.throws();
+                        if !throws.is_empty() {
+                            let names: Vec<String> = throws.iter().map(|thrown| thrown.to_string()).collect();
+                            detail.push_str(&format!(" throws {}", names.join(", ")));
+                        }
+                        for thrown in throws {
+                            analysis.references.push(TypeReference { path: thrown.to_string(), span: thrown.span });
+                        }
+
+                        functions.push(Symbol {
+                            name: function.name(),
+                            kind: SymbolKind::Function,
+                            span: function.name.span,
+                            detail,
+                            docstring: docstring_before(source, function.name.span.0),
+                            members: vec![],
+                        });
+                    }
+
+                    analysis.symbols.push(Symbol {
+                        name: protocol.name(),
+                        kind: SymbolKind::Protocol,
+                        span: protocol.name.span,
+                        detail: format!("protocol {}", protocol.name()),
+                        docstring: docstring_before(source, protocol.name.span.0),
+                        members: functions,
+                    });
+                }
+                Declaration::Validator(validator) => {
+                    let properties = validator.properties().iter().map(|property| {
+                        analysis.collect_references(property.property_type());
+
+                        let default = property.default().map(|d| format!(" = {}", d)).unwrap_or_default();
+                        Symbol {
+                            name: property.name(),
+                            kind: SymbolKind::Property,
+                            span: property.name.span,
+                            detail: format!(
+                                "{}: {}{}", property.name(), type_name(property.property_type()), default
+                            ),
+                            docstring: docstring_before(source, property.name.span.0),
+                            members: vec![],
+                        }
+                    }).collect();
+
+                    analysis.symbols.push(Symbol {
+                        name: validator.name(),
+                        kind: SymbolKind::Validator,
+                        span: validator.name.span,
+                        detail: format!("validator {}", validator.name()),
+                        docstring: docstring_before(source, validator.name.span.0),
+                        members: properties,
+                    });
+                }
+            }
+        }
+
+        analysis
+    }
+
+    /// Top level symbol with the given name
+    pub fn symbol(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.iter().find(|s| s.name == name)
+    }
+
+    /// The symbol, member or not, whose name contains `offset`
+    pub fn symbol_at(&self, offset: usize) -> Option<(&Symbol, Option<&Symbol>)> {
+        for symbol in &self.symbols {
+            if contains(symbol.span, offset) { return Some((symbol, None)) }
+
+            if let Some(member) = symbol.members.iter().find(|m| contains(m.span, offset)) {
+                return Some((symbol, Some(member)))
+            }
+        }
+
+        None
+    }
+
+    pub fn reference_at(&self, offset: usize) -> Option<&TypeReference> {
+        self.references.iter().find(|r| contains(r.span, offset))
+    }
+
+    /// The imported item whose name contains `offset`, with the import it belongs to
+    pub fn import_at(&self, offset: usize) -> Option<(&Import, &str)> {
+        self.imports.iter().find_map(|import| match &import.items {
+            ImportedItems::Single { name, span, .. } if contains(*span, offset) => {
+                Some((import, name.as_str()))
+            }
+            ImportedItems::Multiple(items) => items.iter()
+                .find(|(_, span)| contains(*span, offset))
+                .map(|(name, _)| (import, name.as_str())),
+            _ => None,
+        })
+    }
+
+    fn fields(&mut self, fields: &[Field], source: &str) -> Vec<Symbol> {
+        let mut symbols = vec![];
+        for field in fields {
+            self.collect_references(&field.field_type);
+
+            let optional = if field.optional() { "optional " } else { "" };
+            symbols.push(Symbol {
+                name: field.name(),
+                kind: SymbolKind::Field,
+                span: field.name.span,
+                detail: format!("{}{}: {}", optional, field.name(), type_name(&field.field_type)),
+                docstring: docstring_before(source, field.name.span.0),
+                members: vec![],
+            });
+        }
+        symbols
+    }
+
+    fn collect_references(&mut self, type_def: &Type) {
+        match type_def {
+            Type::Named(name) => self.references.push(TypeReference {
+                path: name.to_string(), span: name.span,
+            }),
+            Type::Array(array) => self.collect_references(array.elem_type()),
+            _ => {}
+        }
+    }
+
+    fn push_use(&mut self, path: &UsePath, alias: Option<String>, namespace: &[String]) {
+        match path {
+            UsePath::Absolute(scoped) => {
+                self.push_import(split_path(scoped.as_str()), scoped, alias)
+            }
+            UsePath::Relative(relative) => {
+                let mut absolute = namespace.to_vec();
+                match relative.prefix {
+                    RelativePrefix::Self_ => {}
+                    RelativePrefix::Parent => { absolute.pop(); }
+                    RelativePrefix::Crate => absolute.clear(),
+                }
+                absolute.extend(split_path(relative.path.as_str()));
+
+                let Some(name) = absolute.pop() else { return };
+                self.imports.push(Import {
+                    namespace: absolute,
+                    // Relative paths carry no spans, so they are left out of renames
+                    items: ImportedItems::Single { name, span: (0, 0), alias },
+                });
+            }
+            UsePath::Glob(glob) => self.imports.push(Import {
+                namespace: split_path(glob.path.as_str()),
+                items: ImportedItems::Glob,
+            }),
+            UsePath::Multi(multi) => self.imports.push(Import {
+                namespace: split_path(multi.path.as_str()),
+                items: ImportedItems::Multiple(
+                    multi.items().iter().map(|item| (item.to_string(), item.span)).collect()
+                ),
+            }),
+        }
+    }
+
+    fn push_import<T>(&mut self, mut path: Vec<String>, spanned: &Spanned<T>, alias: Option<String>) {
+        let Some(name) = path.pop() else { return };
+        let span = (spanned.span.1 - name.len(), spanned.span.1);
+
+        self.imports.push(Import {
+            namespace: path,
+            items: ImportedItems::Single { name, span, alias },
+        });
+    }
+}
+
+fn split_path(path: &str) -> Vec<String> {
+    path.split("::").map(str::to_owned).collect()
+}
+
+fn contains(span: (usize, usize), offset: usize) -> bool {
+    span.0 <= offset && offset <= span.1
+}
+
+/// Type as written in a schema
+pub fn type_name(type_def: &Type) -> String {
+    match type_def {
+        Type::I8(_) => "i8".to_owned(),
+        Type::I16(_) => "i16".to_owned(),
+        Type::I32(_) => "i32".to_owned(),
+        Type::I64(_) => "i64".to_owned(),
+        Type::U8(_) => "u8".to_owned(),
+        Type::U16(_) => "u16".to_owned(),
+        Type::U32(_) => "u32".to_owned(),
+        Type::U64(_) => "u64".to_owned(),
+        Type::F32(_) => "f32".to_owned(),
+        Type::F64(_) => "f64".to_owned(),
+        Type::Bool(_) => "bool".to_owned(),
+        Type::Str(_) => "str".to_owned(),
+        Type::String(_) => "string".to_owned(),
+        Type::Named(name) => name.to_string(),
+        Type::Array(array) => match &array.size {
+            Some(size) => format!("{}[{}]", type_name(array.elem_type()), size.value()),
+            None => format!("{}[]", type_name(array.elem_type())),
+        },
+    }
+}
@@ -0,0 +1,64 @@
+// Package configuration documents (`config.idp`)
+
+// Standard Uses
+
+// Crate Uses
+use crate::package::{collect_parse_errors, Problem, Severity};
+
+// External Uses
+use comline_core::package::config::ir::context::ProjectContext;
+use comline_core::package::config::idl::grammar::Congregation;
+use comline_core::package::config::ir::interpreter::interpret::interpret_context;
+use comline_core::schema::ir::lint::LintLevels;
+
+
+/// Keys understood by the configuration interpreter, with what they hold
+pub const CONFIG_KEYS: &[(&str, &str)] = &[
+    ("specification_version", "Version of the configuration format"),
+    ("schemas_source_path", "Directory holding the package schemas"),
+    ("schema_paths", "Schemas of the package, relative to the source path"),
+    ("code_generation", "Languages to generate code for, as `languages = { ... }`"),
+    ("publish_registries", "Registries the package is published into"),
+    ("languages", "Generation targets, inside `code_generation`"),
+    ("package_versions", "Package versions to generate for, inside a language"),
+    ("generator_version", "Versions of the generator a language accepts, inside a language, e.g. `^0.1`"),
+    ("path", "Directory to generate into, inside a language, e.g. `out/{{language}}/{{version}}`"),
+    ("options", "Options of the generator, inside a language, e.g. `{ cargo_crate = true }`"),
+    ("lints", "Levels of lint rules, as `rule = allow | warn | deny`"),
+    ("uri", "Location of a registry, e.g. `local://{{package_path}}/registry`"),
+];
+
+
+pub fn diagnostics(source: &str) -> Vec<Problem> {
+    let congregation = match comline_core::package::config::idl::grammar::parse(source) {
+        Ok(congregation) => congregation,
+        Err(errors) => {
+            let mut problems = vec![];
+            for error in &errors {
+                collect_parse_errors(error, &mut problems);
+            }
+            return problems
+        }
+    };
+
+    // Interpretation errors carry no spans, they are reported on the package name
+    let span = (0, source.find('\n').unwrap_or(source.len()));
+    let context = ProjectContext::with_config(congregation);
+
+    match interpret_context(&context) {
+        Ok(_) => vec![],
+        Err(error) => vec![Problem {
+            span, message: error.to_string(), severity: Severity::Error,
+        }],
+    }
+}
+
+/// Lint levels of a configuration, or the defaults if it cannot be interpreted
+pub fn lint_levels(congregation: &Congregation) -> LintLevels {
+    let context = ProjectContext::with_config(congregation.clone());
+
+    match interpret_context(&context) {
+        Ok(units) => LintLevels::from_config(&units),
+        Err(_) => LintLevels::default(),
+    }
+}
@@ -0,0 +1,6 @@
+// Relative Modules
+pub mod analysis;
+pub mod config;
+pub mod line_index;
+pub mod package;
+pub mod server;
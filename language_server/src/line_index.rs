@@ -0,0 +1,58 @@
+// Standard Uses
+
+// Crate Uses
+
+// External Uses
+use lsp_types::{Position, Range};
+
+
+/// Converts between byte offsets and LSP positions, whose columns are
+/// counted in UTF-16 code units
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Self { line_starts }
+    }
+
+    pub fn position(&self, text: &str, offset: usize) -> Position {
+        let offset = offset.min(text.len());
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(exact) => exact,
+            Err(insert) => insert - 1,
+        };
+        let line_start = self.line_starts[line];
+        let character = text[line_start..offset].encode_utf16().count();
+
+        Position::new(line as u32, character as u32)
+    }
+
+    pub fn range(&self, text: &str, span: (usize, usize)) -> Range {
+        Range::new(self.position(text, span.0), self.position(text, span.1))
+    }
+
+    pub fn offset(&self, text: &str, position: Position) -> usize {
+        let Some(&line_start) = self.line_starts.get(position.line as usize) else {
+            return text.len()
+        };
+        let line_end = self.line_starts.get(position.line as usize + 1)
+            .copied().unwrap_or(text.len());
+
+        let line = text[line_start..line_end].trim_end_matches(['\n', '\r']);
+
+        let mut units = 0;
+        for (index, c) in line.char_indices() {
+            if units >= position.character as usize { return line_start + index }
+            units += c.len_utf16();
+        }
+
+        line_start + line.len()
+    }
+}
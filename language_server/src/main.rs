@@ -0,0 +1,11 @@
+// Standard Uses
+
+// Crate Uses
+
+// External Uses
+use eyre::Result;
+
+
+fn main() -> Result<()> {
+    comline_language_server::server::run()
+}
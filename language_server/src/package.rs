@@ -0,0 +1,523 @@
+// Package model
+// Every schema of the package the edited document belongs to, with open
+// documents taking precedence over what is on disk
+
+// Standard Uses
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+// Crate Uses
+use crate::analysis::{Analysis, ImportedItems, Symbol, SymbolKind};
+use crate::line_index::LineIndex;
+
+// External Uses
+use comline_core::package::config::idl::constants::CONGREGATION_EXTENSION;
+use comline_core::schema::idl::constants::SCHEMA_EXTENSION;
+use comline_core::schema::idl::grammar::Declaration;
+use comline_core::schema::ir::compiler::interpreter::IncrementalInterpreter;
+use comline_core::schema::ir::compiler::Compile;
+use comline_core::schema::ir::lint::{self, LintLevel, LintLevels, LintTarget};
+use comline_core::schema::ir::validation;
+use comline_core::utils::codemap::{CodeMap, FileMap};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rust_sitter::errors::{ParseError, ParseErrorReason};
+
+
+pub const PRIMITIVES: &[&str] = &[
+    "bool", "u8", "u16", "u32", "u64", "i8", "i16", "i32", "i64", "f32", "f64", "str", "string",
+];
+
+pub const KEYWORDS: &[&str] = &[
+    "use", "import", "const", "struct", "enum", "protocol", "async", "function", "optional", "as",
+    "validator", "validate", "error", "message", "throws",
+];
+
+static IDENTIFIER: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_]*$").unwrap());
+static QUOTED: Lazy<Regex> = Lazy::new(|| Regex::new(r"'([^']*)'").unwrap());
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub span: (usize, usize),
+    pub message: String,
+    pub severity: Severity,
+}
+
+/// A span inside one of the package schemas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Location {
+    pub schema: usize,
+    pub span: (usize, usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompletionKind {
+    Primitive,
+    Keyword,
+    Symbol(SymbolKind),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub label: String,
+    pub kind: CompletionKind,
+    pub detail: Option<String>,
+}
+
+pub struct SchemaFile {
+    pub path: PathBuf,
+    pub namespace: Vec<String>,
+    pub file: Arc<FileMap>,
+    pub index: LineIndex,
+    pub declarations: Option<Vec<Declaration>>,
+    pub parse_errors: Vec<ParseError>,
+    pub analysis: Analysis,
+}
+
+impl SchemaFile {
+    pub fn source(&self) -> &str { self.file.contents() }
+}
+
+pub struct Package {
+    pub root: PathBuf,
+    pub name: Option<String>,
+    pub codemap: CodeMap,
+    pub schemas: Vec<SchemaFile>,
+    /// Lint levels from the package configuration
+    pub lints: LintLevels,
+}
+
+impl Package {
+    /// Loads the package that `document` belongs to, that is the closest
+    /// ancestor directory with a package configuration. Documents outside of
+    /// a package are loaded as a package of their own
+    pub fn for_document(document: &Path, open: &HashMap<PathBuf, String>) -> Self {
+        match find_package_root(document) {
+            Some(root) => Self::load(&root, open),
+            None => {
+                let mut package = Self::empty(document.parent().unwrap_or(Path::new("")));
+                let namespace = document.file_stem()
+                    .map(|stem| vec![stem.to_string_lossy().into_owned()])
+                    .unwrap_or_default();
+
+                if let Some(source) = read_document(document, open) {
+                    package.add_schema(document.to_path_buf(), namespace, source);
+                }
+                package
+            }
+        }
+    }
+
+    /// Loads every schema under the `src/` directory of the package at `root`
+    pub fn load(root: &Path, open: &HashMap<PathBuf, String>) -> Self {
+        let mut package = Self::empty(root);
+        let config = read_document(&config_path(root), open)
+            .and_then(|source| comline_core::package::config::idl::grammar::parse(&source).ok());
+        if let Some(congregation) = config {
+            package.lints = crate::config::lint_levels(&congregation);
+            package.name = Some(congregation.name.value);
+        }
+
+        let sources = root.join("src");
+        let pattern = format!("{}/**/*.{}", sources.display(), SCHEMA_EXTENSION);
+        let mut paths = glob::glob(&pattern)
+            .map(|paths| paths.flatten().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        // Documents that were not saved yet
+        for path in open.keys() {
+            let is_schema = path.extension().is_some_and(|e| e == SCHEMA_EXTENSION);
+            if is_schema && path.starts_with(&sources) && !paths.contains(path) {
+                paths.push(path.clone());
+            }
+        }
+        paths.sort();
+
+        for path in paths {
+            let Some(source) = read_document(&path, open) else { continue };
+            let namespace = path.strip_prefix(&sources).unwrap()
+                .with_extension("")
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+
+            package.add_schema(path, namespace, source);
+        }
+
+        package
+    }
+
+    fn empty(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(), name: None, codemap: CodeMap::new(), schemas: vec![],
+            lints: LintLevels::default(),
+        }
+    }
+
+    pub fn add_schema(&mut self, path: PathBuf, namespace: Vec<String>, source: String) -> usize {
+        let file = self.codemap.insert_file(path.display().to_string(), source);
+        let source = file.contents();
+
+        let (declarations, parse_errors) = match comline_core::schema::idl::grammar::parse(source) {
+            Ok(document) => (Some(document.0), vec![]),
+            Err(errors) => (None, errors),
+        };
+        let analysis = declarations.as_deref()
+            .map(|declarations| Analysis::new(source, declarations, &namespace))
+            .unwrap_or_default();
+
+        self.schemas.push(SchemaFile {
+            index: LineIndex::new(source),
+            path, namespace, file, declarations, parse_errors, analysis,
+        });
+
+        self.schemas.len() - 1
+    }
+
+    pub fn schema_by_path(&self, path: &Path) -> Option<usize> {
+        self.schemas.iter().position(|s| s.path == path)
+    }
+
+    /// The schema declaring the namespace, which may be led by the package name
+    pub fn schema_by_namespace(&self, namespace: &[String]) -> Option<usize> {
+        let relative = match (&self.name, namespace.split_first()) {
+            (Some(name), Some((first, rest))) if name == first => Some(rest),
+            _ => None,
+        };
+
+        self.schemas.iter().position(|s| s.namespace == namespace)
+            .or_else(|| relative.and_then(|r| self.schemas.iter().position(|s| s.namespace == r)))
+    }
+
+    pub fn symbol(&self, location: Location) -> Option<(&Symbol, Option<&Symbol>)> {
+        self.schemas[location.schema].analysis.symbol_at(location.span.0)
+    }
+
+    /// Resolves a type path as seen from a schema, through its declarations
+    /// and imports
+    pub fn resolve(&self, schema: usize, path: &str) -> Option<Location> {
+        let analysis = &self.schemas[schema].analysis;
+
+        if let Some((namespace, name)) = path.rsplit_once("::") {
+            let namespace = namespace.split("::").map(str::to_owned).collect::<Vec<_>>();
+            let target = self.schema_by_namespace(&namespace)?;
+            let symbol = self.schemas[target].analysis.symbol(name)?;
+
+            return Some(Location { schema: target, span: symbol.span })
+        }
+
+        if let Some(symbol) = analysis.symbol(path) {
+            return Some(Location { schema, span: symbol.span })
+        }
+
+        analysis.imports.iter().find_map(|import| {
+            let name = import.imported_name_of(path)?;
+            let target = self.schema_by_namespace(&import.namespace)?;
+            let symbol = self.schemas[target].analysis.symbol(name)?;
+
+            Some(Location { schema: target, span: symbol.span })
+        })
+    }
+
+    /// Like [`Self::resolve`], falling back to the only declaration with that
+    /// name in the whole package, to navigate schemas that are yet to be
+    /// imported properly
+    pub fn resolve_loosely(&self, schema: usize, path: &str) -> Option<Location> {
+        if let Some(location) = self.resolve(schema, path) { return Some(location) }
+
+        let mut candidates = self.schemas.iter().enumerate().filter_map(|(index, s)| {
+            s.analysis.symbol(path).map(|symbol| Location { schema: index, span: symbol.span })
+        });
+
+        match (candidates.next(), candidates.next()) {
+            (Some(only), None) => Some(only),
+            _ => None,
+        }
+    }
+
+    /// Where the symbol at `offset` is declared
+    pub fn definition(&self, schema: usize, offset: usize) -> Option<Location> {
+        let analysis = &self.schemas[schema].analysis;
+
+        if let Some(reference) = analysis.reference_at(offset) {
+            return self.resolve_loosely(schema, &reference.path)
+        }
+
+        if let Some((import, name)) = analysis.import_at(offset) {
+            let target = self.schema_by_namespace(&import.namespace)?;
+            let symbol = self.schemas[target].analysis.symbol(name)?;
+
+            return Some(Location { schema: target, span: symbol.span })
+        }
+
+        analysis.symbol_at(offset).map(|(symbol, member)| Location {
+            schema, span: member.unwrap_or(symbol).span,
+        })
+    }
+
+    /// Markdown describing the symbol at `offset`
+    pub fn hover(&self, schema: usize, offset: usize) -> Option<String> {
+        let location = self.definition(schema, offset)?;
+        let (symbol, member) = self.symbol(location)?;
+
+        let (signature, docstring) = match member {
+            Some(member) => (member.detail.clone(), member.docstring.as_deref()),
+            None => (describe(symbol), symbol.docstring.as_deref()),
+        };
+
+        let mut markdown = format!("```comline\n{}\n```", signature);
+        if let Some(docstring) = docstring {
+            markdown.push_str("\n\n");
+            markdown.push_str(docstring);
+        }
+
+        Some(markdown)
+    }
+
+    /// Names that can be written where a type is expected
+    pub fn completions(&self, schema: usize) -> Vec<Completion> {
+        let mut seen = HashSet::new();
+        let mut completions = vec![];
+
+        let mut visible_types = self.schemas[schema].analysis.symbols.iter()
+            .map(|symbol| (symbol.name.clone(), symbol))
+            .collect::<Vec<_>>();
+
+        for import in &self.schemas[schema].analysis.imports {
+            let Some(target) = self.schema_by_namespace(&import.namespace) else { continue };
+
+            for symbol in &self.schemas[target].analysis.symbols {
+                if let Some(local) = import.local_name_of(&symbol.name) {
+                    visible_types.push((local.to_owned(), symbol));
+                }
+            }
+        }
+
+        for (name, symbol) in visible_types {
+            if symbol.kind.is_type() && seen.insert(name.clone()) {
+                completions.push(Completion {
+                    label: name,
+                    kind: CompletionKind::Symbol(symbol.kind),
+                    detail: Some(symbol.detail.clone()),
+                });
+            }
+        }
+
+        // Types elsewhere in the package, written by their full path
+        for other in self.schemas.iter().filter(|s| !s.namespace.is_empty()) {
+            for symbol in other.analysis.symbols.iter().filter(|s| s.kind.is_type()) {
+                let path = format!("{}::{}", other.namespace.join("::"), symbol.name);
+
+                if seen.insert(path.clone()) {
+                    completions.push(Completion {
+                        label: path,
+                        kind: CompletionKind::Symbol(symbol.kind),
+                        detail: Some(symbol.detail.clone()),
+                    });
+                }
+            }
+        }
+
+        completions.extend(PRIMITIVES.iter().map(|primitive| Completion {
+            label: primitive.to_string(), kind: CompletionKind::Primitive, detail: None,
+        }));
+        completions.extend(KEYWORDS.iter().map(|keyword| Completion {
+            label: keyword.to_string(), kind: CompletionKind::Keyword, detail: None,
+        }));
+
+        completions
+    }
+
+    /// Edits renaming the symbol at `offset` to `new_name`, everywhere in the package
+    pub fn rename(&self, schema: usize, offset: usize, new_name: &str) -> Result<Vec<Location>, String> {
+        if !IDENTIFIER.is_match(new_name) || PRIMITIVES.contains(&new_name) || KEYWORDS.contains(&new_name) {
+            return Err(format!("'{}' is not a valid name", new_name))
+        }
+
+        let Some(target) = self.definition(schema, offset) else {
+            return Err("There is no symbol to rename here".to_owned())
+        };
+        let Some((symbol, member)) = self.symbol(target) else {
+            return Err("There is no symbol to rename here".to_owned())
+        };
+
+        // Members are only ever referred to by their declaration
+        if member.is_some() { return Ok(vec![target]) }
+
+        let mut edits = vec![target];
+        for (index, other) in self.schemas.iter().enumerate() {
+            for reference in &other.analysis.references {
+                // References through an alias keep the alias
+                if reference.name() != symbol.name { continue }
+
+                if self.resolve_loosely(index, &reference.path) == Some(target) {
+                    edits.push(Location { schema: index, span: reference.name_span() });
+                }
+            }
+
+            for import in &other.analysis.imports {
+                if self.schema_by_namespace(&import.namespace) != Some(target.schema) { continue }
+
+                let spans = match &import.items {
+                    ImportedItems::Single { name, span, .. } => vec![(name, *span)],
+                    ImportedItems::Multiple(items) => items.iter().map(|(n, s)| (n, *s)).collect(),
+                    ImportedItems::Glob => vec![],
+                };
+
+                for (name, span) in spans {
+                    if *name == symbol.name && span != (0, 0) {
+                        edits.push(Location { schema: index, span });
+                    }
+                }
+            }
+        }
+
+        edits.dedup();
+        Ok(edits)
+    }
+
+    /// Parse errors, then the validator's findings with unresolved imports
+    /// telling apart types that do exist elsewhere in the package
+    pub fn diagnostics(&self, schema: usize) -> Vec<Problem> {
+        let file = &self.schemas[schema];
+
+        let Some(declarations) = &file.declarations else {
+            let mut problems = vec![];
+            for error in &file.parse_errors {
+                collect_parse_errors(error, &mut problems);
+            }
+            return problems
+        };
+
+        let frozen = IncrementalInterpreter::from_declarations(declarations.clone());
+
+        let mut problems = self.lints(schema);
+        let Err(errors) = validation::validate(&frozen) else { return problems };
+
+        let mut unknown_types = HashSet::new();
+        for error in errors {
+            if let Some(type_name) = error.message.strip_prefix("Unknown type '") {
+                unknown_types.insert(type_name.trim_end_matches('\'').to_owned());
+                continue
+            }
+
+            problems.push(Problem {
+                span: self.span_of_context(schema, &error.context),
+                message: error.message,
+                severity: Severity::Error,
+            });
+        }
+
+        for reference in &file.analysis.references {
+            let base = reference.path.as_str();
+            if unknown_types.contains(base) && self.resolve(schema, base).is_none() {
+                problems.push(Problem {
+                    span: reference.span,
+                    message: format!("Unknown type '{}'", base),
+                    severity: Severity::Error,
+                });
+            }
+        }
+
+        problems
+    }
+
+    /// Lints of a schema, with the other schemas of the package in view
+    fn lints(&self, schema: usize) -> Vec<Problem> {
+        let targets: Vec<_> = self.schemas.iter()
+            .filter_map(|file| Some(LintTarget {
+                namespace: &file.namespace,
+                source: file.source(),
+                declarations: file.declarations.as_deref()?,
+            }))
+            .collect();
+        // Schemas that do not parse have no target
+        let position = self.schemas[..schema].iter()
+            .filter(|file| file.declarations.is_some())
+            .count();
+
+        lint::lint_schemas(&targets, &self.lints).swap_remove(position).into_iter()
+            .map(|lint| Problem {
+                span: lint.span,
+                message: format!("{} ({})", lint.message, lint.rule.name()),
+                severity: match lint.level {
+                    LintLevel::Deny => Severity::Error,
+                    _ => Severity::Warning,
+                },
+            })
+            .collect()
+    }
+
+    /// Validation errors only name what they are about, e.g. `Struct 'User',
+    /// field 'email'`, which is narrowed down to the span of that name
+    fn span_of_context(&self, schema: usize, context: &str) -> (usize, usize) {
+        let analysis = &self.schemas[schema].analysis;
+        let mut names = QUOTED.captures_iter(context).map(|c| c.get(1).unwrap().as_str());
+
+        let Some(symbol) = names.next().and_then(|name| {
+            // Duplicates are reported on their last definition
+            analysis.symbols.iter().rev().find(|s| s.name == name)
+        }) else {
+            return (0, 0)
+        };
+
+        names.next()
+            .and_then(|name| symbol.members.iter().find(|m| m.name == name))
+            .map_or(symbol.span, |member| member.span)
+    }
+}
+
+/// A declaration with its members
+fn describe(symbol: &Symbol) -> String {
+    match symbol.kind {
+        SymbolKind::Constant => format!("const {}: {}", symbol.name, symbol.detail),
+        SymbolKind::Struct | SymbolKind::Enum | SymbolKind::Protocol | SymbolKind::Validator
+        | SymbolKind::Error => {
+            let mut text = format!("{} {} {{\n", symbol.kind.keyword(), symbol.name);
+            for member in &symbol.members {
+                text.push_str(&format!("    {}\n", member.detail));
+            }
+            text.push('}');
+            text
+        }
+        _ => symbol.detail.clone(),
+    }
+}
+
+pub(crate) fn collect_parse_errors(error: &ParseError, problems: &mut Vec<Problem>) {
+    let message = match &error.reason {
+        ParseErrorReason::UnexpectedToken(token) => format!("Unexpected token '{}'", token),
+        ParseErrorReason::MissingToken(token) => format!("Missing '{}'", token),
+        ParseErrorReason::FailedNode(inner) if !inner.is_empty() => {
+            for error in inner {
+                collect_parse_errors(error, problems);
+            }
+            return
+        }
+        ParseErrorReason::FailedNode(_) => "Syntax error".to_owned(),
+    };
+
+    problems.push(Problem { span: (error.start, error.end), message, severity: Severity::Error });
+}
+
+pub fn config_path(root: &Path) -> PathBuf {
+    root.join(format!("config.{}", CONGREGATION_EXTENSION))
+}
+
+fn find_package_root(document: &Path) -> Option<PathBuf> {
+    document.ancestors().skip(1)
+        .find(|directory| config_path(directory).is_file())
+        .map(Path::to_path_buf)
+}
+
+fn read_document(path: &Path, open: &HashMap<PathBuf, String>) -> Option<String> {
+    open.get(path).cloned().or_else(|| std::fs::read_to_string(path).ok())
+}
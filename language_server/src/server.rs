@@ -0,0 +1,353 @@
+// Language Server Protocol plumbing
+// Translates requests and notifications from the editor into queries on the
+// package model, and its answers back
+
+// Standard Uses
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// Crate Uses
+use crate::analysis::{Symbol, SymbolKind};
+use crate::config::{self, CONFIG_KEYS};
+use crate::package::{CompletionKind, Location, Package, Problem, Severity};
+use crate::line_index::LineIndex;
+
+// External Uses
+use comline_core::package::config::idl::constants::CONGREGATION_EXTENSION;
+use comline_core::package::format::format_source;
+use eyre::Result;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+    Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{
+    Completion, DocumentSymbolRequest, Formatting, GotoDefinition, HoverRequest, Rename,
+    Request as _,
+};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionResponse, Diagnostic,
+    DiagnosticSeverity, DocumentSymbol, DocumentSymbolResponse, GotoDefinitionResponse, Hover,
+    HoverContents, HoverProviderCapability, MarkupContent, MarkupKind, OneOf,
+    PublishDiagnosticsParams, ServerCapabilities, TextDocumentPositionParams,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit, Url, WorkspaceEdit,
+};
+
+
+pub fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        definition_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![":".to_owned()]),
+            ..Default::default()
+        }),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        rename_provider: Some(OneOf::Left(true)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    }
+}
+
+/// Serves a client over stdio until it asks to shut down
+pub fn run() -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+
+    connection.initialize(serde_json::to_value(capabilities())?)?;
+    Server::default().serve(&connection)?;
+
+    io_threads.join()?;
+    Ok(())
+}
+
+#[derive(Default)]
+pub struct Server {
+    /// Contents of the documents open in the editor, by path
+    open: HashMap<PathBuf, String>,
+}
+
+impl Server {
+    pub fn serve(&mut self, connection: &Connection) -> Result<()> {
+        for message in &connection.receiver {
+            match message {
+                Message::Request(request) => {
+                    if connection.handle_shutdown(&request)? { break }
+                    connection.sender.send(Message::Response(self.handle_request(request)))?;
+                }
+                Message::Notification(notification) => {
+                    for outgoing in self.handle_notification(notification) {
+                        connection.sender.send(Message::Notification(outgoing))?;
+                    }
+                }
+                Message::Response(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn handle_request(&self, request: Request) -> Response {
+        let id = request.id.clone();
+        let result = match request.method.as_str() {
+            GotoDefinition::METHOD => params(request).and_then(|p| to_value(self.definition(p))),
+            HoverRequest::METHOD => params(request).and_then(|p| to_value(self.hover(p))),
+            Completion::METHOD => params(request).and_then(|p| to_value(self.completion(p))),
+            DocumentSymbolRequest::METHOD => {
+                params(request).and_then(|p| to_value(self.document_symbols(p)))
+            }
+            Formatting::METHOD => params(request).and_then(|p| to_value(self.formatting(p))),
+            Rename::METHOD => params(request).and_then(|p| {
+                let edit = self.rename(p).map_err(|e| (ErrorCode::RequestFailed, e))?;
+                to_value(edit)
+            }),
+            method => Err((ErrorCode::MethodNotFound, format!("Unsupported request '{}'", method))),
+        };
+
+        match result {
+            Ok(value) => Response::new_ok(id, value),
+            Err((code, message)) => Response::new_err(id, code as i32, message),
+        }
+    }
+
+    /// Keeps track of open documents, answering with fresh diagnostics for them
+    pub fn handle_notification(&mut self, notification: Notification) -> Vec<Notification> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let Ok(p) = notification.extract::<lsp_types::DidOpenTextDocumentParams>(
+                    DidOpenTextDocument::METHOD
+                ) else { return vec![] };
+                let Some(path) = to_path(&p.text_document.uri) else { return vec![] };
+                self.open.insert(path, p.text_document.text);
+            }
+            DidChangeTextDocument::METHOD => {
+                let Ok(mut p) = notification.extract::<lsp_types::DidChangeTextDocumentParams>(
+                    DidChangeTextDocument::METHOD
+                ) else { return vec![] };
+                let Some(path) = to_path(&p.text_document.uri) else { return vec![] };
+
+                // Documents are always synchronized whole
+                if let Some(change) = p.content_changes.pop() {
+                    self.open.insert(path, change.text);
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                let Ok(p) = notification.extract::<lsp_types::DidCloseTextDocumentParams>(
+                    DidCloseTextDocument::METHOD
+                ) else { return vec![] };
+                if let Some(path) = to_path(&p.text_document.uri) {
+                    self.open.remove(&path);
+                }
+
+                return vec![publish(p.text_document.uri, vec![])]
+            }
+            DidSaveTextDocument::METHOD => {}
+            _ => return vec![],
+        }
+
+        // Any change may fix or break the documents importing from it
+        let mut paths = self.open.keys().cloned().collect::<Vec<_>>();
+        paths.sort();
+        paths.into_iter()
+            .filter_map(|path| Some(publish(Url::from_file_path(&path).ok()?, self.diagnostics(&path))))
+            .collect()
+    }
+
+    pub fn diagnostics(&self, path: &Path) -> Vec<Diagnostic> {
+        if is_config(path) {
+            let Some(source) = self.open.get(path) else { return vec![] };
+            let index = LineIndex::new(source);
+
+            return config::diagnostics(source).into_iter()
+                .map(|problem| to_diagnostic(&index, source, problem))
+                .collect()
+        }
+
+        let package = Package::for_document(path, &self.open);
+        let Some(schema) = package.schema_by_path(path) else { return vec![] };
+        let file = &package.schemas[schema];
+
+        package.diagnostics(schema).into_iter()
+            .map(|problem| to_diagnostic(&file.index, file.source(), problem))
+            .collect()
+    }
+
+    fn definition(&self, p: lsp_types::GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let (package, schema, offset) = self.locate(&p.text_document_position_params)?;
+        let location = package.definition(schema, offset)?;
+
+        Some(GotoDefinitionResponse::Scalar(to_location(&package, location)?))
+    }
+
+    fn hover(&self, p: lsp_types::HoverParams) -> Option<Hover> {
+        let (package, schema, offset) = self.locate(&p.text_document_position_params)?;
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: package.hover(schema, offset)?,
+            }),
+            range: None,
+        })
+    }
+
+    fn completion(&self, p: lsp_types::CompletionParams) -> Option<CompletionResponse> {
+        let path = to_path(&p.text_document_position.text_document.uri)?;
+
+        if is_config(&path) {
+            return Some(CompletionResponse::Array(CONFIG_KEYS.iter().map(|(key, detail)| {
+                CompletionItem {
+                    label: key.to_string(),
+                    kind: Some(CompletionItemKind::PROPERTY),
+                    detail: Some(detail.to_string()),
+                    ..Default::default()
+                }
+            }).collect()))
+        }
+
+        let package = Package::for_document(&path, &self.open);
+        let schema = package.schema_by_path(&path)?;
+
+        Some(CompletionResponse::Array(package.completions(schema).into_iter().map(|c| {
+            CompletionItem {
+                kind: Some(match c.kind {
+                    CompletionKind::Primitive => CompletionItemKind::TYPE_PARAMETER,
+                    CompletionKind::Keyword => CompletionItemKind::KEYWORD,
+                    CompletionKind::Symbol(SymbolKind::Enum) => CompletionItemKind::ENUM,
+                    CompletionKind::Symbol(_) => CompletionItemKind::STRUCT,
+                }),
+                label: c.label,
+                detail: c.detail,
+                ..Default::default()
+            }
+        }).collect()))
+    }
+
+    fn document_symbols(&self, p: lsp_types::DocumentSymbolParams) -> Option<DocumentSymbolResponse> {
+        let path = to_path(&p.text_document.uri)?;
+        let package = Package::for_document(&path, &self.open);
+        let file = &package.schemas[package.schema_by_path(&path)?];
+
+        let symbols = file.analysis.symbols.iter()
+            .map(|symbol| to_document_symbol(&file.index, file.source(), symbol))
+            .collect();
+
+        Some(DocumentSymbolResponse::Nested(symbols))
+    }
+
+    /// Replaces the whole document with its canonical layout, documents that
+    /// do not parse are left as they are
+    fn formatting(&self, p: lsp_types::DocumentFormattingParams) -> Option<Vec<TextEdit>> {
+        let path = to_path(&p.text_document.uri)?;
+        let source = self.open.get(&path)?;
+
+        let formatted = format_source(&path, source).ok()?;
+        if formatted == *source { return Some(vec![]) }
+
+        let index = LineIndex::new(source);
+        Some(vec![TextEdit::new(index.range(source, (0, source.len())), formatted)])
+    }
+
+    fn rename(&self, p: lsp_types::RenameParams) -> Result<Option<WorkspaceEdit>, String> {
+        let Some((package, schema, offset)) = self.locate(&p.text_document_position) else {
+            return Ok(None)
+        };
+
+        let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+        for location in package.rename(schema, offset, &p.new_name)? {
+            let Some(location) = to_location(&package, location) else { continue };
+            changes.entry(location.uri).or_default()
+                .push(TextEdit::new(location.range, p.new_name.clone()));
+        }
+
+        Ok(Some(WorkspaceEdit { changes: Some(changes), ..Default::default() }))
+    }
+
+    /// The package, schema and byte offset a position points at
+    fn locate(&self, position: &TextDocumentPositionParams) -> Option<(Package, usize, usize)> {
+        let path = to_path(&position.text_document.uri)?;
+        let package = Package::for_document(&path, &self.open);
+        let schema = package.schema_by_path(&path)?;
+
+        let file = &package.schemas[schema];
+        let offset = file.index.offset(file.source(), position.position);
+
+        Some((package, schema, offset))
+    }
+}
+
+type RequestResult = std::result::Result<serde_json::Value, (ErrorCode, String)>;
+
+fn params<P: serde::de::DeserializeOwned>(request: Request) -> std::result::Result<P, (ErrorCode, String)> {
+    serde_json::from_value(request.params).map_err(|e| (ErrorCode::InvalidParams, e.to_string()))
+}
+
+fn to_value<T: serde::Serialize>(value: T) -> RequestResult {
+    serde_json::to_value(value).map_err(|e| (ErrorCode::InternalError, e.to_string()))
+}
+
+fn publish(uri: Url, diagnostics: Vec<Diagnostic>) -> Notification {
+    Notification::new(
+        PublishDiagnostics::METHOD.to_owned(),
+        PublishDiagnosticsParams { uri, diagnostics, version: None },
+    )
+}
+
+fn to_path(uri: &Url) -> Option<PathBuf> {
+    uri.to_file_path().ok()
+}
+
+fn is_config(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == CONGREGATION_EXTENSION)
+}
+
+fn to_location(package: &Package, location: Location) -> Option<lsp_types::Location> {
+    let file = &package.schemas[location.schema];
+
+    Some(lsp_types::Location {
+        uri: Url::from_file_path(&file.path).ok()?,
+        range: file.index.range(file.source(), location.span),
+    })
+}
+
+fn to_diagnostic(index: &LineIndex, source: &str, problem: Problem) -> Diagnostic {
+    Diagnostic {
+        range: index.range(source, problem.span),
+        severity: Some(match problem.severity {
+            Severity::Error => DiagnosticSeverity::ERROR,
+            Severity::Warning => DiagnosticSeverity::WARNING,
+        }),
+        source: Some("comline".to_owned()),
+        message: problem.message,
+        ..Default::default()
+    }
+}
+
+#[allow(deprecated)] // `DocumentSymbol::deprecated` has to be filled in
+fn to_document_symbol(index: &LineIndex, source: &str, symbol: &Symbol) -> DocumentSymbol {
+    let range = index.range(source, symbol.span);
+
+    DocumentSymbol {
+        name: symbol.name.clone(),
+        detail: Some(symbol.detail.clone()),
+        kind: match symbol.kind {
+            SymbolKind::Constant => lsp_types::SymbolKind::CONSTANT,
+            SymbolKind::Struct => lsp_types::SymbolKind::STRUCT,
+            SymbolKind::Field => lsp_types::SymbolKind::FIELD,
+            SymbolKind::Enum => lsp_types::SymbolKind::ENUM,
+            SymbolKind::Variant => lsp_types::SymbolKind::ENUM_MEMBER,
+            SymbolKind::Protocol => lsp_types::SymbolKind::INTERFACE,
+            SymbolKind::Function => lsp_types::SymbolKind::METHOD,
+            SymbolKind::Validator => lsp_types::SymbolKind::FUNCTION,
+            SymbolKind::Property => lsp_types::SymbolKind::PROPERTY,
+            SymbolKind::Error => lsp_types::SymbolKind::STRUCT,
+        },
+        tags: None,
+        deprecated: None,
+        range,
+        selection_range: range,
+        children: Some(symbol.members.iter()
+            .map(|member| to_document_symbol(index, source, member))
+            .collect()),
+    }
+}
@@ -0,0 +1,213 @@
+// Standard Uses
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// Crate Uses
+
+// External Uses
+use comline_language_server::config;
+use comline_language_server::package::{CompletionKind, Package, Severity};
+
+
+const CONFIG: &str = "congregation shop\nspecification_version = 1\n";
+
+const TYPES: &str = r#"/// Someone who buys things
+struct Customer {
+    /// Unique identifier
+    id: u64
+    optional email: string
+}
+
+enum Tier {
+    Basic
+    Gold
+}
+"#;
+
+const ORDERS: &str = r#"use types::Customer
+use shop::types::{Tier}
+
+struct Order {
+    buyer: Customer
+    tier: Tier
+    items: Item[]
+}
+
+struct Item {
+    price: u32
+}
+"#;
+
+
+fn write_package(root: &Path) {
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("config.idp"), CONFIG).unwrap();
+    std::fs::write(root.join("src/types.ids"), TYPES).unwrap();
+    std::fs::write(root.join("src/orders.ids"), ORDERS).unwrap();
+}
+
+fn load(root: &Path) -> (Package, usize, usize) {
+    let package = Package::load(root, &HashMap::new());
+    let types = package.schema_by_path(&root.join("src/types.ids")).unwrap();
+    let orders = package.schema_by_path(&root.join("src/orders.ids")).unwrap();
+
+    (package, types, orders)
+}
+
+/// Offset of the `nth` occurrence of `text` in `source`
+fn offset(source: &str, text: &str, nth: usize) -> usize {
+    source.match_indices(text).nth(nth).unwrap().0
+}
+
+#[test]
+fn loads_schemas_with_their_namespaces() {
+    let dir = tempfile::tempdir().unwrap();
+    write_package(dir.path());
+    let (package, types, orders) = load(dir.path());
+
+    assert_eq!(package.name.as_deref(), Some("shop"));
+    assert_eq!(package.schemas[types].namespace, vec!["types".to_owned()]);
+    assert_eq!(package.schemas[orders].namespace, vec!["orders".to_owned()]);
+}
+
+#[test]
+fn goes_to_definitions_across_imports() {
+    let dir = tempfile::tempdir().unwrap();
+    write_package(dir.path());
+    let (package, types, orders) = load(dir.path());
+
+    let buyer = package.definition(orders, offset(ORDERS, "Customer", 1)).unwrap();
+    assert_eq!(buyer.schema, types);
+    assert_eq!(&TYPES[buyer.span.0..buyer.span.1], "Customer");
+
+    // Through a multi import led by the package name
+    let tier = package.definition(orders, offset(ORDERS, "Tier", 1)).unwrap();
+    assert_eq!((tier.schema, &TYPES[tier.span.0..tier.span.1]), (types, "Tier"));
+
+    // From the import itself
+    let imported = package.definition(orders, offset(ORDERS, "Customer", 0)).unwrap();
+    assert_eq!(imported, buyer);
+
+    let item = package.definition(orders, offset(ORDERS, "Item", 0)).unwrap();
+    assert_eq!((item.schema, &ORDERS[item.span.0..item.span.1]), (orders, "Item"));
+}
+
+#[test]
+fn hover_shows_fields_and_docstring() {
+    let dir = tempfile::tempdir().unwrap();
+    write_package(dir.path());
+    let (package, _, orders) = load(dir.path());
+
+    let hover = package.hover(orders, offset(ORDERS, "Customer", 1)).unwrap();
+    assert_eq!(
+        hover,
+        "```comline\nstruct Customer {\n    id: u64\n    optional email: string\n}\n```\n\n\
+         Someone who buys things"
+    );
+}
+
+#[test]
+fn completes_visible_types_primitives_and_keywords() {
+    let dir = tempfile::tempdir().unwrap();
+    write_package(dir.path());
+    let (package, _, orders) = load(dir.path());
+
+    let completions = package.completions(orders);
+    let labels = completions.iter().map(|c| c.label.as_str()).collect::<Vec<_>>();
+
+    for expected in ["Order", "Item", "Customer", "Tier", "types::Customer", "u32", "struct"] {
+        assert!(labels.contains(&expected), "Missing completion '{}'", expected);
+    }
+    assert!(completions.iter().any(|c| c.label == "string" && c.kind == CompletionKind::Primitive));
+}
+
+#[test]
+fn diagnoses_only_unresolvable_types() {
+    let dir = tempfile::tempdir().unwrap();
+    write_package(dir.path());
+    let (package, types, orders) = load(dir.path());
+
+    assert!(package.diagnostics(types).is_empty());
+    assert!(package.diagnostics(orders).is_empty(), "{:?}", package.diagnostics(orders));
+
+    let mut open = HashMap::new();
+    let edited = ORDERS.replace("items: Item[]", "items: Missing[]");
+    open.insert(dir.path().join("src/orders.ids"), edited.clone());
+
+    let package = Package::load(dir.path(), &open);
+    let orders = package.schema_by_path(&dir.path().join("src/orders.ids")).unwrap();
+    let problems = package.diagnostics(orders);
+
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].message, "Unknown type 'Missing'");
+    assert_eq!(&edited[problems[0].span.0..problems[0].span.1], "Missing");
+}
+
+#[test]
+fn reports_lints_with_configured_levels() {
+    let dir = tempfile::tempdir().unwrap();
+    write_package(dir.path());
+
+    let mut open = HashMap::new();
+    open.insert(dir.path().join("src/orders.ids"), format!("{}\nstruct Empty {{}}\n", ORDERS));
+    let package = Package::load(dir.path(), &open);
+    let orders = package.schema_by_path(&dir.path().join("src/orders.ids")).unwrap();
+
+    let problems = package.diagnostics(orders);
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].message, "Struct 'Empty' has no fields (empty_structs)");
+    assert_eq!(problems[0].severity, Severity::Warning);
+
+    open.insert(
+        dir.path().join("config.idp"), format!("{}lints = {{\n    empty_structs = deny\n}}\n", CONFIG)
+    );
+    let package = Package::load(dir.path(), &open);
+    assert_eq!(package.diagnostics(orders)[0].severity, Severity::Error);
+}
+
+#[test]
+fn diagnoses_parse_errors() {
+    let mut package = Package::for_document(Path::new("/nowhere/broken.ids"), &HashMap::new());
+    let schema = package.add_schema(
+        PathBuf::from("/nowhere/broken.ids"), vec!["broken".to_owned()], "struct { }".to_owned()
+    );
+
+    assert!(!package.diagnostics(schema).is_empty());
+}
+
+#[test]
+fn renames_types_across_the_package() {
+    let dir = tempfile::tempdir().unwrap();
+    write_package(dir.path());
+    let (package, types, orders) = load(dir.path());
+
+    let edits = package.rename(types, offset(TYPES, "Customer", 0), "Buyer").unwrap();
+    let mut renamed = edits.iter()
+        .map(|l| (l.schema, l.span.0))
+        .collect::<Vec<_>>();
+    renamed.sort();
+
+    let mut expected = vec![
+        (types, offset(TYPES, "Customer", 0)),
+        (orders, offset(ORDERS, "Customer", 0)),
+        (orders, offset(ORDERS, "Customer", 1)),
+    ];
+    expected.sort();
+    assert_eq!(renamed, expected);
+
+    // Fields are only renamed where they are declared
+    let edits = package.rename(types, offset(TYPES, "email", 0), "mail").unwrap();
+    assert_eq!(edits.len(), 1);
+
+    assert!(package.rename(types, offset(TYPES, "Customer", 0), "u8").is_err());
+}
+
+#[test]
+fn reports_configurations_that_cannot_be_interpreted() {
+    assert!(config::diagnostics(CONFIG).is_empty());
+
+    let problems = config::diagnostics("congregation shop\nunknown_key = 1\n");
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].message, "Assignment 'unknown_key' is not a valid assignment");
+    assert_eq!((problems[0].span, problems[0].severity), ((0, 17), Severity::Error));
+}
@@ -0,0 +1,61 @@
+// Standard Uses
+
+// Crate Uses
+
+// External Uses
+use comline_language_server::server::Server;
+use lsp_server::{Notification, Request, RequestId};
+use serde_json::json;
+
+
+#[test]
+fn publishes_diagnostics_and_answers_requests() {
+    let dir = tempfile::tempdir().unwrap();
+    let schema = dir.path().join("standalone.ids");
+    let uri = lsp_types::Url::from_file_path(&schema).unwrap();
+
+    let mut server = Server::default();
+    let published = server.handle_notification(Notification::new(
+        "textDocument/didOpen".to_owned(),
+        json!({ "textDocument": {
+            "uri": uri, "languageId": "comline", "version": 1,
+            "text": "struct Point {\n    x: f32\n    y: Unknown\n}\n",
+        }}),
+    ));
+
+    assert_eq!(published.len(), 1);
+    assert_eq!(published[0].method, "textDocument/publishDiagnostics");
+    let diagnostics = &published[0].params["diagnostics"];
+    assert_eq!(diagnostics[0]["message"], "Unknown type 'Unknown'");
+    assert_eq!(diagnostics[0]["range"]["start"], json!({ "line": 2, "character": 7 }));
+
+    let response = server.handle_request(Request::new(
+        RequestId::from(1), "textDocument/documentSymbol".to_owned(),
+        json!({ "textDocument": { "uri": uri } }),
+    ));
+    let symbols = response.result.unwrap();
+    assert_eq!(symbols[0]["name"], "Point");
+    assert_eq!(symbols[0]["children"][1]["detail"], "y: Unknown");
+}
+
+#[test]
+fn formats_open_documents() {
+    let dir = tempfile::tempdir().unwrap();
+    let uri = lsp_types::Url::from_file_path(dir.path().join("standalone.ids")).unwrap();
+
+    let mut server = Server::default();
+    server.handle_notification(Notification::new(
+        "textDocument/didOpen".to_owned(),
+        json!({ "textDocument": {
+            "uri": uri, "languageId": "comline", "version": 1, "text": "struct A { b:u8 }",
+        }}),
+    ));
+
+    let response = server.handle_request(Request::new(
+        RequestId::from(1), "textDocument/formatting".to_owned(),
+        json!({ "textDocument": { "uri": uri }, "options": { "tabSize": 4, "insertSpaces": true } }),
+    ));
+    let edits = response.result.unwrap();
+    assert_eq!(edits[0]["newText"], "struct A {\n    b: u8\n}\n");
+    assert_eq!(edits[0]["range"]["end"], json!({ "line": 0, "character": 17 }));
+}
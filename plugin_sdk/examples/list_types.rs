@@ -0,0 +1,31 @@
+//! A plugin listing the types every schema declares, in a file per schema
+
+// External Uses
+use comline_plugin_sdk::{run, FrozenUnit, PluginRequest, PluginResponse};
+
+
+fn main() {
+    run(|request: &PluginRequest| {
+        let mut response = PluginResponse::new();
+
+        for schema in &request.schemas {
+            let mut listing = String::new();
+            for unit in &schema.units {
+                match unit {
+                    FrozenUnit::Struct { name, .. } => listing += &format!("struct {}\n", name),
+                    FrozenUnit::Enum { name, .. } => listing += &format!("enum {}\n", name),
+                    FrozenUnit::Protocol { name, .. } => listing += &format!("protocol {}\n", name),
+                    _ => {}
+                }
+            }
+
+            if listing.is_empty() {
+                response.warning(Some(&schema.namespace.join("::")), "Declares no types");
+                continue
+            }
+            response.file(format!("{}.txt", schema.namespace.join("/")), listing);
+        }
+
+        Ok(response)
+    })
+}
@@ -0,0 +1,104 @@
+//! Writing code generators for Comline as plugins
+//!
+//! A plugin is an executable that a package names in `code_generation`,
+//! through the `plugin` option of a language:
+//!
+//! ```text
+//! code_generation = {
+//!     languages = {
+//!         kotlin#1 = {
+//!             package_versions = [all]
+//!             options = { plugin = "tools/comline-kotlin", nullable = true }
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Paths are relative to the package, while bare names are looked up in
+//! `PATH`. For every build the plugin is run once, and:
+//! - Its standard input is a [`PluginRequest`] as JSON, with the package name
+//!   and version, the options of the language, the compiled schemas and the
+//!   versions of the package frozen before, oldest first
+//! - Its standard output must be a [`PluginResponse`] as JSON, with the files
+//!   to write relative to the generation path and the problems it found
+//! - Its standard error is shown to whoever builds when it exits
+//!   unsuccessfully, which fails the build
+//!
+//! Diagnostics that are errors fail the build as well and warnings are
+//! reported. Both sides give the [`PROTOCOL_VERSION`] they speak, a plugin
+//! should refuse requests of a version it does not know.
+//!
+//! With this crate a plugin is a function from request to response:
+//!
+//! ```no_run
+//! use comline_plugin_sdk::{run, PluginRequest, PluginResponse};
+//!
+//! fn main() {
+//!     run(|request: &PluginRequest| {
+//!         let mut response = PluginResponse::new();
+//!         response.file("package.txt", format!("{} {}\n", request.package.name, request.package.version));
+//!         Ok(response)
+//!     })
+//! }
+//! ```
+
+// Standard Uses
+use std::io::{Read, Write};
+
+// External Uses
+use eyre::{bail, Result};
+
+pub use comline_core::codelib_gen::plugin::{
+    Diagnostic, DiagnosticLevel, PackageInfo, PluginRequest, PluginResponse, PROTOCOL_VERSION,
+};
+pub use comline_core::codelib_gen::{FrozenVersion, GeneratedFile, SchemaUnits};
+pub use comline_core::schema::ir::frozen::unit::{FrozenArgument, FrozenUnit};
+pub use comline_core::schema::ir::compiler::interpreted::kind_search::{KindValue, Primitive};
+
+
+/// Runs a plugin over the standard input and output, exiting unsuccessfully
+/// if the request cannot be read or the response written
+///
+/// Errors `generate` returns are answered as error diagnostics
+pub fn run<F>(generate: F)
+    where F: FnOnce(&PluginRequest) -> Result<PluginResponse>
+{
+    let stdin = std::io::stdin().lock();
+    let stdout = std::io::stdout().lock();
+
+    if let Err(e) = run_with(stdin, stdout, generate) {
+        eprintln!("{:?}", e);
+        std::process::exit(1)
+    }
+}
+
+/// Runs a plugin reading its request from `input` and writing its response
+/// to `output`
+pub fn run_with<F>(mut input: impl Read, mut output: impl Write, generate: F) -> Result<()>
+    where F: FnOnce(&PluginRequest) -> Result<PluginResponse>
+{
+    let mut request = String::new();
+    input.read_to_string(&mut request)?;
+
+    // The version is checked before the rest, which may have changed with it
+    let version: serde_json::Value = serde_json::from_str(&request)?;
+    match version["protocol_version"].as_u64() {
+        Some(version) if version == PROTOCOL_VERSION as u64 => {}
+        Some(version) => bail!(
+            "Requests of protocol version {} are not known, only of version {}", version, PROTOCOL_VERSION
+        ),
+        None => bail!("Request has no protocol version"),
+    }
+    let request: PluginRequest = serde_json::from_str(&request)?;
+
+    let response = generate(&request).unwrap_or_else(|e| {
+        let mut response = PluginResponse::new();
+        response.error(None, format!("{:#}", e));
+        response
+    });
+
+    serde_json::to_writer(&mut output, &response)?;
+    output.flush()?;
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+// Standard Uses
+
+// Crate Uses
+
+// External Uses
+use comline_plugin_sdk::{run_with, DiagnosticLevel, FrozenUnit, PluginResponse};
+use eyre::bail;
+use serde_json::{json, Value};
+
+
+/// The request of a package with a single schema declaring a struct
+fn request(protocol_version: u32) -> String {
+    json!({
+        "protocol_version": protocol_version,
+        "package": { "name": "teams", "version": "1.2.0" },
+        "options": { "suffix": "kt" },
+        "schemas": [{ "namespace": ["types"], "units": [
+            { "Struct": { "docstring": null, "parameters": [], "name": "Tag", "fields": [] } }
+        ]}],
+        "history": [],
+    }).to_string()
+}
+
+fn respond<F>(request: &str, generate: F) -> eyre::Result<Value>
+    where F: FnOnce(&comline_plugin_sdk::PluginRequest) -> eyre::Result<PluginResponse>
+{
+    let mut output = vec![];
+    run_with(request.as_bytes(), &mut output, generate)?;
+    Ok(serde_json::from_slice(&output)?)
+}
+
+
+#[test]
+fn answer_with_generated_files() {
+    let response = respond(&request(1), |request| {
+        let FrozenUnit::Struct { name, .. } = &request.schemas[0].units[0] else { bail!("No struct") };
+
+        let mut response = PluginResponse::new();
+        response.file(format!("types.{}", request.options["suffix"]), format!("class {}\n", name))
+            .warning(Some("types"), "Tags have no fields");
+        Ok(response)
+    }).unwrap();
+
+    assert_eq!(response, json!({
+        "protocol_version": 1,
+        "files": [{ "path": "types.kt", "contents": "class Tag\n" }],
+        "diagnostics": [{ "level": "warning", "message": "Tags have no fields", "schema": "types" }],
+    }));
+}
+
+#[test]
+fn answer_errors_as_diagnostics() {
+    let response = respond(&request(1), |_| bail!("Kotlin is not ready")).unwrap();
+    let response: PluginResponse = serde_json::from_value(response).unwrap();
+
+    assert!(response.files.is_empty());
+    assert_eq!(response.diagnostics[0].level, DiagnosticLevel::Error);
+    assert_eq!(response.diagnostics[0].message, "Kotlin is not ready");
+}
+
+#[test]
+fn refuse_unknown_protocol_versions() {
+    let error = respond(&request(2), |_| Ok(PluginResponse::new())).unwrap_err();
+    assert_eq!(error.to_string(), "Requests of protocol version 2 are not known, only of version 1");
+}
@@ -0,0 +1,4 @@
+//! What code Comline generates depends on at runtime
+
+// Relative Modules
+pub mod wire;
@@ -0,0 +1,336 @@
+//! The Comline binary encoding
+//!
+//! Values are encoded after their schema, without names nor types, so that
+//! reading them back needs the schema they were written with:
+//! - `bool` is a byte, `0` or `1`
+//! - `u8` and `i8` are a byte, in two's complement for `i8`
+//! - Other integers are varints, little endian groups of 7 bits with the high
+//!   bit set on every byte but the last, and signed integers are zigzag
+//!   encoded first, so that `0, -1, 1, -2` are `0, 1, 2, 3`
+//! - `f32` and `f64`, and `float` which is `f64`, are their IEEE 754 bits in
+//!   little endian
+//! - `str` and `string` are the varint length of their UTF-8 bytes, then the
+//!   bytes
+//! - Arrays like `T[]` are their varint count of elements, then the elements,
+//!   while arrays like `T[N]` are only their `N` elements
+//! - Structs are their fields in the order they are declared, where optional
+//!   fields are tagged by a byte, `0` when absent or `1` followed by the value
+//! - Enums are the varint index of their variant, in the order variants are
+//!   declared
+//! - Unions are the varint index of the kind of the value, in the order kinds
+//!   are written, then the value
+
+// Standard Uses
+use std::fmt::{Display, Formatter};
+
+
+/// Encodes a value into bytes
+pub fn encode<T: Encode + ?Sized>(value: &T) -> Vec<u8> {
+    let mut buffer = vec![];
+    value.encode(&mut buffer);
+    buffer
+}
+
+/// Decodes a value from bytes, which must hold nothing else
+pub fn decode<T: Decode>(bytes: &[u8]) -> Result<T, DecodeError> {
+    let mut reader = Reader::new(bytes);
+    let value = T::decode(&mut reader)?;
+
+    match reader.remaining() {
+        0 => Ok(value),
+        remaining => Err(DecodeError::TrailingBytes(remaining)),
+    }
+}
+
+
+pub trait Encode {
+    fn encode(&self, buffer: &mut Vec<u8>);
+}
+
+pub trait Decode: Sized {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The bytes ended before the value did
+    UnexpectedEnd,
+    /// A varint is longer than the largest integer
+    VarintTooLong,
+    /// An integer does not fit the type it is read as
+    OutOfRange { type_name: &'static str, value: u128 },
+    /// A byte that should be a `bool` or the tag of an optional is neither `0` nor `1`
+    InvalidTag(u8),
+    InvalidUtf8,
+    /// An index that no variant or kind of the type has
+    UnknownDiscriminant { type_name: &'static str, discriminant: u128 },
+    /// Bytes left after the value
+    TrailingBytes(usize),
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "The bytes ended before the value did"),
+            Self::VarintTooLong => write!(f, "A varint is longer than 128 bits"),
+            Self::OutOfRange { type_name, value } => write!(f, "{} does not fit in `{}`", value, type_name),
+            Self::InvalidTag(byte) => write!(f, "{} is neither 0 nor 1", byte),
+            Self::InvalidUtf8 => write!(f, "A string is not valid UTF-8"),
+            Self::UnknownDiscriminant { type_name, discriminant } => {
+                write!(f, "`{}` has nothing at index {}", type_name, discriminant)
+            }
+            Self::TrailingBytes(count) => write!(f, "{} bytes are left after the value", count),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+
+/// Bytes being decoded
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    /// Number of bytes not read yet
+    pub fn remaining(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn read_byte(&mut self) -> Result<u8, DecodeError> {
+        let (&byte, rest) = self.bytes.split_first().ok_or(DecodeError::UnexpectedEnd)?;
+        self.bytes = rest;
+        Ok(byte)
+    }
+
+    pub fn read_bytes(&mut self, count: usize) -> Result<&'a [u8], DecodeError> {
+        if count > self.bytes.len() { return Err(DecodeError::UnexpectedEnd) }
+
+        let (bytes, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+        Ok(bytes)
+    }
+
+    pub fn read_varint(&mut self) -> Result<u128, DecodeError> {
+        let mut value = 0u128;
+
+        for shift in (0..128).step_by(7) {
+            let byte = self.read_byte()?;
+            let bits = (byte & 0x7f) as u128;
+            if shift == 126 && bits > 0b11 { return Err(DecodeError::VarintTooLong) }
+
+            value |= bits << shift;
+            if byte & 0x80 == 0 { return Ok(value) }
+        }
+
+        Err(DecodeError::VarintTooLong)
+    }
+
+    /// The tag of an optional value, or a `bool`
+    pub fn read_tag(&mut self) -> Result<bool, DecodeError> {
+        match self.read_byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(DecodeError::InvalidTag(other)),
+        }
+    }
+
+    /// A varint length of bytes, which cannot be more than the bytes left
+    pub fn read_length(&mut self) -> Result<usize, DecodeError> {
+        match usize::try_from(self.read_varint()?) {
+            Ok(length) if length <= self.remaining() => Ok(length),
+            _ => Err(DecodeError::UnexpectedEnd),
+        }
+    }
+
+    /// A varint count of elements
+    pub fn read_count(&mut self) -> Result<usize, DecodeError> {
+        let count = self.read_varint()?;
+        usize::try_from(count).map_err(|_| DecodeError::OutOfRange { type_name: "usize", value: count })
+    }
+}
+
+pub fn write_varint(buffer: &mut Vec<u8>, mut value: u128) {
+    while value >= 0x80 {
+        buffer.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+pub fn zigzag(value: i128) -> u128 {
+    ((value << 1) ^ (value >> 127)) as u128
+}
+
+pub fn unzigzag(value: u128) -> i128 {
+    ((value >> 1) as i128) ^ -((value & 1) as i128)
+}
+
+
+impl Encode for bool {
+    fn encode(&self, buffer: &mut Vec<u8>) { buffer.push(*self as u8) }
+}
+
+impl Decode for bool {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> { reader.read_tag() }
+}
+
+impl Encode for u8 {
+    fn encode(&self, buffer: &mut Vec<u8>) { buffer.push(*self) }
+}
+
+impl Decode for u8 {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> { reader.read_byte() }
+}
+
+impl Encode for i8 {
+    fn encode(&self, buffer: &mut Vec<u8>) { buffer.push(*self as u8) }
+}
+
+impl Decode for i8 {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> { Ok(reader.read_byte()? as i8) }
+}
+
+macro_rules! unsigned_varints {
+    ($($type:ty),*) => {$(
+        impl Encode for $type {
+            fn encode(&self, buffer: &mut Vec<u8>) { write_varint(buffer, *self as u128) }
+        }
+
+        impl Decode for $type {
+            fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+                let value = reader.read_varint()?;
+                <$type>::try_from(value)
+                    .map_err(|_| DecodeError::OutOfRange { type_name: stringify!($type), value })
+            }
+        }
+    )*};
+}
+
+macro_rules! signed_varints {
+    ($($type:ty),*) => {$(
+        impl Encode for $type {
+            fn encode(&self, buffer: &mut Vec<u8>) { write_varint(buffer, zigzag(*self as i128)) }
+        }
+
+        impl Decode for $type {
+            fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+                let value = reader.read_varint()?;
+                <$type>::try_from(unzigzag(value))
+                    .map_err(|_| DecodeError::OutOfRange { type_name: stringify!($type), value })
+            }
+        }
+    )*};
+}
+
+unsigned_varints!(u16, u32, u64, u128);
+signed_varints!(i16, i32, i64, i128);
+
+impl Encode for f32 {
+    fn encode(&self, buffer: &mut Vec<u8>) { buffer.extend(self.to_le_bytes()) }
+}
+
+impl Decode for f32 {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(f32::from_le_bytes(reader.read_bytes(4)?.try_into().unwrap()))
+    }
+}
+
+impl Encode for f64 {
+    fn encode(&self, buffer: &mut Vec<u8>) { buffer.extend(self.to_le_bytes()) }
+}
+
+impl Decode for f64 {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(f64::from_le_bytes(reader.read_bytes(8)?.try_into().unwrap()))
+    }
+}
+
+impl Encode for str {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        write_varint(buffer, self.len() as u128);
+        buffer.extend(self.as_bytes());
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, buffer: &mut Vec<u8>) { self.as_str().encode(buffer) }
+}
+
+impl Decode for String {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        let length = reader.read_length()?;
+        let bytes = reader.read_bytes(length)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| DecodeError::InvalidUtf8)
+    }
+}
+
+impl<T: Encode> Encode for [T] {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        write_varint(buffer, self.len() as u128);
+        for element in self { element.encode(buffer) }
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, buffer: &mut Vec<u8>) { self.as_slice().encode(buffer) }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        let count = reader.read_count()?;
+
+        // Counts are not trusted for how much to allocate up front
+        let mut elements = Vec::with_capacity(count.min(reader.remaining()));
+        for _ in 0..count { elements.push(T::decode(reader)?) }
+        Ok(elements)
+    }
+}
+
+/// Fixed arrays have no count, as their schema gives it
+impl<T: Encode, const N: usize> Encode for [T; N] {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        for element in self { element.encode(buffer) }
+    }
+}
+
+impl<T: Decode, const N: usize> Decode for [T; N] {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        let elements = (0..N).map(|_| T::decode(reader)).collect::<Result<Vec<T>, _>>()?;
+        Ok(elements.try_into().unwrap_or_else(|_| unreachable!()))
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        match self {
+            None => buffer.push(0),
+            Some(value) => {
+                buffer.push(1);
+                value.encode(buffer);
+            }
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        match reader.read_tag()? {
+            false => Ok(None),
+            true => Ok(Some(T::decode(reader)?)),
+        }
+    }
+}
+
+impl<T: Encode + ?Sized> Encode for Box<T> {
+    fn encode(&self, buffer: &mut Vec<u8>) { self.as_ref().encode(buffer) }
+}
+
+impl<T: Decode> Decode for Box<T> {
+    fn decode(reader: &mut Reader) -> Result<Self, DecodeError> { Ok(Box::new(T::decode(reader)?)) }
+}
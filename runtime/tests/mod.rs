@@ -0,0 +1,72 @@
+// Standard Uses
+use std::fmt::Debug;
+
+// External Uses
+use comline_runtime::wire::{decode, encode, Decode, DecodeError, Encode};
+
+
+/// Encodes a value into the given bytes, and decodes it back
+fn assert_wire<T: Encode + Decode + PartialEq + Debug>(value: T, bytes: &[u8]) {
+    assert_eq!(encode(&value), bytes, "Encoding of {:?}", value);
+    assert_eq!(decode::<T>(bytes).unwrap(), value);
+}
+
+
+#[test]
+fn encode_integers() {
+    assert_wire(7u8, &[7]);
+    assert_wire(-2i8, &[0xfe]);
+    assert_wire(0u32, &[0]);
+    assert_wire(127u16, &[0x7f]);
+    assert_wire(300u32, &[0xac, 0x02]);
+    assert_wire(u64::MAX, &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]);
+    assert_wire(u128::MAX, &[[0xff; 18].as_slice(), &[0x03]].concat());
+
+    // Signed integers are zigzag encoded
+    assert_wire(0i32, &[0]);
+    assert_wire(-1i32, &[1]);
+    assert_wire(1i64, &[2]);
+    assert_wire(-64i16, &[0x7f]);
+    assert_wire(64i16, &[0x80, 0x01]);
+    assert_wire(i128::MIN, &[[0xff; 18].as_slice(), &[0x03]].concat());
+}
+
+#[test]
+fn encode_floats_and_booleans() {
+    assert_wire(true, &[1]);
+    assert_wire(false, &[0]);
+    assert_wire(1.5f32, &[0x00, 0x00, 0xc0, 0x3f]);
+    assert_wire(-2.0f64, &[0, 0, 0, 0, 0, 0, 0, 0xc0]);
+}
+
+#[test]
+fn encode_strings_and_arrays() {
+    assert_wire("hé".to_owned(), &[3, b'h', 0xc3, 0xa9]);
+    assert_wire(String::new(), &[0]);
+
+    assert_wire(vec![1u16, 300], &[2, 1, 0xac, 0x02]);
+    assert_wire(vec![vec![true], vec![]], &[2, 1, 1, 0]);
+    // Fixed arrays have no count
+    assert_wire([4u8, 5, 6], &[4, 5, 6]);
+    assert_wire([vec![1u8], vec![]], &[1, 1, 0]);
+}
+
+#[test]
+fn encode_optionals() {
+    assert_wire(None::<u32>, &[0]);
+    assert_wire(Some(300u32), &[1, 0xac, 0x02]);
+    assert_wire(Some("a".to_owned()), &[1, 1, b'a']);
+}
+
+#[test]
+fn refuse_malformed_bytes() {
+    assert_eq!(decode::<u32>(&[0x80]), Err(DecodeError::UnexpectedEnd));
+    assert_eq!(decode::<u128>(&[0xff; 20]), Err(DecodeError::VarintTooLong));
+    assert_eq!(decode::<u16>(&[0x80, 0x80, 0x04]), Err(DecodeError::OutOfRange { type_name: "u16", value: 65536 }));
+    assert_eq!(decode::<i8>(&[1, 2]), Err(DecodeError::TrailingBytes(1)));
+    assert_eq!(decode::<bool>(&[2]), Err(DecodeError::InvalidTag(2)));
+    assert_eq!(decode::<Option<u8>>(&[3, 1]), Err(DecodeError::InvalidTag(3)));
+    assert_eq!(decode::<String>(&[2, 0xc3, 0x28]), Err(DecodeError::InvalidUtf8));
+    assert_eq!(decode::<String>(&[5, b'a']), Err(DecodeError::UnexpectedEnd));
+    assert_eq!(decode::<Vec<u8>>(&[0xff, 0xff, 0xff, 0xff, 0x0f]), Err(DecodeError::UnexpectedEnd));
+}